@@ -0,0 +1,85 @@
+//!
+//! Benchmarks for looking up delta-t, comparing the binary search with the linear scan it
+//! replaced.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+#[macro_use]
+extern crate criterion;
+extern crate astro_calc;
+
+use astro_calc::astro_time::{Builder, DeltaTTable};
+use criterion::{black_box, Criterion};
+
+// About the size of the embedded table, which has yearly values from 1620.
+const ROWS: usize = 600;
+
+// The Modified Julian Day of the first row, 1913 May 23.
+const FIRST_MJD: f64 = 20_000.0;
+
+// Rows of Modified Julian Day and delta-t, a year apart.
+fn rows() -> Vec<(f64, f64)> {
+    (0..ROWS).map(|i| (FIRST_MJD + i as f64 * 365.25, 120.0 - 0.2 * i as f64)).collect()
+}
+
+// Julian Days spread over the table, between its rows.
+fn queries() -> Vec<f64> {
+    (0..64).map(|i| 2_400_000.5 + FIRST_MJD + (i as f64 * 9.37 + 0.5) * 365.25).collect()
+}
+
+// The reverse linear scan the embedded table was searched with before the binary search.
+fn linear_scan(table: &[(f64, f64)], julian_day: f64) -> Option<f64> {
+    if julian_day < table[0].0 || julian_day >= table[table.len() - 1].0 {
+        return None;
+    }
+
+    let i = (0..(table.len() - 1)).rev().find(|&i| table[i].0 < julian_day)?;
+    let (left, bottom) = table[i];
+    let (right, top) = table[i + 1];
+
+    Some((top - bottom) / (right - left) * (julian_day - left) + bottom)
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let text: String = rows()
+        .iter()
+        .map(|&(mjd, delta_t)| format!("{} {} {}\n", mjd, 1858.87 + mjd / 365.25, delta_t))
+        .collect();
+    let table = DeltaTTable::from_reader(text.as_bytes()).unwrap();
+    let entries: Vec<(f64, f64)> = rows()
+        .iter()
+        .map(|&(mjd, delta_t)| (mjd + 2_400_000.5, delta_t))
+        .collect();
+    let queries = queries();
+
+    for &jd in &queries {
+        assert_eq!(table.delta_t(jd), linear_scan(&entries, jd));
+    }
+
+    c.bench_function("delta_t binary search", |b| {
+        b.iter(|| {
+            queries.iter().map(|&jd| table.delta_t(black_box(jd)).unwrap()).sum::<f64>()
+        })
+    });
+    c.bench_function("delta_t linear scan", |b| {
+        b.iter(|| {
+            queries.iter().map(|&jd| linear_scan(&entries, black_box(jd)).unwrap()).sum::<f64>()
+        })
+    });
+}
+
+fn bench_conversion(c: &mut Criterion) {
+    // Before the leap seconds, so the embedded table is used.
+    let time = Builder::from_gregorian_utc(1850, 6, 1, 0, 0, 0).build().unwrap();
+
+    c.bench_function("universal to dynamical time", |b| {
+        b.iter(|| black_box(time).as_dt().unwrap())
+    });
+}
+
+criterion_group!(benches, bench_lookup, bench_conversion);
+criterion_main!(benches);
@@ -0,0 +1,193 @@
+//!
+//! Module for a trait for calendars, so dates in any of them can be converted to and from times.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The Gregorian and Julian calendars are implemented here. Other calendars, such as the French
+//! Revolutionary or the Coptic calendar, may be added outside of this crate by implementing
+//! `Calendar` for them.
+//!
+use super::super::error::*;
+use super::{calendar_date, day_fraction, gregorian_julian_day, is_valid_gregorian,
+            is_valid_julian, is_valid_time, to_hms, AstroTime, Builder};
+
+/// A calendar, which numbers the days by year, month, and day of the month.
+///
+/// The days are the civil days from midnight to midnight, and a day is identified by the Julian
+/// Day number at the midnight it starts with, which ends in .5.
+///
+/// # Examples
+///
+/// The Egyptian calendar of the Era of Nabonassar, which Ptolemy used in the Almagest, has 12
+/// months of 30 days, followed by 5 more days that are taken as month 13 here.
+///
+/// ```
+/// # use astro_calc::astro_time::{Builder, Calendar};
+/// # use astro_calc::error::{AstroAlgorithmsError, AstroResult};
+/// struct Egyptian;
+///
+/// // 1 Thoth of the year 1, 747 BC February 26 in the Julian calendar.
+/// const EPOCH: f64 = 1_448_637.5;
+///
+/// impl Calendar for Egyptian {
+///     fn julian_day(&self, year: i32, month: i32, day: i32) -> AstroResult<f64> {
+///         let days = if month == 13 { 5 } else { 30 };
+///         if year < 1 || month < 1 || month > 13 || day < 1 || day > days {
+///             return Err(AstroAlgorithmsError::InvalidArgument("Invalid date".to_owned()));
+///         }
+///         Ok(EPOCH + f64::from(365 * (year - 1) + 30 * (month - 1) + day - 1))
+///     }
+///
+///     fn date(&self, julian_day: f64) -> AstroResult<(i32, i32, i32)> {
+///         let days = (julian_day - EPOCH).floor() as i32;
+///         if days < 0 {
+///             return Err(AstroAlgorithmsError::InvalidArgument("Invalid date".to_owned()));
+///         }
+///         Ok((days / 365 + 1, days % 365 / 30 + 1, days % 365 % 30 + 1))
+///     }
+/// }
+///
+/// let epoch = Builder::from_calendar(&Egyptian, 1, 1, 1, 12, 0, 0).build().unwrap();
+/// assert_eq!(epoch.to_julian_utc(), (-746, 2, 26, 12, 0, 0));
+/// assert_eq!(epoch.to_calendar(&Egyptian).unwrap(), (1, 1, 1, 12, 0, 0));
+/// ```
+pub trait Calendar {
+    /// Get the Julian Day number at the midnight starting a date.
+    ///
+    /// Returns an error if the date isn't in the calendar.
+    fn julian_day(&self, year: i32, month: i32, day: i32) -> AstroResult<f64>;
+
+    /// Get the year, month, and day of the date starting at the midnight at a Julian Day number.
+    ///
+    /// Returns an error if the day is outside of the range of the calendar.
+    fn date(&self, julian_day: f64) -> AstroResult<(i32, i32, i32)>;
+}
+
+/// The Gregorian calendar, extended to the years before it was introduced in 1582.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gregorian;
+
+/// The Julian calendar, extended to the years before 8 AD when its leap years became regular.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Julian;
+
+impl Calendar for Gregorian {
+    fn julian_day(&self, year: i32, month: i32, day: i32) -> AstroResult<f64> {
+        if is_valid_gregorian(year, month, day) {
+            Ok(gregorian_julian_day(year, month, f64::from(day)))
+        } else {
+            Err(AstroAlgorithmsError::InvalidGregorianDate(year, month, day))
+        }
+    }
+
+    fn date(&self, julian_day: f64) -> AstroResult<(i32, i32, i32)> {
+        Ok(calendar_date((julian_day + 0.5).floor(), true))
+    }
+}
+
+impl Calendar for Julian {
+    fn julian_day(&self, mut year: i32, mut month: i32, day: i32) -> AstroResult<f64> {
+        // From chapter 7, pages 60-61 of Astronomical Algorithms, 2nd Edition by Jean Meeus.
+        if !is_valid_julian(year, month, day) {
+            return Err(AstroAlgorithmsError::InvalidJulianDate(year, month, day));
+        }
+
+        if month < 3 {
+            year -= 1;
+            month += 12;
+        }
+
+        Ok(f64::floor(365.25 * f64::from(year + 4716)) +
+           f64::floor(30.6001 * f64::from(month + 1)) + f64::from(day) - 1524.5)
+    }
+
+    fn date(&self, julian_day: f64) -> AstroResult<(i32, i32, i32)> {
+        Ok(calendar_date((julian_day + 0.5).floor(), false))
+    }
+}
+
+impl Builder {
+    /// Create from a date in any calendar and a time, assuming it is in the UTC time zone.
+    ///
+    /// It defaults to `TimeType::UT`.
+    pub fn from_calendar<C>(calendar: &C,
+                            year: i32,
+                            month: i32,
+                            day: i32,
+                            hour: i32,
+                            minute: i32,
+                            second: i32)
+                            -> Builder
+        where C: Calendar + ?Sized
+    {
+        match calendar.julian_day(year, month, day) {
+            Ok(jd) if is_valid_time(hour, minute, second) => {
+                Builder::from_julian_date_parts(jd, day_fraction(hour, minute, second))
+            }
+            Ok(_) => {
+                Builder::new(Err(AstroAlgorithmsError::InvalidTime(hour, minute, second)))
+            }
+            Err(err) => Builder::new(Err(err)),
+        }
+    }
+}
+
+impl AstroTime {
+    /// Get the year, month, day, hour, minute, and second in any calendar, rounded to the nearest
+    /// second.
+    ///
+    /// Like `to_gregorian_utc`, the time isn't converted to UTC first.
+    pub fn to_calendar<C>(&self, calendar: &C) -> AstroResult<(i32, i32, i32, i32, i32, i32)>
+        where C: Calendar + ?Sized
+    {
+        // Round to the nearest second first, so the seconds never round up to 60.
+        let f = f64::round(self.fraction * 86_400.0) / 86_400.0;
+        let (year, month, day) = calendar.date(self.day + f64::floor(f))?;
+        let (hour, minute, second) = to_hms(f - f64::floor(f));
+
+        Ok((year, month, day, hour, minute, second))
+    }
+}
+
+#[cfg(test)]
+mod calendar_tests {
+    use super::*;
+
+    #[test]
+    fn test_calendars() {
+        // Example 7.a and 7.b, Astronomical Algorithms 2nd ed.
+        assert_eq!(Gregorian.julian_day(1957, 10, 4).unwrap(), 2_436_115.5);
+        assert_eq!(Julian.julian_day(333, 1, 27).unwrap(), 1_842_712.5);
+        assert_eq!(Gregorian.date(2_436_115.5).unwrap(), (1957, 10, 4));
+        assert_eq!(Julian.date(1_842_712.5).unwrap(), (333, 1, 27));
+
+        // The day of the reform, in both calendars.
+        assert_eq!(Gregorian.julian_day(1582, 10, 15).unwrap(),
+                   Julian.julian_day(1582, 10, 5).unwrap());
+
+        assert!(Gregorian.julian_day(1900, 2, 29).is_err());
+        assert!(Julian.julian_day(1900, 2, 29).is_ok());
+        assert!(Julian.julian_day(2017, 13, 1).is_err());
+    }
+
+    #[test]
+    fn test_from_and_to_calendar() {
+        let calendars: [&dyn Calendar; 2] = [&Gregorian, &Julian];
+        for calendar in &calendars {
+            let time = Builder::from_calendar(*calendar, 2017, 8, 21, 18, 25, 59).build().unwrap();
+            assert_eq!(time.to_calendar(*calendar).unwrap(), (2017, 8, 21, 18, 25, 59));
+        }
+
+        let time = Builder::from_calendar(&Gregorian, 2017, 8, 21, 18, 25, 0).build().unwrap();
+        let expected = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).build().unwrap();
+        assert!(time == expected);
+        assert_eq!(time.to_calendar(&Julian).unwrap(), time.to_julian_utc());
+
+        assert!(Builder::from_calendar(&Gregorian, 2017, 2, 29, 0, 0, 0).build().is_err());
+        assert!(Builder::from_calendar(&Julian, 2017, 8, 21, 24, 0, 0).build().is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//!
+//! Module for dates in the Islamic and Jewish calendars.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Follows chapter 9 of Astronomical Algorithms, 2nd Edition by Jean Meeus. The Islamic calendar
+//! is the tabular calendar with 11 leap years in each cycle of 30 years. In practice the months
+//! begin with the first sighting of the crescent Moon, so the dates in use may differ by a day or
+//! two. The Jewish calendar is found from the date of the Passover, 15 Nisan.
+//!
+//! Both calendars begin the day at sunset, but the dates here are for the civil day from midnight
+//! to midnight that the day mostly falls on.
+//!
+use super::super::error::*;
+use super::{AstroTime, Builder};
+
+// The Julian Day of 1 Muharram of the year 1, 622 July 16 in the Julian calendar.
+const ISLAMIC_EPOCH: f64 = 1_948_439.5;
+
+// The last year supported in the Islamic and Jewish calendars, a million years, well short of
+// where the arithmetic on the years overflows.
+const MAX_YEAR: i32 = 1_000_000;
+
+// The Jewish months in the order of the civil year, which starts with Tishri. The months are
+// numbered from Nisan, and month 13 is the second Adar of a leap year.
+const JEWISH_MONTHS: [i32; 13] = [7, 8, 9, 10, 11, 12, 13, 1, 2, 3, 4, 5, 6];
+
+impl Builder {
+    /// Create from a date in the tabular Islamic calendar, at midnight in the UTC time zone.
+    ///
+    /// The months are numbered from 1 for Muharram to 12 for Dhu al-Hijjah. It defaults to
+    /// `TimeType::UT`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// // Example 9.d, Astronomical Algorithms 2nd ed.
+    /// let time = Builder::from_islamic(1412, 2, 2).build().unwrap();
+    /// assert_eq!(time.to_gregorian_utc(), (1991, 8, 13, 0, 0, 0));
+    /// ```
+    pub fn from_islamic(year: i32, month: i32, day: i32) -> Builder {
+        if !(1..=MAX_YEAR).contains(&year) || !(1..=12).contains(&month) || day < 1 ||
+           day > islamic_month_length(year, month) {
+            return Builder::new(Err(invalid_date("Islamic", year, month, day)));
+        }
+
+        Builder::from_julian_date(islamic_julian_day(year, month) + f64::from(day - 1))
+    }
+
+    /// Create from a date in the Jewish calendar, at midnight in the UTC time zone.
+    ///
+    /// The months are numbered from 1 for Nisan to 12 for Adar, with 12 for Adar I and 13 for
+    /// Adar II in a leap year. The year starts on 1 Tishri, month 7. It defaults to
+    /// `TimeType::UT`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// // Rosh Hashanah, 1 Tishri 5778.
+    /// let time = Builder::from_jewish(5778, 7, 1).build().unwrap();
+    /// assert_eq!(time.to_gregorian_utc(), (2017, 9, 21, 0, 0, 0));
+    /// ```
+    pub fn from_jewish(year: i32, month: i32, day: i32) -> Builder {
+        Builder::new(jewish(year, month, day))
+    }
+}
+
+impl AstroTime {
+    /// Get the year, month, and day in the tabular Islamic calendar of the UTC date.
+    pub fn to_islamic(&self) -> AstroResult<(i32, i32, i32)> {
+        let jd = midnight(self)?;
+        if jd < ISLAMIC_EPOCH {
+            return Err(AstroAlgorithmsError::InvalidArgument("The date is before the Islamic \
+                                                              calendar"
+                .to_owned()));
+        }
+
+        let year = ((30.0 * (jd - ISLAMIC_EPOCH) + 10_646.0) / 10_631.0).floor();
+        if year > f64::from(MAX_YEAR) {
+            return Err(out_of_range("Islamic"));
+        }
+        let year = year as i32;
+        let mut month = 12;
+        while islamic_julian_day(year, month) > jd {
+            month -= 1;
+        }
+        let day = (jd - islamic_julian_day(year, month)) as i32 + 1;
+
+        Ok((year, month, day))
+    }
+
+    /// Get the year, month, and day in the Jewish calendar of the UTC date.
+    ///
+    /// See `Builder::from_jewish` for the numbering of the months.
+    pub fn to_jewish(&self) -> AstroResult<(i32, i32, i32)> {
+        let jd = midnight(self)?;
+        let (gregorian_year, ..) = self.as_utc()?.to_gregorian_utc();
+        if gregorian_year > MAX_YEAR - 3761 {
+            return Err(out_of_range("Jewish"));
+        }
+
+        let mut year = gregorian_year + 3761;
+        let mut start = tishri_1(year)?;
+        if jd < start {
+            year -= 1;
+            start = tishri_1(year)?;
+        }
+        let length = (tishri_1(year + 1)? - start) as i32;
+
+        let mut day = (jd - start) as i32 + 1;
+        for &month in &JEWISH_MONTHS {
+            let days = jewish_month_length(length, month);
+            if day <= days {
+                return Ok((year, month, day));
+            }
+            day -= days;
+        }
+
+        Err(AstroAlgorithmsError::InvalidArgument("The date is past the end of the Jewish year"
+            .to_owned()))
+    }
+}
+
+// The Julian Day of the first day of an Islamic month.
+fn islamic_julian_day(year: i32, month: i32) -> f64 {
+    ISLAMIC_EPOCH + f64::from((year - 1) * 354 + (3 + 11 * year) / 30 + 29 * (month - 1) +
+                              month / 2)
+}
+
+// The odd months have 30 days and the even months 29, except the last month of a leap year.
+fn islamic_month_length(year: i32, month: i32) -> i32 {
+    if month % 2 == 1 || (month == 12 && (14 + 11 * year) % 30 < 11) {
+        30
+    } else {
+        29
+    }
+}
+
+fn jewish(year: i32, month: i32, day: i32) -> AstroResult<AstroTime> {
+    if !(1..=MAX_YEAR).contains(&year) || !(1..=13).contains(&month) {
+        return Err(invalid_date("Jewish", year, month, day));
+    }
+
+    let start = tishri_1(year)?;
+    let length = (tishri_1(year + 1)? - start) as i32;
+    if day < 1 || day > jewish_month_length(length, month) {
+        return Err(invalid_date("Jewish", year, month, day));
+    }
+
+    let before: i32 = JEWISH_MONTHS.iter()
+        .take_while(|&&m| m != month)
+        .map(|&m| jewish_month_length(length, m))
+        .sum();
+
+    Builder::from_julian_date(start + f64::from(before + day - 1)).build()
+}
+
+// The length of a Jewish month in a year of `year_length` days, 0 for the second Adar of a common
+// year. The year lengths are 353, 354, or 355 days, with 30 days more in a leap year.
+fn jewish_month_length(year_length: i32, month: i32) -> i32 {
+    let leap = year_length > 355;
+    match month {
+        8 if year_length % 10 == 5 => 30,
+        9 if year_length % 10 == 3 => 29,
+        12 if leap => 30,
+        13 if !leap => 0,
+        1 | 3 | 5 | 7 | 9 | 11 => 30,
+        _ => 29,
+    }
+}
+
+// The Julian Day of 1 Tishri, the Jewish New Year, which is 163 days after the Passover of the
+// previous year.
+fn tishri_1(year: i32) -> AstroResult<f64> {
+    Ok(passover(year - 3761)? + 163.0)
+}
+
+// The Julian Day of the Passover, 15 Nisan, in the year `x` of the Christian era, in the
+// Gregorian calendar from 1583 and the Julian calendar before.
+fn passover(x: i32) -> AstroResult<f64> {
+    let gregorian = x >= 1583;
+    let s = if gregorian { (3 * (x / 100) - 5) / 4 } else { 0 };
+    let a = (12 * x + 12).rem_euclid(19);
+    let b = x.rem_euclid(4);
+
+    let q = -1.904_412_361_576 + 1.554_241_796_621 * f64::from(a) + 0.25 * f64::from(b) -
+            0.003_177_794_022 * f64::from(x) + f64::from(s);
+    let int_q = q.floor();
+    let r = q - int_q;
+    let int_q = int_q as i32;
+    let j = (int_q + 3 * x + 5 * b + 2 - s).rem_euclid(7);
+
+    let d = if j == 2 || j == 4 || j == 6 {
+        int_q + 23
+    } else if j == 1 && a > 6 && r >= 0.632_870_370 {
+        int_q + 24
+    } else if j == 0 && a > 11 && r >= 0.897_723_765 {
+        int_q + 23
+    } else {
+        int_q + 22
+    };
+
+    let march_1 = if gregorian {
+        Builder::from_gregorian_utc(x, 3, 1, 0, 0, 0).build()?
+    } else {
+        Builder::from_julian_utc(x, 3, 1, 0, 0, 0).build()?
+    };
+
+    Ok(march_1.julian_day_number() + f64::from(d - 1))
+}
+
+// The Julian Day at the start of the UTC day.
+fn midnight(time: &AstroTime) -> AstroResult<f64> {
+    Ok((time.as_utc()?.julian_day_number() - 0.5).floor() + 0.5)
+}
+
+fn out_of_range(calendar: &str) -> AstroAlgorithmsError {
+    AstroAlgorithmsError::InvalidArgument(format!("The date is past the year {} of the {} \
+                                                   calendar",
+                                                  MAX_YEAR,
+                                                  calendar))
+}
+
+fn invalid_date(calendar: &str, year: i32, month: i32, day: i32) -> AstroAlgorithmsError {
+    AstroAlgorithmsError::InvalidArgument(format!("Invalid {} date {}-{:02}-{:02}",
+                                                  calendar,
+                                                  year,
+                                                  month,
+                                                  day))
+}
+
+#[cfg(test)]
+mod calendars_tests {
+    use super::*;
+
+    fn gregorian(year: i32, month: i32, day: i32) -> AstroTime {
+        Builder::from_gregorian_utc(year, month, day, 0, 0, 0).build().unwrap()
+    }
+
+    #[test]
+    fn test_islamic() {
+        // The epoch, and the start of Ramadan 1438.
+        let epoch = Builder::from_islamic(1, 1, 1).build().unwrap();
+        assert!(epoch == Builder::from_julian_utc(622, 7, 16, 0, 0, 0).build().unwrap());
+        assert!(Builder::from_islamic(1438, 9, 1).build().unwrap() == gregorian(2017, 5, 27));
+
+        // Example 9.d, Astronomical Algorithms 2nd ed.
+        let time = Builder::from_gregorian_utc(1991, 8, 13, 18, 0, 0).build().unwrap();
+        assert_eq!(time.to_islamic().unwrap(), (1412, 2, 2));
+
+        // Round trips through leap and common years.
+        for &(year, month, day) in &[(1, 1, 1), (1438, 12, 29), (1439, 12, 30), (1440, 1, 1)] {
+            let time = Builder::from_islamic(year, month, day).build().unwrap();
+            assert_eq!(time.to_islamic().unwrap(), (year, month, day));
+        }
+
+        assert!(Builder::from_islamic(1438, 12, 30).build().is_err());
+        assert!(Builder::from_islamic(1438, 2, 30).build().is_err());
+        assert!(Builder::from_islamic(1438, 13, 1).build().is_err());
+        assert!(gregorian(600, 1, 1).to_islamic().is_err());
+
+        // Past the supported years, rather than overflowing.
+        let far = Builder::from_julian_date(1.0e12).build().unwrap();
+        assert!(far.to_islamic().is_err());
+        assert!(Builder::from_islamic(i32::MAX, 1, 1).build().is_err());
+    }
+
+    #[test]
+    fn test_jewish() {
+        // Example 9.a, Astronomical Algorithms 2nd ed., the Passover of 1990 and the new year
+        // of 5750.
+        assert!(Builder::from_jewish(5750, 1, 15).build().unwrap() == gregorian(1990, 4, 10));
+        assert!(Builder::from_jewish(5750, 7, 1).build().unwrap() == gregorian(1989, 9, 30));
+
+        // Hanukkah 5778, and Purim in a common year and a leap year.
+        assert!(Builder::from_jewish(5778, 9, 25).build().unwrap() == gregorian(2017, 12, 13));
+        assert!(Builder::from_jewish(5777, 12, 14).build().unwrap() == gregorian(2017, 3, 12));
+        assert!(Builder::from_jewish(5776, 13, 14).build().unwrap() == gregorian(2016, 3, 24));
+
+        assert_eq!(gregorian(2017, 9, 20).to_jewish().unwrap(), (5777, 6, 29));
+        assert_eq!(gregorian(2017, 9, 21).to_jewish().unwrap(), (5778, 7, 1));
+        assert_eq!(gregorian(2016, 3, 24).to_jewish().unwrap(), (5776, 13, 14));
+
+        // Every day of a leap year round trips.
+        let start = Builder::from_jewish(5776, 7, 1).build().unwrap().julian_day_number();
+        let end = Builder::from_jewish(5777, 7, 1).build().unwrap().julian_day_number();
+        assert_eq!(end - start, 385.0);
+        for i in 0..385 {
+            let time = Builder::from_julian_date(start + f64::from(i)).build().unwrap();
+            let (year, month, day) = time.to_jewish().unwrap();
+            assert!(Builder::from_jewish(year, month, day).build().unwrap() == time);
+        }
+
+        assert!(Builder::from_jewish(5777, 13, 1).build().is_err());
+        assert!(Builder::from_jewish(5777, 2, 30).build().is_err());
+        assert!(Builder::from_jewish(0, 7, 1).build().is_err());
+
+        let far = Builder::from_julian_date(1.0e12).build().unwrap();
+        assert!(far.to_jewish().is_err());
+        assert!(Builder::from_jewish(i32::MAX, 7, 1).build().is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//!
+//! Module for loading tables of delta-t, the difference between dynamical and universal time, at
+//! runtime.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The embedded table ends in 2017. Newer values, and predictions, are published by the USNO and
+//! the IERS, and can be loaded. An installed table is only used before 1972, since UTC is
+//! converted exactly with the leap seconds from then on.
+//!
+use std::io::BufRead;
+use std::sync::{RwLock, RwLockReadGuard};
+
+use super::super::error::*;
+use super::Builder;
+
+// The Modified Julian Day of Julian Day 0.
+const MJD_OFFSET: f64 = 2_400_000.5;
+
+// The table installed in place of the embedded table, if any.
+static INSTALLED: RwLock<Option<DeltaTTable>> = RwLock::new(None);
+
+/// A table of delta-t values, ΔT = TT - UT in seconds, loaded at runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeltaTTable {
+    // Pairs of Julian Day and delta-t, sorted by Julian Day.
+    entries: Vec<(f64, f64)>,
+}
+
+impl DeltaTTable {
+    /// Parse a table in one of the USNO/IERS text formats.
+    ///
+    /// Three kinds of rows are recognized:
+    ///
+    /// ```text
+    /// 2017  1  1  68.5928                    year, month, day, ΔT (deltat.data)
+    /// 58849.000  2020.00  69.36  -0.18  0.1  MJD, decimal year, ΔT, ... (deltat.preds)
+    /// 1657.0  44  ...                        decimal year, ΔT, ... (historic_deltat.data)
+    /// ```
+    ///
+    /// Blank lines and lines that don't start with a number, such as headers, are skipped. The rows
+    /// may be in any order, and at least two are needed to interpolate.
+    pub fn from_reader<R: BufRead>(reader: R) -> AstroResult<DeltaTTable> {
+        let mut entries = Vec::new();
+
+        for (number, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| parse_error(number, &err.to_string()))?;
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let first = match tokens.first().map(|token| token.parse::<f64>()) {
+                Some(Ok(first)) if first.is_finite() => first,
+                Some(Ok(_)) => return Err(parse_error(number, "Expected a finite number")),
+                _ => continue,
+            };
+
+            let value = |i: usize| -> AstroResult<f64> {
+                tokens.get(i)
+                    .and_then(|token| token.parse().ok())
+                    .filter(|value: &f64| value.is_finite())
+                    .ok_or_else(|| parse_error(number, "Expected a finite number"))
+            };
+
+            let entry = if first > 10_000.0 {
+                (first + MJD_OFFSET, value(2)?)
+            } else if tokens.len() >= 4 && tokens[..3].iter().all(|t| t.parse::<i32>().is_ok()) {
+                let date: Vec<i32> = tokens[..3].iter().map(|t| t.parse().unwrap()).collect();
+                let jd = Builder::from_gregorian_utc(date[0], date[1], date[2], 0, 0, 0)
+                    .build()
+                    .map_err(|_| parse_error(number, "Invalid date"))?
+                    .julian_day_number();
+                (jd, value(3)?)
+            } else {
+                (decimal_year_to_julian_day(first)
+                     .map_err(|_| parse_error(number, "Invalid year"))?,
+                 value(1)?)
+            };
+            entries.push(entry);
+        }
+
+        if entries.len() < 2 {
+            return Err(AstroAlgorithmsError::InvalidArgument("A delta-t table needs at least two \
+                                                              rows"
+                .to_owned()));
+        }
+        entries.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        Ok(DeltaTTable { entries })
+    }
+
+    /// The range of Julian Days covered by the table.
+    pub fn range(&self) -> (f64, f64) {
+        (self.entries[0].0, self.entries[self.entries.len() - 1].0)
+    }
+
+    /// Linearly interpolate delta-t in seconds at a Julian Day, or `None` if it is outside the
+    /// table.
+    pub fn delta_t(&self, julian_day: f64) -> Option<f64> {
+        interpolate(&self.entries, julian_day)
+    }
+
+    /// Use this table for conversions between universal and dynamical time, in place of the
+    /// embedded table and the polynomials outside it, over the dates it covers.
+    ///
+    /// From 1972 on UTC is converted exactly with the leap seconds, so the table is only used for
+    /// earlier dates. Later values, such as the predictions, are still available from `delta_t`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::{Builder, DeltaTTable};
+    /// let table = DeltaTTable::from_reader("1000 1 1 1500.0\n2100 1 1 1500.0\n".as_bytes())
+    ///     .unwrap();
+    /// let old = Builder::from_gregorian_utc(1900, 1, 1, 0, 0, 0).build().unwrap();
+    /// let new = Builder::from_gregorian_utc(2020, 1, 1, 0, 0, 0).build().unwrap();
+    /// let delta_t = |time: &astro_calc::astro_time::AstroTime| {
+    ///     (time.as_dt().unwrap().julian_day_number() - time.julian_day_number()) * 86_400.0
+    /// };
+    ///
+    /// table.install();
+    /// assert!((delta_t(&old) - 1500.0).abs() < 1.0e-3);
+    /// // The leap seconds are used instead from 1972 on.
+    /// assert!((delta_t(&new) - 69.184).abs() < 1.0e-3);
+    ///
+    /// DeltaTTable::uninstall();
+    /// assert!((delta_t(&old) - 1500.0).abs() > 1000.0);
+    /// ```
+    pub fn install(self) {
+        *INSTALLED.write().unwrap() = Some(self);
+    }
+
+    /// Go back to using only the embedded table.
+    pub fn uninstall() {
+        *INSTALLED.write().unwrap() = None;
+    }
+}
+
+// The installed table, if any, locked for reading.
+pub(crate) fn installed() -> RwLockReadGuard<'static, Option<DeltaTTable>> {
+    INSTALLED.read().unwrap()
+}
+
+// Linearly interpolate a table of Julian Days and values sorted by Julian Day with a binary
+// search, or `None` outside the table.
+pub(crate) fn interpolate(table: &[(f64, f64)], julian_day: f64) -> Option<f64> {
+    let (first, last) = (table.first()?.0, table.last()?.0);
+    // NaN isn't in the range either, so the comparisons below can't fail.
+    if !(first..=last).contains(&julian_day) {
+        return None;
+    }
+
+    let i = match table.binary_search_by(|entry| entry.0.partial_cmp(&julian_day).unwrap()) {
+        Ok(i) => return Some(table[i].1),
+        Err(i) => i,
+    };
+    let (left, bottom) = table[i - 1];
+    let (right, top) = table[i];
+
+    Some((top - bottom) / (right - left) * (julian_day - left) + bottom)
+}
+
+fn decimal_year_to_julian_day(year: f64) -> AstroResult<f64> {
+    let whole = year.floor();
+    let start = Builder::from_gregorian_utc(whole as i32, 1, 1, 0, 0, 0).build()?;
+    let end = Builder::from_gregorian_utc(whole as i32 + 1, 1, 1, 0, 0, 0).build()?;
+    let (start, end) = (start.julian_day_number(), end.julian_day_number());
+
+    Ok(start + (year - whole) * (end - start))
+}
+
+fn parse_error(line: usize, msg: &str) -> AstroAlgorithmsError {
+    AstroAlgorithmsError::InvalidArgument(format!("Parsing delta-t table line {}: {}",
+                                                  line + 1,
+                                                  msg))
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod delta_t_tests {
+    use super::*;
+
+    fn jd(year: i32, month: i32, day: i32) -> f64 {
+        Builder::from_gregorian_utc(year, month, day, 0, 0, 0).build().unwrap().julian_day_number()
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let text = "\
+ 2017  1  1  68.5928
+ 2017  2  1  68.6298
+
+     MJD        YEAR    TT-UT Pred  UT1-UTC Pred  ERROR
+  58849.000  2020.00      69.36       -0.177      0.107
+1657.0  44  1.0
+";
+        let table = DeltaTTable::from_reader(text.as_bytes()).unwrap();
+        assert_eq!(table.range(), (jd(1657, 1, 1), 58_849.0 + MJD_OFFSET));
+        assert_eq!(table.delta_t(jd(1657, 1, 1)), Some(44.0));
+        assert_eq!(table.delta_t(jd(2017, 2, 1)), Some(68.6298));
+        assert_eq!(table.delta_t(jd(2020, 1, 1)), Some(69.36));
+
+        let middle = (jd(2017, 1, 1) + jd(2017, 2, 1)) / 2.0;
+        assert!(approx_eq(table.delta_t(middle).unwrap(), 68.6113, 1.0e-9));
+        assert!(table.delta_t(jd(1600, 1, 1)).is_none());
+        assert!(table.delta_t(jd(2021, 1, 1)).is_none());
+        assert!(table.delta_t(f64::NAN).is_none());
+
+        assert!(DeltaTTable::from_reader("2017 1 1 68.59\n".as_bytes()).is_err());
+        assert!(DeltaTTable::from_reader("2017 1 1 68.59\n2017 2 30 68.6\n".as_bytes()).is_err());
+        assert!(DeltaTTable::from_reader("2017 1 1 68.59\n2017\n".as_bytes()).is_err());
+        assert!(DeltaTTable::from_reader("2017 1 1 68.59\nnan 1.0\n".as_bytes()).is_err());
+        assert!(DeltaTTable::from_reader("2017 1 1 68.59\n2018.0 inf\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let table = [(1.0, 10.0), (2.0, 20.0), (4.0, 0.0)];
+        assert_eq!(interpolate(&table, 1.0), Some(10.0));
+        assert_eq!(interpolate(&table, 1.5), Some(15.0));
+        assert_eq!(interpolate(&table, 2.0), Some(20.0));
+        assert_eq!(interpolate(&table, 3.5), Some(5.0));
+        assert_eq!(interpolate(&table, 4.0), Some(0.0));
+        assert_eq!(interpolate(&table, 0.5), None);
+        assert_eq!(interpolate(&table, 4.5), None);
+        assert_eq!(interpolate(&[], 1.0), None);
+        assert_eq!(interpolate(&table, f64::NAN), None);
+    }
+}
@@ -0,0 +1,117 @@
+//!
+//! Module for spans of time.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::ops::{Add, Neg, Sub};
+
+use super::super::error::*;
+
+/// A span of time, which may be negative.
+///
+/// The internal representation is as a number of days, like the Julian Day numbers of
+/// `AstroTime`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct AstroDuration {
+    days: f64,
+}
+
+impl AstroDuration {
+    /// Create a duration from a number of days.
+    pub fn from_days(days: f64) -> AstroResult<AstroDuration> {
+        if days.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if days.is_infinite() {
+            Err(AstroAlgorithmsError::EncounteredInf)
+        } else {
+            Ok(AstroDuration { days })
+        }
+    }
+
+    /// Create a duration from a number of hours.
+    pub fn from_hours(hours: f64) -> AstroResult<AstroDuration> {
+        AstroDuration::from_days(hours / 24.0)
+    }
+
+    /// Create a duration from a number of minutes.
+    pub fn from_minutes(minutes: f64) -> AstroResult<AstroDuration> {
+        AstroDuration::from_days(minutes / 1_440.0)
+    }
+
+    /// Create a duration from a number of seconds.
+    pub fn from_seconds(seconds: f64) -> AstroResult<AstroDuration> {
+        AstroDuration::from_days(seconds / 86_400.0)
+    }
+
+    /// The length in days.
+    pub fn days(&self) -> f64 {
+        self.days
+    }
+
+    /// The length in hours.
+    pub fn hours(&self) -> f64 {
+        self.days * 24.0
+    }
+
+    /// The length in minutes.
+    pub fn minutes(&self) -> f64 {
+        self.days * 1_440.0
+    }
+
+    /// The length in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.days * 86_400.0
+    }
+}
+
+impl Add for AstroDuration {
+    type Output = AstroDuration;
+
+    fn add(self, other: AstroDuration) -> AstroDuration {
+        AstroDuration { days: self.days + other.days }
+    }
+}
+
+impl Sub for AstroDuration {
+    type Output = AstroDuration;
+
+    fn sub(self, other: AstroDuration) -> AstroDuration {
+        AstroDuration { days: self.days - other.days }
+    }
+}
+
+impl Neg for AstroDuration {
+    type Output = AstroDuration;
+
+    fn neg(self) -> AstroDuration {
+        AstroDuration { days: -self.days }
+    }
+}
+
+#[cfg(test)]
+mod duration_tests {
+    use super::*;
+    use std::f64;
+
+    #[test]
+    fn test_astro_duration() {
+        let hour = AstroDuration::from_hours(1.0).unwrap();
+        assert_eq!(hour, AstroDuration::from_minutes(60.0).unwrap());
+        assert_eq!(hour.seconds(), 3_600.0);
+        assert_eq!(hour.days(), 1.0 / 24.0);
+
+        let day = AstroDuration::from_days(1.0).unwrap();
+        assert_eq!((day - hour).hours(), 23.0);
+        assert_eq!((hour + hour).minutes(), 120.0);
+        assert!(-hour < hour);
+
+        assert_eq!(AstroDuration::from_seconds(f64::NAN).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+        assert_eq!(AstroDuration::from_days(f64::INFINITY).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredInf);
+    }
+}
@@ -0,0 +1,159 @@
+//!
+//! Module for the standard epochs and other equinoxes coordinates are referred to.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::fmt;
+use std::str::FromStr;
+
+use super::super::error::*;
+use super::{AstroTime, B1900, B1950, J2000};
+
+/// An epoch of the equator, ecliptic, and equinox that coordinates and orbital elements are
+/// referred to, either one of the standard epochs or a Julian or Besselian epoch of any year.
+///
+/// It is parsed from text such as "J2000", "B1950.0", or "equinox 2024.5". A year without a
+/// prefix is a Julian epoch, as they have been used since 1984.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::{Epoch, J2000};
+/// let epoch: Epoch = "equinox 2024.5".parse().unwrap();
+/// assert_eq!(epoch, Epoch::Julian(2024.5));
+/// assert_eq!(epoch.to_string(), "J2024.5");
+///
+/// let days = epoch.time().unwrap().julian_day_number() - J2000.julian_day_number();
+/// assert!((days - 24.5 * 365.25).abs() < 1.0e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Epoch {
+    /// The standard epoch J2000.0.
+    J2000,
+    /// The Besselian epoch B1900.0.
+    B1900,
+    /// The Besselian epoch B1950.0.
+    B1950,
+    /// A Julian epoch, such as 2024.5 for J2024.5.
+    Julian(f64),
+    /// A Besselian epoch, such as 1875.0 for B1875.0.
+    Besselian(f64),
+}
+
+impl Epoch {
+    /// The time of the epoch, in dynamical time.
+    ///
+    /// The standard epochs are the constants `J2000`, `B1900`, and `B1950`. Returns an error for a
+    /// year that is NaN, infinite, or before Julian Day 0.
+    pub fn time(&self) -> AstroResult<AstroTime> {
+        match *self {
+            Epoch::J2000 => Ok(J2000),
+            Epoch::B1900 => Ok(B1900),
+            Epoch::B1950 => Ok(B1950),
+            Epoch::Julian(year) => AstroTime::from_julian_epoch(year),
+            Epoch::Besselian(year) => AstroTime::from_besselian_epoch(year),
+        }
+    }
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (prefix, year) = match *self {
+            Epoch::J2000 => ('J', 2000.0),
+            Epoch::B1900 => ('B', 1900.0),
+            Epoch::B1950 => ('B', 1950.0),
+            Epoch::Julian(year) => ('J', year),
+            Epoch::Besselian(year) => ('B', year),
+        };
+
+        if year.fract() == 0.0 {
+            write!(f, "{}{:.1}", prefix, year)
+        } else {
+            write!(f, "{}{}", prefix, year)
+        }
+    }
+}
+
+impl FromStr for Epoch {
+    type Err = AstroAlgorithmsError;
+
+    /// Parse an epoch such as "J2000", "B1950.0", or "equinox 2024.5".
+    fn from_str(text: &str) -> AstroResult<Epoch> {
+        let error = || AstroAlgorithmsError::InvalidArgument(format!("Invalid epoch '{}'", text));
+
+        let mut trimmed = text.trim();
+        if let Some(prefix) = trimmed.get(..7) {
+            if prefix.eq_ignore_ascii_case("equinox") {
+                trimmed = trimmed[7..].trim_start();
+            }
+        }
+
+        let (besselian, year) = match trimmed.chars().next() {
+            Some('J') | Some('j') => (false, &trimmed[1..]),
+            Some('B') | Some('b') => (true, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+        let year: f64 = year.parse().map_err(|_| error())?;
+        if !year.is_finite() {
+            return Err(error());
+        }
+
+        Ok(if besselian && year == 1900.0 {
+            Epoch::B1900
+        } else if besselian && year == 1950.0 {
+            Epoch::B1950
+        } else if besselian {
+            Epoch::Besselian(year)
+        } else if year == 2000.0 {
+            Epoch::J2000
+        } else {
+            Epoch::Julian(year)
+        })
+    }
+}
+
+#[cfg(test)]
+mod epoch_tests {
+    use super::*;
+
+    #[test]
+    fn test_time() {
+        assert!(Epoch::J2000.time().unwrap() == J2000);
+        assert!(Epoch::B1950.time().unwrap() == B1950);
+        assert!(Epoch::B1900.time().unwrap() == B1900);
+
+        let j2050 = Epoch::Julian(2050.0).time().unwrap();
+        assert!((j2050.julian_day_number() - 2_469_807.5).abs() < 1.0e-6);
+        let b1875 = Epoch::Besselian(1875.0).time().unwrap();
+        assert!((b1875.julian_day_number() - 2_405_889.258_55).abs() < 1.0e-5);
+
+        assert!(Epoch::Julian(f64::NAN).time().is_err());
+        assert!(Epoch::Besselian(-10_000.0).time().is_err());
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("J2000".parse::<Epoch>().unwrap(), Epoch::J2000);
+        assert_eq!(" j2000.0 ".parse::<Epoch>().unwrap(), Epoch::J2000);
+        assert_eq!("B1950.0".parse::<Epoch>().unwrap(), Epoch::B1950);
+        assert_eq!("Equinox B1900".parse::<Epoch>().unwrap(), Epoch::B1900);
+        assert_eq!("equinox 2024.5".parse::<Epoch>().unwrap(), Epoch::Julian(2024.5));
+        assert_eq!("B1875".parse::<Epoch>().unwrap(), Epoch::Besselian(1875.0));
+
+        for text in &["", "equinox", "J", "X2000", "J2000x", "Jinf", "NaN", "équinoxe 2000"] {
+            assert!(text.parse::<Epoch>().is_err());
+        }
+
+        assert_eq!(Epoch::J2000.to_string(), "J2000.0");
+        assert_eq!(Epoch::B1950.to_string(), "B1950.0");
+        assert_eq!(Epoch::Julian(2024.5).to_string(), "J2024.5");
+        assert_eq!(Epoch::Besselian(1875.0).to_string(), "B1875.0");
+        for epoch in &[Epoch::B1900, Epoch::Julian(1991.25), Epoch::Besselian(1855.5)] {
+            assert_eq!(epoch.to_string().parse::<Epoch>().unwrap(), *epoch);
+        }
+    }
+}
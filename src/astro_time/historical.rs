@@ -0,0 +1,212 @@
+//!
+//! Module for dates in the calendar in use at the time, as in historical records.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The Gregorian calendar was introduced in 1582, with Thursday 4 October in the Julian calendar
+//! followed by Friday 15 October. Other countries adopted it later, and dropped more days.
+//!
+use super::super::error::*;
+use super::{AstroTime, Builder};
+
+/// The adoption of the Gregorian calendar in place of the Julian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarReform {
+    year: i32,
+    month: i32,
+    day: i32,
+}
+
+impl CalendarReform {
+    /// The reform of Pope Gregory XIII, adopted by Italy, Spain, Portugal and Poland in 1582.
+    pub const PAPAL: CalendarReform = CalendarReform {
+        year: 1582,
+        month: 10,
+        day: 15,
+    };
+
+    /// Great Britain and its colonies, 1752.
+    pub const GREAT_BRITAIN: CalendarReform = CalendarReform {
+        year: 1752,
+        month: 9,
+        day: 14,
+    };
+
+    /// Russia, 1918.
+    pub const RUSSIA: CalendarReform = CalendarReform {
+        year: 1918,
+        month: 2,
+        day: 14,
+    };
+
+    /// Greece, 1923.
+    pub const GREECE: CalendarReform = CalendarReform {
+        year: 1923,
+        month: 3,
+        day: 1,
+    };
+
+    /// Create a reform from the first date of the Gregorian calendar, in that calendar.
+    pub fn new(year: i32, month: i32, day: i32) -> AstroResult<CalendarReform> {
+        // Check the date.
+        Builder::from_gregorian_utc(year, month, day, 0, 0, 0).build()?;
+
+        Ok(CalendarReform { year, month, day })
+    }
+
+    /// The first date of the Gregorian calendar as year, month, and day.
+    pub fn first_gregorian_date(&self) -> (i32, i32, i32) {
+        (self.year, self.month, self.day)
+    }
+
+    // The Julian Day number of the first day of the Gregorian calendar.
+    fn julian_day_number(&self) -> f64 {
+        Builder::from_gregorian_utc(self.year, self.month, self.day, 0, 0, 0)
+            .build()
+            .map(|time| time.julian_day_number())
+            .unwrap()
+    }
+}
+
+impl Default for CalendarReform {
+    /// The papal reform of 1582.
+    fn default() -> CalendarReform {
+        CalendarReform::PAPAL
+    }
+}
+
+/// Builder for times given as a date in the calendar in use at the time, in the UTC time zone.
+///
+/// Dates before the reform are in the Julian calendar, and from the reform on in the Gregorian
+/// calendar. The days dropped by the reform are an error.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::{Builder, CalendarReform, HistoricalDate};
+/// // Isaac Newton was born on Christmas Day 1642 in England, which was still using the Julian
+/// // calendar.
+/// let newton = HistoricalDate::new(1642, 12, 25)
+///     .reform(CalendarReform::GREAT_BRITAIN)
+///     .build()
+///     .unwrap();
+/// let gregorian = Builder::from_gregorian_utc(1643, 1, 4, 0, 0, 0).build().unwrap();
+/// assert!(newton == gregorian);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoricalDate {
+    year: i32,
+    month: i32,
+    day: i32,
+    hour: i32,
+    minute: i32,
+    second: i32,
+    reform: CalendarReform,
+}
+
+impl HistoricalDate {
+    /// Start building from a date, at midnight and with the papal reform.
+    pub fn new(year: i32, month: i32, day: i32) -> HistoricalDate {
+        HistoricalDate {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            reform: CalendarReform::default(),
+        }
+    }
+
+    /// Set the time of day.
+    pub fn time(self, hour: i32, minute: i32, second: i32) -> HistoricalDate {
+        HistoricalDate {
+            hour,
+            minute,
+            second,
+            ..self
+        }
+    }
+
+    /// Set the reform in use where the date was recorded.
+    pub fn reform(self, reform: CalendarReform) -> HistoricalDate {
+        HistoricalDate { reform, ..self }
+    }
+
+    /// Finish building and get the result in Universal Time.
+    pub fn build(self) -> AstroResult<AstroTime> {
+        let (y, m, d) = (self.year, self.month, self.day);
+        let (h, mi, s) = (self.hour, self.minute, self.second);
+        let reform_jd = self.reform.julian_day_number();
+
+        // The same date is later in the Julian calendar, so at most one of these is true.
+        let julian = Builder::from_julian_utc(y, m, d, 0, 0, 0).build();
+        if julian.as_ref().map(|t| t.julian_day_number() < reform_jd).unwrap_or(true) {
+            return Builder::from_julian_utc(y, m, d, h, mi, s).build();
+        }
+
+        let gregorian = Builder::from_gregorian_utc(y, m, d, h, mi, s).build()?;
+        if gregorian.julian_day_number() >= reform_jd {
+            Ok(gregorian)
+        } else {
+            Err(AstroAlgorithmsError::InvalidArgument(format!("{}-{:02}-{:02} was dropped by the \
+                                                               calendar reform",
+                                                              y,
+                                                              m,
+                                                              d)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod historical_tests {
+    use super::*;
+
+    #[test]
+    fn test_papal_reform() {
+        // Chapter 7, Astronomical Algorithms 2nd ed.
+        let last_julian = HistoricalDate::new(1582, 10, 4).build().unwrap();
+        let first_gregorian = HistoricalDate::new(1582, 10, 15).build().unwrap();
+        assert_eq!(last_julian.julian_day_number(), 2_299_159.5);
+        assert_eq!(first_gregorian.julian_day_number(), 2_299_160.5);
+        assert!(HistoricalDate::new(1582, 10, 10).build().is_err());
+
+        // Example 7.b, 333 January 27 at noon.
+        let time = HistoricalDate::new(333, 1, 27).time(12, 0, 0).build().unwrap();
+        assert_eq!(time.julian_day_number(), 1_842_713.0);
+
+        // Example 7.a, Sputnik.
+        let sputnik = HistoricalDate::new(1957, 10, 4).time(19, 26, 24).build().unwrap();
+        assert!((sputnik.julian_day_number() - 2_436_116.31).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn test_other_reforms() {
+        let britain = |y, m, d| {
+            HistoricalDate::new(y, m, d).reform(CalendarReform::GREAT_BRITAIN).build()
+        };
+        let last_julian = britain(1752, 9, 2).unwrap();
+        let first_gregorian = britain(1752, 9, 14).unwrap();
+        assert_eq!(first_gregorian.julian_day_number() - last_julian.julian_day_number(),
+                   1.0);
+        assert!(britain(1752, 9, 3).is_err());
+
+        // The October revolution was on 25 October 1917 in Russia, 7 November in the West.
+        let revolution = HistoricalDate::new(1917, 10, 25)
+            .reform(CalendarReform::RUSSIA)
+            .build()
+            .unwrap();
+        let west = HistoricalDate::new(1917, 11, 7).build().unwrap();
+        assert!(revolution == west);
+
+        assert_eq!(CalendarReform::new(1700, 3, 1).unwrap().first_gregorian_date(),
+                   (1700, 3, 1));
+        assert!(CalendarReform::new(1700, 2, 30).is_err());
+        assert!(HistoricalDate::new(1700, 2, 29).reform(CalendarReform::GREECE).build().is_ok());
+        assert!(HistoricalDate::new(1700, 2, 29).build().is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//!
+//! Module for parsing times in the ISO 8601 format.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The extended format is supported, a Gregorian calendar date optionally followed by a time of
+//! day and a zone, as in `2017-04-10`, `2017-04-10T19:21Z`, or `2017-04-10T13:21:00.25-06:00`.
+//! Times without a zone are taken to be in UTC.
+//!
+use std::str::FromStr;
+
+use super::super::error::*;
+use super::{AstroDuration, AstroTime, Builder};
+
+impl Builder {
+    /// Create from a date and time in the ISO 8601 extended format.
+    ///
+    /// The date and time may be separated by a `T` or a space, the seconds may have a decimal
+    /// fraction, and the zone is `Z` or an offset from UTC such as `+02:00`, `-0700`, or `+05`. A
+    /// time without a zone is in UTC, and a date without a time is at midnight.
+    ///
+    /// It defaults to `TimeType::UT`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let time = Builder::from_iso8601("2017-04-10T13:21:00-06:00").build().unwrap();
+    /// let utc = Builder::from_gregorian_utc(2017, 4, 10, 19, 21, 0).build().unwrap();
+    /// assert!(time == utc);
+    /// ```
+    pub fn from_iso8601(text: &str) -> Builder {
+        Builder::new(parse(text))
+    }
+}
+
+impl FromStr for AstroTime {
+    type Err = AstroAlgorithmsError;
+
+    /// Parse a time in the ISO 8601 extended format, see `Builder::from_iso8601`.
+    fn from_str(text: &str) -> AstroResult<AstroTime> {
+        parse(text)
+    }
+}
+
+fn parse(text: &str) -> AstroResult<AstroTime> {
+    let error = || {
+        AstroAlgorithmsError::InvalidArgument(format!("Invalid ISO 8601 time '{}'", text))
+    };
+
+    let trimmed = text.trim();
+    let (date, time) = match trimmed.find(&['T', 't', ' '][..]) {
+        Some(i) => (&trimmed[..i], Some(&trimmed[i + 1..])),
+        None => (trimmed, None),
+    };
+
+    let fields: Vec<&str> = date.split('-').collect();
+    if fields.len() != 3 || fields[0].len() < 4 {
+        return Err(error());
+    }
+    let year = number(fields[0], fields[0].len()).ok_or_else(error)?;
+    let month = number(fields[1], 2).ok_or_else(error)?;
+    let day = number(fields[2], 2).ok_or_else(error)?;
+
+    let (hour, minute, second, fraction, zone_offset) = match time {
+        Some(time) => parse_time(time).ok_or_else(error)?,
+        None => (0, 0, 0, 0.0, 0.0),
+    };
+
+    let zone_offset = AstroDuration::from_minutes(zone_offset)?;
+    Builder::from_gregorian_in_zone(year, month, day, hour, minute, second, zone_offset)
+        .build()?
+        .add_duration(AstroDuration::from_seconds(fraction)?)
+}
+
+// The hour, minute, whole and fractional seconds, and the zone offset in minutes.
+fn parse_time(time: &str) -> Option<(i32, i32, i32, f64, f64)> {
+    let (clock, zone_offset) = if time.ends_with('Z') || time.ends_with('z') {
+        (&time[..time.len() - 1], 0.0)
+    } else {
+        match time.rfind(&['+', '-'][..]) {
+            Some(i) => (&time[..i], parse_offset(&time[i..])?),
+            None => (time, 0.0),
+        }
+    };
+
+    let fields: Vec<&str> = clock.split(':').collect();
+    if fields.len() < 2 || fields.len() > 3 {
+        return None;
+    }
+    let hour = number(fields[0], 2)?;
+    let minute = number(fields[1], 2)?;
+
+    let (second, fraction) = match fields.get(2) {
+        Some(seconds) => {
+            let mut parts = seconds.splitn(2, &['.', ','][..]);
+            let whole = number(parts.next()?, 2)?;
+            let fraction = match parts.next() {
+                Some(digits) => {
+                    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                        return None;
+                    }
+                    format!("0.{}", digits).parse().ok()?
+                }
+                None => 0.0,
+            };
+            (whole, fraction)
+        }
+        None => (0, 0.0),
+    };
+
+    Some((hour, minute, second, fraction, zone_offset))
+}
+
+// An offset from UTC such as +02:00, -0700, or +05 in minutes.
+fn parse_offset(offset: &str) -> Option<f64> {
+    let sign = if offset.starts_with('-') { -1.0 } else { 1.0 };
+    let digits = offset[1..].replace(':', "");
+
+    let (hours, minutes) = match digits.len() {
+        2 => (number(&digits, 2)?, 0),
+        // Only ASCII digits are valid, and slicing anything else may split a character.
+        4 if digits.is_ascii() => (number(&digits[..2], 2)?, number(&digits[2..], 2)?),
+        _ => return None,
+    };
+    if minutes >= 60 {
+        return None;
+    }
+
+    Some(sign * f64::from(hours * 60 + minutes))
+}
+
+// Parse a field with an exact number of digits.
+fn number(field: &str, digits: usize) -> Option<i32> {
+    if field.len() == digits && field.bytes().all(|b| b.is_ascii_digit()) {
+        field.parse().ok()
+    } else {
+        None
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod iso8601_tests {
+    use super::*;
+
+    fn utc(year: i32, month: i32, day: i32, hour: i32, minute: i32, second: i32) -> AstroTime {
+        Builder::from_gregorian_utc(year, month, day, hour, minute, second).build().unwrap()
+    }
+
+    #[test]
+    fn test_from_iso8601() {
+        let expected = utc(2017, 4, 10, 19, 21, 0);
+        for text in &["2017-04-10T19:21:00Z",
+                      "2017-04-10T19:21Z",
+                      "2017-04-10 19:21:00",
+                      "2017-04-10T13:21:00-06:00",
+                      "2017-04-10T13:21:00-0600",
+                      "2017-04-10T21:21:00+02",
+                      "2017-04-11T00:51:00+05:30",
+                      " 2017-04-10t19:21:00z "] {
+            let time = Builder::from_iso8601(text).build().unwrap();
+            assert!(approx_eq(time.julian_day_number(), expected.julian_day_number(), 1.0e-9),
+                    "{}",
+                    text);
+        }
+
+        assert!(Builder::from_iso8601("2017-04-10").build().unwrap() == utc(2017, 4, 10, 0, 0, 0));
+
+        let time: AstroTime = "2017-04-10T19:21:00.5Z".parse().unwrap();
+        let expected = expected.julian_day_number() + 0.5 / 86_400.0;
+        assert!(approx_eq(time.julian_day_number(), expected, 1.0e-9));
+        let time: AstroTime = "2017-04-10T19:21:00,5Z".parse().unwrap();
+        assert!(approx_eq(time.julian_day_number(), expected, 1.0e-9));
+    }
+
+    #[test]
+    fn test_invalid() {
+        for text in &["",
+                      "2017-4-10",
+                      "17-04-10",
+                      "2017-04-10T",
+                      "2017-04-10T19",
+                      "2017-04-10T19:21:00.Z",
+                      "2017-04-10T19:21:00+1",
+                      "2017-04-10T19:21:00+01:75",
+                      "2017-04-10T19:21:00+1é1",
+                      "2017-04-10T19:21:00:00Z",
+                      "2017/04/10"] {
+            match Builder::from_iso8601(text).build() {
+                Err(AstroAlgorithmsError::InvalidArgument(_)) => {}
+                result => panic!("{} gave {:?}", text, result),
+            }
+        }
+
+        assert_eq!("2017-02-29T00:00:00Z".parse::<AstroTime>(),
+                   Err(AstroAlgorithmsError::InvalidGregorianDate(2017, 2, 29)));
+        assert_eq!("2017-04-10T25:00:00Z".parse::<AstroTime>(),
+                   Err(AstroAlgorithmsError::InvalidTime(25, 0, 0)));
+    }
+}
@@ -8,147 +8,442 @@
 //! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
 //!
 use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::option::Option;
 
+use super::coords::{equation_of_equinoxes, map_to_branch, GeoCoords};
 use super::error::*;
-
+use super::math::*;
+use super::sun;
+
+mod calendar;
+mod calendars;
+mod delta_t;
+mod duration;
+mod epoch;
+mod historical;
+mod iso8601;
+mod range;
 mod time_data;
 
+pub use self::calendar::*;
+pub use self::delta_t::*;
+pub use self::duration::*;
+pub use self::epoch::*;
+pub use self::historical::*;
+pub use self::range::*;
+
+/// The standard epoch J2000.0, JDE 2451545.0.
+pub const J2000: AstroTime = AstroTime {
+    day: 2_451_544.5,
+    fraction: 0.5,
+    time_type: TimeType::DT,
+};
+
+/// The Besselian epoch B1900.0, JDE 2415020.3135.
+pub const B1900: AstroTime = AstroTime {
+    day: 2_415_019.5,
+    fraction: 0.813_5,
+    time_type: TimeType::DT,
+};
+
+/// The Besselian epoch B1950.0, JDE 2433282.4235.
+pub const B1950: AstroTime = AstroTime {
+    day: 2_433_281.5,
+    fraction: 0.923_5,
+    time_type: TimeType::DT,
+};
+
+// The lengths in days of the Julian and Besselian (tropical) years, and the Julian Ephemeris Day
+// of B1900.0 to the precision of the definition of Besselian epochs.
+const JULIAN_YEAR: f64 = 365.25;
+const BESSELIAN_YEAR: f64 = 365.242_198_781;
+const B1900_JDE: f64 = 2_415_020.313_52;
+
+// The Julian Day number of the Unix epoch, 1970 January 1 at 0h UTC.
+const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+
+// The Julian Day number of the GPS epoch, 1980 January 6 at 0h UTC, and International Atomic Time
+// less GPS time in seconds, which has been fixed since.
+const GPS_EPOCH_JULIAN_DAY: f64 = 2_444_244.5;
+const TAI_MINUS_GPS: f64 = 19.0;
+const SECONDS_PER_WEEK: f64 = 604_800.0;
+
+// The resolution of the fraction of the day an AstroTime keeps.
+const NANOSECONDS_PER_DAY: f64 = 86_400.0e9;
+
+// Terrestrial (dynamical) time less International Atomic Time, in seconds.
+const TT_MINUS_TAI: f64 = 32.184;
+
 /// Represent different types of time.
 ///
 /// Dynamic Time is measured by atomic clocks and represents the kind of time you do physical
 /// calculations with. Universal time is determined by the position of Earth with respect to the
-/// Sun and varies by leap seconds to account for minor changes in Earth's orbit.
-#[derive(PartialEq, Eq, Debug)]
+/// Sun and varies by leap seconds to account for minor changes in Earth's orbit. International
+/// Atomic Time is behind dynamical time by exactly 32.184 seconds, and ahead of UTC by the leap
+/// seconds.
+///
+/// The time scales are linked by these differences:
+///
+/// | From | To  | Difference                                       |
+/// |------|-----|--------------------------------------------------|
+/// | UTC  | TAI | the leap seconds, exact from 1972                |
+/// | TAI  | TT  | 32.184 seconds, exact                            |
+/// | TT   | TDB | periodic terms of up to 1.7 milliseconds         |
+/// | UTC  | UT1 | DUT1, supplied by the user                       |
+/// | UTC  | TT  | delta-t before 1972, interpolated in a table     |
+///
+/// `AstroTime::to_scale` converts between any two, following the links, except that
+/// `AstroTime::to_scale_with_dut1` is needed for UT1. Delta-t is strictly the difference of TT and
+/// UT1, but it is only used before the leap seconds where UT1 and UTC aren't distinguished.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum TimeType {
     /// Universal Time, also known as UTC, Zulu, or GMT
+    ///
+    /// This is treated as Coordinated Universal Time. UT1, the time kept by the rotation of the
+    /// Earth, differs from it by less than 0.9 seconds, see `TimeType::UT1`.
     UT,
     /// Dynamic Time
     DT,
+    /// International Atomic Time
+    TAI,
+    /// Universal Time UT1, the time kept by the rotation of the Earth that sidereal time is
+    /// measured from.
+    ///
+    /// It differs from UTC by DUT1 = UT1 - UTC, which is published by the IERS in Bulletin A and
+    /// supplied to `AstroTime::as_ut1`. Converting from UT1 takes DUT1 as zero.
+    UT1,
+    /// Barycentric Dynamical Time, the time argument of the JPL ephemerides.
+    ///
+    /// It differs from dynamical time, TT, by periodic terms of at most 1.7 milliseconds.
+    TDB,
+}
+
+impl fmt::Display for TimeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            TimeType::UT => "UT",
+            TimeType::DT => "DT",
+            TimeType::TAI => "TAI",
+            TimeType::UT1 => "UT1",
+            TimeType::TDB => "TDB",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The time scales `AstroTime::to_scale` converts between.
+///
+/// Unlike `TimeType`, UTC and UT1 are kept apart, since converting between them needs DUT1.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TimeScale {
+    /// Coordinated Universal Time, kept as `TimeType::UT`.
+    UTC,
+    /// Universal Time UT1, the time kept by the rotation of the Earth.
+    UT1,
+    /// International Atomic Time
+    TAI,
+    /// Terrestrial Time, the dynamical time kept as `TimeType::DT`.
+    TT,
+    /// Barycentric Dynamical Time
+    TDB,
+}
+
+impl From<TimeType> for TimeScale {
+    fn from(time_type: TimeType) -> TimeScale {
+        match time_type {
+            TimeType::UT => TimeScale::UTC,
+            TimeType::DT => TimeScale::TT,
+            TimeType::TAI => TimeScale::TAI,
+            TimeType::UT1 => TimeScale::UT1,
+            TimeType::TDB => TimeScale::TDB,
+        }
+    }
+}
+
+impl From<TimeScale> for TimeType {
+    fn from(scale: TimeScale) -> TimeType {
+        match scale {
+            TimeScale::UTC => TimeType::UT,
+            TimeScale::TT => TimeType::DT,
+            TimeScale::TAI => TimeType::TAI,
+            TimeScale::UT1 => TimeType::UT1,
+            TimeScale::TDB => TimeType::TDB,
+        }
+    }
+}
+
+impl fmt::Display for TimeScale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            TimeScale::UTC => "UTC",
+            TimeScale::UT1 => "UT1",
+            TimeScale::TAI => "TAI",
+            TimeScale::TT => "TT",
+            TimeScale::TDB => "TDB",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Formulations for the mean sidereal time at Greenwich.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SiderealModel {
+    /// The polynomial in universal time from chapter 12 of Astronomical Algorithms, 2nd ed.
+    Meeus,
+    /// The Earth rotation angle plus the IAU 2000 precession polynomial.
+    Iau2000,
+    /// The Earth rotation angle plus the IAU 2006 precession polynomial.
+    Iau2006,
+}
+
+/// The days of the week.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Weekday {
+    /// Sunday
+    Sunday,
+    /// Monday
+    Monday,
+    /// Tuesday
+    Tuesday,
+    /// Wednesday
+    Wednesday,
+    /// Thursday
+    Thursday,
+    /// Friday
+    Friday,
+    /// Saturday
+    Saturday,
 }
 
 /// Builder for AstroTime
 #[derive(Debug)]
 pub struct Builder {
     target: AstroResult<AstroTime>,
+    // A leap second, 23:59:60, is kept as the second before until it is built, so it is a second
+    // later in whatever time type it was given in.
+    leap_second: bool,
 }
 
 impl Builder {
+    fn new(target: AstroResult<AstroTime>) -> Builder {
+        Builder {
+            target,
+            leap_second: false,
+        }
+    }
+
     /// Create an AstroTime from a Julian Day number.
     ///
     /// It defaults to `TimeType::UT`.
     pub fn from_julian_date(raw: f64) -> Builder {
-        if raw >= 0.0 {
-            Builder {
-                target: Ok(AstroTime {
-                    julian_day: raw,
-                    time_type: TimeType::UT,
-                }),
-            }
+        Builder::from_julian_date_parts(raw, 0.0)
+    }
+
+    /// Create an AstroTime from a Julian Day number given as the sum of two parts, such as the
+    /// Julian Day number at midnight and the fraction of the day since.
+    ///
+    /// A single Julian Day number only resolves about 20 microseconds near the present, but the
+    /// time is kept in two parts like this, as in the IAU SOFA library, to the nearest nanosecond.
+    /// It defaults to `TimeType::UT`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let noon = Builder::from_julian_date_parts(2_451_544.5, 0.5).build().unwrap();
+    /// let later = Builder::from_julian_date_parts(2_451_545.0, 1.0e-10).build().unwrap();
+    /// assert_eq!(later.julian_day_parts().0, 2_451_544.5);
+    /// let seconds = later.duration_since(&noon).unwrap().seconds();
+    /// assert!((seconds - 8.64e-6).abs() < 1.0e-9);
+    /// ```
+    pub fn from_julian_date_parts(day: f64, fraction: f64) -> Builder {
+        let jd = day + fraction;
+        if jd.is_nan() {
+            Builder::new(Err(AstroAlgorithmsError::EncounteredNaN))
+        } else if jd.is_infinite() {
+            Builder::new(Err(AstroAlgorithmsError::EncounteredInf))
+        } else if jd >= 0.0 {
+            Builder::new(Ok(AstroTime::from_parts(day, fraction, TimeType::UT)))
         } else {
-            Builder {
-                target: Err(AstroAlgorithmsError::RangeError(DateRangeError::DateUnderflow(raw,
-                                                                                           0.0))),
-            }
+            Builder::new(Err(AstroAlgorithmsError::RangeError(DateRangeError::DateUnderflow(jd,
+                                                                                        0.0))))
         }
     }
 
     /// Create from a date and time in the Gregorian calendar assuming it is in the UTC time zone.
     ///
-    /// It defaults to `TimeType::UT`.
-    pub fn from_gregorian_utc(mut year: i32,
-                              mut month: i32,
+    /// It defaults to `TimeType::UT`. A leap second, 23:59:60 at the end of a day one was added
+    /// to, isn't counted in universal time, so it is created in `TimeType::TAI` instead, see
+    /// `is_leap_second`. Marked as another time type, such as with `dynamical_time`, it is one
+    /// second after 23:59:59 in that time type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let leap = Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 60).build().unwrap();
+    /// let tai = Builder::from_gregorian_utc(2017, 1, 1, 0, 0, 36).atomic_time().build().unwrap();
+    /// assert!(leap == tai);
+    /// ```
+    pub fn from_gregorian_utc(year: i32,
+                              month: i32,
                               day: i32,
                               hour: i32,
                               minute: i32,
                               second: i32)
                               -> Builder {
-        // From chapter 7, pages 60-61 of Astronomical Algorithms, 2nd Edition by Jean Meeus.
-        use std::f64;
-
-        if is_valid_gregorian(year, month, day) && is_valid_time(hour, minute, second) {
-
-            let decimal_day = day as f64 + day_fraction(hour, minute, second);
+        if is_leap_second(year, month, day, hour, minute, second) {
+            return Builder {
+                leap_second: true,
+                ..Builder::from_gregorian_utc(year, month, day, hour, minute, 59)
+            };
+        }
 
-            if month < 3 {
-                year -= 1;
-                month += 12;
-            }
+        Builder::from_calendar(&Gregorian, year, month, day, hour, minute, second)
+    }
 
-            #[allow(non_snake_case)]
-            let A = f64::floor(year as f64 / 100.0);
-            #[allow(non_snake_case)]
-            let B = 2.0 - A + f64::floor(A / 4.0);
-
-            let jd = f64::floor(365.25 * (year + 4716) as f64) +
-                     f64::floor(30.6001 * (month + 1) as f64) +
-                     decimal_day + B - 1524.5;
-
-            if jd >= 0.0 {
-                Builder {
-                    target: Ok(AstroTime {
-                        julian_day: jd,
-                        time_type: TimeType::UT,
-                    }),
-                }
-            } else {
-                Builder {
-                    target:
-                        Err(AstroAlgorithmsError::RangeError(DateRangeError::DateUnderflow(jd,
-                                                                                           0.0))),
-                }
-            }
-        } else if !is_valid_gregorian(year, month, day) {
-            Builder { target: Err(AstroAlgorithmsError::InvalidGregorianDate(year, month, day)) }
+    /// Create from a year and month in the Gregorian calendar and a decimal day of the month, in
+    /// the UTC time zone, without checking the day is in the month.
+    ///
+    /// Several algorithms in Astronomical Algorithms give dates this way, such as January 0.5 for
+    /// noon on the last day of the previous year, or day 32 for the first day of the next month.
+    /// The day is counted on from the start of the month, so it may be zero, negative, or past the
+    /// end of the month. The month must still be from 1 to 12.
+    ///
+    /// It defaults to `TimeType::UT`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let january_0 = Builder::from_gregorian_unchecked_day(2017, 1, 0.5).build().unwrap();
+    /// let new_years_eve = Builder::from_gregorian_utc(2016, 12, 31, 12, 0, 0).build().unwrap();
+    /// assert!(january_0 == new_years_eve);
+    /// ```
+    pub fn from_gregorian_unchecked_day(year: i32, month: i32, day: f64) -> Builder {
+        if !(1..=12).contains(&month) {
+            Builder::new(Err(AstroAlgorithmsError::InvalidArgument(format!("Invalid month {}",
+                                                                           month))))
+        } else if day.is_nan() {
+            Builder::new(Err(AstroAlgorithmsError::EncounteredNaN))
+        } else if day.is_infinite() {
+            Builder::new(Err(AstroAlgorithmsError::EncounteredInf))
         } else {
-            Builder { target: Err(AstroAlgorithmsError::InvalidTime(hour, minute, second)) }
+            Builder::from_julian_date_parts(gregorian_julian_day(year, month, day.floor()),
+                                            day - day.floor())
         }
     }
 
-    /// Create from a date and time in the Gregorian calendar assuming it is in the UTC time zone.
+    /// Create from a date and time in the Julian calendar assuming it is in the UTC time zone.
     ///
     /// It defaults to `TimeType::UT`.
-    pub fn from_julian_utc(mut year: i32,
-                           mut month: i32,
+    pub fn from_julian_utc(year: i32,
+                           month: i32,
                            day: i32,
                            hour: i32,
                            minute: i32,
                            second: i32)
                            -> Builder {
-        // From chapter 7, pages 60-61 of Astronomical Algorithms, 2nd Edition by Jean Meeus.
-        use std::f64;
+        Builder::from_calendar(&Julian, year, month, day, hour, minute, second)
+    }
 
-        if is_valid_julian(year, month, day) && is_valid_time(hour, minute, second) {
+    /// Create from a date and time in the Gregorian calendar assuming it is in the UTC time zone,
+    /// with fractional seconds.
+    ///
+    /// It defaults to `TimeType::UT`, except during a leap second, see `from_gregorian_utc`.
+    pub fn from_gregorian_utc_precise(year: i32,
+                                      month: i32,
+                                      day: i32,
+                                      hour: i32,
+                                      minute: i32,
+                                      second: f64)
+                                      -> Builder {
+        if second.is_nan() {
+            return Builder::new(Err(AstroAlgorithmsError::EncounteredNaN));
+        } else if second.is_infinite() {
+            return Builder::new(Err(AstroAlgorithmsError::EncounteredInf));
+        }
 
-            let decimal_day = day as f64 + day_fraction(hour, minute, second);
+        let leap = (60.0..61.0).contains(&second) &&
+                   is_leap_second(year, month, day, hour, minute, 60);
+        if !((0.0..60.0).contains(&second) || leap) {
+            return Builder::new(Err(AstroAlgorithmsError::InvalidTime(hour,
+                                                                      minute,
+                                                                      second.floor() as i32)));
+        }
 
-            if month < 3 {
-                year -= 1;
-                month += 12;
-            }
+        let whole = second.floor();
+        let fraction = AstroDuration::from_seconds(second - whole).unwrap();
+        let builder = Builder::from_gregorian_utc(year, month, day, hour, minute, whole as i32);
+        Builder { target: builder.target.and_then(|atime| atime.add_duration(fraction)), ..builder }
+    }
 
-            let jd = f64::floor(365.25 * (year + 4716) as f64) +
-                     f64::floor(30.6001 * (month + 1) as f64) + decimal_day -
-                     1524.5;
+    /// Create from a date and time in the Gregorian calendar in a time zone that is
+    /// `offset_minutes` ahead of UTC, e.g. -420 for Mountain Standard Time.
+    ///
+    /// The time is converted to UTC. Offsets outside the range of the world's time zones, from 12
+    /// hours behind to 14 hours ahead of UTC, are an error.
+    ///
+    /// It defaults to `TimeType::UT`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// // Mountain daylight time, 6 hours behind UTC.
+    /// let local = Builder::from_gregorian_with_offset(2017, 8, 21, 11, 25, 0, -360)
+    ///     .build()
+    ///     .unwrap();
+    /// let utc = Builder::from_gregorian_utc(2017, 8, 21, 17, 25, 0).build().unwrap();
+    /// assert!(local == utc);
+    /// ```
+    pub fn from_gregorian_with_offset(year: i32,
+                                      month: i32,
+                                      day: i32,
+                                      hour: i32,
+                                      minute: i32,
+                                      second: i32,
+                                      offset_minutes: i32)
+                                      -> Builder {
+        if !(-12 * 60..=14 * 60).contains(&offset_minutes) {
+            let message = format!("Invalid UTC offset of {} minutes", offset_minutes);
+            return Builder::new(Err(AstroAlgorithmsError::InvalidArgument(message)));
+        }
 
-            if jd >= 0.0 {
-                Builder {
-                    target: Ok(AstroTime {
-                        julian_day: jd,
-                        time_type: TimeType::UT,
-                    }),
-                }
-            } else {
-                Builder {
-                    target:
-                        Err(AstroAlgorithmsError::RangeError(DateRangeError::DateUnderflow(jd,
-                                                                                           0.0))),
-                }
+        let offset = AstroDuration::from_minutes(f64::from(offset_minutes)).unwrap();
+        Builder::from_gregorian_in_zone(year, month, day, hour, minute, second, offset)
+    }
+
+    // Create from a date and time in a zone that is `zone_offset` ahead of UTC. A leap second is
+    // at 23:59:60 UTC, which is another minute of the day in the zone, so it is found from the
+    // time in UTC.
+    fn from_gregorian_in_zone(year: i32,
+                              month: i32,
+                              day: i32,
+                              hour: i32,
+                              minute: i32,
+                              second: i32,
+                              zone_offset: AstroDuration)
+                              -> Builder {
+        if second != 60 {
+            return Builder::from_gregorian_utc(year, month, day, hour, minute, second)
+                .zone_time(zone_offset);
+        }
+
+        let utc = Builder::from_gregorian_utc(year, month, day, hour, minute, 0)
+            .zone_time(zone_offset)
+            .build()
+            .map(|atime| atime.to_gregorian_utc_precise());
+        match utc {
+            Ok((y, mo, d, h, mi, s)) if s == 0.0 && is_leap_second(y, mo, d, h, mi, 60) => {
+                Builder::from_gregorian_utc(y, mo, d, h, mi, 60)
             }
-        } else if !is_valid_julian(year, month, day) {
-            Builder { target: Err(AstroAlgorithmsError::InvalidJulianDate(year, month, day)) }
-        } else {
-            Builder { target: Err(AstroAlgorithmsError::InvalidTime(hour, minute, second)) }
+            Ok(_) => Builder::new(Err(AstroAlgorithmsError::InvalidTime(hour, minute, second))),
+            Err(err) => Builder::new(Err(err)),
         }
     }
 
@@ -160,24 +455,242 @@ impl Builder {
     /// Note that this DOES NOT DO ANY CONVERSION from UTC to dynamcial time using delta-t. It is
     /// only for specifying a dynamical time while building.
     pub fn dynamical_time(self) -> Builder {
-        match self.target {
-            Ok(mut atime) => {
-                atime.time_type = TimeType::DT;
-                Builder { target: Ok(atime) }
-            }
-            _ => self, // do nothing
+        self.with_time_type(TimeType::DT)
+    }
+
+    /// Set the Time type to `TimeType::TAI` to mark this as International Atomic Time.
+    ///
+    /// Like `dynamical_time`, this DOES NOT DO ANY CONVERSION from UTC.
+    pub fn atomic_time(self) -> Builder {
+        self.with_time_type(TimeType::TAI)
+    }
+
+    /// Set the Time type to `TimeType::UT1`.
+    ///
+    /// Like `dynamical_time`, this DOES NOT DO ANY CONVERSION from UTC.
+    pub fn ut1(self) -> Builder {
+        self.with_time_type(TimeType::UT1)
+    }
+
+    // Mark the time as given in another time type, without converting it.
+    fn with_time_type(self, time_type: TimeType) -> Builder {
+        Builder {
+            target: self.target.map(|atime| AstroTime { time_type, ..atime }),
+            ..self
         }
     }
 
+    /// Take the date and time given as the local mean time at a place rather than in the UTC time
+    /// zone.
+    ///
+    /// A leap second is only at 23:59:60 UTC, so it is an error to give one in local time.
+    pub fn local_mean_time(self, geo: GeoCoords) -> Builder {
+        let offset = -mean_time_offset(geo);
+        self.shift_wall_clock(|atime| atime.add_duration(offset))
+    }
+
+    /// Take the date and time given as the local apparent (sundial) time at a place rather than
+    /// in the UTC time zone.
+    ///
+    /// A leap second is only at 23:59:60 UTC, so it is an error to give one in local time.
+    pub fn local_apparent_time(self, geo: GeoCoords) -> Builder {
+        self.shift_wall_clock(|atime| {
+            let mean = atime.add_duration(-mean_time_offset(geo))?;
+
+            // The equation of time changes by at most half a minute a day, so once is enough.
+            let guess = mean.add_duration(-sun::equation_of_time(&mean)?)?;
+            mean.add_duration(-sun::equation_of_time(&guess)?)
+        })
+    }
+
+    /// Take the date and time given as the time in a zone that is `zone_offset` ahead of UTC, e.g.
+    /// -7 hours for Mountain Standard Time.
+    ///
+    /// A leap second at 23:59:60 UTC falls in another minute of the day in the zone, so it is an
+    /// error to give one here unless the offset is zero. Use `from_gregorian_with_offset` instead,
+    /// which finds it from the time in UTC.
+    pub fn zone_time(self, zone_offset: AstroDuration) -> Builder {
+        if zone_offset.seconds() == 0.0 {
+            return self;
+        }
+        self.shift_wall_clock(|atime| atime.add_duration(-zone_offset))
+    }
+
+    // Convert a date and time given on a local clock to UTC. The leap second was found by taking
+    // the clock as UTC, so with any offset it is the wrong second.
+    fn shift_wall_clock<F>(self, shift: F) -> Builder
+        where F: FnOnce(AstroTime) -> AstroResult<AstroTime>
+    {
+        if self.leap_second {
+            return Builder::new(Err(AstroAlgorithmsError::InvalidArgument("A leap second is \
+                                                                           only at 23:59:60 \
+                                                                           UTC"
+                .to_owned())));
+        }
+
+        Builder { target: self.target.and_then(shift), ..self }
+    }
+
     /// Finish building and get result.
     pub fn build(self) -> AstroResult<AstroTime> {
-        self.target
+        if !self.leap_second {
+            return self.target;
+        }
+
+        // Universal time doesn't count the leap second, so it is only in atomic time.
+        let second = AstroDuration::from_seconds(1.0)?;
+        self.target.and_then(|atime| match atime.time_type {
+            TimeType::UT => atime.as_tai()?.add_duration(second),
+            _ => atime.add_duration(second),
+        })
     }
 }
 
 #[cfg(test)]
 mod astro_tm_bldr_tests {
     use astro_time::*;
+    use coords::RadianAngle;
+
+    #[test]
+    fn test_leap_second() {
+        let before = Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 59).build().unwrap();
+        let after = Builder::from_gregorian_utc(2017, 1, 1, 0, 0, 0).build().unwrap();
+        let leap = Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 60).build().unwrap();
+        assert!(leap.time_type == TimeType::TAI);
+        assert!(approx_eq(leap.duration_since(&before).unwrap().seconds(), 1.0, 1.0e-6));
+        assert!(approx_eq(after.as_tai().unwrap().duration_since(&leap).unwrap().seconds(),
+                          1.0,
+                          1.0e-6));
+
+        let half = Builder::from_gregorian_utc_precise(2016, 12, 31, 23, 59, 60.5)
+            .build()
+            .unwrap();
+        assert!(approx_eq(half.duration_since(&leap).unwrap().seconds(), 0.5, 1.0e-6));
+        assert!(leap == "2016-12-31T23:59:60Z".parse::<AstroTime>().unwrap());
+
+        // Only at the end of a day with a leap second.
+        assert!(Builder::from_gregorian_utc(2016, 12, 30, 23, 59, 60).build().is_err());
+        assert!(Builder::from_gregorian_utc_precise(2016, 12, 30, 23, 59, 60.5).build().is_err());
+        assert!(Builder::from_gregorian_utc_precise(2016, 12, 31, 23, 59, 61.0).build().is_err());
+
+        // Marked as another time type, it is still a second after the second before.
+        let before_dt = Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 59)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let leap_dt = Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 60)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        assert!(leap_dt.time_type == TimeType::DT);
+        assert!(approx_eq(leap_dt.duration_since(&before_dt).unwrap().seconds(), 1.0, 1.0e-6));
+
+        let before_tai = Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 59)
+            .atomic_time()
+            .build()
+            .unwrap();
+        let leap_tai = Builder::from_gregorian_utc_precise(2016, 12, 31, 23, 59, 60.5)
+            .atomic_time()
+            .build()
+            .unwrap();
+        assert!(leap_tai.time_type == TimeType::TAI);
+        assert!(approx_eq(leap_tai.duration_since(&before_tai).unwrap().seconds(), 1.5, 1.0e-6));
+
+        // In another time zone the leap second is found from the time in UTC.
+        assert!(leap == "2017-01-01T00:59:60+01:00".parse::<AstroTime>().unwrap());
+        assert!("2016-12-31T23:59:60+01:00".parse::<AstroTime>().is_err());
+        let mountain = Builder::from_gregorian_with_offset(2016, 12, 31, 16, 59, 60, -420)
+            .build()
+            .unwrap();
+        assert!(mountain == leap);
+        assert!(Builder::from_gregorian_with_offset(2016, 12, 31, 23, 59, 60, -420)
+            .build()
+            .is_err());
+
+        let hour = AstroDuration::from_hours(1.0).unwrap();
+        let utc_leap = || Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 60);
+        assert!(utc_leap().zone_time(hour).build().is_err());
+        let zero = AstroDuration::from_hours(0.0).unwrap();
+        assert!(utc_leap().zone_time(zero).build().unwrap() == leap);
+    }
+
+    #[test]
+    fn test_precise_seconds() {
+        let time = Builder::from_gregorian_utc_precise(2017, 8, 21, 18, 25, 32.437)
+            .build()
+            .unwrap();
+        assert_eq!(time.to_gregorian_utc_precise(), (2017, 8, 21, 18, 25, 32.437));
+        assert_eq!(time.to_gregorian_utc(), (2017, 8, 21, 18, 25, 32));
+        let whole = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 32).build().unwrap();
+        assert!(approx_eq((time.julian_day_number() - whole.julian_day_number()) * 86_400.0,
+                          0.437,
+                          1.0e-4));
+
+        // Rounding to the millisecond carries into the next day.
+        let time = Builder::from_gregorian_utc_precise(2017, 12, 31, 23, 59, 59.9996)
+            .build()
+            .unwrap();
+        assert_eq!(time.to_gregorian_utc_precise(), (2018, 1, 1, 0, 0, 0.0));
+        let time = Builder::from_gregorian_utc_precise(2017, 12, 31, 23, 59, 59.999)
+            .build()
+            .unwrap();
+        assert_eq!(time.to_gregorian_utc_precise(), (2017, 12, 31, 23, 59, 59.999));
+
+        assert_eq!(Builder::from_gregorian_utc_precise(2017, 1, 1, 0, 0, 60.0).build(),
+                   Err(AstroAlgorithmsError::InvalidTime(0, 0, 60)));
+        assert!(Builder::from_gregorian_utc_precise(2017, 1, 1, 0, 0, -0.5).build().is_err());
+        assert_eq!(Builder::from_gregorian_utc_precise(2017, 1, 1, 0, 0, f64::NAN).build(),
+                   Err(AstroAlgorithmsError::EncounteredNaN));
+        assert_eq!(Builder::from_gregorian_utc_precise(2017, 2, 29, 0, 0, 1.5).build(),
+                   Err(AstroAlgorithmsError::InvalidGregorianDate(2017, 2, 29)));
+    }
+
+    #[test]
+    fn test_from_gregorian_unchecked_day() {
+        let time = Builder::from_gregorian_unchecked_day(2017, 4, 10.5).build().unwrap();
+        assert_eq!(time.to_gregorian_utc(), (2017, 4, 10, 12, 0, 0));
+
+        // January 0.0 is the same as julian_day_zero.
+        let time = Builder::from_gregorian_unchecked_day(2017, 1, 0.0).build().unwrap();
+        assert!(time == julian_day_zero(2017).unwrap());
+
+        // Days past the end of the month roll over, through leap days.
+        let time = Builder::from_gregorian_unchecked_day(2016, 2, 30.25).build().unwrap();
+        assert_eq!(time.to_gregorian_utc(), (2016, 3, 1, 6, 0, 0));
+        let time = Builder::from_gregorian_unchecked_day(2017, 12, 32.0).build().unwrap();
+        assert_eq!(time.to_gregorian_utc(), (2018, 1, 1, 0, 0, 0));
+        let time = Builder::from_gregorian_unchecked_day(2017, 3, -1.0).build().unwrap();
+        assert_eq!(time.to_gregorian_utc(), (2017, 2, 27, 0, 0, 0));
+
+        assert!(Builder::from_gregorian_unchecked_day(2017, 13, 1.0).build().is_err());
+        assert!(Builder::from_gregorian_unchecked_day(2017, 0, 1.0).build().is_err());
+        assert_eq!(Builder::from_gregorian_unchecked_day(2017, 1, f64::NAN).build(),
+                   Err(AstroAlgorithmsError::EncounteredNaN));
+        assert_eq!(Builder::from_gregorian_unchecked_day(2017, 1, f64::INFINITY).build(),
+                   Err(AstroAlgorithmsError::EncounteredInf));
+    }
+
+    #[test]
+    fn test_from_gregorian_with_offset() {
+        // Mountain daylight time.
+        let local = Builder::from_gregorian_with_offset(2017, 8, 21, 11, 25, 0, -360)
+            .build()
+            .unwrap();
+        assert_eq!(local.to_gregorian_utc(), (2017, 8, 21, 17, 25, 0));
+
+        // India, half an hour off the hour, onto the previous day in UTC.
+        let local = Builder::from_gregorian_with_offset(2017, 1, 1, 2, 0, 0, 330).build().unwrap();
+        assert_eq!(local.to_gregorian_utc(), (2016, 12, 31, 20, 30, 0));
+
+        // The extremes of the world's time zones.
+        assert!(Builder::from_gregorian_with_offset(2017, 1, 1, 0, 0, 0, -720).build().is_ok());
+        assert!(Builder::from_gregorian_with_offset(2017, 1, 1, 0, 0, 0, 840).build().is_ok());
+        assert!(Builder::from_gregorian_with_offset(2017, 1, 1, 0, 0, 0, -721).build().is_err());
+        assert!(Builder::from_gregorian_with_offset(2017, 1, 1, 0, 0, 0, 841).build().is_err());
+
+        assert_eq!(Builder::from_gregorian_with_offset(2017, 2, 29, 0, 0, 0, 60).build(),
+                   Err(AstroAlgorithmsError::InvalidGregorianDate(2017, 2, 29)));
+    }
 
     #[test]
     fn test_from_julian_date() {
@@ -352,6 +865,39 @@ mod astro_tm_bldr_tests {
         }
     }
 
+    #[test]
+    fn test_local_times() {
+        // Greenwich Mean Time and the Mountain Standard Time zone near 105 W.
+        let geo = |longitude: f64| {
+            GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                           RadianAngle::new(longitude.to_radians()).unwrap(),
+                           0.0)
+                .unwrap()
+        };
+        let mst = AstroDuration::from_hours(-7.0).unwrap();
+        let time = Builder::from_gregorian_utc(2017, 2, 11, 19, 0, 0).build().unwrap();
+
+        assert_eq!(time.to_local_mean_time(geo(0.0)).unwrap(), (2017, 2, 11, 19, 0, 0));
+        assert_eq!(time.to_local_mean_time(geo(105.0)).unwrap(), (2017, 2, 11, 12, 0, 0));
+        assert_eq!(time.to_local_mean_time(geo(-150.0)).unwrap(), (2017, 2, 12, 5, 0, 0));
+        assert_eq!(time.to_zone_time(mst).unwrap(), (2017, 2, 11, 12, 0, 0));
+
+        // A sundial is about 14 minutes behind in mid February.
+        let (_, _, _, hour, minute, _) = time.to_local_apparent_time(geo(105.0)).unwrap();
+        assert_eq!((hour, minute), (11, 45));
+
+        // And back again.
+        let noon = Builder::from_gregorian_utc(2017, 2, 11, 12, 0, 0);
+        assert!(noon.local_mean_time(geo(105.0)).build().unwrap() == time);
+        let noon = Builder::from_gregorian_utc(2017, 2, 11, 12, 0, 0);
+        assert!(noon.zone_time(mst).build().unwrap() == time);
+        let sundial = Builder::from_gregorian_utc(2017, 2, 11, 11, 45, 47)
+            .local_apparent_time(geo(105.0))
+            .build()
+            .unwrap();
+        assert!(approx_eq(sundial.julian_day_number(), time.julian_day_number(), 1.0 / 86_400.0));
+    }
+
     #[test]
     fn test_from_julian_utc() {
 
@@ -460,193 +1006,1274 @@ mod astro_tm_bldr_tests {
 
 /// Represent a time.
 ///
-/// The internal representation is as a Julian Day number, but it is only valid for dates with
-/// Julian Day number >= 0.0. Many methods check for this and will return an error if found.
-#[derive(Debug)]
+/// The internal representation is as a Julian Day number split into the Julian Day number at
+/// midnight and the fraction of the day since, which keeps the time to the nearest nanosecond. It
+/// is only valid for dates with Julian Day number >= 0.0. Many methods check for this and will
+/// return an error if found.
+#[derive(Debug, Clone, Copy)]
 pub struct AstroTime {
-    julian_day: f64,
+    // The Julian Day number at midnight, ending in .5, and the fraction of the day in [0, 1).
+    day: f64,
+    fraction: f64,
     time_type: TimeType,
 }
 
-impl PartialEq for AstroTime {
-    fn eq(&self, other: &AstroTime) -> bool {
-        self.time_type == other.time_type && self.julian_day == other.julian_day
+impl PartialEq for AstroTime {
+    fn eq(&self, other: &AstroTime) -> bool {
+        self.time_type == other.time_type && self.day == other.day &&
+        self.fraction == other.fraction
+    }
+}
+
+/// Times are only ordered if they have the same time type, see `cmp_normalized` to compare times
+/// of different types.
+impl PartialOrd for AstroTime {
+    fn partial_cmp(&self, other: &AstroTime) -> Option<Ordering> {
+        if self.time_type == other.time_type {
+            (self.day, self.fraction).partial_cmp(&(other.day, other.fraction))
+        } else {
+            None
+        }
+    }
+}
+
+/// Move a time later by a duration, see `add_duration`.
+///
+/// The result is an error if it would be before Julian Day 0.
+impl Add<AstroDuration> for AstroTime {
+    type Output = AstroResult<AstroTime>;
+
+    fn add(self, duration: AstroDuration) -> AstroResult<AstroTime> {
+        self.add_duration(duration)
+    }
+}
+
+/// Move a time earlier by a duration, see `add_duration`.
+///
+/// The result is an error if it would be before Julian Day 0.
+impl Sub<AstroDuration> for AstroTime {
+    type Output = AstroResult<AstroTime>;
+
+    fn sub(self, duration: AstroDuration) -> AstroResult<AstroTime> {
+        self.add_duration(-duration)
+    }
+}
+
+/// The duration between two times, see `duration_since`.
+///
+/// If the time types are different, the time subtracted is converted to the type of the other.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::Builder;
+/// let sunset = Builder::from_gregorian_utc(2017, 8, 21, 2, 10, 0).build().unwrap();
+/// let moonrise = Builder::from_gregorian_utc(2017, 8, 21, 4, 40, 0).build().unwrap();
+/// let interval = (moonrise - sunset).unwrap();
+/// assert!((interval.minutes() - 150.0).abs() < 1.0e-6);
+/// ```
+impl Sub for AstroTime {
+    type Output = AstroResult<AstroDuration>;
+
+    fn sub(self, earlier: AstroTime) -> AstroResult<AstroDuration> {
+        self.duration_since(&earlier)
+    }
+}
+
+/// Convert a system time, which is taken to be in UTC.
+///
+/// Returns an error for a time before Julian Day 0, like the `Builder`.
+impl TryFrom<SystemTime> for AstroTime {
+    type Error = AstroAlgorithmsError;
+
+    fn try_from(time: SystemTime) -> AstroResult<AstroTime> {
+        let (seconds, nanos) = match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => (after.as_secs() as f64, f64::from(after.subsec_nanos())),
+            Err(before) => {
+                let before = before.duration();
+                (-(before.as_secs() as f64), -f64::from(before.subsec_nanos()))
+            }
+        };
+
+        // Keep the whole days apart from the seconds to keep the nanoseconds.
+        let days = (seconds / 86_400.0).floor();
+        let fraction = (seconds - days * 86_400.0 + nanos * 1.0e-9) / 86_400.0;
+
+        Builder::from_julian_date_parts(UNIX_EPOCH_JULIAN_DAY + days, fraction).build()
+    }
+}
+
+impl AstroTime {
+    /// Create a time from a Unix timestamp, the number of seconds since 1970 January 1 at 0h UTC.
+    ///
+    /// Unix time doesn't count leap seconds, like the Universal Time of this crate.
+    pub fn from_unix_timestamp(seconds: i64) -> AstroResult<AstroTime> {
+        Builder::from_julian_date_parts(UNIX_EPOCH_JULIAN_DAY + seconds.div_euclid(86_400) as f64,
+                                        seconds.rem_euclid(86_400) as f64 / 86_400.0)
+            .build()
+    }
+
+    /// Get the Unix timestamp, rounded to the nearest second.
+    ///
+    /// The time is converted to universal time first. Returns an error for a time too far from
+    /// 1970 for the seconds to fit in an `i64`.
+    pub fn to_unix_timestamp(&self) -> AstroResult<i64> {
+        let utc = self.as_utc()?;
+        let days = utc.day - UNIX_EPOCH_JULIAN_DAY;
+        let seconds = days * 86_400.0 + (utc.fraction * 86_400.0).round();
+
+        // The limits are 2^63 seconds either side of the epoch, exactly as an f64.
+        let limit = i64::MAX as f64;
+        let limit_jd = |sign: f64| UNIX_EPOCH_JULIAN_DAY + sign * limit / 86_400.0;
+        let jd = utc.julian_day_number();
+        if seconds >= limit {
+            Err(AstroAlgorithmsError::RangeError(DateRangeError::DateOverflow(jd, limit_jd(1.0))))
+        } else if seconds < -limit {
+            Err(AstroAlgorithmsError::RangeError(DateRangeError::DateUnderflow(jd, limit_jd(-1.0))))
+        } else {
+            Ok(seconds as i64)
+        }
+    }
+
+    /// Convert to a system time.
+    ///
+    /// The time is converted to universal time first.
+    pub fn to_system_time(&self) -> AstroResult<SystemTime> {
+        let seconds = self.as_utc()?.days_since_parts(UNIX_EPOCH_JULIAN_DAY, 0.0) * 86_400.0;
+
+        let time = Duration::try_from_secs_f64(seconds.abs()).ok().and_then(|offset| {
+            if seconds >= 0.0 {
+                UNIX_EPOCH.checked_add(offset)
+            } else {
+                UNIX_EPOCH.checked_sub(offset)
+            }
+        });
+        time.ok_or_else(|| {
+            AstroAlgorithmsError::InvalidArgument("The time can't be represented as a system time"
+                .to_owned())
+        })
+    }
+
+    /// Create a time from GPS time, the number of seconds since 1980 January 6 at 0h UTC.
+    ///
+    /// GPS time counts the leap seconds, and is always 19 seconds behind International Atomic
+    /// Time, so the time is created as `TimeType::TAI`. Use `as_utc` to apply the leap seconds.
+    pub fn from_gps_seconds(seconds: f64) -> AstroResult<AstroTime> {
+        let seconds = seconds + TAI_MINUS_GPS;
+        let days = (seconds / 86_400.0).floor();
+
+        Builder::from_julian_date_parts(GPS_EPOCH_JULIAN_DAY + days,
+                                        (seconds - days * 86_400.0) / 86_400.0)
+            .atomic_time()
+            .build()
+    }
+
+    /// Create a time from a GPS week number, counted from the GPS epoch without rolling over,
+    /// and the seconds into the week, which starts at midnight between Saturday and Sunday.
+    ///
+    /// See `from_gps_seconds`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::AstroTime;
+    /// let time = AstroTime::from_gps_week(1963, 152_750.0).unwrap();
+    /// let utc = time.as_utc().unwrap();
+    /// assert_eq!(utc.to_gregorian_utc(), (2017, 8, 21, 18, 25, 32));
+    /// ```
+    pub fn from_gps_week(week: i32, seconds: f64) -> AstroResult<AstroTime> {
+        if week < 0 || !(0.0..SECONDS_PER_WEEK).contains(&seconds) {
+            return Err(AstroAlgorithmsError::InvalidArgument(format!("Invalid GPS week {} and \
+                                                                      seconds {}",
+                                                                     week,
+                                                                     seconds)));
+        }
+
+        AstroTime::from_gps_seconds(f64::from(week) * SECONDS_PER_WEEK + seconds)
+    }
+
+    /// Get the GPS time, the number of seconds since 1980 January 6 at 0h UTC.
+    ///
+    /// The time is converted to International Atomic Time first, which is an error for UTC
+    /// before 1972, when the table of leap seconds starts.
+    pub fn to_gps_seconds(&self) -> AstroResult<f64> {
+        Ok(self.as_tai()?.days_since_parts(GPS_EPOCH_JULIAN_DAY, 0.0) * 86_400.0 - TAI_MINUS_GPS)
+    }
+
+    /// Get the GPS week number and the seconds into the week, see `to_gps_seconds`.
+    ///
+    /// Returns an error for times before the GPS epoch.
+    pub fn to_gps_week(&self) -> AstroResult<(i32, f64)> {
+        let seconds = self.to_gps_seconds()?;
+        if seconds < 0.0 {
+            return Err(AstroAlgorithmsError::InvalidArgument("The time is before the GPS epoch"
+                .to_owned()));
+        }
+
+        let week = (seconds / SECONDS_PER_WEEK).floor();
+
+        Ok((week as i32, seconds - week * SECONDS_PER_WEEK))
+    }
+
+    /// Create a time in dynamical time from a Julian epoch such as 2024.5 for J2024.5.
+    ///
+    /// A Julian year is exactly 365.25 days, counted from J2000.0.
+    pub fn from_julian_epoch(epoch: f64) -> AstroResult<AstroTime> {
+        Builder::from_julian_date_parts(J2000.day, J2000.fraction + (epoch - 2000.0) * JULIAN_YEAR)
+            .dynamical_time()
+            .build()
+    }
+
+    /// Create a time in dynamical time from a Besselian epoch such as 1950.0 for B1950.0.
+    ///
+    /// A Besselian year is a tropical year, counted from B1900.0 as defined by Lieske (1979).
+    pub fn from_besselian_epoch(epoch: f64) -> AstroResult<AstroTime> {
+        Builder::from_julian_date(B1900_JDE + (epoch - 1900.0) * BESSELIAN_YEAR)
+            .dynamical_time()
+            .build()
+    }
+
+    /// Get the Julian epoch, such as 2000.0 for J2000.0.
+    ///
+    /// The time is converted to dynamical time first.
+    pub fn to_julian_epoch(&self) -> AstroResult<f64> {
+        Ok(2000.0 + self.as_dt()?.days_since_parts(J2000.day, J2000.fraction) / JULIAN_YEAR)
+    }
+
+    /// Get the Besselian epoch, such as 1950.0 for B1950.0.
+    ///
+    /// The time is converted to dynamical time first.
+    pub fn to_besselian_epoch(&self) -> AstroResult<f64> {
+        Ok(1900.0 + self.as_dt()?.days_since_parts(B1900_JDE, 0.0) / BESSELIAN_YEAR)
+    }
+
+    /// Get the time scale this time is kept in.
+    pub fn time_type(&self) -> TimeType {
+        self.time_type
+    }
+
+    /// Get the Julian Day number as a floating point value.
+    ///
+    /// This is only precise to about 20 microseconds near the present, use `julian_day_parts` for
+    /// the full precision.
+    pub fn julian_day_number(&self) -> f64 {
+        self.day + self.fraction
+    }
+
+    /// Get the Julian Ephemeris Day, the Julian Day number in dynamical time, which most of the
+    /// algorithms of Astronomical Algorithms take as their argument.
+    ///
+    /// The time is converted to dynamical time first, see `as_dt`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let time = Builder::from_gregorian_utc(2017, 1, 1, 0, 0, 0).build().unwrap();
+    /// let jde = time.julian_ephemeris_day().unwrap();
+    /// assert!((jde - time.julian_day_number() - 69.184 / 86_400.0).abs() < 1.0e-9);
+    /// ```
+    pub fn julian_ephemeris_day(&self) -> AstroResult<f64> {
+        Ok(self.as_dt()?.julian_day_number())
+    }
+
+    /// Get the Julian Day number in two parts, the Julian Day number at midnight and the fraction
+    /// of the day since, in [0, 1).
+    pub fn julian_day_parts(&self) -> (f64, f64) {
+        (self.day, self.fraction)
+    }
+
+    /// Get the Modified Julian Day as defined by the number of days since midnight
+    /// 17 November, 1858.
+    pub fn modified_julian_day_number(&self) -> f64 {
+        (self.day - 2_400_000.5) + self.fraction
+    }
+
+    /// Get the day of the week of the UTC date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::{Builder, Weekday};
+    /// // Example 7.e, Astronomical Algorithms 2nd ed.
+    /// let a_date = Builder::from_gregorian_utc(1954, 6, 30, 0, 0, 0).build().unwrap();
+    /// assert_eq!(a_date.day_of_week().unwrap(), Weekday::Wednesday);
+    /// ```
+    pub fn day_of_week(&self) -> AstroResult<Weekday> {
+        Ok(weekday(self.as_utc()?.julian_day_number()))
+    }
+
+    /// Get the year, month, day, hour, minute, and second in the UTC time zone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let a_date = Builder::from_gregorian_utc( 2017, 2, 11, 19, 58, 5).build().unwrap();
+    /// let (year, month, day, hour, minute, second) = a_date.to_gregorian_utc();
+    /// assert!( year == 2017 );
+    /// assert!( month == 2 );
+    /// assert!( day == 11 );
+    /// assert!( hour == 19 );
+    /// assert!( minute == 58 );
+    /// assert!( second == 5 );
+    /// ```
+    // TODO create a simple datetime type and return it.
+    pub fn to_gregorian_utc(&self) -> (i32, i32, i32, i32, i32, i32) {
+        // Round to the nearest second first, so the seconds never round up to 60.
+        let (year, month, day, f) = self.date_and_fraction(86_400.0, true);
+        let (hour, minute, second) = to_hms(f);
+
+        (year, month, day, hour, minute, second)
+    }
+
+    /// Get the year, month, day, hour, minute, and second in the UTC time zone, with the seconds
+    /// rounded to the nearest millisecond.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let a_date = Builder::from_gregorian_utc_precise(2017, 2, 11, 19, 58, 5.125)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(a_date.to_gregorian_utc_precise(), (2017, 2, 11, 19, 58, 5.125));
+    /// ```
+    pub fn to_gregorian_utc_precise(&self) -> (i32, i32, i32, i32, i32, f64) {
+        let (year, month, day, f) = self.date_and_fraction(86_400_000.0, true);
+
+        let millis = f64::round(f * 86_400_000.0) as i64;
+        let hour = (millis / 3_600_000) as i32;
+        let minute = (millis % 3_600_000 / 60_000) as i32;
+        let second = (millis % 60_000) as f64 / 1_000.0;
+
+        (year, month, day, hour, minute, second)
+    }
+
+    /// Get the year, month, day, hour, minute, and second in the UTC time zone in the Julian
+    /// calendar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// // Example 7.c, Astronomical Algorithms 2nd ed.
+    /// let a_date = Builder::from_julian_date(1_842_713.0).build().unwrap();
+    /// assert_eq!(a_date.to_julian_utc(), (333, 1, 27, 12, 0, 0));
+    /// ```
+    pub fn to_julian_utc(&self) -> (i32, i32, i32, i32, i32, i32) {
+        let (year, month, day, f) = self.date_and_fraction(86_400.0, false);
+        let (hour, minute, second) = to_hms(f);
+
+        (year, month, day, hour, minute, second)
+    }
+
+    // The year, month, day, and fraction of the day after rounding to 1 / ticks_per_day, in the
+    // Gregorian or the Julian calendar.
+    fn date_and_fraction(&self, ticks_per_day: f64, gregorian: bool) -> (i32, i32, i32, f64) {
+        // Round the fraction of the day, and carry a whole day into the day.
+        let f = f64::round(self.fraction * ticks_per_day) / ticks_per_day;
+        let z = self.day + 0.5 + f64::floor(f);
+        let f = f - f64::floor(f);
+
+        let (year, month, day) = calendar_date(z, gregorian);
+        (year, month, day, f)
+    }
+
+    /// Whatever time type (dynamical or UTC) create a copy in UTC by applying an *_approximate_*
+    /// conversion. This can be wildly inaccurate for years before 1620 and years after 2017.
+    /// Future dates use a forecasted correction value, which is very hard to predict.
+    ///
+    /// Offsets are linearly interpolated from data take from "Astronomical Algorithms, 2nd ed."
+    /// by Jean Meeus, pg 79 and some data downloaded from the US Navy's website. It is hard coded
+    /// into the library. Outside the table the polynomial expressions of Espenak and Meeus are
+    /// used, which are meant for -1999 to +3000.
+    ///
+    /// From 1972 on dynamical time and International Atomic Time are converted exactly using the
+    /// table of leap seconds instead.
+    ///
+    /// UT1 is taken as UTC, which is good to 0.9 seconds.
+    pub fn as_utc(&self) -> AstroResult<AstroTime> {
+        match self.time_type {
+            TimeType::UT | TimeType::UT1 => self.shift(0.0, TimeType::UT),
+            TimeType::DT => {
+                let tai = self.julian_day_number() - TT_MINUS_TAI / 86_400.0;
+                let offset = match tai_minus_utc(tai, true) {
+                    Ok(leap_seconds) => -(TT_MINUS_TAI + leap_seconds) / 86_400.0,
+                    Err(_) => -self.get_delta_t(),
+                };
+                self.shift(offset, TimeType::UT)
+            }
+            TimeType::TDB => self.as_dt()?.as_utc(),
+            TimeType::TAI => {
+                let leap_seconds = tai_minus_utc(self.julian_day_number(), true)?;
+                self.shift(-leap_seconds / 86_400.0, TimeType::UT)
+            }
+        }
+    }
+
+    /// Whatever time type (dynamical or UTC) create a copy in dynamical time by applying an
+    /// *_approximate_* conversion. This can be wildly inaccurate for years before 1620 and years
+    /// after 2017. Future dates use a forecasted correction value, which is very hard to predict.
+    ///
+    /// Offsets are linearly interpolated from data take from "Astronomical Algorithms, 2nd ed."
+    /// by Jean Meeus, pg 79 and some data downloaded from the US Navy's website. It is hard coded
+    /// into the library. Outside the table the polynomial expressions of Espenak and Meeus are
+    /// used, which are meant for -1999 to +3000.
+    ///
+    /// From 1972 on UTC is converted exactly using the table of leap seconds instead, and
+    /// International Atomic Time is always converted exactly. UT1 is taken as UTC, which is good
+    /// to 0.9 seconds.
+    pub fn as_dt(&self) -> AstroResult<AstroTime> {
+        let jd = self.julian_day_number();
+        let offset = match self.time_type {
+            TimeType::DT => 0.0,
+            TimeType::UT | TimeType::UT1 => {
+                match tai_minus_utc(jd, false) {
+                    Ok(leap_seconds) => (leap_seconds + TT_MINUS_TAI) / 86_400.0,
+                    Err(_) => self.get_delta_t(),
+                }
+            }
+            TimeType::TAI => TT_MINUS_TAI / 86_400.0,
+            TimeType::TDB => -tdb_minus_tt(jd) / 86_400.0,
+        };
+
+        self.shift(offset, TimeType::DT)
+    }
+
+    /// Whatever time type create a copy in Barycentric Dynamical Time.
+    ///
+    /// TDB less TT is calculated with the series of Fairhead & Bretagnon as abridged in USNO
+    /// Circular 179, good to 10 microseconds from 1600 to 2200.
+    pub fn as_tdb(&self) -> AstroResult<AstroTime> {
+        match self.time_type {
+            TimeType::TDB => self.shift(0.0, TimeType::TDB),
+            _ => {
+                let tt = self.as_dt()?;
+                tt.shift(tdb_minus_tt(tt.julian_day_number()) / 86_400.0, TimeType::TDB)
+            }
+        }
+    }
+
+    /// Whatever time type create a copy in UT1, given DUT1 = UT1 - UTC from IERS Bulletin A.
+    ///
+    /// Sidereal time, and so hour angles, are properly measured from UT1. A copy of a time that is
+    /// already UT1 is returned unchanged. DUT1 is kept within 0.9 seconds by the leap seconds, and
+    /// an error is returned if its magnitude is a second or more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::{AstroDuration, Builder};
+    /// let utc = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).build().unwrap();
+    /// let dut1 = AstroDuration::from_seconds(0.3).unwrap();
+    /// let ut1 = utc.as_ut1(dut1).unwrap();
+    /// assert!(ut1.sidereal_greenwich() > utc.sidereal_greenwich());
+    /// ```
+    pub fn as_ut1(&self, dut1: AstroDuration) -> AstroResult<AstroTime> {
+        if dut1.seconds().abs() >= 1.0 {
+            return Err(AstroAlgorithmsError::InvalidArgument(format!("DUT1 of {} seconds is \
+                                                                      too large",
+                                                                     dut1.seconds())));
+        }
+
+        match self.time_type {
+            TimeType::UT1 => self.shift(0.0, TimeType::UT1),
+            _ => self.as_utc()?.shift(dut1.days(), TimeType::UT1),
+        }
+    }
+
+    /// Whatever time type create a copy in International Atomic Time.
+    ///
+    /// UTC is converted exactly using the table of leap seconds, and an error is returned for
+    /// times before 1972 when the table starts. Dynamical time is converted exactly.
+    pub fn as_tai(&self) -> AstroResult<AstroTime> {
+        match self.time_type {
+            TimeType::TAI => self.shift(0.0, TimeType::TAI),
+            TimeType::UT | TimeType::UT1 => {
+                let leap_seconds = tai_minus_utc(self.julian_day_number(), false)?;
+                self.shift(leap_seconds / 86_400.0, TimeType::TAI)
+            }
+            TimeType::DT => self.shift(-TT_MINUS_TAI / 86_400.0, TimeType::TAI),
+            TimeType::TDB => self.as_dt()?.shift(-TT_MINUS_TAI / 86_400.0, TimeType::TAI),
+        }
+    }
+
+    /// Create a copy in any time scale, following the links between the scales described for
+    /// `TimeType`.
+    ///
+    /// Converting between UT1 and the other scales needs DUT1, so an error is returned for them,
+    /// use `to_scale_with_dut1` to supply it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::{Builder, TimeScale};
+    /// let utc = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).build().unwrap();
+    /// let tdb = utc.to_scale(TimeScale::TDB).unwrap();
+    /// let back = tdb.to_scale(TimeScale::UTC).unwrap();
+    /// assert!((back.julian_day_number() - utc.julian_day_number()).abs() < 1.0e-9);
+    /// assert!(utc.to_scale(TimeScale::UT1).is_err());
+    /// ```
+    pub fn to_scale(&self, scale: TimeScale) -> AstroResult<AstroTime> {
+        match (self.time_type, scale) {
+            (TimeType::UT1, TimeScale::UT1) => self.shift(0.0, TimeType::UT1),
+            (TimeType::UT1, _) | (_, TimeScale::UT1) => {
+                Err(AstroAlgorithmsError::InvalidArgument("DUT1 is needed to convert between \
+                                                           UT1 and the other time scales"
+                    .to_owned()))
+            }
+            (_, TimeScale::UTC) => self.as_utc(),
+            (_, TimeScale::TT) => self.as_dt(),
+            (_, TimeScale::TAI) => self.as_tai(),
+            (_, TimeScale::TDB) => self.as_tdb(),
+        }
+    }
+
+    /// Create a copy in any time scale, given DUT1 = UT1 - UTC from IERS Bulletin A for
+    /// converting to or from UT1.
+    ///
+    /// Returns an error if the magnitude of DUT1 is a second or more, like `as_ut1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::{AstroDuration, Builder, TimeScale};
+    /// let utc = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).build().unwrap();
+    /// let dut1 = AstroDuration::from_seconds(0.3).unwrap();
+    /// let ut1 = utc.to_scale_with_dut1(TimeScale::UT1, dut1).unwrap();
+    /// let back = ut1.to_scale_with_dut1(TimeScale::UTC, dut1).unwrap();
+    /// assert!((back.julian_day_number() - utc.julian_day_number()).abs() < 1.0e-9);
+    /// ```
+    pub fn to_scale_with_dut1(&self,
+                              scale: TimeScale,
+                              dut1: AstroDuration)
+                              -> AstroResult<AstroTime> {
+        let utc = match self.time_type {
+            TimeType::UT1 => {
+                // Check DUT1 before using it, as_ut1 only checks it when converting to UT1.
+                self.as_ut1(dut1)?.shift(-dut1.days(), TimeType::UT)?
+            }
+            _ => *self,
+        };
+
+        match scale {
+            TimeScale::UT1 => utc.as_ut1(dut1),
+            _ => utc.to_scale(scale),
+        }
+    }
+
+    /// Get the sidereal time at Greenwich.
+    ///
+    /// Returns the sidereal time in decimal degrees.
+    // TODO - return some generalized angle/time type.
+    pub fn sidereal_greenwich(&self) -> f64 {
+        // Chapter 12 of Astronomical Algorithms 2nd ed. By Jean Meeus.
+        use std::f64;
+
+        let jd0 = f64::floor(self.julian_day_number() + 0.5) - 0.5;
+        let t = (jd0 - 2_451_545.0) / 36_525.0;
+
+        // Each whole day turns 360 degrees as well, so leave those turns out of the product to keep
+        // the precision of the fraction of the day.
+        let days = self.day - 2_451_544.5;
+        let mut theta_0 = 280.460_618_37 + 0.985_647_366_29 * days +
+                          360.985_647_366_29 * (self.fraction - 0.5) +
+                          t * t * (0.000_387_933 - t / 38_710_000.0);
+        theta_0 %= 360.0;
+
+        while theta_0 < 0.0 {
+            theta_0 += 360.0;
+        }
+
+        while theta_0 >= 360.0 {
+            theta_0 -= 360.0;
+        }
+
+        theta_0
+    }
+
+    /// Get the apparent sidereal time at Greenwich, the mean sidereal time corrected for nutation
+    /// by the equation of the equinoxes.
+    ///
+    /// Returns the sidereal time in decimal degrees. The time is converted to universal time
+    /// first. This is the sidereal time to use with apparent coordinates.
+    pub fn apparent_sidereal_greenwich(&self) -> AstroResult<f64> {
+        let theta_0 = self.as_utc()?.sidereal_greenwich() +
+                      equation_of_equinoxes(self)?.radians().to_degrees();
+
+        Ok(map_to_branch(theta_0, 0.0, 360.0))
+    }
+
+    /// Get the IAU 2000 Earth rotation angle, the angle between the Celestial Intermediate Origin
+    /// and the Terrestrial Intermediate Origin.
+    ///
+    /// Returns the angle in decimal degrees. The time is converted to universal time first.
+    pub fn earth_rotation_angle(&self) -> AstroResult<f64> {
+        let utc = self.as_utc()?;
+        let tu = utc.days_since_parts(2_451_545.0, 0.0);
+
+        // Keep the whole days out of the product to preserve precision.
+        let turns = utc.fraction + 0.5 + 0.779_057_273_264 + 0.002_737_811_911_354_48 * tu;
+
+        Ok(360.0 * (turns - turns.floor()))
+    }
+
+    /// Get the mean sidereal time at Greenwich using the chosen formulation.
+    ///
+    /// Returns the sidereal time in decimal degrees. Unlike `sidereal_greenwich`, the time is
+    /// converted to universal time first, and the IAU models also use dynamical time for the
+    /// precession polynomial. The IAU models are more accurate over long periods.
+    pub fn sidereal_greenwich_with(&self, model: SiderealModel) -> AstroResult<f64> {
+        if model == SiderealModel::Meeus {
+            return Ok(self.as_utc()?.sidereal_greenwich());
+        }
+
+        let era = self.earth_rotation_angle()?;
+        let t = (self.julian_ephemeris_day()? - 2_451_545.0) / 36_525.0;
+
+        // Polynomial in arc-seconds
+        let poly = match model {
+            SiderealModel::Iau2000 => {
+                0.014_506 +
+                t * (4_612.157_399_66 + t * (1.396_677_21 + t * (-0.000_093_44 + t * 0.000_018_82)))
+            }
+            _ => {
+                0.014_506 +
+                t * (4_612.156_534 +
+                     t * (1.391_581_7 +
+                          t * (-0.000_000_44 + t * (-0.000_029_956 - t * 0.000_000_036_8))))
+            }
+        };
+
+        let mut theta_0 = era + poly / 3600.0;
+        while theta_0 < 0.0 {
+            theta_0 += 360.0;
+        }
+        while theta_0 >= 360.0 {
+            theta_0 -= 360.0;
+        }
+
+        Ok(theta_0)
+    }
+
+    /// Create a copy moved later by a duration, or earlier if it is negative.
+    ///
+    /// The copy has the same time type. Returns an error if the result would be before Julian Day
+    /// 0.
+    pub fn add_duration(&self, duration: AstroDuration) -> AstroResult<AstroTime> {
+        self.shift(duration.days(), self.time_type)
+    }
+
+    /// The duration from an earlier time to this one, negative if `earlier` is actually later.
+    ///
+    /// If the time types are different, `earlier` is converted to this time's type first.
+    pub fn duration_since(&self, earlier: &AstroTime) -> AstroResult<AstroDuration> {
+        let earlier = match self.time_type {
+            TimeType::UT => earlier.as_utc()?,
+            TimeType::DT => earlier.as_dt()?,
+            TimeType::TAI => earlier.as_tai()?,
+            TimeType::UT1 => earlier.as_ut1(AstroDuration::from_days(0.0)?)?,
+            TimeType::TDB => earlier.as_tdb()?,
+        };
+
+        AstroDuration::from_days(self.days_since_parts(earlier.day, earlier.fraction))
+    }
+
+    /// Compare two times of any time types, converting both to dynamical time first.
+    ///
+    /// Times of different types are not ordered by `partial_cmp`, so use this to sort a list of
+    /// mixed types. The conversions are as approximate as `as_dt` is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::Builder;
+    /// let utc = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).build().unwrap();
+    /// let tai = utc.as_tai().unwrap();
+    /// let later = Builder::from_gregorian_utc(2017, 8, 21, 18, 26, 0).build().unwrap();
+    ///
+    /// let mut times = vec![later, tai, utc.as_dt().unwrap()];
+    /// times.sort_by(|a, b| a.cmp_normalized(b).unwrap());
+    /// assert!(times[2] == later);
+    /// ```
+    pub fn cmp_normalized(&self, other: &AstroTime) -> AstroResult<Ordering> {
+        let left = self.as_dt()?;
+        let right = other.as_dt()?;
+
+        Ok((left.day, left.fraction)
+            .partial_cmp(&(right.day, right.fraction))
+            .expect("Parts of a valid time are not NaN"))
     }
-}
 
-impl PartialOrd for AstroTime {
-    fn partial_cmp(&self, other: &AstroTime) -> Option<Ordering> {
-        if self.time_type == other.time_type {
-            self.julian_day.partial_cmp(&other.julian_day)
-        } else {
-            None
-        }
+    /// Get the year, month, day, hour, minute, and second of the local mean time at a place.
+    ///
+    /// Local mean time differs from universal time by the longitude, an hour for every 15 degrees.
+    pub fn to_local_mean_time(&self,
+                              geo: GeoCoords)
+                              -> AstroResult<(i32, i32, i32, i32, i32, i32)> {
+        Ok(self.as_utc()?.add_duration(mean_time_offset(geo))?.to_gregorian_utc())
     }
-}
 
-impl AstroTime {
-    /// Get the Julian Day number as a floating point value.
-    pub fn julian_day_number(&self) -> f64 {
-        self.julian_day
+    /// Get the year, month, day, hour, minute, and second of the local apparent time at a place,
+    /// the time shown by a sundial.
+    ///
+    /// Local apparent time is the local mean time plus the equation of time.
+    pub fn to_local_apparent_time(&self,
+                                  geo: GeoCoords)
+                                  -> AstroResult<(i32, i32, i32, i32, i32, i32)> {
+        let offset = mean_time_offset(geo) + sun::equation_of_time(self)?;
+        Ok(self.as_utc()?.add_duration(offset)?.to_gregorian_utc())
     }
 
-    /// Get the Modified Julian Day as defined by the number of days since midnight
-    /// 17 November, 1858.
-    pub fn modified_julian_day_number(&self) -> f64 {
-        self.julian_day - 2_400_000.5
+    /// Get the year, month, day, hour, minute, and second in a time zone that is `zone_offset`
+    /// ahead of UTC.
+    pub fn to_zone_time(&self,
+                        zone_offset: AstroDuration)
+                        -> AstroResult<(i32, i32, i32, i32, i32, i32)> {
+        Ok(self.as_utc()?.add_duration(zone_offset)?.to_gregorian_utc())
     }
 
-    /// Get the year, month, day, hour, minute, and second in the UTC time zone.
+    /// Get the year, month, day, hour, and minute in a time zone that is `zone_offset` ahead of
+    /// UTC, rounded to the nearest minute as printed in almanacs.
+    ///
+    /// The time is rounded before it is split into a date and time, so the date is always the
+    /// civil day the rounded time falls on. A time of 23:59:40 is given as 00:00 on the next day,
+    /// and an event at 00:03 zone time is on the day after the universal time date when the zone
+    /// is behind UTC.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use astro_calc::astro_time::Builder;
-    /// let a_date = Builder::from_gregorian_utc( 2017, 2, 11, 19, 58, 5).build().unwrap();
-    /// let (year, month, day, hour, minute, second) = a_date.to_gregorian_utc();
-    /// assert!( year == 2017 );
-    /// assert!( month == 2 );
-    /// assert!( day == 11 );
-    /// assert!( hour == 19 );
-    /// assert!( minute == 58 );
-    /// assert!( second == 5 );
+    /// # use astro_calc::astro_time::{AstroDuration, Builder};
+    /// let time = Builder::from_gregorian_utc(2017, 12, 31, 16, 59, 40).build().unwrap();
+    /// let zone = AstroDuration::from_hours(7.0).unwrap();
+    /// assert_eq!(time.to_zone_time_rounded(zone).unwrap(), (2018, 1, 1, 0, 0));
     /// ```
-    // TODO create a simple datetime type and return it.
-    pub fn to_gregorian_utc(&self) -> (i32, i32, i32, i32, i32, i32) {
-        // Adapted from chapter 7, pages 60-61 of Astronomical Algorithms, 2nd Edition
-        // by Jean Meeus.
-        use std::f64;
+    pub fn to_zone_time_rounded(&self,
+                                zone_offset: AstroDuration)
+                                -> AstroResult<(i32, i32, i32, i32, i32)> {
+        let zone_time = self.as_utc()?.add_duration(zone_offset)?;
+        let (year, month, day, f) = zone_time.date_and_fraction(1_440.0, true);
+        let (hour, minute, _) = to_hms(f);
+
+        Ok((year, month, day, hour, minute))
+    }
 
-        let z = f64::floor(self.julian_day + 0.5);
-        let f = self.julian_day + 0.5 - z;
+    // Normalize a Julian Day number given in two parts to the Julian Day number at midnight and
+    // the fraction of the day. Both parts are split first, so a small fraction added to a large
+    // day loses nothing.
+    //
+    // The fraction is rounded to the nearest nanosecond, so times that differ only by the
+    // rounding of the arithmetic, such as a time zone offset added to noon and 7 pm, are equal.
+    fn from_parts(day: f64, fraction: f64, time_type: TimeType) -> AstroTime {
+        let (whole_day, whole_fraction) = ((day - 0.5).floor() + 0.5, fraction.floor());
+        let mut day_part = whole_day + whole_fraction;
+        let mut fraction_part = (day - whole_day) + (fraction - whole_fraction);
+        fraction_part = f64::round(fraction_part * NANOSECONDS_PER_DAY) / NANOSECONDS_PER_DAY;
+        if fraction_part >= 1.0 {
+            day_part += 1.0;
+            fraction_part -= 1.0;
+        }
 
-        let alpha = f64::floor((z - 1_867_216.25) / 36_524.25);
-        let a = z + 1.0 + alpha - f64::floor(alpha / 4.0);
-
-        let b = a + 1524.0;
-        let c = f64::floor((b - 122.1) / 365.25);
-        let d = f64::floor(365.25 * c);
-        let e = f64::floor((b - d) / 30.6001);
-
-        // Extra call to floor to ensure rounding mode
-        let day = f64::floor(b - d - f64::floor(30.6001 * e)) as i32;
-        let mut month = f64::floor(e - 1.0) as i32;
-        if e > 13.0 {
-            month = f64::floor(e - 13.0) as i32;
+        AstroTime {
+            day: day_part,
+            fraction: fraction_part,
+            time_type,
         }
+    }
 
-        let year = f64::floor(if month > 2 { c - 4716.0 } else { c - 4715.0 }) as i32;
+    // A copy moved later by a number of days and given a time type, without any conversion.
+    fn shift(&self, days: f64, time_type: TimeType) -> AstroResult<AstroTime> {
+        Builder::from_julian_date_parts(self.day, self.fraction + days)
+            .build()
+            .map(|atime| AstroTime { time_type, ..atime })
+    }
 
-        let (hour, minute, second) = to_hms(f);
+    // The days since a Julian Day number given in two parts, subtracting the larger parts first.
+    fn days_since_parts(&self, day: f64, fraction: f64) -> f64 {
+        (self.day - day) + (self.fraction - fraction)
+    }
 
-        (year, month, day, hour, minute, second)
+    // Calculate the delta-t value for applying a conversion between unversal
+    // and dynamical time.
+    fn get_delta_t(&self) -> f64 {
+        delta_t_days(self.julian_day_number(), delta_t::installed().as_ref())
     }
+}
 
-    /// Whatever time type (dynamical or UTC) create a copy in UTC by applying an *_approximate_*
-    /// conversion. This can be wildly inaccurate for years before 1620 and years after 2017.
-    /// Future dates use a forecasted correction value, which is very hard to predict.
-    ///
-    /// Offsets are linearly interpolated from data take from "Astronomical Algorithms, 2nd ed."
-    /// by Jean Meeus, pg 79 and some data downloaded from the US Navy's website. It is hard coded
-    /// into the library.
-    pub fn as_utc(&self) -> AstroResult<AstroTime> {
-        if self.time_type == TimeType::UT {
-            Builder::from_julian_date(self.julian_day).build()
-        } else {
-            let dt = self.get_delta_t();
-            Builder::from_julian_date(self.julian_day - dt).build()
+// Delta-t in days at a Julian Day. A table installed in place of the embedded table is used if it
+// covers the day.
+fn delta_t_days(julian_day: f64, installed: Option<&DeltaTTable>) -> f64 {
+    use self::time_data::TIME_DELTA;
+
+    if let Some(delta_t) = installed.and_then(|table| table.delta_t(julian_day)) {
+        trace_event!(julian_day, delta_t, "delta-t from the installed table");
+        return delta_t / 86_400.0;
+    }
+
+    // Use linear interpolation on the table if possible
+    if let Some(delta_t) = delta_t::interpolate(&TIME_DELTA, julian_day) {
+        trace_event!(julian_day, delta_t, "delta-t from the embedded table");
+        delta_t / 86_400.0
+    } else {
+        let delta_t = espenak_meeus_delta_t(2000.0 + (julian_day - 2_451_544.5) / 365.242_5);
+        trace_event!(julian_day, delta_t, "delta-t from the Espenak and Meeus polynomials");
+        delta_t / 86_400.0
+    }
+}
+
+#[cfg(test)]
+mod astro_time_tests {
+    use astro_time::*;
+
+    #[test]
+    fn test_epochs() {
+        assert_eq!(AstroTime::from_julian_epoch(2000.0).unwrap(), J2000);
+        assert_eq!(J2000.to_julian_epoch().unwrap(), 2000.0);
+        assert!(approx_eq(B1900.to_besselian_epoch().unwrap(), 1900.0, 1.0e-6));
+        assert!(approx_eq(B1950.to_besselian_epoch().unwrap(), 1950.0, 1.0e-6));
+        let b1950 = AstroTime::from_besselian_epoch(1950.0).unwrap();
+        assert!(approx_eq(b1950.julian_day_number(), B1950.julian_day_number(), 1.0e-4));
+
+        // J2024.5 is half a Julian year after J2024.0, and B1950.0 was J1949.99979.
+        let j2024_5 = AstroTime::from_julian_epoch(2024.5).unwrap();
+        assert_eq!(j2024_5.julian_day_number(), 2_451_545.0 + 24.5 * 365.25);
+        assert!(approx_eq(j2024_5.to_julian_epoch().unwrap(), 2024.5, 1.0e-12));
+        assert!(approx_eq(B1950.to_julian_epoch().unwrap(), 1_949.999_79, 1.0e-6));
+
+        // A universal time is converted first, dynamical time was ahead by about a minute.
+        let utc = Builder::from_julian_date(2_451_545.0).build().unwrap();
+        assert!(utc.to_julian_epoch().unwrap() > 2000.0);
+    }
+
+    #[test]
+    fn test_get_delta_t() {
+        let jd = |year| Builder::from_gregorian_utc(year, 1, 1, 0, 0, 0).build().unwrap();
+
+        // On the first entry of the table and between entries.
+        assert!(approx_eq(jd(1620).get_delta_t() * 86_400.0, 121.0, 1.0e-9));
+        assert!(approx_eq(jd(1621).get_delta_t() * 86_400.0, 116.5, 0.01));
+
+        // Before the table, from the polynomials.
+        assert!(approx_eq(jd(1500).get_delta_t() * 86_400.0,
+                          espenak_meeus_delta_t(1500.0),
+                          0.1));
+
+        // An installed table takes the place of both where it covers the day, and not elsewhere.
+        let table = DeltaTTable::from_reader("1000 1 1 1500.0\n1700 1 1 1500.0\n".as_bytes())
+            .unwrap();
+        for &year in &[1000, 1500, 1621, 1700] {
+            let day = jd(year).julian_day_number();
+            assert!(approx_eq(delta_t_days(day, Some(&table)) * 86_400.0, 1500.0, 1.0e-9));
         }
+        for &year in &[999, 1701, 2000] {
+            let day = jd(year).julian_day_number();
+            assert_eq!(delta_t_days(day, Some(&table)), delta_t_days(day, None));
+        }
+        assert_eq!(delta_t_days(jd(1621).julian_day_number(), None),
+                   jd(1621).get_delta_t());
     }
 
-    /// Whatever time type (dynamical or UTC) create a copy in dynamical time by applying an
-    /// *_approximate_* conversion. This can be wildly inaccurate for years before 1620 and years
-    /// after 2017. Future dates use a forecasted correction value, which is very hard to predict.
-    ///
-    /// Offsets are linearly interpolated from data take from "Astronomical Algorithms, 2nd ed."
-    /// by Jean Meeus, pg 79 and some data downloaded from the US Navy's website. It is hard coded
-    /// into the library.
-    pub fn as_dt(&self) -> AstroResult<AstroTime> {
-        if self.time_type == TimeType::DT {
-            Builder::from_julian_date(self.julian_day).dynamical_time().build()
-        } else {
-            let dt = self.get_delta_t();
-            Builder::from_julian_date(self.julian_day + dt).dynamical_time().build()
+    #[test]
+    fn test_espenak_meeus_delta_t() {
+        // Values from the Five Millennium Canon of Solar Eclipses, table 1.1.
+        for &(year, delta_t, tol) in &[(-1000.0, 25_400.0, 100.0),
+                                       (0.0, 10_580.0, 10.0),
+                                       (1000.0, 1_570.0, 10.0),
+                                       (1600.0, 120.0, 1.0),
+                                       (1800.0, 13.7, 0.1),
+                                       (1900.0, -2.8, 0.1),
+                                       (1950.0, 29.1, 0.1),
+                                       (2000.0, 63.9, 0.1)] {
+            assert!(approx_eq(espenak_meeus_delta_t(year), delta_t, tol),
+                    "{} {}",
+                    year,
+                    espenak_meeus_delta_t(year));
+        }
+
+        // Nearly continuous where the expressions meet.
+        for &year in &[-500.0, 500.0, 1600.0, 1700.0, 1800.0, 1860.0, 1900.0, 1920.0, 1941.0,
+                       1961.0, 1986.0, 2005.0, 2050.0, 2150.0] {
+            let step = espenak_meeus_delta_t(year) - espenak_meeus_delta_t(year - 1.0e-9);
+            assert!(step.abs() < 5.0, "{} {}", year, step);
         }
     }
 
-    /// Get the sidereal time at Greenwich.
-    ///
-    /// Returns the sidereal time in decimal degrees.
-    // TODO - return some generalized angle/time type.
-    pub fn sidereal_greenwich(&self) -> f64 {
-        // Chapter 12 of Astronomical Algorithms 2nd ed. By Jean Meeus.
-        use std::f64;
+    #[test]
+    fn test_add_duration() {
+        let time = Builder::from_julian_date(2_451_545.0).dynamical_time().build().unwrap();
+        let later = time.add_duration(AstroDuration::from_hours(12.0).unwrap()).unwrap();
+        assert!(later == Builder::from_julian_date(2_451_545.5).dynamical_time().build().unwrap());
 
-        let jd0 = f64::floor(self.julian_day + 0.5) - 0.5;
-        let t = (jd0 - 2_451_545.0) / 36_525.0;
-        let mut theta_0 = 280.460_618_37 + 360.985_647_366_29 * (self.julian_day - 2_451_545.0) +
-                          t * t * (0.000_387_933 - t / 38_710_000.0);
+        let back = later.add_duration(-AstroDuration::from_hours(12.0).unwrap()).unwrap();
+        assert!(back == time);
 
-        while theta_0 < 0.0 {
-            theta_0 += 360.0;
+        let too_early = AstroDuration::from_days(-2_451_546.0).unwrap();
+        assert!(time.add_duration(too_early).is_err());
+    }
+
+    #[test]
+    fn test_operators() {
+        // Step through a night an hour at a time.
+        let dusk = Builder::from_gregorian_utc(2017, 8, 21, 3, 0, 0).build().unwrap();
+        let hour = AstroDuration::from_hours(1.0).unwrap();
+        let mut time = dusk;
+        for _ in 0..8 {
+            time = (time + hour).unwrap();
         }
+        assert!(approx_eq((time - dusk).unwrap().hours(), 8.0, 1.0e-7));
+        assert!(approx_eq((time - hour).unwrap().julian_day_number(),
+                          dusk.julian_day_number() + 7.0 / 24.0,
+                          1.0e-9));
+
+        // The time subtracted is converted to the type of the other.
+        let dt = dusk.as_dt().unwrap();
+        assert!(approx_eq((time - dt).unwrap().hours(), 8.0, 1.0e-6));
+        assert!(approx_eq((dt - time).unwrap().hours(), -8.0, 1.0e-6));
+
+        // Only times of the same type are ordered, unless they are normalized.
+        assert!(dt.partial_cmp(&time).is_none());
+        assert_eq!(dt.cmp_normalized(&time).unwrap(), Ordering::Less);
+        assert_eq!(time.cmp_normalized(&dt).unwrap(), Ordering::Greater);
+        assert_eq!(dt.cmp_normalized(&dusk).unwrap(), Ordering::Equal);
+        assert_eq!(dusk.as_tai().unwrap().cmp_normalized(&dt).unwrap(), Ordering::Equal);
+
+        let start = Builder::from_julian_date(0.5).build().unwrap();
+        assert!((start - AstroDuration::from_hours(12.0).unwrap()).is_ok());
+        assert!((start - AstroDuration::from_days(1.0).unwrap()).is_err());
+    }
 
-        while theta_0 >= 360.0 {
-            theta_0 -= 360.0;
+    #[test]
+    fn test_tai() {
+        // The leap second at the end of 2016 made TAI - UTC 37 seconds.
+        let before = Builder::from_gregorian_utc(2016, 12, 31, 23, 59, 0).build().unwrap();
+        let after = Builder::from_gregorian_utc(2017, 1, 1, 0, 1, 0).build().unwrap();
+        let tai = |t: AstroTime| t.as_tai().unwrap().julian_day_number();
+        assert!(approx_eq((tai(before) - before.julian_day_number()) * 86_400.0, 36.0, 1.0e-4));
+        assert!(approx_eq((tai(after) - after.julian_day_number()) * 86_400.0, 37.0, 1.0e-4));
+
+        // The two minutes of UTC were 121 seconds long.
+        let elapsed = after.as_tai().unwrap().duration_since(&before).unwrap();
+        assert!(approx_eq(elapsed.seconds(), 121.0, 1.0e-4));
+        assert!(approx_eq(after.duration_since(&before).unwrap().seconds(), 120.0, 1.0e-4));
+
+        // Round trips through the leap second.
+        for &time in &[before, after] {
+            let back = time.as_tai().unwrap().as_utc().unwrap();
+            assert!(approx_eq(back.julian_day_number(), time.julian_day_number(), 1.0e-9));
         }
+        let time = Builder::from_gregorian_utc(2017, 1, 1, 0, 0, 35)
+            .atomic_time()
+            .build()
+            .unwrap();
+        assert_eq!(time.as_utc().unwrap().to_gregorian_utc(), (2016, 12, 31, 23, 59, 59));
+        // Two seconds later, past the leap second 23:59:60.
+        assert_eq!(time.add_duration(AstroDuration::from_seconds(2.0).unwrap())
+                       .unwrap()
+                       .as_utc()
+                       .unwrap()
+                       .to_gregorian_utc(),
+                   (2017, 1, 1, 0, 0, 0));
+
+        // Dynamical time is exactly 32.184 seconds ahead.
+        let dt = time.as_dt().unwrap();
+        assert!(approx_eq((dt.julian_day_number() - time.julian_day_number()) * 86_400.0,
+                          32.184,
+                          1.0e-4));
+        assert!(dt.as_tai().unwrap() == time);
+
+        // The leap seconds start in 1972.
+        let old = Builder::from_gregorian_utc(1971, 12, 31, 0, 0, 0).build().unwrap();
+        assert!(old.as_tai().is_err());
+        assert!(old.as_dt().unwrap().as_tai().is_ok());
+    }
 
-        theta_0
+    #[test]
+    fn test_ut1() {
+        let utc = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).build().unwrap();
+        let dut1 = AstroDuration::from_seconds(0.35).unwrap();
+        let ut1 = utc.as_ut1(dut1).unwrap();
+        assert!(approx_eq((ut1.julian_day_number() - utc.julian_day_number()) * 86_400.0,
+                          0.35,
+                          1.0e-4));
+        assert!(ut1.as_ut1(dut1).unwrap() == ut1);
+
+        // The Earth turns 0.35 seconds of sidereal time, 0.0015 degrees, further.
+        let d_theta = ut1.sidereal_greenwich() - utc.sidereal_greenwich();
+        assert!(approx_eq(d_theta, 0.35 * 1.002_737_9 / 240.0, 1.0e-6));
+
+        // Other conversions take DUT1 as zero.
+        let marked = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).ut1().build().unwrap();
+        assert_eq!(marked.as_utc().unwrap().julian_day_number(), utc.julian_day_number());
+        assert!(marked.as_dt().unwrap() == utc.as_dt().unwrap());
+        let since = marked.add_duration(dut1).unwrap().duration_since(&utc).unwrap();
+        assert!(approx_eq(since.seconds(), 0.35, 1.0e-4));
+
+        assert!(utc.as_ut1(AstroDuration::from_seconds(-1.0).unwrap()).is_err());
     }
 
-    // Calculate the delta-t value for applying a conversion between unversal
-    // and dynamical time.
-    fn get_delta_t(&self) -> f64 {
-        use self::time_data::TIME_DELTA;
-        use std::usize::MAX;
-
-        // Use linear interpolation on the table if possible
-        if self.julian_day >= TIME_DELTA[0].0 &&
-           self.julian_day < TIME_DELTA[TIME_DELTA.len() - 1].0 {
-            let mut i: usize = MAX;
-            for ii in (0..(TIME_DELTA.len() - 1)).rev() {
-                let (jd, _) = TIME_DELTA[ii];
-                if jd < self.julian_day {
-                    i = ii;
-                    break;
-                }
+    #[test]
+    fn test_to_scale() {
+        let scales = [TimeScale::UTC, TimeScale::TT, TimeScale::TAI, TimeScale::TDB];
+        let utc = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0).build().unwrap();
+
+        // TT - UTC is exactly 69.184 seconds since the start of 2017.
+        let tt = utc.to_scale(TimeScale::TT).unwrap();
+        assert!(approx_eq((tt.julian_day_number() - utc.julian_day_number()) * 86_400.0,
+                          69.184,
+                          1.0e-4));
+
+        for &from in &scales {
+            let time = utc.to_scale(from).unwrap();
+            for &to in &scales {
+                let there = time.to_scale(to).unwrap();
+                assert_eq!(TimeScale::from(there.time_type()), to);
+                let back = there.to_scale(TimeScale::UTC).unwrap();
+                assert!(approx_eq(back.julian_day_number(), utc.julian_day_number(), 1.0e-9),
+                        "{:?} to {:?}",
+                        from,
+                        to);
             }
+        }
 
-            debug_assert!(i < TIME_DELTA.len() - 1);
-            let (left, _) = TIME_DELTA[i];
-            let (right, _) = TIME_DELTA[i + 1];
-            let (_, bottom) = TIME_DELTA[i];
-            let (_, top) = TIME_DELTA[i + 1];
+        // UT1 is only linked to the others by DUT1.
+        let dut1 = AstroDuration::from_seconds(0.3).unwrap();
+        let ut1 = utc.to_scale_with_dut1(TimeScale::UT1, dut1).unwrap();
+        assert_eq!(ut1.time_type(), TimeType::UT1);
+        assert!(approx_eq((ut1.julian_day_number() - utc.julian_day_number()) * 86_400.0,
+                          0.3,
+                          1.0e-4));
+        assert!(utc.to_scale(TimeScale::UT1).is_err());
+        assert!(ut1.to_scale(TimeScale::UTC).is_err());
+        assert!(ut1.to_scale(TimeScale::UT1).unwrap() == ut1);
+        for &to in &scales {
+            let there = ut1.to_scale_with_dut1(to, dut1).unwrap();
+            let expected = utc.to_scale(to).unwrap();
+            assert_eq!(there.time_type(), expected.time_type());
+            assert!(approx_eq(there.julian_day_number(), expected.julian_day_number(), 1.0e-9),
+                    "UT1 to {:?}",
+                    to);
+            let back = there.to_scale_with_dut1(TimeScale::UT1, dut1).unwrap();
+            assert!(approx_eq(back.julian_day_number(), ut1.julian_day_number(), 1.0e-9));
+        }
+        let too_large = AstroDuration::from_seconds(1.5).unwrap();
+        assert!(ut1.to_scale_with_dut1(TimeScale::UTC, too_large).is_err());
+        assert!(utc.to_scale_with_dut1(TimeScale::UT1, too_large).is_err());
+
+        // Before the leap seconds UTC and dynamical time are linked by delta-t.
+        let old = Builder::from_gregorian_utc(1960, 1, 1, 0, 0, 0).build().unwrap();
+        let old_tt = old.to_scale(TimeScale::TT).unwrap();
+        assert!(approx_eq((old_tt.julian_day_number() - old.julian_day_number()) * 86_400.0,
+                          33.2,
+                          0.5));
+        assert!(old.to_scale(TimeScale::TAI).is_err());
+
+        let names: Vec<_> = [TimeScale::UTC, TimeScale::UT1, TimeScale::TAI, TimeScale::TT,
+                             TimeScale::TDB]
+            .iter()
+            .map(|scale| scale.to_string())
+            .collect();
+        assert_eq!(names, vec!["UTC", "UT1", "TAI", "TT", "TDB"]);
+        for &scale in &[TimeScale::UTC, TimeScale::UT1, TimeScale::TT] {
+            assert_eq!(TimeScale::from(TimeType::from(scale)), scale);
+        }
+    }
 
-            ((top - bottom) / (right - left) * (self.julian_day - left) + bottom) / 86_400.0
-        } else {
+    #[test]
+    fn test_tdb() {
+        // TDB - TT is greatest, about 1.66 milliseconds, near the start of April when the Earth
+        // is a quarter orbit past perihelion, and least half a year later.
+        let october = Builder::from_gregorian_utc(2017, 10, 3, 0, 0, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let diff = (october.as_tdb().unwrap().julian_day_number() - october.julian_day_number()) *
+                   86_400.0;
+        assert!(approx_eq(diff, -0.001_66, 1.0e-4));
+        let tt = Builder::from_gregorian_utc(2017, 4, 3, 0, 0, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let tdb = tt.as_tdb().unwrap();
+        let diff = (tdb.julian_day_number() - tt.julian_day_number()) * 86_400.0;
+        assert!(approx_eq(diff, 0.001_66, 1.0e-4));
 
-            // Algorithm adapted from chapter 10, pages 78-80 of Astronomical
-            // Algorithms,  2nd Edition by Jean Meeus.
-            let t: f64 = (self.julian_day -
-                          Builder::from_gregorian_utc(2000, 1, 1, 0, 0, 0)
-                .build()
-                .unwrap()
-                .julian_day) / 36524.25;
+        // And about zero near the start of January.
+        let tt = Builder::from_gregorian_utc(2017, 1, 3, 0, 0, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let diff = (tt.as_tdb().unwrap().julian_day_number() - tt.julian_day_number()) * 86_400.0;
+        assert!(diff.abs() < 2.0e-4);
 
-            if self.julian_day <
-               Builder::from_gregorian_utc(948, 1, 1, 0, 0, 0).build().unwrap().julian_day {
-                (2177.0 + 497.0 * t + 44.1 * t * t) / 86_400.0
-            } else {
-                // Year must not be in table or before 948
-                (102.0 + 102.0 * t + 25.3 * t * t) / 86_400.0
+        // Round trips.
+        assert!(approx_eq(tdb.as_dt().unwrap().julian_day_number(),
+                          Builder::from_gregorian_utc(2017, 4, 3, 0, 0, 0)
+                              .build()
+                              .unwrap()
+                              .julian_day_number(),
+                          1.0e-9));
+        let utc = Builder::from_gregorian_utc(2017, 10, 1, 0, 0, 0).build().unwrap();
+        let back = utc.as_tdb().unwrap().as_utc().unwrap();
+        assert!(approx_eq(back.julian_day_number(), utc.julian_day_number(), 1.0e-9));
+        assert!(tdb.as_tdb().unwrap() == tdb);
+        let since = tdb.duration_since(&tdb.as_tai().unwrap()).unwrap();
+        assert!(since.seconds().abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_zone_time_rounded() {
+        let utc = AstroDuration::from_hours(0.0).unwrap();
+        let time = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 29).build().unwrap();
+        assert_eq!(time.to_zone_time_rounded(utc).unwrap(), (2017, 8, 21, 18, 25));
+        let time = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 30).build().unwrap();
+        assert_eq!(time.to_zone_time_rounded(utc).unwrap(), (2017, 8, 21, 18, 26));
+
+        // Rounding up past midnight moves to the next day, and month and year.
+        let time = Builder::from_gregorian_utc(2017, 12, 31, 23, 59, 31).build().unwrap();
+        assert_eq!(time.to_zone_time_rounded(utc).unwrap(), (2018, 1, 1, 0, 0));
+        let time = Builder::from_gregorian_utc(2017, 12, 31, 23, 59, 29).build().unwrap();
+        assert_eq!(time.to_zone_time_rounded(utc).unwrap(), (2017, 12, 31, 23, 59));
+
+        // A moonset at 00:03 mountain daylight time belongs to the next civil day, though it is
+        // 06:03 on the previous universal time day.
+        let mdt = AstroDuration::from_hours(-6.0).unwrap();
+        let time = Builder::from_gregorian_utc(2017, 8, 22, 6, 3, 10).build().unwrap();
+        assert_eq!(time.to_zone_time_rounded(mdt).unwrap(), (2017, 8, 22, 0, 3));
+        let time = Builder::from_gregorian_utc(2017, 8, 22, 5, 59, 50).build().unwrap();
+        assert_eq!(time.to_zone_time_rounded(mdt).unwrap(), (2017, 8, 22, 0, 0));
+
+        // Dynamical time is converted to universal time first.
+        let time = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        assert_eq!(time.to_zone_time_rounded(utc).unwrap(),
+                   time.as_utc().unwrap().to_zone_time_rounded(utc).unwrap());
+        assert!(time.to_zone_time_rounded(utc).unwrap() < (2017, 8, 21, 18, 25));
+    }
+
+    #[test]
+    fn test_unix_timestamp() {
+        let epoch = AstroTime::from_unix_timestamp(0).unwrap();
+        assert_eq!(epoch.to_gregorian_utc(), (1970, 1, 1, 0, 0, 0));
+
+        let time = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 32).build().unwrap();
+        assert_eq!(time.to_unix_timestamp().unwrap(), 1_503_339_932);
+        let back = AstroTime::from_unix_timestamp(1_503_339_932).unwrap();
+        assert!(approx_eq(back.julian_day_number(), time.julian_day_number(), 1.0e-9));
+        assert_eq!(AstroTime::from_unix_timestamp(-86_400).unwrap().to_gregorian_utc(),
+                   (1969, 12, 31, 0, 0, 0));
+
+        // Before Julian Day 0.
+        assert!(AstroTime::from_unix_timestamp(-300_000_000_000).is_err());
+
+        // Past the range of an i64.
+        for &jd in &[1.0e15, 1.0e300] {
+            match Builder::from_julian_date(jd).build().unwrap().to_unix_timestamp() {
+                Err(AstroAlgorithmsError::RangeError(DateRangeError::DateOverflow(..))) => {}
+                result => panic!("JD {} gave {:?}", jd, result),
             }
         }
     }
-}
 
-#[cfg(test)]
-mod astro_time_tests {
-    use astro_time::*;
+    #[test]
+    fn test_gps_time() {
+        let epoch = AstroTime::from_gps_seconds(0.0).unwrap();
+        assert_eq!(epoch.as_utc().unwrap().to_gregorian_utc(), (1980, 1, 6, 0, 0, 0));
+        assert!(approx_eq(epoch.to_gps_seconds().unwrap(), 0.0, 1.0e-6));
+
+        // GPS time was 18 seconds ahead of UTC after the leap second at the end of 2016.
+        let time = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 32).build().unwrap();
+        let unix = time.to_unix_timestamp().unwrap() as f64;
+        let gps = time.to_gps_seconds().unwrap();
+        assert!(approx_eq(gps, unix - 315_964_800.0 + 18.0, 1.0e-5));
+
+        let (week, seconds) = time.to_gps_week().unwrap();
+        assert_eq!(week, 1963);
+        assert!(approx_eq(seconds, 152_750.0, 1.0e-5));
+        let back = AstroTime::from_gps_week(week, seconds).unwrap();
+        assert!(back.time_type == TimeType::TAI);
+        assert!(approx_eq(back.as_utc().unwrap().julian_day_number(),
+                          time.julian_day_number(),
+                          1.0e-9));
+
+        assert!(AstroTime::from_gps_week(-1, 0.0).is_err());
+        assert!(AstroTime::from_gps_week(1963, 604_800.0).is_err());
+        assert!(AstroTime::from_gps_seconds(f64::NAN).is_err());
+        let before = Builder::from_gregorian_utc(1979, 1, 1, 0, 0, 0).build().unwrap();
+        assert!(before.to_gps_week().is_err());
+    }
+
+    #[test]
+    fn test_system_time() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let system = UNIX_EPOCH + Duration::from_secs(1_503_339_932);
+        let time = AstroTime::try_from(system).unwrap();
+        assert_eq!(time.to_gregorian_utc(), (2017, 8, 21, 18, 25, 32));
+
+        let back = time.to_system_time().unwrap();
+        let error = match back.duration_since(system) {
+            Ok(d) => d,
+            Err(e) => e.duration(),
+        };
+        assert!(error < Duration::from_millis(1));
+
+        let before = UNIX_EPOCH - Duration::from_secs(86_400);
+        let before_time = AstroTime::try_from(before).unwrap();
+        assert_eq!(before_time.to_gregorian_utc(), (1969, 12, 31, 0, 0, 0));
+        assert_eq!(before_time.to_system_time().unwrap(), before);
+
+        // Before Julian Day 0, which the builder doesn't accept either.
+        let ancient = UNIX_EPOCH - Duration::from_secs(2_500_000 * 86_400);
+        assert!(AstroTime::try_from(ancient).is_err());
+
+        let far = Builder::from_julian_date(1.0e15).build().unwrap();
+        assert!(far.to_system_time().is_err());
+    }
+
+    #[test]
+    fn test_duration_since() {
+        let ut = Builder::from_gregorian_utc(2017, 8, 21, 18, 0, 0).build().unwrap();
+        let dt = ut.as_dt().unwrap();
+        let hour_later = ut.add_duration(AstroDuration::from_hours(1.0).unwrap()).unwrap();
+
+        assert!(approx_eq(hour_later.duration_since(&ut).unwrap().hours(), 1.0, 1.0e-7));
+        assert!(approx_eq(ut.duration_since(&hour_later).unwrap().hours(), -1.0, 1.0e-7));
+        assert!(approx_eq(hour_later.duration_since(&dt).unwrap().hours(), 1.0, 1.0e-7));
+        assert!(approx_eq(dt.duration_since(&ut).unwrap().seconds(), 0.0, 1.0e-4));
+    }
+
+    #[test]
+    fn test_julian_day_parts() {
+        let time = Builder::from_gregorian_utc(2017, 8, 21, 18, 0, 0).build().unwrap();
+        assert_eq!(time.julian_day_parts(), (2_457_986.5, 0.75));
+        let noon = Builder::from_julian_date(2_451_545.0).build().unwrap();
+        assert_eq!(noon.julian_day_parts(), (2_451_544.5, 0.5));
+
+        // Microseconds survive, far below the resolution of a single Julian Day number.
+        let micro = AstroDuration::from_seconds(1.0e-6).unwrap();
+        let later = time.add_duration(micro).unwrap();
+        assert!(approx_eq(later.duration_since(&time).unwrap().seconds(), 1.0e-6, 1.0e-9));
+        assert!(later > time);
+        assert_eq!(later.to_gregorian_utc_precise(), (2017, 8, 21, 18, 0, 0.0));
+
+        // Carrying past midnight, and back before it.
+        let six_hours = AstroDuration::from_hours(6.0).unwrap();
+        let day = AstroDuration::from_days(1.0).unwrap();
+        assert_eq!((time + six_hours).unwrap().julian_day_parts(), (2_457_987.5, 0.0));
+        assert_eq!((time - day).unwrap().julian_day_parts(), (2_457_985.5, 0.75));
+
+        assert!(Builder::from_julian_date_parts(0.0, -1.0).build().is_err());
+        assert_eq!(Builder::from_julian_date_parts(f64::INFINITY, 0.0).build().unwrap_err(),
+                   AstroAlgorithmsError::EncounteredInf);
+        assert_eq!(Builder::from_julian_date(f64::NAN).build().unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+        assert_eq!(AstroTime::from_julian_epoch(f64::NAN).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+    }
 
     #[test]
     fn test_modified_julian_day_number() {
@@ -698,6 +2325,24 @@ mod astro_time_tests {
             .to_gregorian_utc() == (-1000, 7, 3, 12, 0, 0));
     }
 
+    #[test]
+    fn test_to_julian_utc() {
+        // Examples 7.c and 7.e, Astronomical Algorithms 2nd ed.
+        assert_eq!(Builder::from_julian_date(1_842_713.0).build().unwrap().to_julian_utc(),
+                   (333, 1, 27, 12, 0, 0));
+        assert_eq!(Builder::from_julian_date(1_507_900.125).build().unwrap().to_julian_utc(),
+                   (-584, 5, 28, 15, 0, 0));
+
+        // The day before the Gregorian calendar was adopted.
+        let reform = Builder::from_gregorian_utc(1582, 10, 15, 0, 0, 0).build().unwrap();
+        assert_eq!(reform.to_julian_utc(), (1582, 10, 5, 0, 0, 0));
+
+        for &(year, month, day) in &[(-4712, 1, 1), (-4, 2, 29), (1000, 2, 29), (2017, 12, 31)] {
+            let time = Builder::from_julian_utc(year, month, day, 23, 59, 59).build().unwrap();
+            assert_eq!(time.to_julian_utc(), (year, month, day, 23, 59, 59));
+        }
+    }
+
     #[test]
     fn test_as_utc() {
         let a_dt = Builder::from_gregorian_utc(1977, 2, 18, 3, 37, 40)
@@ -721,6 +2366,10 @@ mod astro_time_tests {
         let as_dt = a_utc.as_dt().unwrap();
 
         assert!(approx_eq(as_dt.julian_day_number(), a_dt.julian_day_number(), 1.0e-5));
+
+        assert_eq!(a_utc.julian_ephemeris_day().unwrap(), as_dt.julian_day_number());
+        assert_eq!(a_dt.julian_ephemeris_day().unwrap(), a_dt.julian_day_number());
+        assert_eq!(J2000.julian_ephemeris_day().unwrap(), 2_451_545.0);
     }
 
     #[test]
@@ -732,6 +2381,169 @@ mod astro_time_tests {
                           128.737_873_4,
                           1.0e-6));
     }
+
+    #[test]
+    fn test_apparent_sidereal_greenwich() {
+        // Example 12.a, Astronomical Algorithms 2nd ed., 13h 10m 46.1351s.
+        let time = Builder::from_gregorian_utc(1987, 4, 10, 0, 0, 0).build().unwrap();
+        let apparent = time.apparent_sidereal_greenwich().unwrap();
+        assert!(approx_eq(apparent * 240.0, (13.0 * 60.0 + 10.0) * 60.0 + 46.135_1, 1.0e-3));
+        assert!(apparent < time.sidereal_greenwich());
+    }
+
+    #[test]
+    fn test_earth_rotation_angle() {
+        // Test case from the IAU SOFA library, UT1 MJD 54388.0
+        let era = Builder::from_julian_date(2_454_388.5).build().unwrap().earth_rotation_angle();
+        assert!(approx_eq(era.unwrap().to_radians(), 0.402_283_724_002_815_8, 1.0e-10));
+    }
+
+    #[test]
+    fn test_sidereal_greenwich_with() {
+        // Test cases from the IAU SOFA library, UT1 and TT MJD 53736.0. This library applies a
+        // delta-T to get TT, but the difference is negligible.
+        let time = Builder::from_julian_date(2_453_736.5).build().unwrap();
+        let gmst00 = time.sidereal_greenwich_with(SiderealModel::Iau2000).unwrap();
+        assert!(approx_eq(gmst00.to_radians(), 1.754_174_972_210_740_6, 1.0e-9));
+        let gmst06 = time.sidereal_greenwich_with(SiderealModel::Iau2006).unwrap();
+        assert!(approx_eq(gmst06.to_radians(), 1.754_174_971_870_091_2, 1.0e-9));
+
+        // All models agree to a few milliseconds of time in the modern era.
+        let time = Builder::from_gregorian_utc(1987, 4, 10, 19, 21, 0).build().unwrap();
+        let meeus = time.sidereal_greenwich_with(SiderealModel::Meeus).unwrap();
+        assert!(approx_eq(meeus, time.sidereal_greenwich(), 1.0e-12));
+        let gmst06 = time.sidereal_greenwich_with(SiderealModel::Iau2006).unwrap();
+        assert!(approx_eq(meeus, gmst06, 1.0e-4));
+    }
+}
+
+// The Julian Day number of a decimal day of a month in the Gregorian calendar. From chapter 7,
+// pages 60-61 of Astronomical Algorithms, 2nd Edition by Jean Meeus.
+fn gregorian_julian_day(mut year: i32, mut month: i32, decimal_day: f64) -> f64 {
+    if month < 3 {
+        year -= 1;
+        month += 12;
+    }
+
+    #[allow(non_snake_case)]
+    let A = f64::floor(year as f64 / 100.0);
+    #[allow(non_snake_case)]
+    let B = 2.0 - A + f64::floor(A / 4.0);
+
+    f64::floor(365.25 * (year + 4716) as f64) + f64::floor(30.6001 * (month + 1) as f64) +
+    decimal_day + B - 1524.5
+}
+
+// The year, month, and day of the day starting at the midnight before a Julian Day number of
+// z - 0.5, in the Gregorian or the Julian calendar. Adapted from chapter 7, page 63 of
+// Astronomical Algorithms, 2nd Edition by Jean Meeus.
+fn calendar_date(z: f64, gregorian: bool) -> (i32, i32, i32) {
+    let a = if gregorian {
+        let alpha = f64::floor((z - 1_867_216.25) / 36_524.25);
+        z + 1.0 + alpha - f64::floor(alpha / 4.0)
+    } else {
+        z
+    };
+
+    let b = a + 1524.0;
+    let c = f64::floor((b - 122.1) / 365.25);
+    let d = f64::floor(365.25 * c);
+    let e = f64::floor((b - d) / 30.6001);
+
+    // Extra call to floor to ensure rounding mode
+    let day = f64::floor(b - d - f64::floor(30.6001 * e)) as i32;
+    let mut month = f64::floor(e - 1.0) as i32;
+    if e > 13.0 {
+        month = f64::floor(e - 13.0) as i32;
+    }
+
+    let year = f64::floor(if month > 2 { c - 4716.0 } else { c - 4715.0 }) as i32;
+
+    (year, month, day)
+}
+
+// Delta-t in seconds at a decimal year from the polynomial expressions of F. Espenak and J. Meeus,
+// "Five Millennium Canon of Solar Eclipses: -1999 to +3000", NASA/TP-2006-214141.
+fn espenak_meeus_delta_t(y: f64) -> f64 {
+    // Horner's method, with the coefficients in increasing powers.
+    let poly = |t: f64, c: &[f64]| c.iter().rev().fold(0.0, |acc, &c| acc * t + c);
+    let parabola = |y: f64| -20.0 + 32.0 * ((y - 1820.0) / 100.0).powi(2);
+
+    if y < -500.0 {
+        parabola(y)
+    } else if y < 500.0 {
+        poly(y / 100.0,
+             &[10_583.6, -1_014.41, 33.783_11, -5.952_053, -0.179_845_2, 0.022_174_192,
+               0.009_031_652_1])
+    } else if y < 1600.0 {
+        poly((y - 1000.0) / 100.0,
+             &[1_574.2, -556.01, 71.234_72, 0.319_781, -0.850_346_3, -0.005_050_998,
+               0.008_357_207_3])
+    } else if y < 1700.0 {
+        poly(y - 1600.0, &[120.0, -0.980_8, -0.015_32, 1.0 / 7_129.0])
+    } else if y < 1800.0 {
+        poly(y - 1700.0,
+             &[8.83, 0.160_3, -0.005_928_5, 0.000_133_36, -1.0 / 1_174_000.0])
+    } else if y < 1860.0 {
+        poly(y - 1800.0,
+             &[13.72, -0.332_447, 0.006_861_2, 0.004_111_6, -0.000_374_36, 0.000_012_127_2,
+               -0.000_000_169_9, 0.000_000_000_875])
+    } else if y < 1900.0 {
+        poly(y - 1860.0,
+             &[7.62, 0.573_7, -0.251_754, 0.016_806_68, -0.000_447_362_4, 1.0 / 233_174.0])
+    } else if y < 1920.0 {
+        poly(y - 1900.0, &[-2.79, 1.494_119, -0.059_893_9, 0.006_196_6, -0.000_197])
+    } else if y < 1941.0 {
+        poly(y - 1920.0, &[21.20, 0.844_93, -0.076_100, 0.002_093_6])
+    } else if y < 1961.0 {
+        poly(y - 1950.0, &[29.07, 0.407, -1.0 / 233.0, 1.0 / 2_547.0])
+    } else if y < 1986.0 {
+        poly(y - 1975.0, &[45.45, 1.067, -1.0 / 260.0, -1.0 / 718.0])
+    } else if y < 2005.0 {
+        poly(y - 2000.0,
+             &[63.86, 0.334_5, -0.060_374, 0.001_727_5, 0.000_651_814, 0.000_023_735_99])
+    } else if y < 2050.0 {
+        poly(y - 2000.0, &[62.92, 0.322_17, 0.005_589])
+    } else if y < 2150.0 {
+        parabola(y) - 0.562_8 * (2150.0 - y)
+    } else {
+        parabola(y)
+    }
+}
+
+// TDB - TT in seconds at a Julian Ephemeris Day, from USNO Circular 179 equation 2.6. The
+// difference is small enough that either time can be used as the argument.
+fn tdb_minus_tt(jde: f64) -> f64 {
+    let t = (jde - 2_451_545.0) / 36_525.0;
+
+    0.001_657 * sin(628.307_6 * t + 6.240_1) + 0.000_022 * sin(575.338_5 * t + 4.297_0) +
+    0.000_014 * sin(1_256.615_2 * t + 6.196_9) + 0.000_005 * sin(606.977_7 * t + 4.021_2) +
+    0.000_005 * sin(52.969_1 * t + 0.444_4) + 0.000_002 * sin(21.329_9 * t + 5.543_1) +
+    0.000_010 * t * sin(628.307_6 * t + 4.249_0)
+}
+
+// The leap seconds TAI - UTC in effect at a Julian Day in TAI, or in UTC if is_tai is false.
+fn tai_minus_utc(jd: f64, is_tai: bool) -> AstroResult<f64> {
+    use self::time_data::LEAP_SECONDS;
+
+    LEAP_SECONDS.iter()
+        .rev()
+        .find(|&&(start, offset)| {
+            let start = if is_tai { start + offset / 86_400.0 } else { start };
+            start <= jd
+        })
+        .map(|&(_, offset)| {
+            trace_event!(julian_day = jd, leap_seconds = offset, "TAI - UTC");
+            offset
+        })
+        .ok_or_else(|| {
+            AstroAlgorithmsError::RangeError(DateRangeError::DateUnderflow(jd, LEAP_SECONDS[0].0))
+        })
+}
+
+// Local mean time less universal time, longitudes are measured positive west.
+fn mean_time_offset(geo: GeoCoords) -> AstroDuration {
+    AstroDuration::from_hours(-geo.longitude().radians().to_degrees() / 15.0).unwrap()
 }
 
 ///
@@ -803,6 +2615,68 @@ pub fn month_and_day_gregorian(year: i32, day_of_year: i32) -> AstroResult<(i32,
     }
 }
 
+/// Calculate the date of Easter Sunday in the Gregorian calendar as a month and day.
+///
+/// Algorithm adapted from chapter 8, page 67 of Astronomical Algorithms, 2nd Edition by Jean
+/// Meeus. It is valid from 1583 on, when the Gregorian Easter was first kept.
+pub fn easter_gregorian(year: i32) -> AstroResult<(i32, i32)> {
+    if year < 1583 {
+        return Err(AstroAlgorithmsError::InvalidArgument(format!("The Gregorian Easter is not \
+                                                                  defined before 1583, {}",
+                                                                 year)));
+    }
+
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let n = h + l - 7 * m + 114;
+
+    Ok((n / 31, n % 31 + 1))
+}
+
+/// Calculate the date of Easter Sunday in the Julian calendar as a month and day.
+///
+/// Algorithm adapted from chapter 8, page 69 of Astronomical Algorithms, 2nd Edition by Jean
+/// Meeus. The Orthodox churches still keep this Easter, convert it to the Gregorian calendar with
+/// `Builder::from_julian_utc`.
+pub fn easter_julian(year: i32) -> AstroResult<(i32, i32)> {
+    if year < 1 {
+        return Err(AstroAlgorithmsError::InvalidArgument(format!("The Julian Easter is only \
+                                                                  calculated from the year 1, {}",
+                                                                 year)));
+    }
+
+    let a = year % 4;
+    let b = year % 7;
+    let c = year % 19;
+    let d = (19 * c + 15) % 30;
+    let e = (2 * a + 4 * b - d + 34) % 7;
+    let f = d + e + 114;
+
+    Ok((f / 31, f % 31 + 1))
+}
+
+/// Calculate the day of the week of a date in the Gregorian calendar.
+///
+/// Algorithm adapted from chapter 7, page 65 of Astronomical Algorithms, 2nd Edition by Jean
+/// Meeus.
+pub fn day_of_week_gregorian(year: i32, month: i32, day: i32) -> AstroResult<Weekday> {
+    if is_valid_gregorian(year, month, day) {
+        Ok(weekday(gregorian_julian_day(year, month, f64::from(day))))
+    } else {
+        Err(AstroAlgorithmsError::InvalidGregorianDate(year, month, day))
+    }
+}
+
 /// Is this a leap year in the Gregorian calendar
 pub fn is_gregorian_leap_year(year: i32) -> bool {
     if year % 4 != 0 {
@@ -840,7 +2714,30 @@ pub fn is_valid_julian(year: i32, month: i32, day: i32) -> bool {
     }
 }
 
+/// Check if a time in UTC is a leap second, 23:59:60 at the end of a day that one was added to
+/// according to the table of leap seconds.
+///
+/// Only the leap seconds announced in IERS Bulletin C before the table was compiled are known.
+pub fn is_leap_second(year: i32,
+                      month: i32,
+                      day: i32,
+                      hour: i32,
+                      minute: i32,
+                      second: i32)
+                      -> bool {
+    use self::time_data::LEAP_SECONDS;
+
+    if hour != 23 || minute != 59 || second != 60 || !is_valid_gregorian(year, month, day) {
+        return false;
+    }
+
+    let next_day = gregorian_julian_day(year, month, f64::from(day)) + 1.0;
+    LEAP_SECONDS.windows(2).any(|pair| pair[1].0 == next_day && pair[1].1 > pair[0].1)
+}
+
 /// Validate a time.
+///
+/// A leap second isn't a valid time on any day, see `is_leap_second`.
 pub fn is_valid_time(hour: i32, minute: i32, second: i32) -> bool {
     match hour {
         0...23 => (),
@@ -857,6 +2754,21 @@ pub fn is_valid_time(hour: i32, minute: i32, second: i32) -> bool {
     true
 }
 
+// The day of the week of a Julian Day, counting from Sunday with the days starting at midnight.
+fn weekday(julian_day: f64) -> Weekday {
+    use self::Weekday::*;
+
+    match ((julian_day + 1.5).floor() as i64).rem_euclid(7) {
+        0 => Sunday,
+        1 => Monday,
+        2 => Tuesday,
+        3 => Wednesday,
+        4 => Thursday,
+        5 => Friday,
+        _ => Saturday,
+    }
+}
+
 // The days per month in the Gregorian calendar.
 fn days_per_month_gregorian(month: i32, year: i32) -> i32 {
 
@@ -899,14 +2811,9 @@ fn to_hms(day_fraction: f64) -> (i32, i32, i32) {
     // Since this is private the module author controls validation before use.
     debug_assert!(day_fraction < 1.0);
 
-    let mut remainder = day_fraction - f64::floor(day_fraction);
-    let hour = f64::floor(remainder * 24.0) as i32;
-    remainder -= hour as f64 / 24.0;
-    let minute = f64::floor(remainder * 1_440.0) as i32;
-    remainder -= minute as f64 / 1_440.0;
-    let second = f64::floor(remainder * 86_400.0 + 0.5) as i32;
+    let seconds = f64::round((day_fraction - f64::floor(day_fraction)) * 86_400.0) as i32;
 
-    (hour, minute, second)
+    (seconds / 3_600, seconds % 3_600 / 60, seconds % 60)
 }
 
 // test approximate equality, only used in unit tests.
@@ -954,6 +2861,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_easter_gregorian() {
+        // The examples and table of chapter 8, Astronomical Algorithms 2nd ed.
+        for &(year, month, day) in &[(1991, 3, 31),
+                                     (1992, 4, 19),
+                                     (1993, 4, 11),
+                                     (1954, 4, 18),
+                                     (2000, 4, 23),
+                                     (1818, 3, 22),
+                                     (2285, 3, 22),
+                                     (1886, 4, 25),
+                                     (1943, 4, 25),
+                                     (2038, 4, 25)] {
+            assert_eq!(easter_gregorian(year).unwrap(), (month, day), "{}", year);
+        }
+        assert!(easter_gregorian(1582).is_err());
+    }
+
+    #[test]
+    fn test_easter_julian() {
+        for &year in &[179, 711, 1243] {
+            assert_eq!(easter_julian(year).unwrap(), (4, 12));
+        }
+
+        // The Orthodox Easter of 2017 was on the same day as the Gregorian Easter.
+        let (month, day) = easter_julian(2017).unwrap();
+        let orthodox = Builder::from_julian_utc(2017, month, day, 0, 0, 0).build().unwrap();
+        let (_, month, day, ..) = orthodox.to_gregorian_utc();
+        assert_eq!((month, day), easter_gregorian(2017).unwrap());
+        assert!(easter_julian(0).is_err());
+    }
+
     #[test]
     fn test_is_gregorian_leap_year() {
         assert!(is_gregorian_leap_year(1996));
@@ -964,6 +2903,24 @@ mod tests {
         assert!(!is_gregorian_leap_year(2010));
     }
 
+    #[test]
+    fn test_day_of_week() {
+        // Example 7.e, Astronomical Algorithms 2nd ed.
+        assert_eq!(day_of_week_gregorian(1954, 6, 30).unwrap(), Weekday::Wednesday);
+        assert_eq!(day_of_week_gregorian(2000, 1, 1).unwrap(), Weekday::Saturday);
+        assert_eq!(day_of_week_gregorian(1582, 10, 15).unwrap(), Weekday::Friday);
+        assert!(day_of_week_gregorian(2017, 2, 29).is_err());
+
+        // The UTC date, from late in the day and from dynamical time.
+        let time = Builder::from_gregorian_utc(2017, 9, 24, 23, 59, 59).build().unwrap();
+        assert_eq!(time.day_of_week().unwrap(), Weekday::Sunday);
+        let time = Builder::from_gregorian_utc(2017, 9, 25, 0, 0, 30)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        assert_eq!(time.day_of_week().unwrap(), Weekday::Sunday);
+    }
+
     #[test]
     fn test_is_julian_leap_year() {
         assert!(is_julian_leap_year(1996));
@@ -1033,4 +2990,18 @@ mod tests {
         assert!(!is_valid_time(4, 60, 1));
         assert!(!is_valid_time(4, 1, 60));
     }
+
+    #[test]
+    fn test_is_leap_second() {
+        assert!(is_leap_second(2016, 12, 31, 23, 59, 60));
+        assert!(is_leap_second(2015, 6, 30, 23, 59, 60));
+        assert!(is_leap_second(1972, 6, 30, 23, 59, 60));
+
+        // The first entry of the table is the start of UTC, not a leap second.
+        assert!(!is_leap_second(1971, 12, 31, 23, 59, 60));
+        assert!(!is_leap_second(2016, 12, 30, 23, 59, 60));
+        assert!(!is_leap_second(2016, 12, 31, 23, 58, 60));
+        assert!(!is_leap_second(2016, 12, 31, 23, 59, 59));
+        assert!(!is_leap_second(2016, 12, 32, 23, 59, 60));
+    }
 }
@@ -0,0 +1,129 @@
+//!
+//! Module for stepping through a range of times.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use super::super::error::*;
+use super::{AstroDuration, AstroTime};
+
+/// An iterator over times a fixed step apart, see `AstroTime::range_to`.
+#[derive(Debug, Clone)]
+pub struct TimeRange {
+    start: AstroTime,
+    step: f64,
+    index: usize,
+    count: usize,
+}
+
+impl AstroTime {
+    /// Step from this time towards a later one, up to but not including `end`.
+    ///
+    /// Each time is calculated from the start, so the errors of adding the steps don't accumulate.
+    /// The times have this time's type, and `end` is converted to it if the types are different.
+    /// Returns an error if the step isn't positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use astro_calc::astro_time::{AstroDuration, Builder};
+    /// let start = Builder::from_gregorian_utc(2017, 8, 21, 0, 0, 0).build().unwrap();
+    /// let end = Builder::from_gregorian_utc(2017, 8, 22, 0, 0, 0).build().unwrap();
+    /// let hour = AstroDuration::from_hours(1.0).unwrap();
+    ///
+    /// let times: Vec<_> = start.range_to(&end, hour).unwrap().collect();
+    /// assert_eq!(times.len(), 24);
+    /// assert_eq!(times[23].to_gregorian_utc(), (2017, 8, 21, 23, 0, 0));
+    /// ```
+    pub fn range_to(&self, end: &AstroTime, step: AstroDuration) -> AstroResult<TimeRange> {
+        let step = step.days();
+        if step <= 0.0 {
+            return Err(AstroAlgorithmsError::InvalidArgument("The step of a range of times \
+                                                              must be positive"
+                .to_owned()));
+        }
+
+        let span = -self.duration_since(end)?.days();
+        let mut range = TimeRange {
+            start: *self,
+            step,
+            index: 0,
+            count: f64::max((span / step).ceil(), 0.0) as usize,
+        };
+
+        // The division may round up past a last time that lands on the end.
+        while range.count > 0 && range.time(range.count - 1).duration_since(end)?.days() >= 0.0 {
+            range.count -= 1;
+        }
+
+        Ok(range)
+    }
+}
+
+impl TimeRange {
+    // The time a number of steps from the start.
+    fn time(&self, steps: usize) -> AstroTime {
+        self.start
+            .shift(steps as f64 * self.step, self.start.time_type)
+            .expect("Times after a valid time are valid")
+    }
+}
+
+impl Iterator for TimeRange {
+    type Item = AstroTime;
+
+    fn next(&mut self) -> Option<AstroTime> {
+        if self.index < self.count {
+            self.index += 1;
+            Some(self.time(self.index - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for TimeRange {}
+
+#[cfg(test)]
+mod range_tests {
+    use super::*;
+    use super::super::{Builder, TimeType};
+
+    #[test]
+    fn test_range_to() {
+        let start = Builder::from_gregorian_utc(2017, 8, 21, 0, 0, 0).build().unwrap();
+        let end = Builder::from_gregorian_utc(2017, 8, 31, 0, 0, 0).build().unwrap();
+        let day = AstroDuration::from_days(1.0).unwrap();
+
+        let range = start.range_to(&end, day).unwrap();
+        assert_eq!(range.len(), 10);
+        for (i, time) in range.enumerate() {
+            assert_eq!(time.to_gregorian_utc(), (2017, 8, 21 + i as i32, 0, 0, 0));
+        }
+
+        // Steps that don't divide the span evenly, and a tenth of a second, which isn't exact.
+        let range = start.range_to(&end, AstroDuration::from_hours(25.0).unwrap()).unwrap();
+        assert_eq!(range.count(), 10);
+        let tenth = AstroDuration::from_seconds(0.1).unwrap();
+        let second = (start + AstroDuration::from_seconds(1.0).unwrap()).unwrap();
+        assert_eq!(start.range_to(&second, tenth).unwrap().count(), 10);
+
+        // The end is converted to the type of the start.
+        let times: Vec<_> = start.range_to(&end.as_dt().unwrap(), day).unwrap().collect();
+        assert_eq!(times.len(), 10);
+        assert!(times.iter().all(|t| t.time_type() == TimeType::UT));
+
+        assert_eq!(end.range_to(&start, day).unwrap().count(), 0);
+        assert_eq!(start.range_to(&start, day).unwrap().count(), 0);
+        assert!(start.range_to(&end, -day).is_err());
+        assert!(start.range_to(&end, AstroDuration::from_days(0.0).unwrap()).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //!
 //! Module contains a list of dates and delta-t values, which are converted to
 //! Julian Days and delta-t values for converting between UTC and dynamical
-//! time.
+//! time, and the table of leap seconds for converting between UTC and TAI.
 //!
 //! Authors: Ryan Leach
 //!
@@ -765,4 +765,52 @@ lazy_static! {
 
         list
     };
+
+    // The Julian Days of the dates from which each difference TAI - UTC, in seconds, applies.
+    pub static ref LEAP_SECONDS: Vec<(f64,f64)> = {
+
+        let leap_second_date_list = vec![
+            // Values from the IERS Bulletin C.
+            (1972, 1, 1, 10.0),
+            (1972, 7, 1, 11.0),
+            (1973, 1, 1, 12.0),
+            (1974, 1, 1, 13.0),
+            (1975, 1, 1, 14.0),
+            (1976, 1, 1, 15.0),
+            (1977, 1, 1, 16.0),
+            (1978, 1, 1, 17.0),
+            (1979, 1, 1, 18.0),
+            (1980, 1, 1, 19.0),
+            (1981, 7, 1, 20.0),
+            (1982, 7, 1, 21.0),
+            (1983, 7, 1, 22.0),
+            (1985, 7, 1, 23.0),
+            (1988, 1, 1, 24.0),
+            (1990, 1, 1, 25.0),
+            (1991, 1, 1, 26.0),
+            (1992, 7, 1, 27.0),
+            (1993, 7, 1, 28.0),
+            (1994, 7, 1, 29.0),
+            (1996, 1, 1, 30.0),
+            (1997, 7, 1, 31.0),
+            (1999, 1, 1, 32.0),
+            (2006, 1, 1, 33.0),
+            (2009, 1, 1, 34.0),
+            (2012, 7, 1, 35.0),
+            (2015, 7, 1, 36.0),
+            (2017, 1, 1, 37.0)
+        ];
+
+        leap_second_date_list
+            .into_iter()
+            .map(|(year, month, day, offset)| {
+                let jd = Builder::from_gregorian_utc(year, month, day, 0, 0, 0)
+                    .build()
+                    // Ok to panic here, should always catch this during testing.
+                    .unwrap()
+                    .julian_day_number();
+                (jd, offset)
+            })
+            .collect()
+    };
 }
@@ -0,0 +1,159 @@
+//!
+//! Module for physical properties of the major bodies of the solar system.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The radii are from the report of the IAU Working Group on Cartographic Coordinates and
+//! Rotational Elements: 2015.
+//!
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+
+/// The major bodies of the solar system, other than the Earth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Body {
+    /// The Sun
+    Sun,
+    /// The Moon
+    Moon,
+    /// Mercury
+    Mercury,
+    /// Venus
+    Venus,
+    /// Mars
+    Mars,
+    /// Jupiter
+    Jupiter,
+    /// Saturn
+    Saturn,
+    /// Uranus
+    Uranus,
+    /// Neptune
+    Neptune,
+}
+
+impl Body {
+    /// The equatorial radius in kilometers.
+    pub fn equatorial_radius_km(self) -> f64 {
+        self.radii().0
+    }
+
+    /// The polar radius in kilometers.
+    pub fn polar_radius_km(self) -> f64 {
+        self.radii().1
+    }
+
+    // Equatorial and polar radii in kilometers.
+    fn radii(self) -> (f64, f64) {
+        match self {
+            Body::Sun => (695_700.0, 695_700.0),
+            Body::Moon => (1_737.4, 1_737.4),
+            Body::Mercury => (2_440.53, 2_438.26),
+            Body::Venus => (6_051.8, 6_051.8),
+            Body::Mars => (3_396.19, 3_376.20),
+            Body::Jupiter => (71_492.0, 66_854.0),
+            Body::Saturn => (60_268.0, 54_364.0),
+            Body::Uranus => (25_559.0, 24_973.0),
+            Body::Neptune => (24_764.0, 24_341.0),
+        }
+    }
+}
+
+/// Which diameter of a flattened body to calculate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiameterAxis {
+    /// The diameter along the equator.
+    Equatorial,
+    /// The apparent diameter along the projected rotation axis, when viewed from a planetocentric
+    /// latitude.
+    ///
+    /// This is the polar diameter when viewed from the plane of the body's equator, and grows to
+    /// the equatorial diameter when viewed from above a pole.
+    Polar(RadianAngle),
+}
+
+/// Calculate the apparent equatorial diameter of a body at a distance in kilometers.
+pub fn apparent_diameter(body: Body, distance_km: f64) -> AstroResult<RadianAngle> {
+    apparent_diameter_with(body, distance_km, DiameterAxis::Equatorial)
+}
+
+/// Calculate the apparent diameter of a body at a distance in kilometers along an axis.
+///
+/// The diameter is the angle subtended by the body, which is exact even for the Sun and Moon.
+/// Returns an error if the distance is not larger than the radius of the body.
+pub fn apparent_diameter_with(body: Body,
+                              distance_km: f64,
+                              axis: DiameterAxis)
+                              -> AstroResult<RadianAngle> {
+    let (a, b) = body.radii();
+    let radius = match axis {
+        DiameterAxis::Equatorial => a,
+        DiameterAxis::Polar(latitude) => {
+            let e2 = 1.0 - (b / a) * (b / a);
+            let cos_lat = cos(latitude.radians());
+            a * f64::sqrt(1.0 - e2 * cos_lat * cos_lat)
+        }
+    };
+
+    if distance_km.is_nan() {
+        Err(AstroAlgorithmsError::EncounteredNaN)
+    } else if distance_km <= radius {
+        Err(AstroAlgorithmsError::InvalidArgument("Distance must be greater than the radius of \
+                                                   the body"
+            .to_owned()))
+    } else {
+        RadianAngle::new(2.0 * asin(radius / distance_km))
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod bodies_tests {
+    use super::*;
+
+    fn arcseconds(angle: RadianAngle) -> f64 {
+        angle.radians().to_degrees() * 3600.0
+    }
+
+    #[test]
+    fn test_apparent_diameter() {
+        // The Moon in example 47.a, Astronomical Algorithms 2nd ed.
+        let d = apparent_diameter(Body::Moon, 368_409.7).unwrap();
+        assert!(approx_eq(arcseconds(d) / 2.0, 972.7, 0.1));
+
+        // The Sun at 1 AU.
+        let d = apparent_diameter(Body::Sun, ASTRONOMICAL_UNIT_KM).unwrap();
+        assert!(approx_eq(arcseconds(d) / 2.0, 959.2, 0.1));
+
+        assert!(apparent_diameter(Body::Moon, 1_000.0).is_err());
+        assert!(apparent_diameter(Body::Moon, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_polar_diameter() {
+        let distance = 5.0 * ASTRONOMICAL_UNIT_KM;
+        let equatorial = apparent_diameter(Body::Jupiter, distance).unwrap().radians();
+
+        let edge_on = DiameterAxis::Polar(RadianAngle::new(0.0).unwrap());
+        let polar = apparent_diameter_with(Body::Jupiter, distance, edge_on).unwrap().radians();
+        assert!(approx_eq(polar / equatorial, 66_854.0 / 71_492.0, 1.0e-9));
+
+        let pole_on = DiameterAxis::Polar(RadianAngle::new(90f64.to_radians()).unwrap());
+        let polar = apparent_diameter_with(Body::Jupiter, distance, pole_on).unwrap().radians();
+        assert!(approx_eq(polar, equatorial, 1.0e-15));
+
+        // Venus is round.
+        let d = apparent_diameter_with(Body::Venus, distance, edge_on).unwrap();
+        assert_eq!(d, apparent_diameter(Body::Venus, distance).unwrap());
+    }
+}
@@ -0,0 +1,261 @@
+//!
+//! Module for finding conjunctions of the Moon and planets.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! A conjunction is taken to be the time the angular separation of two bodies is least, sometimes
+//! called an appulse, rather than the time they have the same right ascension or longitude. The
+//! separations are geocentric, so for the Moon they can differ from those seen by an observer by
+//! up to a degree.
+//!
+use super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::bodies::Body;
+use super::coords::*;
+use super::ephemeris::{apparent_equatorial, CachedEphemeris};
+use super::error::*;
+use super::sun;
+
+// The bodies included in the catalog of conjunctions.
+const CATALOG_BODIES: [Body; 8] = [Body::Moon,
+                                   Body::Mercury,
+                                   Body::Venus,
+                                   Body::Mars,
+                                   Body::Jupiter,
+                                   Body::Saturn,
+                                   Body::Uranus,
+                                   Body::Neptune];
+
+// The precision of the time of a conjunction in days, about a second.
+const CONJUNCTION_PRECISION: f64 = 1.0e-5;
+
+/// The closest approach of two bodies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conjunction {
+    first: Body,
+    second: Body,
+    time: AstroTime,
+    separation: RadianAngle,
+    elongation: RadianAngle,
+}
+
+impl Conjunction {
+    /// The first body.
+    pub fn first(&self) -> Body {
+        self.first
+    }
+
+    /// The second body.
+    pub fn second(&self) -> Body {
+        self.second
+    }
+
+    /// The time of the least separation in Universal Time.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+
+    /// The geocentric separation of the bodies.
+    pub fn separation(&self) -> RadianAngle {
+        self.separation
+    }
+
+    /// The elongation of the first body from the Sun.
+    pub fn elongation(&self) -> RadianAngle {
+        self.elongation
+    }
+}
+
+/// Find the conjunctions of two bodies between two times, in order of time.
+///
+/// Every time the separation reaches a minimum is a conjunction, however wide. Minima at the ends
+/// of the range are not included.
+pub fn conjunctions(first: Body,
+                    second: Body,
+                    start: &AstroTime,
+                    end: &AstroTime)
+                    -> AstroResult<Vec<Conjunction>> {
+    let (jd_start, jd_end) = range(start, end)?;
+    if first == second {
+        return Err(AstroAlgorithmsError::InvalidArgument("A conjunction needs two different \
+                                                          bodies"
+            .to_owned()));
+    }
+
+    let first = CachedEphemeris::new(first, start, end, cache_step(first)?)?;
+    let second = CachedEphemeris::new(second, start, end, cache_step(second)?)?;
+
+    find_conjunctions(&first, &second, jd_start, jd_end)
+}
+
+/// Find all the conjunctions of the Moon and planets with each other closer than a separation
+/// between two times, in order of time.
+///
+/// The first body of each conjunction is the Moon or the planet nearer the Sun.
+pub fn conjunction_catalog(start: &AstroTime,
+                           end: &AstroTime,
+                           threshold: RadianAngle)
+                           -> AstroResult<Vec<Conjunction>> {
+    let (jd_start, jd_end) = range(start, end)?;
+
+    let mut caches = Vec::with_capacity(CATALOG_BODIES.len());
+    for &body in &CATALOG_BODIES {
+        caches.push(CachedEphemeris::new(body, start, end, cache_step(body)?)?);
+    }
+
+    let mut catalog = vec![];
+    for (i, first) in caches.iter().enumerate() {
+        for second in &caches[i + 1..] {
+            let found = find_conjunctions(first, second, jd_start, jd_end)?;
+            catalog.extend(found.into_iter()
+                .filter(|c| c.separation.radians() < threshold.radians()));
+        }
+    }
+
+    catalog.sort_by(|a, b| {
+        a.time.julian_day_number().partial_cmp(&b.time.julian_day_number()).unwrap()
+    });
+
+    Ok(catalog)
+}
+
+// Scan the separation of two bodies for minima, and refine them with a golden section search.
+fn find_conjunctions(first: &CachedEphemeris,
+                     second: &CachedEphemeris,
+                     jd_start: f64,
+                     jd_end: f64)
+                     -> AstroResult<Vec<Conjunction>> {
+    let separation = |jd: f64| -> AstroResult<f64> {
+        let time = ut(jd)?;
+        let a = first.apparent_equatorial(&time)?;
+        let b = second.apparent_equatorial(&time)?;
+        Ok(angular_separation(a, b).radians())
+    };
+
+    let step = f64::min(cache_step(first.body())?.days(), cache_step(second.body())?.days());
+    let steps = ((jd_end - jd_start) / step).ceil() as usize;
+    let mut samples = Vec::with_capacity(steps + 1);
+    for i in 0..(steps + 1) {
+        let jd = f64::min(jd_start + i as f64 * step, jd_end);
+        samples.push((jd, separation(jd)?));
+    }
+
+    let mut found = vec![];
+    for window in samples.windows(3) {
+        if window[1].1 >= window[0].1 || window[1].1 > window[2].1 {
+            continue;
+        }
+
+        let (mut lo, mut hi) = (window[0].0, window[2].0);
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        while hi - lo > CONJUNCTION_PRECISION {
+            let left = hi - ratio * (hi - lo);
+            let right = lo + ratio * (hi - lo);
+            if separation(left)? < separation(right)? {
+                hi = right;
+            } else {
+                lo = left;
+            }
+        }
+
+        // The separation and elongation from the full theories at the time.
+        let time = ut(0.5 * (lo + hi))?;
+        let a = apparent_equatorial(first.body(), &time)?;
+        let b = apparent_equatorial(second.body(), &time)?;
+        found.push(Conjunction {
+            first: first.body(),
+            second: second.body(),
+            time,
+            separation: angular_separation(a, b),
+            elongation: angular_separation(sun::apparent_equatorial(&time)?, a),
+        });
+    }
+
+    Ok(found)
+}
+
+// The Julian days of a range of times, which must be in order.
+fn range(start: &AstroTime, end: &AstroTime) -> AstroResult<(f64, f64)> {
+    let jd_start = start.as_utc()?.julian_day_number();
+    let jd_end = end.as_utc()?.julian_day_number();
+    if jd_end < jd_start {
+        Err(AstroAlgorithmsError::InvalidArgument("The end of the range for conjunctions must not \
+                                                   be before the start"
+            .to_owned()))
+    } else {
+        Ok((jd_start, jd_end))
+    }
+}
+
+// The step between the cached positions of a body, short enough for positions good to a few
+// arcseconds, and to sample the separation without missing a minimum.
+fn cache_step(body: Body) -> AstroResult<AstroDuration> {
+    match body {
+        Body::Moon => AstroDuration::from_hours(3.0),
+        _ => AstroDuration::from_days(1.0),
+    }
+}
+
+fn ut(jd: f64) -> AstroResult<AstroTime> {
+    Builder::from_julian_date(jd).build()
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod conjunctions_tests {
+    use super::*;
+
+    fn date(year: i32, month: i32, day: i32) -> AstroTime {
+        Builder::from_gregorian_utc(year, month, day, 0, 0, 0).build().unwrap()
+    }
+
+    #[test]
+    fn test_conjunctions() {
+        // The great conjunction of Jupiter and Saturn on 2020 December 21, 6' apart.
+        let (start, end) = (date(2020, 11, 1), date(2021, 2, 1));
+        let found = conjunctions(Body::Jupiter, Body::Saturn, &start, &end).unwrap();
+        assert_eq!(found.len(), 1);
+        let great = found[0];
+        assert_eq!(great.first(), Body::Jupiter);
+        assert_eq!(great.second(), Body::Saturn);
+        let days = great.time().duration_since(&date(2020, 12, 21)).unwrap().days();
+        assert!(days > 0.0 && days < 1.5);
+        assert!(great.separation().radians().to_degrees() < 0.2);
+        assert!(approx_eq(great.elongation().radians().to_degrees(), 30.0, 1.0));
+
+        assert!(conjunctions(Body::Mars, Body::Mars, &start, &end).is_err());
+    }
+
+    #[test]
+    fn test_conjunction_catalog() {
+        let threshold = RadianAngle::new(5f64.to_radians()).unwrap();
+        let catalog = conjunction_catalog(&date(2020, 12, 1), &date(2021, 1, 1), threshold)
+            .unwrap();
+
+        // The Moon passed Jupiter and Saturn on December 17.
+        let moon: Vec<_> = catalog.iter()
+            .filter(|c| c.first() == Body::Moon && c.second() == Body::Jupiter)
+            .collect();
+        assert_eq!(moon.len(), 1);
+        let days = moon[0].time().duration_since(&date(2020, 12, 17)).unwrap().days();
+        assert!(days > 0.0 && days < 1.0);
+
+        assert!(catalog.iter().any(|c| c.first() == Body::Jupiter && c.second() == Body::Saturn));
+        for pair in catalog.windows(2) {
+            assert!(pair[0].time() <= pair[1].time());
+        }
+        for c in &catalog {
+            assert!(c.separation().radians() < threshold.radians());
+        }
+
+        assert!(conjunction_catalog(&date(2021, 1, 1), &date(2020, 12, 1), threshold).is_err());
+    }
+}
@@ -15,6 +15,7 @@ use std::convert::From;
 use std::fmt;
 
 use super::super::error::*;
+use super::super::math::*;
 
 /// Represent an angle in radians.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -68,6 +69,11 @@ impl RadianAngle {
             Ok(RadianAngle { radians: radians })
         }
     }
+
+    /// Get the value of the angle in radians.
+    pub fn radians(&self) -> f64 {
+        self.radians
+    }
 }
 
 impl DegreeAngle {
@@ -81,6 +87,11 @@ impl DegreeAngle {
             Ok(DegreeAngle { degrees: degrees })
         }
     }
+
+    /// Get the value of the angle in decimal degrees.
+    pub fn degrees(&self) -> f64 {
+        self.degrees
+    }
 }
 
 impl DMSAngle {
@@ -105,6 +116,10 @@ impl DMSAngle {
             Err(AstroAlgorithmsError::EncounteredNaN)
         } else if seconds.is_infinite() {
             Err(AstroAlgorithmsError::EncounteredInf)
+        } else if minutes.abs() > 59 {
+            Err(AstroAlgorithmsError::InvalidAngle("Minutes limited to range [0,60)".to_owned()))
+        } else if seconds.abs() >= 60.0 {
+            Err(AstroAlgorithmsError::InvalidAngle("Seconds limited to range [0,60)".to_owned()))
         } else {
             Ok(DMSAngle {
                 degrees: degrees,
@@ -126,6 +141,10 @@ impl HMSAngle {
             Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue)
         } else if hours > 23 {
             Err(AstroAlgorithmsError::InvalidAngle("Hour limited to range [0,24)".to_owned()))
+        } else if minutes > 59 {
+            Err(AstroAlgorithmsError::InvalidAngle("Minutes limited to range [0,60)".to_owned()))
+        } else if seconds >= 60.0 {
+            Err(AstroAlgorithmsError::InvalidAngle("Seconds limited to range [0,60)".to_owned()))
         } else {
             Ok(HMSAngle {
                 hours: hours,
@@ -192,6 +211,10 @@ mod angle_constructor_tests {
 
         assert_eq!(DMSAngle::new(222, 22, f64::INFINITY).unwrap_err(),
                    AstroAlgorithmsError::EncounteredInf);
+
+        assert!(DMSAngle::new(-222, 60, 22.22).is_err());
+        assert!(DMSAngle::new(222, 22, 60.0).is_err());
+        assert!(DMSAngle::new(-222, 22, 59.99).is_ok());
     }
 
     #[test]
@@ -210,6 +233,10 @@ mod angle_constructor_tests {
                    AstroAlgorithmsError::EncounteredInappropriateNegativeValue);
         assert_eq!(HMSAngle::new(24, 22, 22.22).unwrap_err(),
                    AstroAlgorithmsError::InvalidAngle("Hour limited to range [0,24)".to_owned()));
+        assert_eq!(HMSAngle::new(22, 60, 22.22).unwrap_err(),
+                   AstroAlgorithmsError::InvalidAngle("Minutes limited to range [0,60)"
+                       .to_owned()));
+        assert!(HMSAngle::new(22, 22, 60.0).is_err());
     }
 }
 
@@ -248,14 +275,12 @@ impl From<DegreeAngle> for RadianAngle {
 }
 impl From<DMSAngle> for RadianAngle {
     fn from(dms: DMSAngle) -> Self {
-        let degrees = dms.degrees as f64 + (60 * dms.minutes) as f64 + 3600.0 * dms.seconds;
-        RadianAngle { radians: degrees.to_radians() }
+        RadianAngle { radians: dms_to_degrees(&dms).to_radians() }
     }
 }
 impl From<HMSAngle> for RadianAngle {
     fn from(hms: HMSAngle) -> Self {
-        let degrees = (hms.hours * 15) as f64 + (60 * hms.minutes) as f64 + 3600.0 * hms.seconds;
-        RadianAngle { radians: degrees.to_radians() }
+        RadianAngle { radians: hms_to_degrees(&hms).to_radians() }
     }
 }
 
@@ -266,31 +291,18 @@ impl From<RadianAngle> for DegreeAngle {
 }
 impl From<DMSAngle> for DegreeAngle {
     fn from(dms: DMSAngle) -> Self {
-        let degrees = dms.degrees as f64 + (60 * dms.minutes) as f64 + 3600.0 * dms.seconds;
-        DegreeAngle { degrees: degrees }
+        DegreeAngle { degrees: dms_to_degrees(&dms) }
     }
 }
 impl From<HMSAngle> for DegreeAngle {
     fn from(hms: HMSAngle) -> Self {
-        let degrees = (hms.hours * 15) as f64 + (60 * hms.minutes) as f64 + 3600.0 * hms.seconds;
-        DegreeAngle { degrees: degrees }
+        DegreeAngle { degrees: hms_to_degrees(&hms) }
     }
 }
 
 impl From<RadianAngle> for DMSAngle {
     fn from(radians: RadianAngle) -> Self {
-        let decimal_degrees = radians.radians.to_degrees();
-        let degrees = decimal_degrees.trunc();
-        let mut remainder = decimal_degrees - degrees;
-        let minutes = (remainder * 60.0).trunc();
-        remainder = remainder - minutes / 60.0;
-        let seconds = remainder * 3600.0;
-
-        DMSAngle {
-            degrees: degrees as i32,
-            minutes: minutes.abs() as i32,
-            seconds: seconds.abs(),
-        }
+        DMSAngle::from(DegreeAngle::from(radians))
     }
 }
 impl From<DegreeAngle> for DMSAngle {
@@ -301,49 +313,31 @@ impl From<DegreeAngle> for DMSAngle {
         remainder = remainder - minutes / 60.0;
         let seconds = remainder * 3600.0;
 
-        DMSAngle {
-            degrees: degrees as i32,
-            minutes: minutes.abs() as i32,
-            seconds: seconds.abs(),
+        if degrees == 0.0 {
+            // Zero degrees cannot carry a negative sign, so leave it on the minutes and seconds.
+            DMSAngle {
+                degrees: 0,
+                minutes: minutes as i32,
+                seconds,
+            }
+        } else {
+            DMSAngle {
+                degrees: degrees as i32,
+                minutes: minutes.abs() as i32,
+                seconds: seconds.abs(),
+            }
         }
     }
 }
 impl From<HMSAngle> for DMSAngle {
     fn from(hms: HMSAngle) -> Self {
-        let decimal_degrees = (hms.hours * 15) as f64 + (60 * hms.minutes) as f64 +
-                              3600.0 * hms.seconds;
-        let degrees = decimal_degrees.trunc();
-        let mut remainder = decimal_degrees - degrees;
-        let minutes = (remainder * 60.0).trunc();
-        remainder = remainder - 60.0 * minutes;
-        let seconds = remainder * 3600.0;
-
-        DMSAngle {
-            degrees: degrees as i32,
-            minutes: minutes.abs() as i32,
-            seconds: seconds.abs(),
-        }
+        DMSAngle::from(DegreeAngle { degrees: hms_to_degrees(&hms) })
     }
 }
 
 impl From<RadianAngle> for HMSAngle {
     fn from(radians: RadianAngle) -> Self {
-        let mut decimal_degrees = radians.radians.to_degrees();
-
-        // Force it to be between 0 and 360 degrees
-        decimal_degrees = map_to_branch(decimal_degrees, 0.0, 360.0);
-
-        let hours = (decimal_degrees / 15.0).trunc();
-        let mut remainder = decimal_degrees / 15.0 - hours;
-        let minutes = (remainder * 60.0).trunc();
-        remainder = remainder - minutes / 60.0;
-        let seconds = remainder * 3600.0;
-
-        HMSAngle {
-            hours: hours as i32,
-            minutes: minutes as i32,
-            seconds: seconds,
-        }
+        HMSAngle::from(DegreeAngle::from(radians))
     }
 }
 impl From<DegreeAngle> for HMSAngle {
@@ -364,23 +358,28 @@ impl From<DegreeAngle> for HMSAngle {
 }
 impl From<DMSAngle> for HMSAngle {
     fn from(dms: DMSAngle) -> Self {
-        let mut decimal_degrees = dms.degrees as f64 + (dms.minutes * 60) as f64 +
-                                  3600.0 * dms.seconds;
-        decimal_degrees = map_to_branch(decimal_degrees, 0.0, 360.0);
-        let hours = (decimal_degrees / 15.0).trunc();
-        let mut remainder = decimal_degrees - (hours * 15.0);
-        let minutes = (remainder * 60.0).trunc();
-        remainder = remainder - 60.0 * minutes;
-        let seconds = remainder * 3600.0;
+        HMSAngle::from(DegreeAngle { degrees: dms_to_degrees(&dms) })
+    }
+}
 
-        HMSAngle {
-            hours: hours as i32,
-            minutes: minutes.abs() as i32,
-            seconds: seconds.abs(),
-        }
+// Convert to decimal degrees. Any of the components may carry the sign, since a value of zero
+// degrees cannot be negative.
+fn dms_to_degrees(dms: &DMSAngle) -> f64 {
+    let magnitude = dms.degrees.abs() as f64 + dms.minutes.abs() as f64 / 60.0 +
+                    dms.seconds.abs() / 3600.0;
+
+    if dms.degrees < 0 || dms.minutes < 0 || dms.seconds < 0.0 {
+        -magnitude
+    } else {
+        magnitude
     }
 }
 
+// Convert to decimal degrees.
+fn hms_to_degrees(hms: &HMSAngle) -> f64 {
+    15.0 * (hms.hours as f64 + hms.minutes as f64 / 60.0 + hms.seconds / 3600.0)
+}
+
 #[cfg(test)]
 mod angle_from_tests {
     use super::*;
@@ -635,6 +634,38 @@ mod angle_from_tests {
         assert!(approx_eq(test_val.seconds, 0.0, 1.0e-10));
     }
 
+    #[test]
+    fn test_from_sexagesimal_with_minutes_and_seconds() {
+        assert!(approx_eq(DegreeAngle::from(DMSAngle::new(49, 13, 42.5).unwrap()).degrees,
+                          49.228_472_222,
+                          1.0e-9));
+        assert!(approx_eq(DegreeAngle::from(DMSAngle::new(-49, 13, 42.5).unwrap()).degrees,
+                          -49.228_472_222,
+                          1.0e-9));
+        assert!(approx_eq(DegreeAngle::from(HMSAngle::new(2, 44, 12.0).unwrap()).degrees,
+                          41.05,
+                          1.0e-12));
+        assert!(approx_eq(RadianAngle::from(HMSAngle::new(2, 44, 12.0).unwrap()).radians,
+                          41.05f64.to_radians(),
+                          1.0e-15));
+
+        // Negative angles smaller than a degree keep their sign.
+        let test_val = DMSAngle::from(DegreeAngle::new(-0.5).unwrap());
+        assert_eq!(test_val.degrees, 0);
+        assert_eq!(test_val.minutes, -30);
+        assert!(approx_eq(DegreeAngle::from(test_val).degrees, -0.5, 1.0e-12));
+
+        let test_val = DMSAngle::from(HMSAngle::new(2, 44, 12.0).unwrap());
+        assert_eq!(test_val.degrees, 41);
+        assert_eq!(test_val.minutes, 3);
+        assert!(approx_eq(test_val.seconds, 0.0, 1.0e-9));
+
+        let test_val = HMSAngle::from(DMSAngle::new(41, 3, 0.0).unwrap());
+        assert_eq!(test_val.hours, 2);
+        assert_eq!(test_val.minutes, 44);
+        assert!(approx_eq(test_val.seconds, 12.0, 1.0e-9));
+    }
+
     #[test]
     fn test_from_for_hms_angle() {
         //
@@ -753,7 +784,8 @@ mod angle_from_tests {
     }
 }
 
-fn map_to_branch(val: f64, min: f64, max: f64) -> f64 {
+// Map a value into the range [min, max], e.g. an angle into [0, 360].
+pub(crate) fn map_to_branch(val: f64, min: f64, max: f64) -> f64 {
     let range = max - min;
 
     if val < min {
@@ -767,6 +799,23 @@ fn map_to_branch(val: f64, min: f64, max: f64) -> f64 {
     }
 }
 
+// The longitude and latitude of the direction of a vector, which doesn't need to be a unit vector.
+pub(crate) fn vector_angles(v: [f64; 3]) -> AstroResult<(RadianAngle, RadianAngle)> {
+    if v.iter().any(|x| x.is_nan()) {
+        return Err(AstroAlgorithmsError::EncounteredNaN);
+    } else if v.iter().any(|x| x.is_infinite()) {
+        return Err(AstroAlgorithmsError::EncounteredInf);
+    } else if v.iter().all(|&x| x == 0.0) {
+        return Err(AstroAlgorithmsError::InvalidArgument("The zero vector has no direction"
+            .to_owned()));
+    }
+
+    let lon = atan2(v[1], v[0]);
+    let lat = atan2(v[2], f64::sqrt(v[0] * v[0] + v[1] * v[1]));
+
+    Ok((RadianAngle::new(lon)?, RadianAngle::new(lat)?))
+}
+
 // test approximate equality, only used in unit tests.
 #[cfg(test)]
 fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
@@ -0,0 +1,140 @@
+//!
+//! Module for the separations between all the targets of a list.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The positions are turned into unit vectors once, and every pair is compared, so the time grows
+//! with the square of the number of targets. With the `parallel` feature the targets are divided
+//! among threads with `rayon`.
+//!
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::angles::*;
+use super::equatorial::*;
+
+/// Calculate the angular separation of every pair of targets.
+///
+/// Row `i` holds the separations of target `i` from each of the targets, so the matrix is
+/// symmetric with zeros on the diagonal. The targets should be referred to the same epoch.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::J2000;
+/// # use astro_calc::coords::{separation_matrix, EquatorialCoords, RadianAngle};
+/// let target = |ra: f64, dec: f64| {
+///     EquatorialCoords::new(RadianAngle::new(ra.to_radians()).unwrap(),
+///                           RadianAngle::new(dec.to_radians()).unwrap(),
+///                           J2000)
+///         .unwrap()
+/// };
+/// let matrix = separation_matrix(&[target(10.0, 0.0), target(20.0, 0.0), target(10.0, 90.0)]);
+/// assert!((matrix[0][1].radians().to_degrees() - 10.0).abs() < 1.0e-12);
+/// assert!((matrix[2][1].radians().to_degrees() - 90.0).abs() < 1.0e-12);
+/// ```
+pub fn separation_matrix(targets: &[EquatorialCoords]) -> Vec<Vec<RadianAngle>> {
+    let vectors = unit_vectors(targets);
+    let row = |va: &[f64; 3]| -> Vec<RadianAngle> {
+        vectors.iter()
+            .map(|vb| RadianAngle::new(vector_separation(va, vb)).unwrap())
+            .collect()
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        vectors.par_iter().map(row).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        vectors.iter().map(row).collect()
+    }
+}
+
+/// Find the nearest other target to each of the targets, with its index and separation.
+///
+/// The result is `None` for a list of one target. If several are equally near, the first in the
+/// list is given. The targets should be referred to the same epoch.
+pub fn nearest_neighbors(targets: &[EquatorialCoords]) -> Vec<Option<(usize, RadianAngle)>> {
+    let vectors = unit_vectors(targets);
+    let nearest = |(i, va): (usize, &[f64; 3])| -> Option<(usize, RadianAngle)> {
+        vectors.iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(j, vb)| (j, vector_separation(va, vb)))
+            .fold(None, |nearest, (j, d)| match nearest {
+                Some((_, nearest_d)) if nearest_d <= d => nearest,
+                _ => Some((j, d)),
+            })
+            .map(|(j, d)| (j, RadianAngle::new(d).unwrap()))
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        vectors.par_iter().enumerate().map(nearest).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        vectors.iter().enumerate().map(nearest).collect()
+    }
+}
+
+fn unit_vectors(targets: &[EquatorialCoords]) -> Vec<[f64; 3]> {
+    targets.iter().map(|target| target.to_unit_vector()).collect()
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod crossmatch_tests {
+    use super::*;
+    use super::super::super::astro_time::J2000;
+
+    fn coords(ra: f64, dec: f64) -> EquatorialCoords {
+        EquatorialCoords::new(RadianAngle::new(ra.to_radians()).unwrap(),
+                              RadianAngle::new(dec.to_radians()).unwrap(),
+                              J2000)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_separation_matrix() {
+        let targets = [coords(10.0, 20.0), coords(200.0, -45.0), coords(10.5, 20.5)];
+        let matrix = separation_matrix(&targets);
+        assert_eq!(matrix.len(), 3);
+
+        for i in 0..3 {
+            assert_eq!(matrix[i][i].radians(), 0.0);
+            for j in 0..3 {
+                let expected = angular_separation(targets[i], targets[j]).radians();
+                assert!(approx_eq(matrix[i][j].radians(), expected, 1.0e-15));
+                assert_eq!(matrix[i][j], matrix[j][i]);
+            }
+        }
+
+        assert!(separation_matrix(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_nearest_neighbors() {
+        let targets = [coords(10.0, 20.0), coords(200.0, -45.0), coords(10.5, 20.5),
+                       coords(199.0, -45.0)];
+        let nearest = nearest_neighbors(&targets);
+        let indices: Vec<_> = nearest.iter().map(|n| n.unwrap().0).collect();
+        assert_eq!(indices, vec![2, 3, 0, 1]);
+        let (_, d) = nearest[0].unwrap();
+        let expected = angular_separation(targets[0], targets[2]).radians();
+        assert!(approx_eq(d.radians(), expected, 1.0e-15));
+
+        assert_eq!(nearest_neighbors(&targets[..1]), vec![None]);
+        assert!(nearest_neighbors(&[]).is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//!
+//! Module for the orientation of the apparent disk of a body on the sky.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! All position angles are measured from the north point of the disk towards the east, i.e.
+//! counter-clockwise as seen on the sky with north up and east to the left.
+//!
+use std::f64::consts::PI;
+
+use super::super::error::*;
+use super::angles::*;
+use super::equatorial::*;
+
+/// The orientation of the disk of the Sun or Moon, as needed to orient images and overlays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskOrientation {
+    axis_position_angle: RadianAngle,
+    center_latitude: RadianAngle,
+    bright_limb_position_angle: Option<RadianAngle>,
+}
+
+impl DiskOrientation {
+    /// Create a new disk orientation.
+    ///
+    /// Position angles are mapped into the range [0, 2π).
+    pub fn new(axis_position_angle: RadianAngle,
+               center_latitude: RadianAngle,
+               bright_limb_position_angle: Option<RadianAngle>)
+               -> AstroResult<DiskOrientation> {
+        let bright_limb_position_angle = match bright_limb_position_angle {
+            Some(chi) => Some(normalize_position_angle(chi)?),
+            None => None,
+        };
+
+        Ok(DiskOrientation {
+            axis_position_angle: normalize_position_angle(axis_position_angle)?,
+            center_latitude,
+            bright_limb_position_angle,
+        })
+    }
+
+    /// The position angle of the northern end of the axis of rotation.
+    pub fn axis_position_angle(&self) -> RadianAngle {
+        self.axis_position_angle
+    }
+
+    /// The latitude of the center of the disk on the body, B₀ for the Sun and the total libration
+    /// in latitude for the Moon. Positive when the north pole is tipped towards the Earth.
+    pub fn center_latitude(&self) -> RadianAngle {
+        self.center_latitude
+    }
+
+    /// The position angle of the midpoint of the illuminated limb, if the body is not self
+    /// luminous.
+    pub fn bright_limb_position_angle(&self) -> Option<RadianAngle> {
+        self.bright_limb_position_angle
+    }
+}
+
+/// Calculate the position angle of the midpoint of the bright limb of a body illuminated by the
+/// Sun, equation 48.5.
+///
+/// Both coordinates should be apparent geocentric coordinates of the same date.
+pub fn bright_limb_position_angle(body: EquatorialCoords,
+                                  sun: EquatorialCoords)
+                                  -> AstroResult<RadianAngle> {
+    Ok(position_angle(body, sun))
+}
+
+// Map a position angle into the range [0, 2π).
+fn normalize_position_angle(angle: RadianAngle) -> AstroResult<RadianAngle> {
+    let mut pa = map_to_branch(angle.radians(), 0.0, 2.0 * PI);
+    if pa >= 2.0 * PI {
+        pa -= 2.0 * PI;
+    }
+
+    RadianAngle::new(pa)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod disk_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    #[test]
+    fn test_bright_limb_position_angle() {
+        // Example 48.a, Astronomical Algorithms 2nd ed.
+        let time = Builder::from_julian_date(2_448_724.5).dynamical_time().build().unwrap();
+        let moon = EquatorialCoords::new(RadianAngle::new(134.688_5f64.to_radians()).unwrap(),
+                                         RadianAngle::new(13.768_4f64.to_radians()).unwrap(),
+                                         time)
+            .unwrap();
+        let sun = EquatorialCoords::new(RadianAngle::new(20.651_1f64.to_radians()).unwrap(),
+                                        RadianAngle::new(8.690_4f64.to_radians()).unwrap(),
+                                        time)
+            .unwrap();
+
+        let chi = bright_limb_position_angle(moon, sun).unwrap();
+        assert!(approx_eq(chi.radians().to_degrees(), 285.0, 0.05));
+    }
+
+    #[test]
+    fn test_disk_orientation_new() {
+        let test_subject = DiskOrientation::new(RadianAngle::new(-0.5).unwrap(),
+                                                RadianAngle::new(0.1).unwrap(),
+                                                None)
+            .unwrap();
+        assert!(approx_eq(test_subject.axis_position_angle().radians(), 2.0 * PI - 0.5, 1.0e-15));
+        assert_eq!(test_subject.center_latitude().radians(), 0.1);
+        assert!(test_subject.bright_limb_position_angle().is_none());
+    }
+}
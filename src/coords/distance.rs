@@ -0,0 +1,121 @@
+//!
+//! Module for distances in the solar system and the time light takes to cross them.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use super::super::astro_time::{AstroDuration, AstroTime};
+use super::super::error::*;
+
+/// The astronomical unit in kilometers, IAU 2012.
+pub const ASTRONOMICAL_UNIT_KM: f64 = 149_597_870.7;
+
+/// The speed of light in kilometers per second.
+pub const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
+
+/// Convert a distance in astronomical units to kilometers.
+pub fn au_to_km(distance_au: f64) -> f64 {
+    distance_au * ASTRONOMICAL_UNIT_KM
+}
+
+/// Convert a distance in kilometers to astronomical units.
+pub fn km_to_au(distance_km: f64) -> f64 {
+    distance_km / ASTRONOMICAL_UNIT_KM
+}
+
+/// Calculate the time light takes to travel a distance in astronomical units.
+pub fn light_travel_time(distance_au: f64) -> AstroResult<AstroDuration> {
+    AstroDuration::from_seconds(au_to_km(distance_au) / SPEED_OF_LIGHT_KM_S)
+}
+
+/// Calculate the distance in astronomical units light travels in a duration.
+pub fn light_travel_distance(duration: AstroDuration) -> f64 {
+    km_to_au(duration.seconds() * SPEED_OF_LIGHT_KM_S)
+}
+
+/// Calculate the time light left a body at a distance in astronomical units to arrive at `time`.
+pub fn antedate(time: &AstroTime, distance_au: f64) -> AstroResult<AstroTime> {
+    time.add_duration(-light_travel_time(distance_au)?)
+}
+
+/// Calculate a position corrected for the light time, by evaluating it at the time the light left
+/// the body.
+///
+/// The function `position` calculates the geometric position of the body and its distance in
+/// astronomical units at a time. Starting from the distance at `time`, it is repeatedly evaluated
+/// at the antedated time until the light time changes by less than a millisecond. The position is
+/// returned along with the light time.
+pub fn light_time_corrected<T, F>(time: &AstroTime, position: F) -> AstroResult<(T, AstroDuration)>
+    where F: Fn(&AstroTime) -> AstroResult<(T, f64)>
+{
+    let (mut pos, mut distance) = position(time)?;
+    let mut tau = light_travel_time(0.0)?;
+
+    for _ in 0..10 {
+        let new_tau = light_travel_time(distance)?;
+        let converged = (new_tau - tau).seconds().abs() < 1.0e-3;
+        tau = new_tau;
+
+        let next = position(&time.add_duration(-tau)?)?;
+        pos = next.0;
+        distance = next.1;
+
+        if converged {
+            break;
+        }
+    }
+
+    Ok((pos, tau))
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod distance_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(au_to_km(2.0), 299_195_741.4);
+        assert!(approx_eq(km_to_au(au_to_km(5.2)), 5.2, 1.0e-15));
+
+        // Light takes 499.004 784 seconds to cross 1 AU.
+        let tau = light_travel_time(1.0).unwrap();
+        assert!(approx_eq(tau.seconds(), 499.004_784, 1.0e-6));
+        assert!(approx_eq(light_travel_distance(tau), 1.0, 1.0e-15));
+    }
+
+    #[test]
+    fn test_antedate() {
+        let time = Builder::from_julian_date(2_451_545.0).dynamical_time().build().unwrap();
+        let earlier = antedate(&time, 1.0).unwrap();
+        assert!(approx_eq(time.duration_since(&earlier).unwrap().seconds(), 499.0, 0.01));
+        assert!(earlier < time);
+    }
+
+    #[test]
+    fn test_light_time_corrected() {
+        // A body receding at 0.01 AU per day from 1 AU at JD 2451545.0.
+        let time = Builder::from_julian_date(2_451_545.0).dynamical_time().build().unwrap();
+        let receding = |t: &AstroTime| {
+            let distance = 1.0 + 0.01 * (t.julian_day_number() - 2_451_545.0);
+            Ok((t.julian_day_number(), distance))
+        };
+
+        let (jd, tau) = light_time_corrected(&time, receding).unwrap();
+
+        // The light time satisfies tau = r(t - tau) / c.
+        let days_per_au = light_travel_time(1.0).unwrap().days();
+        let expected = days_per_au / (1.0 + 0.01 * days_per_au);
+        assert!(approx_eq(tau.days(), expected, 1.0e-10));
+        assert!(approx_eq(jd, 2_451_545.0 - tau.days(), 1.0e-8));
+    }
+}
@@ -0,0 +1,223 @@
+//!
+//! Module for ecliptic (celestial longitude and latitude) coordinates.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::super::astro_time::{AstroTime, Epoch};
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+use super::nutation::*;
+
+/// The equinox ecliptic longitudes are measured from.
+///
+/// The ecliptic of a date and the ecliptic of J2000.0 differ by the precession, about 50" a year,
+/// which is tracked by the epoch of the coordinates. The nutation moves the equinox back and forth
+/// along the ecliptic by up to 17" more, which is tracked by the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EclipticFrame {
+    /// The mean equinox of the epoch, as for catalog positions and the VSOP87 theory.
+    Mean,
+    /// The true equinox of the epoch, corrected for the nutation in longitude, as for apparent
+    /// positions.
+    True,
+}
+
+/// Ecliptic coordinates referred to the ecliptic and equinox of an epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EclipticCoords {
+    longitude: RadianAngle,
+    latitude: RadianAngle,
+    epoch: AstroTime,
+    frame: EclipticFrame,
+}
+
+impl EclipticCoords {
+    /// Create new coordinates.
+    ///
+    /// The latitude must be in the range [-π/2, π/2], and the longitude is mapped into the range
+    /// [0, 2π). The coordinates are referred to the mean equinox, see `with_frame`.
+    pub fn new(longitude: RadianAngle,
+               latitude: RadianAngle,
+               epoch: AstroTime)
+               -> AstroResult<EclipticCoords> {
+        if latitude.radians().abs() > FRAC_PI_2 {
+            Err(AstroAlgorithmsError::InvalidAngle("Latitude limited to range [-90, 90] degrees"
+                .to_owned()))
+        } else {
+            let mut lon = map_to_branch(longitude.radians(), 0.0, 2.0 * PI);
+            if lon >= 2.0 * PI {
+                lon -= 2.0 * PI;
+            }
+
+            Ok(EclipticCoords {
+                longitude: RadianAngle::new(lon)?,
+                latitude,
+                epoch,
+                frame: EclipticFrame::Mean,
+            })
+        }
+    }
+
+    /// Create new coordinates referred to a standard epoch or a catalog equinox, see `new`.
+    ///
+    /// Returns an error if the epoch has no valid time, see `Epoch::time`.
+    pub fn with_epoch(longitude: RadianAngle,
+                      latitude: RadianAngle,
+                      epoch: Epoch)
+                      -> AstroResult<EclipticCoords> {
+        EclipticCoords::new(longitude, latitude, epoch.time()?)
+    }
+
+    /// The celestial longitude, measured from the vernal equinox along the ecliptic.
+    pub fn longitude(&self) -> RadianAngle {
+        self.longitude
+    }
+
+    /// The celestial latitude, positive north of the ecliptic.
+    pub fn latitude(&self) -> RadianAngle {
+        self.latitude
+    }
+
+    /// The celestial longitude in decimal degrees.
+    pub fn lon_degrees(&self) -> f64 {
+        self.longitude.radians().to_degrees()
+    }
+
+    /// The celestial latitude in decimal degrees.
+    pub fn lat_degrees(&self) -> f64 {
+        self.latitude.radians().to_degrees()
+    }
+
+    /// The epoch of the ecliptic and equinox these coordinates are referred to.
+    pub fn epoch(&self) -> AstroTime {
+        self.epoch
+    }
+
+    /// Whether these coordinates are referred to the mean or the true equinox of the epoch.
+    pub fn frame(&self) -> EclipticFrame {
+        self.frame
+    }
+
+    /// Mark the equinox these coordinates are referred to, without changing them.
+    pub fn with_frame(self, frame: EclipticFrame) -> EclipticCoords {
+        EclipticCoords { frame, ..self }
+    }
+
+    /// Refer these coordinates to the mean or the true equinox of the same epoch, by removing or
+    /// adding the nutation in longitude, Δψ.
+    pub fn to_frame(&self, frame: EclipticFrame) -> AstroResult<EclipticCoords> {
+        let delta_psi = match (self.frame, frame) {
+            (EclipticFrame::Mean, EclipticFrame::True) => nutation(&self.epoch)?.0.radians(),
+            (EclipticFrame::True, EclipticFrame::Mean) => -nutation(&self.epoch)?.0.radians(),
+            _ => return Ok(*self),
+        };
+
+        Ok(EclipticCoords::new(RadianAngle::new(self.longitude.radians() + delta_psi)?,
+                               self.latitude,
+                               self.epoch)?
+            .with_frame(frame))
+    }
+
+    /// The Cartesian unit vector pointing at these coordinates, with the x axis towards the
+    /// equinox and the z axis towards the north ecliptic pole.
+    pub fn to_unit_vector(&self) -> [f64; 3] {
+        let (lon, lat) = (self.longitude.radians(), self.latitude.radians());
+
+        [cos(lat) * cos(lon), cos(lat) * sin(lon), sin(lat)]
+    }
+
+    /// Create coordinates pointing along a vector, which doesn't need to be a unit vector.
+    ///
+    /// Returns an error for the zero vector, or a vector with a NaN or infinite component.
+    pub fn from_unit_vector(v: [f64; 3], epoch: AstroTime) -> AstroResult<EclipticCoords> {
+        let (lon, lat) = vector_angles(v)?;
+
+        EclipticCoords::new(lon, lat, epoch)
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod ecliptic_tests {
+    use super::*;
+    use astro_time::{Builder, J2000};
+
+    #[test]
+    fn test_ecliptic_coords_new() {
+        let test_subject = EclipticCoords::new(RadianAngle::new(-FRAC_PI_2).unwrap(),
+                                               RadianAngle::new(0.25).unwrap(),
+                                               J2000)
+            .unwrap();
+        assert!(approx_eq(test_subject.longitude().radians(), 1.5 * PI, 1.0e-15));
+        assert_eq!(test_subject.latitude().radians(), 0.25);
+        assert!(test_subject.epoch() == J2000);
+        assert!(approx_eq(test_subject.lon_degrees(), 270.0, 1.0e-12));
+        assert_eq!(test_subject.lat_degrees(), 0.25f64.to_degrees());
+
+        assert!(EclipticCoords::new(RadianAngle::new(0.0).unwrap(),
+                                    RadianAngle::new(2.0).unwrap(),
+                                    J2000)
+            .is_err());
+
+        let lon = RadianAngle::new(1.0).unwrap();
+        let lat = RadianAngle::new(0.5).unwrap();
+        let j2000 = EclipticCoords::with_epoch(lon, lat, Epoch::J2000).unwrap();
+        assert!(j2000.epoch() == J2000);
+        assert!(EclipticCoords::with_epoch(lon, lat, Epoch::Besselian(-10_000.0)).is_err());
+    }
+
+    #[test]
+    fn test_to_frame() {
+        let time = Builder::from_gregorian_utc(1987, 4, 10, 0, 0, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let mean = EclipticCoords::new(RadianAngle::new(1.0).unwrap(),
+                                       RadianAngle::new(0.1).unwrap(),
+                                       time)
+            .unwrap();
+        assert_eq!(mean.frame(), EclipticFrame::Mean);
+
+        // Example 22.a, Astronomical Algorithms 2nd ed., Δψ = -3.788".
+        let apparent = mean.to_frame(EclipticFrame::True).unwrap();
+        assert_eq!(apparent.frame(), EclipticFrame::True);
+        let seconds = (apparent.longitude().radians() - 1.0).to_degrees() * 3600.0;
+        assert!(approx_eq(seconds, -3.788, 1.0e-3));
+        assert_eq!(apparent.latitude(), mean.latitude());
+
+        let back = apparent.to_frame(EclipticFrame::Mean).unwrap();
+        assert!(approx_eq(back.longitude().radians(), 1.0, 1.0e-15));
+        assert!(mean.to_frame(EclipticFrame::Mean).unwrap() == mean);
+        assert!(mean.with_frame(EclipticFrame::True).longitude() == mean.longitude());
+    }
+
+    #[test]
+    fn test_unit_vector() {
+        let test_subject = EclipticCoords::new(RadianAngle::new(2.0).unwrap(),
+                                               RadianAngle::new(-0.5).unwrap(),
+                                               J2000)
+            .unwrap();
+        let v = test_subject.to_unit_vector();
+        assert!(approx_eq(v[0] * v[0] + v[1] * v[1] + v[2] * v[2], 1.0, 1.0e-15));
+        assert!(approx_eq(v[2], f64::sin(-0.5), 1.0e-15));
+
+        let back = EclipticCoords::from_unit_vector([3.0 * v[0], 3.0 * v[1], 3.0 * v[2]], J2000)
+            .unwrap();
+        assert!(approx_eq(back.longitude().radians(), 2.0, 1.0e-12));
+        assert!(approx_eq(back.latitude().radians(), -0.5, 1.0e-12));
+
+        assert!(EclipticCoords::from_unit_vector([0.0, 0.0, 0.0], J2000).is_err());
+    }
+}
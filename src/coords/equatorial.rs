@@ -0,0 +1,385 @@
+//!
+//! Module for equatorial (right ascension and declination) coordinates.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::super::astro_time::{AstroTime, Epoch};
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+use super::precession::*;
+
+/// Equatorial coordinates referred to the mean equator and equinox of an epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquatorialCoords {
+    right_ascension: RadianAngle,
+    declination: RadianAngle,
+    epoch: AstroTime,
+}
+
+impl EquatorialCoords {
+    /// Create new coordinates.
+    ///
+    /// The declination must be in the range [-π/2, π/2], and the right ascension is mapped into
+    /// the range [0, 2π).
+    pub fn new(right_ascension: RadianAngle,
+               declination: RadianAngle,
+               epoch: AstroTime)
+               -> AstroResult<EquatorialCoords> {
+        if declination.radians().abs() > FRAC_PI_2 {
+            Err(AstroAlgorithmsError::InvalidAngle("Declination limited to range [-90, 90] \
+                                                    degrees"
+                .to_owned()))
+        } else {
+            let mut ra = map_to_branch(right_ascension.radians(), 0.0, 2.0 * PI);
+            if ra >= 2.0 * PI {
+                ra -= 2.0 * PI;
+            }
+
+            Ok(EquatorialCoords {
+                right_ascension: RadianAngle::new(ra)?,
+                declination,
+                epoch,
+            })
+        }
+    }
+
+    /// Create new coordinates referred to a standard epoch or a catalog equinox, see `new`.
+    ///
+    /// Returns an error if the epoch has no valid time, see `Epoch::time`.
+    pub fn with_epoch(right_ascension: RadianAngle,
+                      declination: RadianAngle,
+                      epoch: Epoch)
+                      -> AstroResult<EquatorialCoords> {
+        EquatorialCoords::new(right_ascension, declination, epoch.time()?)
+    }
+
+    /// The right ascension.
+    pub fn right_ascension(&self) -> RadianAngle {
+        self.right_ascension
+    }
+
+    /// The declination.
+    pub fn declination(&self) -> RadianAngle {
+        self.declination
+    }
+
+    /// The right ascension in decimal degrees.
+    pub fn ra_degrees(&self) -> f64 {
+        self.right_ascension.radians().to_degrees()
+    }
+
+    /// The right ascension in decimal hours.
+    pub fn ra_hours(&self) -> f64 {
+        self.ra_degrees() / 15.0
+    }
+
+    /// The declination in decimal degrees.
+    pub fn dec_degrees(&self) -> f64 {
+        self.declination.radians().to_degrees()
+    }
+
+    /// The epoch of the equator and equinox these coordinates are referred to.
+    pub fn epoch(&self) -> AstroTime {
+        self.epoch
+    }
+
+    /// The Cartesian unit vector pointing at these coordinates, with the x axis towards the
+    /// equinox and the z axis towards the north celestial pole.
+    ///
+    /// Separations, rotations, and interpolation are better behaved with vectors than with the
+    /// angles, which are singular at the poles.
+    pub fn to_unit_vector(&self) -> [f64; 3] {
+        let (ra, dec) = (self.right_ascension.radians(), self.declination.radians());
+
+        [cos(dec) * cos(ra), cos(dec) * sin(ra), sin(dec)]
+    }
+
+    /// Create coordinates pointing along a vector, which doesn't need to be a unit vector.
+    ///
+    /// Returns an error for the zero vector, or a vector with a NaN or infinite component.
+    pub fn from_unit_vector(v: [f64; 3], epoch: AstroTime) -> AstroResult<EquatorialCoords> {
+        let (lon, lat) = vector_angles(v)?;
+
+        EquatorialCoords::new(lon, lat, epoch)
+    }
+}
+
+/// Calculate the angular separation between two positions, chapter 17.
+///
+/// Both positions should be referred to the same epoch. The separation is calculated from the
+/// cross and dot products of the direction vectors, which stays accurate for very small and
+/// nearly opposite separations where equation 17.1 breaks down.
+pub fn angular_separation(a: EquatorialCoords, b: EquatorialCoords) -> RadianAngle {
+    RadianAngle::new(vector_separation(&a.to_unit_vector(), &b.to_unit_vector())).unwrap()
+}
+
+// The angle in radians between two unit vectors.
+pub(crate) fn vector_separation(va: &[f64; 3], vb: &[f64; 3]) -> f64 {
+    let dot = va[0] * vb[0] + va[1] * vb[1] + va[2] * vb[2];
+    let cross = [va[1] * vb[2] - va[2] * vb[1],
+                 va[2] * vb[0] - va[0] * vb[2],
+                 va[0] * vb[1] - va[1] * vb[0]];
+    let sin_d = f64::sqrt(cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]);
+
+    atan2(sin_d, dot)
+}
+
+/// Calculate the angular separation between two positions, referring `b` to the epoch of `a`
+/// according to `mismatch`, see `match_epoch`.
+pub fn angular_separation_checked(a: EquatorialCoords,
+                                  b: EquatorialCoords,
+                                  mismatch: EpochMismatch)
+                                  -> AstroResult<RadianAngle> {
+    Ok(angular_separation(a, match_epoch(b, a.epoch, mismatch)?))
+}
+
+/// Calculate the position angle of `to` as seen from `from`, measured from the north towards the
+/// east in the range [0, 2π).
+///
+/// Both positions should be referred to the same epoch. The position angle is undefined if they
+/// coincide, and zero is returned.
+pub fn position_angle(from: EquatorialCoords, to: EquatorialCoords) -> RadianAngle {
+    let (sin_dec_0, cos_dec_0) = sin_cos(to.declination().radians());
+    let (sin_dec, cos_dec) = sin_cos(from.declination().radians());
+    let (sin_d_ra, cos_d_ra) = sin_cos(to.right_ascension().radians() -
+                                       from.right_ascension().radians());
+
+    let chi = atan2(cos_dec_0 * sin_d_ra,
+                    sin_dec_0 * cos_dec - cos_dec_0 * sin_dec * cos_d_ra);
+
+    let mut pa = map_to_branch(chi, 0.0, 2.0 * PI);
+    if pa >= 2.0 * PI {
+        pa -= 2.0 * PI;
+    }
+
+    RadianAngle::new(pa).unwrap()
+}
+
+/// Calculate the position angle of `to` as seen from `from`, referring `to` to the epoch of
+/// `from` according to `mismatch`, see `match_epoch`.
+pub fn position_angle_checked(from: EquatorialCoords,
+                              to: EquatorialCoords,
+                              mismatch: EpochMismatch)
+                              -> AstroResult<RadianAngle> {
+    Ok(position_angle(from, match_epoch(to, from.epoch, mismatch)?))
+}
+
+/// Calculate `n` evenly spaced points along the great circle from `a` to `b`, including both end
+/// points.
+///
+/// This is useful for plotting constellation lines and telescope slew paths. The points are
+/// referred to the epoch of `a`. An error is returned if `b` is referred to a different epoch, if
+/// `n` is less than 2, or if the points are diametrically opposite so the great circle is not
+/// defined.
+pub fn great_circle_points(a: EquatorialCoords,
+                           b: EquatorialCoords,
+                           n: usize)
+                           -> AstroResult<Vec<EquatorialCoords>> {
+    great_circle_points_checked(a, b, n, EpochMismatch::Error)
+}
+
+/// Calculate `n` evenly spaced points along the great circle from `a` to `b` as in
+/// `great_circle_points`, referring `b` to the epoch of `a` according to `mismatch`, see
+/// `match_epoch`.
+pub fn great_circle_points_checked(a: EquatorialCoords,
+                                   b: EquatorialCoords,
+                                   n: usize,
+                                   mismatch: EpochMismatch)
+                                   -> AstroResult<Vec<EquatorialCoords>> {
+    let b = match_epoch(b, a.epoch, mismatch)?;
+    if n < 2 {
+        return Err(AstroAlgorithmsError::InvalidArgument("At least 2 points are required"
+            .to_owned()));
+    }
+
+    let va = a.to_unit_vector();
+    let vb = b.to_unit_vector();
+
+    let dot = va[0] * vb[0] + va[1] * vb[1] + va[2] * vb[2];
+    let cross = [va[1] * vb[2] - va[2] * vb[1],
+                 va[2] * vb[0] - va[0] * vb[2],
+                 va[0] * vb[1] - va[1] * vb[0]];
+    let sin_omega = f64::sqrt(cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]);
+    let omega = atan2(sin_omega, dot);
+
+    if sin_omega < 1.0e-12 && dot < 0.0 {
+        return Err(AstroAlgorithmsError::InvalidAngle("Great circle between antipodal points is \
+                                                       not defined"
+            .to_owned()));
+    }
+
+    let mut points = Vec::with_capacity(n);
+    points.push(a);
+    for i in 1..(n - 1) {
+        let t = i as f64 / (n - 1) as f64;
+
+        // Spherical linear interpolation, the points coincide if omega is zero.
+        let (wa, wb) = if sin_omega < 1.0e-12 {
+            (1.0 - t, t)
+        } else {
+            (sin((1.0 - t) * omega) / sin_omega, sin(t * omega) / sin_omega)
+        };
+
+        let v = [wa * va[0] + wb * vb[0], wa * va[1] + wb * vb[1], wa * va[2] + wb * vb[2]];
+        points.push(EquatorialCoords::from_unit_vector(v, a.epoch)?);
+    }
+    points.push(EquatorialCoords { epoch: a.epoch, ..b });
+
+    Ok(points)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod equatorial_tests {
+    use super::*;
+    use astro_time::J2000;
+
+    fn coords(ra_deg: f64, dec_deg: f64) -> EquatorialCoords {
+        EquatorialCoords::new(RadianAngle::new(ra_deg.to_radians()).unwrap(),
+                              RadianAngle::new(dec_deg.to_radians()).unwrap(),
+                              J2000)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_equatorial_coords_new() {
+        let test_subject = coords(-90.0, 45.0);
+        assert!(approx_eq(test_subject.right_ascension().radians(), 1.5 * PI, 1.0e-15));
+        assert!(approx_eq(test_subject.declination().radians(), PI / 4.0, 1.0e-15));
+        assert!(test_subject.epoch() == J2000);
+        assert!(approx_eq(test_subject.ra_degrees(), 270.0, 1.0e-12));
+        assert!(approx_eq(test_subject.ra_hours(), 18.0, 1.0e-12));
+        assert!(approx_eq(test_subject.dec_degrees(), 45.0, 1.0e-12));
+
+        assert!(EquatorialCoords::new(RadianAngle::new(0.0).unwrap(),
+                                      RadianAngle::new(-2.0).unwrap(),
+                                      J2000)
+            .is_err());
+
+        let ra = RadianAngle::new(1.0).unwrap();
+        let dec = RadianAngle::new(0.5).unwrap();
+        let b1950 = EquatorialCoords::with_epoch(ra, dec, Epoch::B1950).unwrap();
+        assert!(b1950.epoch() == Epoch::B1950.time().unwrap());
+        assert!(EquatorialCoords::with_epoch(ra, dec, Epoch::Julian(f64::NAN)).is_err());
+    }
+
+    #[test]
+    fn test_unit_vector() {
+        let v = coords(90.0, 0.0).to_unit_vector();
+        assert!(approx_eq(v[0], 0.0, 1.0e-15) && approx_eq(v[1], 1.0, 1.0e-15) && v[2] == 0.0);
+        assert!(approx_eq(coords(123.0, 90.0).to_unit_vector()[2], 1.0, 1.0e-15));
+
+        let eq = coords(213.915_4, 19.182_5);
+        let back = EquatorialCoords::from_unit_vector(eq.to_unit_vector(), J2000).unwrap();
+        assert!(approx_eq(back.right_ascension().radians(),
+                          eq.right_ascension().radians(),
+                          1.0e-12));
+        assert!(approx_eq(back.declination().radians(), eq.declination().radians(), 1.0e-12));
+
+        // Not a unit vector.
+        let eq = EquatorialCoords::from_unit_vector([0.0, -2.0, 2.0], J2000).unwrap();
+        assert!(approx_eq(eq.right_ascension().radians(), 1.5 * PI, 1.0e-15));
+        assert!(approx_eq(eq.declination().radians(), PI / 4.0, 1.0e-15));
+
+        assert!(EquatorialCoords::from_unit_vector([0.0, 0.0, 0.0], J2000).is_err());
+        assert!(EquatorialCoords::from_unit_vector([f64::NAN, 0.0, 1.0], J2000).is_err());
+    }
+
+    #[test]
+    fn test_angular_separation() {
+        // Example 17.a, Astronomical Algorithms 2nd ed., Arcturus and Spica.
+        let arcturus = coords(213.915_4, 19.182_5);
+        let spica = coords(201.298_3, -11.161_4);
+        let d = angular_separation(arcturus, spica).radians().to_degrees();
+        assert!(approx_eq(d, 32.793_0, 1.0e-4));
+
+        let tiny = angular_separation(coords(10.0, 20.0), coords(10.0, 20.000_001));
+        assert!(approx_eq(tiny.radians().to_degrees(), 0.000_001, 1.0e-12));
+        assert!(approx_eq(angular_separation(coords(0.0, 0.0), coords(180.0, 0.0)).radians(),
+                          PI,
+                          1.0e-15));
+    }
+
+    #[test]
+    fn test_position_angle() {
+        // Due north and due east of a point, and back again.
+        let a = coords(100.0, 20.0);
+        assert!(approx_eq(position_angle(a, coords(100.0, 25.0)).radians(), 0.0, 1.0e-12));
+        assert!(approx_eq(position_angle(a, coords(101.0, 20.0)).radians().to_degrees(),
+                          90.0,
+                          0.2));
+        assert!(approx_eq(position_angle(coords(100.0, 25.0), a).radians(), PI, 1.0e-12));
+        assert!(approx_eq(position_angle(a, coords(99.0, 20.0)).radians().to_degrees(),
+                          270.0,
+                          0.2));
+    }
+
+    #[test]
+    fn test_great_circle_points() {
+        // Along the equator
+        let points = great_circle_points(coords(10.0, 0.0), coords(100.0, 0.0), 4).unwrap();
+        assert_eq!(points.len(), 4);
+        for (i, p) in points.iter().enumerate() {
+            assert!(approx_eq(p.right_ascension().radians().to_degrees(),
+                              10.0 + 30.0 * i as f64,
+                              1.0e-10));
+            assert!(approx_eq(p.declination().radians(), 0.0, 1.0e-12));
+        }
+
+        // Across the pole, the midpoint is the pole itself.
+        let points = great_circle_points(coords(0.0, 60.0), coords(180.0, 60.0), 3).unwrap();
+        assert!(approx_eq(points[1].declination().radians().to_degrees(), 90.0, 1.0e-10));
+
+        // Crossing the 0h line of right ascension.
+        let points = great_circle_points(coords(350.0, 0.0), coords(10.0, 0.0), 3).unwrap();
+        assert!(approx_eq(points[1].right_ascension().radians(), 0.0, 1.0e-12));
+
+        // Identical points
+        let points = great_circle_points(coords(20.0, 5.0), coords(20.0, 5.0), 5).unwrap();
+        for p in &points {
+            assert!(approx_eq(p.right_ascension().radians().to_degrees(), 20.0, 1.0e-10));
+            assert!(approx_eq(p.declination().radians().to_degrees(), 5.0, 1.0e-10));
+        }
+
+        // Things that should fail
+        assert!(great_circle_points(coords(0.0, 0.0), coords(180.0, 0.0), 5).is_err());
+        assert!(great_circle_points(coords(0.0, 0.0), coords(10.0, 0.0), 1).is_err());
+    }
+
+    #[test]
+    fn test_great_circle_points_checked() {
+        let a = coords(20.0, 10.0);
+        let b = EquatorialCoords::with_epoch(RadianAngle::new(1.0).unwrap(),
+                                             RadianAngle::new(0.5).unwrap(),
+                                             Epoch::B1950)
+            .unwrap();
+
+        match great_circle_points(a, b, 3) {
+            Err(AstroAlgorithmsError::InvalidArgument(_)) => {}
+            result => panic!("{:?}", result),
+        }
+
+        // Precessing b first moves the end point, and all the points are referred to J2000.
+        let points = great_circle_points_checked(a, b, 3, EpochMismatch::Precess).unwrap();
+        let precessed = precess_equatorial(b, J2000).unwrap();
+        assert!(points.iter().all(|p| p.epoch() == J2000));
+        assert!(approx_eq(angular_separation(points[2], precessed).radians(), 0.0, 1.0e-12));
+        assert!(angular_separation(points[2], b).radians().to_degrees() > 0.5);
+
+        // Ignoring the mismatch takes b as it is.
+        let points = great_circle_points_checked(a, b, 3, EpochMismatch::Ignore).unwrap();
+        assert!(approx_eq(angular_separation(points[2], b).radians(), 0.0, 1.0e-12));
+    }
+}
@@ -0,0 +1,219 @@
+//!
+//! Module for formatting angles into fixed width columns for tabular output.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use super::angles::*;
+
+// The most decimal places for the seconds. Beyond about nine, past a nanosecond of arc, the
+// digits of an f64 angle are noise, and the counting in integer units would overflow.
+const MAX_DECIMALS: usize = 9;
+
+/// How to display the sign of an angle in a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignStyle {
+    /// Always print a sign, `+` or `-`.
+    Always,
+    /// Print `-` for negative values and a space for positive values, keeping the width fixed.
+    SpaceIfPositive,
+    /// Print `-` for negative values and nothing for positive values. Negative values will be one
+    /// character wider, so this is only appropriate for columns that are never negative.
+    NegativeOnly,
+}
+
+/// Format for fixed width sexagesimal (base 60) output such as `+49 13 42.5` or `02 44 12.0`.
+///
+/// Seconds are rounded to the requested number of decimals and the rounding is carried into the
+/// minutes and degrees (or hours), so a value never prints with 60 seconds or 60 minutes.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::coords::*;
+/// let dec = DMSAngle::new(49, 13, 42.48).unwrap();
+/// assert_eq!(SexagesimalFormat::degrees().format_dms(dec), "+49 13 42.5");
+///
+/// let ra = HMSAngle::new(2, 44, 12.0).unwrap();
+/// assert_eq!(SexagesimalFormat::hours().format_hms(ra), "02 44 12.0");
+///
+/// let lon = DegreeAngle::new(-7.5).unwrap();
+/// let fmt = SexagesimalFormat::degrees().leading_digits(3).separator(':').decimals(0);
+/// assert_eq!(fmt.format_dms(lon), "-007:30:00");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SexagesimalFormat {
+    sign: SignStyle,
+    leading_digits: usize,
+    zero_pad: bool,
+    decimals: usize,
+    separator: char,
+}
+
+impl SexagesimalFormat {
+    /// Format for signed degrees, like declination: `+49 13 42.5`.
+    pub fn degrees() -> SexagesimalFormat {
+        SexagesimalFormat {
+            sign: SignStyle::Always,
+            leading_digits: 2,
+            zero_pad: true,
+            decimals: 1,
+            separator: ' ',
+        }
+    }
+
+    /// Format for hours, like right ascension: `02 44 12.0`.
+    pub fn hours() -> SexagesimalFormat {
+        SexagesimalFormat {
+            sign: SignStyle::NegativeOnly,
+            leading_digits: 2,
+            zero_pad: true,
+            decimals: 1,
+            separator: ' ',
+        }
+    }
+
+    /// Set how the sign is displayed.
+    pub fn sign(mut self, sign: SignStyle) -> SexagesimalFormat {
+        self.sign = sign;
+        self
+    }
+
+    /// Set the width of the leading degrees or hours field, e.g. 3 for longitudes.
+    pub fn leading_digits(mut self, digits: usize) -> SexagesimalFormat {
+        self.leading_digits = digits;
+        self
+    }
+
+    /// Pad the leading field with zeros (the default) or spaces.
+    pub fn zero_pad(mut self, zero_pad: bool) -> SexagesimalFormat {
+        self.zero_pad = zero_pad;
+        self
+    }
+
+    /// Set the number of decimal places for the seconds, at most 9.
+    pub fn decimals(mut self, decimals: usize) -> SexagesimalFormat {
+        self.decimals = usize::min(decimals, MAX_DECIMALS);
+        self
+    }
+
+    /// Set the character between fields.
+    pub fn separator(mut self, separator: char) -> SexagesimalFormat {
+        self.separator = separator;
+        self
+    }
+
+    /// Format an angle as degrees, minutes, and seconds.
+    pub fn format_dms<T>(&self, angle: T) -> String
+        where DegreeAngle: From<T>
+    {
+        let degrees = DegreeAngle::from(angle).degrees();
+
+        self.format(degrees < 0.0, degrees.abs(), None)
+    }
+
+    /// Format an angle as hours, minutes, and seconds in the range [0h, 24h).
+    pub fn format_hms<T>(&self, angle: T) -> String
+        where DegreeAngle: From<T>
+    {
+        let degrees = map_to_branch(DegreeAngle::from(angle).degrees(), 0.0, 360.0);
+
+        self.format(false, degrees / 15.0, Some(24))
+    }
+
+    // Format a positive value with an optional upper limit where it wraps back to zero.
+    fn format(&self, negative: bool, value: f64, wrap: Option<u64>) -> String {
+        let scale = 10u64.pow(self.decimals as u32);
+        let units_per_minute = 60 * scale;
+        let units_per_whole = 60 * units_per_minute;
+
+        let mut total = f64::round(value * units_per_whole as f64) as u64;
+        if let Some(wrap) = wrap {
+            total %= wrap * units_per_whole;
+        }
+
+        let whole = total / units_per_whole;
+        let minutes = total % units_per_whole / units_per_minute;
+        let seconds = total % units_per_minute;
+
+        // A value that rounds to zero is not negative.
+        let sign = match (self.sign, negative && total > 0) {
+            (SignStyle::Always, false) => "+",
+            (SignStyle::SpaceIfPositive, false) => " ",
+            (SignStyle::NegativeOnly, false) => "",
+            (_, true) => "-",
+        };
+
+        let leading = if self.zero_pad {
+            format!("{:0width$}", whole, width = self.leading_digits)
+        } else {
+            format!("{:width$}", whole, width = self.leading_digits)
+        };
+
+        let seconds = if self.decimals > 0 {
+            format!("{:02}.{:0width$}",
+                    seconds / scale,
+                    seconds % scale,
+                    width = self.decimals)
+        } else {
+            format!("{:02}", seconds)
+        };
+
+        format!("{}{}{}{:02}{}{}",
+                sign,
+                leading,
+                self.separator,
+                minutes,
+                self.separator,
+                seconds)
+    }
+}
+
+#[cfg(test)]
+mod formatting_tests {
+    use super::*;
+
+    #[test]
+    fn test_format_dms() {
+        let fmt = SexagesimalFormat::degrees();
+
+        assert_eq!(fmt.format_dms(DMSAngle::new(49, 13, 42.5).unwrap()),
+                   "+49 13 42.5");
+        assert_eq!(fmt.format_dms(DMSAngle::new(-49, 13, 42.5).unwrap()),
+                   "-49 13 42.5");
+        assert_eq!(fmt.format_dms(DegreeAngle::new(-0.5).unwrap()), "-00 30 00.0");
+        assert_eq!(fmt.format_dms(DegreeAngle::new(-0.000_001).unwrap()),
+                   "+00 00 00.0");
+
+        // Rounding carries into minutes and degrees.
+        assert_eq!(fmt.format_dms(DMSAngle::new(9, 59, 59.96).unwrap()),
+                   "+10 00 00.0");
+
+        let fmt = fmt.sign(SignStyle::SpaceIfPositive).leading_digits(3).zero_pad(false);
+        assert_eq!(fmt.format_dms(DegreeAngle::new(7.5).unwrap()), "   7 30 00.0");
+        assert_eq!(fmt.format_dms(DegreeAngle::new(-117.5).unwrap()), "-117 30 00.0");
+
+        let fmt = SexagesimalFormat::degrees().decimals(3).separator(':');
+        assert_eq!(fmt.format_dms(DMSAngle::new(1, 2, 3.4567).unwrap()),
+                   "+01:02:03.457");
+
+        // Too many decimals are limited rather than overflowing.
+        let fmt = SexagesimalFormat::degrees().decimals(20);
+        assert_eq!(fmt.format_dms(DegreeAngle::new(1.5).unwrap()), "+01 30 00.000000000");
+    }
+
+    #[test]
+    fn test_format_hms() {
+        let fmt = SexagesimalFormat::hours();
+
+        assert_eq!(fmt.format_hms(HMSAngle::new(2, 44, 12.0).unwrap()), "02 44 12.0");
+        assert_eq!(fmt.format_hms(DegreeAngle::new(-15.0).unwrap()), "23 00 00.0");
+        assert_eq!(fmt.format_hms(HMSAngle::new(23, 59, 59.99).unwrap()),
+                   "00 00 00.0");
+        assert_eq!(fmt.decimals(0).format_hms(DegreeAngle::new(180.0).unwrap()),
+                   "12 00 00");
+    }
+}
@@ -0,0 +1,180 @@
+//!
+//! Module for reporting the angles of the rotations between reference frames.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::super::astro_time::AstroTime;
+use super::super::error::*;
+use super::angles::*;
+use super::nutation::*;
+use super::precession::precession_angles;
+
+/// The angles of precession from one epoch to another, and of the nutation at the second epoch.
+///
+/// These are the angles applied by `precess_equatorial` and the apparent place calculations, for
+/// checking against other software. With the `serde` feature it can be serialized, with the angles
+/// in radians and the epochs as Julian Ephemeris Days.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameAngles {
+    from_jde: f64,
+    to_jde: f64,
+    zeta: f64,
+    z: f64,
+    theta: f64,
+    delta_psi: f64,
+    delta_eps: f64,
+    mean_obliquity: f64,
+    true_obliquity: f64,
+}
+
+impl FrameAngles {
+    /// Calculate the angles from the mean equator and equinox of `from` to those of `to`, and the
+    /// nutation and obliquity at `to`.
+    pub fn new(from: &AstroTime, to: &AstroTime) -> AstroResult<FrameAngles> {
+        let from_jde = from.julian_ephemeris_day()?;
+        let to_jde = to.julian_ephemeris_day()?;
+        let (zeta, z, theta) = precession_angles(from_jde, to_jde);
+        let (delta_psi, delta_eps) = nutation(to)?;
+        let mean_obliquity = mean_obliquity(to)?.radians();
+
+        Ok(FrameAngles {
+            from_jde,
+            to_jde,
+            zeta,
+            z,
+            theta,
+            delta_psi: delta_psi.radians(),
+            delta_eps: delta_eps.radians(),
+            mean_obliquity,
+            true_obliquity: mean_obliquity + delta_eps.radians(),
+        })
+    }
+
+    /// The Julian Ephemeris Day of the starting epoch.
+    pub fn from_jde(&self) -> f64 {
+        self.from_jde
+    }
+
+    /// The Julian Ephemeris Day of the final epoch.
+    pub fn to_jde(&self) -> f64 {
+        self.to_jde
+    }
+
+    /// The precession angle ζ.
+    pub fn zeta(&self) -> RadianAngle {
+        RadianAngle::new(self.zeta).unwrap()
+    }
+
+    /// The precession angle z.
+    pub fn z(&self) -> RadianAngle {
+        RadianAngle::new(self.z).unwrap()
+    }
+
+    /// The precession angle θ.
+    pub fn theta(&self) -> RadianAngle {
+        RadianAngle::new(self.theta).unwrap()
+    }
+
+    /// The nutation in longitude, Δψ.
+    pub fn delta_psi(&self) -> RadianAngle {
+        RadianAngle::new(self.delta_psi).unwrap()
+    }
+
+    /// The nutation in obliquity, Δε.
+    pub fn delta_eps(&self) -> RadianAngle {
+        RadianAngle::new(self.delta_eps).unwrap()
+    }
+
+    /// The mean obliquity of the ecliptic, ε₀.
+    pub fn mean_obliquity(&self) -> RadianAngle {
+        RadianAngle::new(self.mean_obliquity).unwrap()
+    }
+
+    /// The true obliquity of the ecliptic, ε = ε₀ + Δε.
+    pub fn true_obliquity(&self) -> RadianAngle {
+        RadianAngle::new(self.true_obliquity).unwrap()
+    }
+}
+
+/// One angle to a line, in seconds of arc except for the obliquities in degrees.
+impl fmt::Display for FrameAngles {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = |radians: f64| radians.to_degrees() * 3600.0;
+
+        writeln!(f, "JDE {:.5} to JDE {:.5}", self.from_jde, self.to_jde)?;
+        writeln!(f, "\u{03B6} = {:.4}\"", seconds(self.zeta))?;
+        writeln!(f, "z = {:.4}\"", seconds(self.z))?;
+        writeln!(f, "\u{03B8} = {:.4}\"", seconds(self.theta))?;
+        writeln!(f, "\u{0394}\u{03C8} = {:.4}\"", seconds(self.delta_psi))?;
+        writeln!(f, "\u{0394}\u{03B5} = {:.4}\"", seconds(self.delta_eps))?;
+        writeln!(f, "\u{03B5}\u{2080} = {:.8}\u{00B0}", self.mean_obliquity.to_degrees())?;
+        write!(f, "\u{03B5} = {:.8}\u{00B0}", self.true_obliquity.to_degrees())
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod frame_tests {
+    use super::*;
+    use astro_time::{Builder, J2000};
+
+    fn seconds(angle: RadianAngle) -> f64 {
+        angle.radians().to_degrees() * 3600.0
+    }
+
+    #[test]
+    fn test_frame_angles() {
+        // Example 22.a, Astronomical Algorithms 2nd ed.
+        let time = Builder::from_gregorian_utc(1987, 4, 10, 0, 0, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let angles = FrameAngles::new(&J2000, &time).unwrap();
+        assert!(approx_eq(seconds(angles.delta_psi()), -3.788, 0.001));
+        assert!(approx_eq(seconds(angles.delta_eps()), 9.443, 0.001));
+        let eps_0 = 23.0 + (26.0 + 27.407 / 60.0) / 60.0;
+        assert!(approx_eq(angles.mean_obliquity().radians().to_degrees(), eps_0, 1.0e-6));
+        let eps = 23.0 + (26.0 + 36.850 / 60.0) / 60.0;
+        assert!(approx_eq(angles.true_obliquity().radians().to_degrees(), eps, 1.0e-6));
+
+        // The same precession as precess_equatorial.
+        let jde = time.julian_day_number();
+        let (zeta, z, theta) = precession_angles(2_451_545.0, jde);
+        assert_eq!(angles.zeta().radians(), zeta);
+        assert_eq!(angles.z().radians(), z);
+        assert_eq!(angles.theta().radians(), theta);
+        assert_eq!((angles.from_jde(), angles.to_jde()), (2_451_545.0, jde));
+
+        // No precession between the same epochs.
+        let angles = FrameAngles::new(&time, &time).unwrap();
+        assert_eq!(angles.zeta().radians(), 0.0);
+    }
+
+    #[test]
+    fn test_display() {
+        let time = Builder::from_gregorian_utc(1987, 4, 10, 0, 0, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let text = format!("{}", FrameAngles::new(&J2000, &time).unwrap());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "JDE 2451545.00000 to JDE 2446895.50000");
+        assert!(lines[4].starts_with("\u{0394}\u{03C8} = -3.78"));
+    }
+}
@@ -0,0 +1,241 @@
+//!
+//! Module for geographic coordinates of an observer on the Earth.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+
+// The IAU 1976 reference ellipsoid, the equatorial radius in meters and the ratio of the polar
+// radius to the equatorial radius.
+const EQUATORIAL_RADIUS_M: f64 = 6_378_140.0;
+const B_OVER_A: f64 = 0.996_647_19;
+
+/// Geographic coordinates of an observer.
+///
+/// Following chapter 13 of Astronomical Algorithms 2nd ed. by Jean Meeus, the longitude is
+/// measured positively **west** of Greenwich and negatively east of it. This is opposite of the
+/// convention used by many maps and GPS receivers. The longitude is kept in the range (-π, π].
+///
+/// The elevation is the height above sea level in meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoCoords {
+    latitude: RadianAngle,
+    longitude: RadianAngle,
+    elevation: f64,
+}
+
+impl GeoCoords {
+    /// Create new coordinates from a latitude, west positive longitude, and elevation in meters.
+    ///
+    /// The latitude must be in the range [-π/2, π/2].
+    pub fn new(latitude: RadianAngle,
+               longitude: RadianAngle,
+               elevation: f64)
+               -> AstroResult<GeoCoords> {
+        if latitude.radians().abs() > FRAC_PI_2 {
+            Err(AstroAlgorithmsError::InvalidAngle("Latitude limited to range [-90, 90] degrees"
+                .to_owned()))
+        } else if elevation.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if elevation.is_infinite() {
+            Err(AstroAlgorithmsError::EncounteredInf)
+        } else {
+            let mut lon = map_to_branch(longitude.radians(), -PI, PI);
+            if lon <= -PI {
+                lon += 2.0 * PI;
+            }
+
+            Ok(GeoCoords {
+                latitude,
+                longitude: RadianAngle::new(lon)?,
+                elevation,
+            })
+        }
+    }
+
+    /// The latitude, positive north.
+    pub fn latitude(&self) -> RadianAngle {
+        self.latitude
+    }
+
+    /// The longitude, positive west.
+    pub fn longitude(&self) -> RadianAngle {
+        self.longitude
+    }
+
+    /// The latitude in decimal degrees, positive north.
+    pub fn lat_degrees(&self) -> f64 {
+        self.latitude.radians().to_degrees()
+    }
+
+    /// The longitude in decimal degrees, positive west.
+    pub fn lon_degrees(&self) -> f64 {
+        self.longitude.radians().to_degrees()
+    }
+
+    /// The elevation above sea level in meters.
+    pub fn elevation(&self) -> f64 {
+        self.elevation
+    }
+
+    /// The quantities ρ sin φ' and ρ cos φ' locating the observer relative to the center of the
+    /// Earth, in units of the Earth's equatorial radius, chapter 11.
+    ///
+    /// These are the components of the observer's position parallel to and perpendicular to the
+    /// Earth's axis, needed for parallax calculations.
+    pub fn geocentric_position(&self) -> (f64, f64) {
+        let phi = self.latitude.radians();
+        let height = self.elevation / EQUATORIAL_RADIUS_M;
+        let u = atan(B_OVER_A * tan(phi));
+
+        (B_OVER_A * sin(u) + height * sin(phi), cos(u) + height * cos(phi))
+    }
+
+    /// Create coordinates from a position in the Earth-centered, Earth-fixed frame in meters.
+    ///
+    /// The x axis points to the Greenwich meridian on the equator, the y axis to 90 degrees east
+    /// longitude, and the z axis to the north pole. The elevation is the height above the
+    /// reference ellipsoid. Returns an error for the center of the Earth.
+    pub fn from_ecef(x: f64, y: f64, z: f64) -> AstroResult<GeoCoords> {
+        let e2 = 1.0 - B_OVER_A * B_OVER_A;
+        let p = hypot(x, y);
+        if x.is_nan() || y.is_nan() || z.is_nan() {
+            return Err(AstroAlgorithmsError::EncounteredNaN);
+        } else if p.is_infinite() || z.is_infinite() {
+            return Err(AstroAlgorithmsError::EncounteredInf);
+        } else if p == 0.0 && z == 0.0 {
+            return Err(AstroAlgorithmsError::InvalidArgument("The center of the Earth has no \
+                                                              geographic coordinates"
+                .to_owned()));
+        }
+
+        // Iterate the latitude, the height is calculated from whichever of the distance from the
+        // axis or from the equator is better conditioned.
+        let mut phi = atan2(z, p * (1.0 - e2));
+        let mut height = 0.0;
+        for _ in 0..10 {
+            let (sin_phi, cos_phi) = sin_cos(phi);
+            let n = EQUATORIAL_RADIUS_M / f64::sqrt(1.0 - e2 * sin_phi * sin_phi);
+            height = if cos_phi.abs() > sin_phi.abs() {
+                p / cos_phi - n
+            } else {
+                z / sin_phi - n * (1.0 - e2)
+            };
+
+            let next = atan2(z, p * (1.0 - e2 * n / (n + height)));
+            let done = (next - phi).abs() < 1.0e-14;
+            phi = next;
+            if done {
+                break;
+            }
+        }
+
+        GeoCoords::new(RadianAngle::new(phi)?, RadianAngle::new(-atan2(y, x))?, height)
+    }
+
+    /// The position in the Earth-centered, Earth-fixed frame in meters, see `from_ecef`.
+    pub fn to_ecef(&self) -> (f64, f64, f64) {
+        let e2 = 1.0 - B_OVER_A * B_OVER_A;
+        let (sin_phi, cos_phi) = sin_cos(self.latitude.radians());
+        let (sin_lon, cos_lon) = sin_cos(self.longitude.radians());
+        let n = EQUATORIAL_RADIUS_M / f64::sqrt(1.0 - e2 * sin_phi * sin_phi);
+
+        let r = (n + self.elevation) * cos_phi;
+        (r * cos_lon, -r * sin_lon, (n * (1.0 - e2) + self.elevation) * sin_phi)
+    }
+}
+
+#[cfg(test)]
+mod geo_tests {
+    use super::*;
+
+    #[test]
+    fn test_geocentric_position() {
+        // Example 11.a, Astronomical Algorithms 2nd ed., Palomar Observatory.
+        let lat = (33.0 + 21.0 / 60.0 + 22.0 / 3600.0f64).to_radians();
+        let palomar = GeoCoords::new(RadianAngle::new(lat).unwrap(),
+                                     RadianAngle::new(2.0).unwrap(),
+                                     1706.0)
+            .unwrap();
+
+        let (rho_sin_phi, rho_cos_phi) = palomar.geocentric_position();
+        assert!((rho_sin_phi - 0.546_861).abs() < 1.0e-6);
+        assert!((rho_cos_phi - 0.836_339).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_ecef() {
+        let lat = (33.0 + 21.0 / 60.0 + 22.0 / 3600.0f64).to_radians();
+        let palomar = GeoCoords::new(RadianAngle::new(lat).unwrap(),
+                                     RadianAngle::new(2.0).unwrap(),
+                                     1706.0)
+            .unwrap();
+
+        let (x, y, z) = palomar.to_ecef();
+        let (rho_sin_phi, rho_cos_phi) = palomar.geocentric_position();
+        assert!((x.hypot(y) / EQUATORIAL_RADIUS_M - rho_cos_phi).abs() < 1.0e-9);
+        assert!((z / EQUATORIAL_RADIUS_M - rho_sin_phi).abs() < 1.0e-9);
+        assert!(x < 0.0 && y < 0.0);
+
+        let back = GeoCoords::from_ecef(x, y, z).unwrap();
+        assert!((back.latitude().radians() - lat).abs() < 1.0e-12);
+        assert!((back.longitude().radians() - 2.0).abs() < 1.0e-12);
+        assert!((back.elevation() - 1706.0).abs() < 1.0e-6);
+
+        // Near the poles, and high above the Earth.
+        let pole = GeoCoords::from_ecef(0.0, 0.0, -7_000_000.0).unwrap();
+        assert!((pole.latitude().radians() + FRAC_PI_2).abs() < 1.0e-12);
+        assert!((pole.elevation() - (7_000_000.0 - EQUATORIAL_RADIUS_M * B_OVER_A)).abs() < 1.0e-6);
+
+        let orbit = GeoCoords::new(RadianAngle::new(1.5).unwrap(),
+                                   RadianAngle::new(-0.3).unwrap(),
+                                   400_000.0)
+            .unwrap();
+        let (x, y, z) = orbit.to_ecef();
+        let back = GeoCoords::from_ecef(x, y, z).unwrap();
+        assert!((back.latitude().radians() - 1.5).abs() < 1.0e-12);
+        assert!((back.longitude().radians() + 0.3).abs() < 1.0e-12);
+        assert!((back.elevation() - 400_000.0).abs() < 1.0e-6);
+
+        assert!(GeoCoords::from_ecef(0.0, 0.0, 0.0).is_err());
+        assert_eq!(GeoCoords::from_ecef(f64::NAN, 0.0, 0.0).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+    }
+
+    #[test]
+    fn test_geo_coords_new() {
+        let test_subject = GeoCoords::new(RadianAngle::new(0.5).unwrap(),
+                                          RadianAngle::new(1.5 * PI).unwrap(),
+                                          1000.0)
+            .unwrap();
+        assert_eq!(test_subject.latitude().radians(), 0.5);
+        assert!((test_subject.longitude().radians() + FRAC_PI_2).abs() < 1.0e-15);
+        assert_eq!(test_subject.elevation(), 1000.0);
+        assert_eq!(test_subject.lat_degrees(), 0.5f64.to_degrees());
+        assert!((test_subject.lon_degrees() + 90.0).abs() < 1.0e-12);
+
+        let test_subject = GeoCoords::new(RadianAngle::new(0.5).unwrap(),
+                                          RadianAngle::new(-PI).unwrap(),
+                                          0.0)
+            .unwrap();
+        assert_eq!(test_subject.longitude().radians(), PI);
+
+        assert!(GeoCoords::new(RadianAngle::new(1.6).unwrap(),
+                               RadianAngle::new(0.0).unwrap(),
+                               0.0)
+            .is_err());
+        assert_eq!(GeoCoords::new(RadianAngle::new(0.0).unwrap(),
+                                  RadianAngle::new(0.0).unwrap(),
+                                  f64::NAN)
+                       .unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+    }
+}
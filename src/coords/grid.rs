@@ -0,0 +1,303 @@
+//!
+//! Module for the lines of coordinate grids, for plotting star charts.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Lines are sampled as polylines of equatorial coordinates of the date, and can be transformed to
+//! horizontal coordinates or projected onto a chart of the sky above an observer. Nutation is
+//! neglected, it moves the lines by less than 20", far less than a chart can show.
+//!
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::super::astro_time::AstroTime;
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+use super::ecliptic::*;
+use super::equatorial::*;
+use super::geo::*;
+use super::horizontal::*;
+use super::nutation::*;
+use super::rotation::*;
+use super::transforms::*;
+
+// The rotation from galactic to equatorial rectangular coordinates of J2000.0, the transpose of
+// the matrix of the Hipparcos catalogue.
+const GALACTIC_TO_EQUATORIAL: [[f64; 3]; 3] =
+    [[-0.054_875_560_4, 0.494_109_427_9, -0.867_666_149],
+     [-0.873_437_090_2, -0.444_829_63, -0.198_076_373_4],
+     [-0.483_835_015_5, 0.746_982_244_5, 0.455_983_776_2]];
+
+/// A line of a coordinate grid on the sky.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridLine {
+    /// The hour circle of a right ascension, from the south to the north celestial pole.
+    HourCircle(RadianAngle),
+    /// The parallel of a declination, all the way around the sky.
+    Parallel(RadianAngle),
+    /// The ecliptic, from the vernal equinox eastward.
+    Ecliptic,
+    /// The galactic equator, from the direction of the galactic center eastward.
+    GalacticEquator,
+}
+
+impl GridLine {
+    /// The hour circles and parallels of an equatorial grid with the given spacings.
+    ///
+    /// The hour circles start at 0h, and the parallels are symmetric about the equator, including
+    /// the equator but not the poles. Returns an error if either spacing is not positive.
+    pub fn equatorial_grid(ra_spacing: RadianAngle,
+                           dec_spacing: RadianAngle)
+                           -> AstroResult<Vec<GridLine>> {
+        let (ra_step, dec_step) = (ra_spacing.radians(), dec_spacing.radians());
+        if ra_step <= 0.0 || dec_step <= 0.0 {
+            return Err(AstroAlgorithmsError::InvalidArgument("The spacing of grid lines must be \
+                                                              positive"
+                .to_owned()));
+        }
+
+        let mut lines = vec![];
+        let mut ra = 0.0;
+        while ra < 2.0 * PI - 1.0e-9 {
+            lines.push(GridLine::HourCircle(RadianAngle::new(ra)?));
+            ra += ra_step;
+        }
+
+        lines.push(GridLine::Parallel(RadianAngle::new(0.0)?));
+        let mut dec = dec_step;
+        while dec < FRAC_PI_2 - 1.0e-9 {
+            lines.push(GridLine::Parallel(RadianAngle::new(dec)?));
+            lines.push(GridLine::Parallel(RadianAngle::new(-dec)?));
+            dec += dec_step;
+        }
+
+        Ok(lines)
+    }
+}
+
+/// A projection of the sky above an observer onto a circular chart.
+///
+/// The zenith is at the center of the chart and the horizon is the unit circle, with north up and
+/// east to the left as when looking up at the sky.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartProjection {
+    /// Preserves shapes, so constellations look as they do on the sky, but enlarges them towards
+    /// the horizon. The usual projection of planispheres.
+    Stereographic,
+    /// The sky as seen on a globe from outside, crowding the horizon.
+    Orthographic,
+    /// The distance from the center is proportional to the zenith distance.
+    AzimuthalEquidistant,
+}
+
+impl ChartProjection {
+    /// Project horizontal coordinates to (x, y) on the chart, or `None` below the horizon.
+    pub fn project(self, hz: HorizontalCoords) -> Option<(f64, f64)> {
+        let z = FRAC_PI_2 - hz.altitude().radians();
+        if z > FRAC_PI_2 {
+            return None;
+        }
+
+        let r = match self {
+            ChartProjection::Stereographic => tan(z / 2.0),
+            ChartProjection::Orthographic => sin(z),
+            ChartProjection::AzimuthalEquidistant => z / FRAC_PI_2,
+        };
+
+        // The azimuth is measured westward from the south.
+        let (sin_a, cos_a) = sin_cos(hz.azimuth().radians());
+        Some((r * sin_a, -r * cos_a))
+    }
+}
+
+/// Sample `n` points along a grid line in equatorial coordinates of the date.
+///
+/// Lines around the whole sky are closed, the last point repeats the first. Returns an error if
+/// `n` is less than 2.
+pub fn grid_line(line: GridLine,
+                 time: &AstroTime,
+                 n: usize)
+                 -> AstroResult<Vec<EquatorialCoords>> {
+    if n < 2 {
+        return Err(AstroAlgorithmsError::InvalidArgument("At least 2 points are required"
+            .to_owned()));
+    }
+
+    let fraction = |i: usize| i as f64 / (n - 1) as f64;
+    let to_date = precession_matrix(time)?;
+    let eps = true_obliquity(time)?;
+
+    (0..n)
+        .map(|i| match line {
+            GridLine::HourCircle(ra) => {
+                EquatorialCoords::new(ra, RadianAngle::new(PI * fraction(i) - FRAC_PI_2)?, *time)
+            }
+            GridLine::Parallel(dec) => {
+                EquatorialCoords::new(RadianAngle::new(2.0 * PI * fraction(i))?, dec, *time)
+            }
+            GridLine::Ecliptic => {
+                let ecl = EclipticCoords::new(RadianAngle::new(2.0 * PI * fraction(i))?,
+                                              RadianAngle::new(0.0)?,
+                                              *time)?;
+                trans_ecliptic_to_equatorial(ecl, eps)
+            }
+            GridLine::GalacticEquator => {
+                let (sin_l, cos_l) = sin_cos(2.0 * PI * fraction(i));
+                let m = &GALACTIC_TO_EQUATORIAL;
+                let v = (m[0][0] * cos_l + m[0][1] * sin_l,
+                         m[1][0] * cos_l + m[1][1] * sin_l,
+                         m[2][0] * cos_l + m[2][1] * sin_l);
+                let (x, y, z) = to_date.rotate(v);
+                EquatorialCoords::new(RadianAngle::new(atan2(y, x))?,
+                                      RadianAngle::new(atan2(z, hypot(x, y)))?,
+                                      *time)
+            }
+        })
+        .collect()
+}
+
+/// Sample `n` points along a grid line in horizontal coordinates for an observer at a time.
+pub fn horizontal_grid_line(line: GridLine,
+                            geo: GeoCoords,
+                            time: &AstroTime,
+                            n: usize)
+                            -> AstroResult<Vec<HorizontalCoords>> {
+    grid_line(line, time, n)?
+        .into_iter()
+        .map(|eq| trans_equatorial_to_horizontal(eq, geo, time))
+        .collect()
+}
+
+/// Sample `n` points along a grid line and project them onto a chart of the sky above an
+/// observer.
+///
+/// The line is split into the runs of points above the horizon, each to be drawn as a separate
+/// polyline. The runs end at the last point above the horizon, they are not cut at the horizon.
+pub fn projected_grid_line(line: GridLine,
+                           geo: GeoCoords,
+                           time: &AstroTime,
+                           n: usize,
+                           projection: ChartProjection)
+                           -> AstroResult<Vec<Vec<(f64, f64)>>> {
+    let mut runs = vec![];
+    let mut run = vec![];
+    for hz in horizontal_grid_line(line, geo, time, n)? {
+        if let Some(point) = projection.project(hz) {
+            run.push(point);
+        } else if !run.is_empty() {
+            runs.push(run);
+            run = vec![];
+        }
+    }
+    if !run.is_empty() {
+        runs.push(run);
+    }
+
+    Ok(runs)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use super::*;
+    use astro_time::{Builder, J2000};
+    use super::super::precession::precess_equatorial;
+
+    fn degrees(angle: f64) -> RadianAngle {
+        RadianAngle::new(angle.to_radians()).unwrap()
+    }
+
+    #[test]
+    fn test_equatorial_grid() {
+        let lines = GridLine::equatorial_grid(degrees(30.0), degrees(30.0)).unwrap();
+        let hour_circles = lines.iter().filter(|l| matches!(l, GridLine::HourCircle(_))).count();
+        assert_eq!(hour_circles, 12);
+        assert_eq!(lines.len(), 12 + 5);
+        assert!(GridLine::equatorial_grid(degrees(0.0), degrees(10.0)).is_err());
+    }
+
+    #[test]
+    fn test_grid_line() {
+        let time = Builder::from_gregorian_utc(2017, 1, 1, 0, 0, 0).build().unwrap();
+
+        let parallel = grid_line(GridLine::Parallel(degrees(40.0)), &time, 25).unwrap();
+        assert_eq!(parallel.len(), 25);
+        assert!(angular_separation(parallel[0], parallel[24]).radians() < 1.0e-12);
+
+        // The ecliptic reaches 23.44 degrees north at 6h.
+        let ecliptic = grid_line(GridLine::Ecliptic, &time, 5).unwrap();
+        assert!(approx_eq(ecliptic[1].declination().radians().to_degrees(), 23.44, 0.01));
+        assert!(approx_eq(ecliptic[1].right_ascension().radians().to_degrees(), 90.0, 0.01));
+
+        // The galactic equator starts at the galactic center, 17h 45.6m -28° 56' (J2000.0), and
+        // is 90 degrees from the galactic pole.
+        let galactic = grid_line(GridLine::GalacticEquator, &time, 361).unwrap();
+        let center = precess_equatorial(EquatorialCoords::new(degrees(266.405),
+                                                              degrees(-28.936),
+                                                              J2000)
+                                            .unwrap(),
+                                        time)
+            .unwrap();
+        assert!(angular_separation(galactic[0], center).radians().to_degrees() < 0.05);
+        let pole = EquatorialCoords::new(degrees(192.859), degrees(27.128), J2000).unwrap();
+        let pole = precess_equatorial(pole, time).unwrap();
+        for eq in &galactic {
+            let distance = angular_separation(*eq, pole).radians().to_degrees();
+            assert!(approx_eq(distance, 90.0, 0.01));
+        }
+
+        assert!(grid_line(GridLine::Ecliptic, &time, 1).is_err());
+    }
+
+    #[test]
+    fn test_projection() {
+        let zenith = HorizontalCoords::new(degrees(90.0), degrees(0.0)).unwrap();
+        let (x, y) = ChartProjection::Stereographic.project(zenith).unwrap();
+        assert!(x.abs() < 1.0e-12 && y.abs() < 1.0e-12);
+
+        // North on the horizon is at the top, and west at the right.
+        let north = HorizontalCoords::new(degrees(0.0), degrees(180.0)).unwrap();
+        let west = HorizontalCoords::new(degrees(0.0), degrees(90.0)).unwrap();
+        for &projection in &[ChartProjection::Stereographic,
+                             ChartProjection::Orthographic,
+                             ChartProjection::AzimuthalEquidistant] {
+            let (x, y) = projection.project(north).unwrap();
+            assert!(approx_eq(x, 0.0, 1.0e-12) && approx_eq(y, 1.0, 1.0e-12));
+            let (x, y) = projection.project(west).unwrap();
+            assert!(approx_eq(x, 1.0, 1.0e-12) && approx_eq(y, 0.0, 1.0e-12));
+        }
+
+        let below = HorizontalCoords::new(degrees(-10.0), degrees(0.0)).unwrap();
+        assert!(ChartProjection::Orthographic.project(below).is_none());
+    }
+
+    #[test]
+    fn test_projected_grid_line() {
+        // From 40° N the celestial equator is half above the horizon.
+        let time = Builder::from_gregorian_utc(2017, 1, 1, 0, 0, 0).build().unwrap();
+        let geo = GeoCoords::new(degrees(40.0), degrees(105.0), 0.0).unwrap();
+        let runs = projected_grid_line(GridLine::Parallel(degrees(0.0)),
+                                       geo,
+                                       &time,
+                                       361,
+                                       ChartProjection::Stereographic)
+            .unwrap();
+        let points: usize = runs.iter().map(|run| run.len()).sum();
+        assert!((178..=182).contains(&points));
+        assert!(runs.len() <= 2);
+        for run in &runs {
+            for &(x, y) in run {
+                assert!(x.hypot(y) <= 1.0 + 1.0e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//!
+//! Module for horizontal (altitude-azimuth) coordinates.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::super::error::*;
+use super::angles::*;
+use super::refraction::*;
+
+/// Horizontal coordinates of an object as seen by an observer.
+///
+/// Following chapter 13 of Astronomical Algorithms 2nd ed. by Jean Meeus, the azimuth is measured
+/// westward from the South, and is kept in the range [0, 2π).
+///
+/// The stored altitude is always the *geometric* altitude, that is the altitude the object would
+/// have without an atmosphere. Use `apparent_altitude` to get the altitude as it would be observed,
+/// and `from_apparent` to create coordinates from an observed altitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HorizontalCoords {
+    altitude: RadianAngle,
+    azimuth: RadianAngle,
+}
+
+impl HorizontalCoords {
+    /// Create new coordinates from a geometric altitude and an azimuth.
+    ///
+    /// The altitude must be in the range [-π/2, π/2].
+    pub fn new(altitude: RadianAngle, azimuth: RadianAngle) -> AstroResult<HorizontalCoords> {
+        if altitude.radians().abs() > FRAC_PI_2 {
+            Err(AstroAlgorithmsError::InvalidAngle("Altitude limited to range [-90, 90] degrees"
+                .to_owned()))
+        } else {
+            let mut az = map_to_branch(azimuth.radians(), 0.0, 2.0 * PI);
+            if az >= 2.0 * PI {
+                az -= 2.0 * PI;
+            }
+
+            Ok(HorizontalCoords {
+                altitude,
+                azimuth: RadianAngle::new(az)?,
+            })
+        }
+    }
+
+    /// Create new coordinates from an apparent (observed) altitude by removing the refraction.
+    pub fn from_apparent(apparent_altitude: RadianAngle,
+                         azimuth: RadianAngle,
+                         conditions: &AtmosphericConditions)
+                         -> AstroResult<HorizontalCoords> {
+        let r = refraction_from_apparent(apparent_altitude, conditions);
+        let h = f64::max(apparent_altitude.radians() - r.radians(), -FRAC_PI_2);
+
+        HorizontalCoords::new(RadianAngle::new(h)?, azimuth)
+    }
+
+    /// The geometric altitude.
+    pub fn altitude(&self) -> RadianAngle {
+        self.altitude
+    }
+
+    /// The azimuth, measured westward from the South.
+    pub fn azimuth(&self) -> RadianAngle {
+        self.azimuth
+    }
+
+    /// The geometric altitude in decimal degrees.
+    pub fn alt_degrees(&self) -> f64 {
+        self.altitude.radians().to_degrees()
+    }
+
+    /// The azimuth in decimal degrees, measured westward from the South.
+    pub fn az_degrees(&self) -> f64 {
+        self.azimuth.radians().to_degrees()
+    }
+
+    /// The apparent altitude, with refraction for the given conditions applied.
+    pub fn apparent_altitude(&self, conditions: &AtmosphericConditions) -> RadianAngle {
+        let r = refraction_from_geometric(self.altitude, conditions);
+
+        RadianAngle::new(f64::min(self.altitude.radians() + r.radians(), FRAC_PI_2)).unwrap()
+    }
+
+    /// The apparent altitude above a visible horizon that is depressed by `dip`, see
+    /// `dip_of_horizon`.
+    pub fn apparent_altitude_with_dip(&self,
+                                      conditions: &AtmosphericConditions,
+                                      dip: RadianAngle)
+                                      -> RadianAngle {
+        RadianAngle::new(self.apparent_altitude(conditions).radians() + dip.radians()).unwrap()
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod horizontal_tests {
+    use super::*;
+
+    #[test]
+    fn test_horizontal_coords_new() {
+        let test_subject = HorizontalCoords::new(RadianAngle::new(0.5).unwrap(),
+                                                 RadianAngle::new(-FRAC_PI_2).unwrap())
+            .unwrap();
+        assert_eq!(test_subject.altitude().radians(), 0.5);
+        assert!(approx_eq(test_subject.azimuth().radians(), 3.0 * FRAC_PI_2, 1.0e-15));
+        assert_eq!(test_subject.alt_degrees(), 0.5f64.to_degrees());
+        assert!(approx_eq(test_subject.az_degrees(), 270.0, 1.0e-12));
+
+        let test_subject = HorizontalCoords::new(RadianAngle::new(0.5).unwrap(),
+                                                 RadianAngle::new(2.0 * PI).unwrap())
+            .unwrap();
+        assert_eq!(test_subject.azimuth().radians(), 0.0);
+
+        assert!(HorizontalCoords::new(RadianAngle::new(2.0).unwrap(),
+                                      RadianAngle::new(0.0).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_apparent_altitude() {
+        let conditions = AtmosphericConditions::default();
+        let az = RadianAngle::new(1.0).unwrap();
+
+        // Example 16.a, Astronomical Algorithms 2nd ed., Venus observed at 0.5 degrees
+        let apparent = RadianAngle::new(0.5f64.to_radians()).unwrap();
+        let coords = HorizontalCoords::from_apparent(apparent, az, &conditions).unwrap();
+        assert!(approx_eq(coords.altitude().radians().to_degrees(),
+                          0.5 - 28.754 / 60.0,
+                          1.0e-4));
+        assert_eq!(coords.azimuth(), az);
+
+        // Round trip to about 0.1 arc-minute
+        assert!(approx_eq(coords.apparent_altitude(&conditions).radians().to_degrees(),
+                          0.5,
+                          0.1 / 60.0));
+
+        // Never lifts an object past the zenith
+        let zenith = HorizontalCoords::new(RadianAngle::new(FRAC_PI_2).unwrap(), az).unwrap();
+        assert_eq!(zenith.apparent_altitude(&conditions).radians(), FRAC_PI_2);
+
+        // From 100 meters above the sea the sea horizon is 17.6' below the astronomical horizon.
+        let dip = dip_of_horizon(100.0).unwrap();
+        assert!(approx_eq(coords.apparent_altitude_with_dip(&conditions, dip)
+                              .radians()
+                              .to_degrees(),
+                          0.5 + 17.6 / 60.0,
+                          0.1 / 60.0));
+    }
+}
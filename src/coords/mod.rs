@@ -8,5 +8,45 @@
 //! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
 //!
 mod angles;
+mod crossmatch;
+mod disk;
+mod distance;
+mod ecliptic;
+mod equatorial;
+mod formatting;
+mod frame;
+mod geo;
+mod grid;
+mod horizontal;
+mod nutation;
+mod parallax;
+mod precession;
+mod projection;
+mod refraction;
+mod rotation;
+mod statistics;
+mod transforms;
+mod unwrapped;
+mod zodiac;
 
 pub use self::angles::*;
+pub use self::crossmatch::*;
+pub use self::disk::*;
+pub use self::distance::*;
+pub use self::ecliptic::*;
+pub use self::equatorial::*;
+pub use self::formatting::*;
+pub use self::frame::*;
+pub use self::geo::*;
+pub use self::grid::*;
+pub use self::horizontal::*;
+pub use self::nutation::*;
+pub use self::parallax::*;
+pub use self::precession::*;
+pub use self::projection::*;
+pub use self::refraction::*;
+pub use self::rotation::*;
+pub use self::statistics::*;
+pub use self::transforms::*;
+pub use self::unwrapped::*;
+pub use self::zodiac::*;
@@ -0,0 +1,532 @@
+//!
+//! Module for nutation and the obliquity of the ecliptic.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Formulas are from chapter 22 of Astronomical Algorithms, 2nd Edition by Jean Meeus, which uses
+//! the 1980 IAU theory of nutation. The IAU 2000B model is also available for better accuracy.
+//!
+use std::f64::consts::PI;
+
+use super::super::astro_time::AstroTime;
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+
+/// The fundamental arguments of the lunar and solar theories, the same quantities are needed for
+/// nutation, the position of the Moon, eclipses, and librations.
+///
+/// The polynomials are from chapter 47, and all angles are mapped into the range [0, 2π).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundamentalArguments {
+    julian_centuries: f64,
+    mean_elongation: RadianAngle,
+    sun_mean_anomaly: RadianAngle,
+    moon_mean_anomaly: RadianAngle,
+    moon_argument_of_latitude: RadianAngle,
+    moon_ascending_node: RadianAngle,
+    moon_mean_longitude: RadianAngle,
+}
+
+impl FundamentalArguments {
+    /// Calculate the fundamental arguments at a time.
+    pub fn for_time(time: &AstroTime) -> AstroResult<FundamentalArguments> {
+        let t = julian_centuries(time)?;
+
+        let angle = |degrees: f64| {
+            let mut rads = map_to_branch(degrees.to_radians(), 0.0, 2.0 * PI);
+            if rads >= 2.0 * PI {
+                rads -= 2.0 * PI;
+            }
+            RadianAngle::new(rads)
+        };
+
+        Ok(FundamentalArguments {
+            julian_centuries: t,
+            mean_elongation: angle(297.850_192_1 +
+                                   t * (445_267.111_403_4 +
+                                        t * (-0.001_881_9 +
+                                             t * (1.0 / 545_868.0 - t / 113_065_000.0))))?,
+            sun_mean_anomaly: angle(357.529_109_2 +
+                                    t * (35_999.050_290_9 +
+                                         t * (-0.000_153_6 + t / 24_490_000.0)))?,
+            moon_mean_anomaly: angle(134.963_396_4 +
+                                     t * (477_198.867_505_5 +
+                                          t * (0.008_741_4 +
+                                               t * (1.0 / 69_699.0 - t / 14_712_000.0))))?,
+            moon_argument_of_latitude: angle(93.272_095_0 +
+                                             t * (483_202.017_523_3 +
+                                                  t * (-0.003_653_9 +
+                                                       t * (-1.0 / 3_526_000.0 +
+                                                            t / 863_310_000.0))))?,
+            moon_ascending_node: angle(125.044_547_9 +
+                                       t * (-1_934.136_289_1 +
+                                            t * (0.002_075_4 +
+                                                 t * (1.0 / 467_441.0 - t / 60_616_000.0))))?,
+            moon_mean_longitude: angle(218.316_447_7 +
+                                       t * (481_267.881_234_21 +
+                                            t * (-0.001_578_6 +
+                                                 t * (1.0 / 538_841.0 - t / 65_194_000.0))))?,
+        })
+    }
+
+    /// Julian centuries of dynamical time since J2000.0, T.
+    pub fn julian_centuries(&self) -> f64 {
+        self.julian_centuries
+    }
+
+    /// Mean elongation of the Moon from the Sun, D.
+    pub fn mean_elongation(&self) -> RadianAngle {
+        self.mean_elongation
+    }
+
+    /// Mean anomaly of the Sun (Earth), M.
+    pub fn sun_mean_anomaly(&self) -> RadianAngle {
+        self.sun_mean_anomaly
+    }
+
+    /// Mean anomaly of the Moon, M'.
+    pub fn moon_mean_anomaly(&self) -> RadianAngle {
+        self.moon_mean_anomaly
+    }
+
+    /// Argument of latitude of the Moon, F, its mean distance from the ascending node.
+    pub fn moon_argument_of_latitude(&self) -> RadianAngle {
+        self.moon_argument_of_latitude
+    }
+
+    /// Longitude of the mean ascending node of the lunar orbit on the ecliptic, Ω, measured from
+    /// the mean equinox of the date.
+    pub fn moon_ascending_node(&self) -> RadianAngle {
+        self.moon_ascending_node
+    }
+
+    /// Mean longitude of the Moon, L', referred to the mean equinox of the date.
+    pub fn moon_mean_longitude(&self) -> RadianAngle {
+        self.moon_mean_longitude
+    }
+}
+
+/// Theories of nutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NutationModel {
+    /// The 1980 IAU theory as tabulated by Meeus, accurate to about 0.0003".
+    Iau1980,
+    /// The 77 term IAU 2000B model of McCarthy & Luzum (2003), accurate to about 0.001" between
+    /// 1995 and 2050.
+    Iau2000B,
+}
+
+/// Calculate the nutation in longitude and in obliquity, (Δψ, Δε), using the 1980 IAU theory.
+pub fn nutation(time: &AstroTime) -> AstroResult<(RadianAngle, RadianAngle)> {
+    nutation_with(time, NutationModel::Iau1980)
+}
+
+/// Calculate the nutation in longitude and in obliquity, (Δψ, Δε), using the chosen model.
+pub fn nutation_with(time: &AstroTime,
+                     model: NutationModel)
+                     -> AstroResult<(RadianAngle, RadianAngle)> {
+    let (delta_psi, delta_eps) = match model {
+        NutationModel::Iau1980 => nutation_1980(&FundamentalArguments::for_time(time)?),
+        NutationModel::Iau2000B => nutation_2000b(julian_centuries(time)?),
+    };
+    trace_event!(julian_day = time.julian_day_number(),
+                 model = ?model,
+                 delta_psi_arcsec = delta_psi.to_degrees() * 3600.0,
+                 delta_eps_arcsec = delta_eps.to_degrees() * 3600.0,
+                 "nutation");
+
+    Ok((RadianAngle::new(delta_psi)?, RadianAngle::new(delta_eps)?))
+}
+
+/// Formulas for the mean obliquity of the ecliptic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObliquityModel {
+    /// The IAU polynomial, equation 22.2. The error reaches 1" over 2000 years and 10" over 4000
+    /// years from J2000.0.
+    Iau1980,
+    /// The series of Laskar, equation 22.3. Accurate to 0.01" over 1000 years and a few seconds of
+    /// arc over 10,000 years from J2000.0, but not valid outside of that range.
+    Laskar,
+}
+
+/// Calculate the mean obliquity of the ecliptic, ε₀, using the IAU formula, equation 22.2.
+pub fn mean_obliquity(time: &AstroTime) -> AstroResult<RadianAngle> {
+    mean_obliquity_with(time, ObliquityModel::Iau1980)
+}
+
+/// Calculate the mean obliquity of the ecliptic, ε₀, using the chosen formula.
+///
+/// The Laskar series is only valid within 10,000 years of J2000.0, and a `RangeError` is returned
+/// for times outside of that range instead of extrapolating. The IAU polynomial always returns a
+/// value, but it degrades quickly far from J2000.0.
+pub fn mean_obliquity_with(time: &AstroTime, model: ObliquityModel) -> AstroResult<RadianAngle> {
+    let t = julian_centuries(time)?;
+
+    let seconds = match model {
+        ObliquityModel::Iau1980 => 21.448 + t * (-46.815_0 + t * (-0.000_59 + t * 0.001_813)),
+        ObliquityModel::Laskar => {
+            let u = t / 100.0;
+            if u.abs() > 1.0 {
+                let jde = time.julian_ephemeris_day()?;
+                let limit = 2_451_545.0 + u.signum() * LASKAR_LIMIT_DAYS;
+                return Err(AstroAlgorithmsError::RangeError(if u > 0.0 {
+                    DateRangeError::DateOverflow(jde, limit)
+                } else {
+                    DateRangeError::DateUnderflow(jde, limit)
+                }));
+            }
+
+            LASKAR_TERMS.iter().rev().fold(0.0, |acc, &c| acc * u + c)
+        }
+    };
+
+    RadianAngle::new((23.0 + (26.0 + seconds / 60.0) / 60.0).to_radians())
+}
+
+/// Calculate the true obliquity of the ecliptic, ε = ε₀ + Δε.
+pub fn true_obliquity(time: &AstroTime) -> AstroResult<RadianAngle> {
+    let (_, delta_eps) = nutation(time)?;
+
+    RadianAngle::new(mean_obliquity(time)?.radians() + delta_eps.radians())
+}
+
+/// Calculate the equation of the equinoxes, Δψ cos ε, the difference of the apparent and mean
+/// sidereal times, chapter 12.
+pub fn equation_of_equinoxes(time: &AstroTime) -> AstroResult<RadianAngle> {
+    let (delta_psi, _) = nutation(time)?;
+    let eps = true_obliquity(time)?;
+
+    RadianAngle::new(delta_psi.radians() * cos(eps.radians()))
+}
+
+// Julian centuries of dynamical time since J2000.0
+fn julian_centuries(time: &AstroTime) -> AstroResult<f64> {
+    Ok((time.julian_ephemeris_day()? - 2_451_545.0) / 36_525.0)
+}
+
+// Days from J2000.0 to the edge of the range of validity of the Laskar series, 10,000 years.
+const LASKAR_LIMIT_DAYS: f64 = 3_652_500.0;
+
+// Coefficients of the Laskar series in powers of U, arc-seconds added to 23° 26'.
+const LASKAR_TERMS: [f64; 11] = [21.448, -4680.93, -1.55, 1999.25, -51.38, -249.67, -39.05, 7.12,
+                                 27.87, 5.79, 2.45];
+
+// Nutation in radians from chapter 22.
+fn nutation_1980(args: &FundamentalArguments) -> (f64, f64) {
+    let t = args.julian_centuries();
+    let d = args.mean_elongation().radians();
+    let m = args.sun_mean_anomaly().radians();
+    let m_prime = args.moon_mean_anomaly().radians();
+    let f = args.moon_argument_of_latitude().radians();
+    let omega = args.moon_ascending_node().radians();
+
+    let mut delta_psi = 0.0;
+    let mut delta_eps = 0.0;
+    for &(cd, cm, cm_prime, cf, co, psi0, psi1, eps0, eps1) in NUTATION_TERMS.iter() {
+        let arg = cd * d + cm * m + cm_prime * m_prime + cf * f + co * omega;
+        delta_psi += (psi0 + psi1 * t) * sin(arg);
+        delta_eps += (eps0 + eps1 * t) * cos(arg);
+    }
+
+    // Coefficients are in units of 0.0001 arc-seconds
+    ((delta_psi / 36_000_000.0).to_radians(), (delta_eps / 36_000_000.0).to_radians())
+}
+
+// Nutation in radians from the IAU 2000B model, t is in Julian centuries since J2000.0.
+//
+// Adapted from the nut00b routine of the IAU SOFA library.
+fn nutation_2000b(t: f64) -> (f64, f64) {
+    // Delaunay arguments in arc-seconds, Simon et al. (1994)
+    let l = 485_868.249_036 + 1_717_915_923.217_8 * t;
+    let l_prime = 1_287_104.793_05 + 129_596_581.048_1 * t;
+    let f = 335_779.526_232 + 1_739_527_262.847_8 * t;
+    let d = 1_072_260.703_69 + 1_602_961_601.209_0 * t;
+    let omega = 450_160.398_036 - 6_962_890.543_1 * t;
+
+    let to_radians = |arcsec: f64| (arcsec % 1_296_000.0 / 3600.0).to_radians();
+    let (l, l_prime, f, d, omega) =
+        (to_radians(l), to_radians(l_prime), to_radians(f), to_radians(d), to_radians(omega));
+
+    // Sum the smallest terms first to limit round off.
+    let mut delta_psi = 0.0;
+    let mut delta_eps = 0.0;
+    for &(cl, cl_prime, cf, cd, co, ps, pst, pc, ec, ect, es) in NUTATION_TERMS_2000B.iter().rev() {
+        let arg = f64::from(cl) * l + f64::from(cl_prime) * l_prime + f64::from(cf) * f +
+                  f64::from(cd) * d + f64::from(co) * omega;
+        let (sin_arg, cos_arg) = sin_cos(arg);
+        delta_psi += (ps + pst * t) * sin_arg + pc * cos_arg;
+        delta_eps += (ec + ect * t) * cos_arg + es * sin_arg;
+    }
+
+    // Coefficients are in units of 0.1 micro arc-seconds, and a fixed offset stands in for the
+    // planetary terms.
+    let to_radians = |arcsec: f64| (arcsec / 3600.0).to_radians();
+    (to_radians(delta_psi * 1.0e-7 - 0.000_135), to_radians(delta_eps * 1.0e-7 + 0.000_388))
+}
+
+// Multiples of D, M, M', F, and Ω, then the coefficients for Δψ and Δε.
+type NutationTerm = (f64, f64, f64, f64, f64, f64, f64, f64, f64);
+
+// Table 22.A, multiples of D, M, M', F, and Ω, then the coefficients of the sine for Δψ and of the
+// cosine for Δε in units of 0.0001".
+#[rustfmt::skip]
+const NUTATION_TERMS: [NutationTerm; 63] = [
+    ( 0.0,  0.0,  0.0,  0.0,  1.0, -171_996.0, -174.2, 92_025.0,  8.9),
+    (-2.0,  0.0,  0.0,  2.0,  2.0,  -13_187.0,   -1.6,  5_736.0, -3.1),
+    ( 0.0,  0.0,  0.0,  2.0,  2.0,   -2_274.0,   -0.2,    977.0, -0.5),
+    ( 0.0,  0.0,  0.0,  0.0,  2.0,    2_062.0,    0.2,   -895.0,  0.5),
+    ( 0.0,  1.0,  0.0,  0.0,  0.0,    1_426.0,   -3.4,     54.0, -0.1),
+    ( 0.0,  0.0,  1.0,  0.0,  0.0,      712.0,    0.1,     -7.0,  0.0),
+    (-2.0,  1.0,  0.0,  2.0,  2.0,     -517.0,    1.2,    224.0, -0.6),
+    ( 0.0,  0.0,  0.0,  2.0,  1.0,     -386.0,   -0.4,    200.0,  0.0),
+    ( 0.0,  0.0,  1.0,  2.0,  2.0,     -301.0,    0.0,    129.0, -0.1),
+    (-2.0, -1.0,  0.0,  2.0,  2.0,      217.0,   -0.5,    -95.0,  0.3),
+    (-2.0,  0.0,  1.0,  0.0,  0.0,     -158.0,    0.0,      0.0,  0.0),
+    (-2.0,  0.0,  0.0,  2.0,  1.0,      129.0,    0.1,    -70.0,  0.0),
+    ( 0.0,  0.0, -1.0,  2.0,  2.0,      123.0,    0.0,    -53.0,  0.0),
+    ( 2.0,  0.0,  0.0,  0.0,  0.0,       63.0,    0.0,      0.0,  0.0),
+    ( 0.0,  0.0,  1.0,  0.0,  1.0,       63.0,    0.1,    -33.0,  0.0),
+    ( 2.0,  0.0, -1.0,  2.0,  2.0,      -59.0,    0.0,     26.0,  0.0),
+    ( 0.0,  0.0, -1.0,  0.0,  1.0,      -58.0,   -0.1,     32.0,  0.0),
+    ( 0.0,  0.0,  1.0,  2.0,  1.0,      -51.0,    0.0,     27.0,  0.0),
+    (-2.0,  0.0,  2.0,  0.0,  0.0,       48.0,    0.0,      0.0,  0.0),
+    ( 0.0,  0.0, -2.0,  2.0,  1.0,       46.0,    0.0,    -24.0,  0.0),
+    ( 2.0,  0.0,  0.0,  2.0,  2.0,      -38.0,    0.0,     16.0,  0.0),
+    ( 0.0,  0.0,  2.0,  2.0,  2.0,      -31.0,    0.0,     13.0,  0.0),
+    ( 0.0,  0.0,  2.0,  0.0,  0.0,       29.0,    0.0,      0.0,  0.0),
+    (-2.0,  0.0,  1.0,  2.0,  2.0,       29.0,    0.0,    -12.0,  0.0),
+    ( 0.0,  0.0,  0.0,  2.0,  0.0,       26.0,    0.0,      0.0,  0.0),
+    (-2.0,  0.0,  0.0,  2.0,  0.0,      -22.0,    0.0,      0.0,  0.0),
+    ( 0.0,  0.0, -1.0,  2.0,  1.0,       21.0,    0.0,    -10.0,  0.0),
+    ( 0.0,  2.0,  0.0,  0.0,  0.0,       17.0,   -0.1,      0.0,  0.0),
+    ( 2.0,  0.0, -1.0,  0.0,  1.0,       16.0,    0.0,     -8.0,  0.0),
+    (-2.0,  2.0,  0.0,  2.0,  2.0,      -16.0,    0.1,      7.0,  0.0),
+    ( 0.0,  1.0,  0.0,  0.0,  1.0,      -15.0,    0.0,      9.0,  0.0),
+    (-2.0,  0.0,  1.0,  0.0,  1.0,      -13.0,    0.0,      7.0,  0.0),
+    ( 0.0, -1.0,  0.0,  0.0,  1.0,      -12.0,    0.0,      6.0,  0.0),
+    ( 0.0,  0.0,  2.0, -2.0,  0.0,       11.0,    0.0,      0.0,  0.0),
+    ( 2.0,  0.0, -1.0,  2.0,  1.0,      -10.0,    0.0,      5.0,  0.0),
+    ( 2.0,  0.0,  1.0,  2.0,  2.0,       -8.0,    0.0,      3.0,  0.0),
+    ( 0.0,  1.0,  0.0,  2.0,  2.0,        7.0,    0.0,     -3.0,  0.0),
+    (-2.0,  1.0,  1.0,  0.0,  0.0,       -7.0,    0.0,      0.0,  0.0),
+    ( 0.0, -1.0,  0.0,  2.0,  2.0,       -7.0,    0.0,      3.0,  0.0),
+    ( 2.0,  0.0,  0.0,  2.0,  1.0,       -7.0,    0.0,      3.0,  0.0),
+    ( 2.0,  0.0,  1.0,  0.0,  0.0,        6.0,    0.0,      0.0,  0.0),
+    (-2.0,  0.0,  2.0,  2.0,  2.0,        6.0,    0.0,     -3.0,  0.0),
+    (-2.0,  0.0,  1.0,  2.0,  1.0,        6.0,    0.0,     -3.0,  0.0),
+    ( 2.0,  0.0, -2.0,  0.0,  1.0,       -6.0,    0.0,      3.0,  0.0),
+    ( 2.0,  0.0,  0.0,  0.0,  1.0,       -6.0,    0.0,      3.0,  0.0),
+    ( 0.0, -1.0,  1.0,  0.0,  0.0,        5.0,    0.0,      0.0,  0.0),
+    (-2.0, -1.0,  0.0,  2.0,  1.0,       -5.0,    0.0,      3.0,  0.0),
+    (-2.0,  0.0,  0.0,  0.0,  1.0,       -5.0,    0.0,      3.0,  0.0),
+    ( 0.0,  0.0,  2.0,  2.0,  1.0,       -5.0,    0.0,      3.0,  0.0),
+    (-2.0,  0.0,  2.0,  0.0,  1.0,        4.0,    0.0,      0.0,  0.0),
+    (-2.0,  1.0,  0.0,  2.0,  1.0,        4.0,    0.0,      0.0,  0.0),
+    ( 0.0,  0.0,  1.0, -2.0,  0.0,        4.0,    0.0,      0.0,  0.0),
+    (-1.0,  0.0,  1.0,  0.0,  0.0,       -4.0,    0.0,      0.0,  0.0),
+    (-2.0,  1.0,  0.0,  0.0,  0.0,       -4.0,    0.0,      0.0,  0.0),
+    ( 1.0,  0.0,  0.0,  0.0,  0.0,       -4.0,    0.0,      0.0,  0.0),
+    ( 0.0,  0.0,  1.0,  2.0,  0.0,        3.0,    0.0,      0.0,  0.0),
+    ( 0.0,  0.0, -2.0,  2.0,  2.0,       -3.0,    0.0,      0.0,  0.0),
+    (-1.0, -1.0,  1.0,  0.0,  0.0,       -3.0,    0.0,      0.0,  0.0),
+    ( 0.0,  1.0,  1.0,  0.0,  0.0,       -3.0,    0.0,      0.0,  0.0),
+    ( 0.0, -1.0,  1.0,  2.0,  2.0,       -3.0,    0.0,      0.0,  0.0),
+    ( 2.0, -1.0, -1.0,  2.0,  2.0,       -3.0,    0.0,      0.0,  0.0),
+    ( 0.0,  0.0,  3.0,  2.0,  2.0,       -3.0,    0.0,      0.0,  0.0),
+    ( 2.0, -1.0,  0.0,  2.0,  2.0,       -3.0,    0.0,      0.0,  0.0),
+];
+
+// Multiples of l, l', F, D, and Ω, then the coefficients for Δψ (sin, t sin, cos) and for Δε
+// (cos, t cos, sin).
+type NutationTerm2000B = (i8, i8, i8, i8, i8, f64, f64, f64, f64, f64, f64);
+
+// Luni-solar terms of the IAU 2000B model, in units of 0.1 micro arc-seconds.
+#[rustfmt::skip]
+const NUTATION_TERMS_2000B: [NutationTerm2000B; 77] = [
+    ( 0,  0,  0,  0, 1, -172_064_161.0, -174_666.0,  33_386.0, 92_052_331.0,  9_086.0, 15_377.0),
+    ( 0,  0,  2, -2, 2,  -13_170_906.0,   -1_675.0, -13_696.0,  5_730_336.0, -3_015.0, -4_587.0),
+    ( 0,  0,  2,  0, 2,   -2_276_413.0,     -234.0,   2_796.0,    978_459.0,   -485.0,  1_374.0),
+    ( 0,  0,  0,  0, 2,    2_074_554.0,      207.0,    -698.0,   -897_492.0,    470.0,   -291.0),
+    ( 0,  1,  0,  0, 0,    1_475_877.0,   -3_633.0,  11_817.0,     73_871.0,   -184.0, -1_924.0),
+    ( 0,  1,  2, -2, 2,     -516_821.0,    1_226.0,    -524.0,    224_386.0,   -677.0,   -174.0),
+    ( 1,  0,  0,  0, 0,      711_159.0,       73.0,    -872.0,     -6_750.0,      0.0,    358.0),
+    ( 0,  0,  2,  0, 1,     -387_298.0,     -367.0,     380.0,    200_728.0,     18.0,    318.0),
+    ( 1,  0,  2,  0, 2,     -301_461.0,      -36.0,     816.0,    129_025.0,    -63.0,    367.0),
+    ( 0, -1,  2, -2, 2,      215_829.0,     -494.0,     111.0,    -95_929.0,    299.0,    132.0),
+    ( 0,  0,  2, -2, 1,      128_227.0,      137.0,     181.0,    -68_982.0,     -9.0,     39.0),
+    (-1,  0,  2,  0, 2,      123_457.0,       11.0,      19.0,    -53_311.0,     32.0,     -4.0),
+    (-1,  0,  0,  2, 0,      156_994.0,       10.0,    -168.0,     -1_235.0,      0.0,     82.0),
+    ( 1,  0,  0,  0, 1,       63_110.0,       63.0,      27.0,    -33_228.0,      0.0,     -9.0),
+    (-1,  0,  0,  0, 1,      -57_976.0,      -63.0,    -189.0,     31_429.0,      0.0,    -75.0),
+    (-1,  0,  2,  2, 2,      -59_641.0,      -11.0,     149.0,     25_543.0,    -11.0,     66.0),
+    ( 1,  0,  2,  0, 1,      -51_613.0,      -42.0,     129.0,     26_366.0,      0.0,     78.0),
+    (-2,  0,  2,  0, 1,       45_893.0,       50.0,      31.0,    -24_236.0,    -10.0,     20.0),
+    ( 0,  0,  0,  2, 0,       63_384.0,       11.0,    -150.0,     -1_220.0,      0.0,     29.0),
+    ( 0,  0,  2,  2, 2,      -38_571.0,       -1.0,     158.0,     16_452.0,    -11.0,     68.0),
+    ( 0, -2,  2, -2, 2,       32_481.0,        0.0,       0.0,    -13_870.0,      0.0,      0.0),
+    (-2,  0,  0,  2, 0,      -47_722.0,        0.0,     -18.0,        477.0,      0.0,    -25.0),
+    ( 2,  0,  2,  0, 2,      -31_046.0,       -1.0,     131.0,     13_238.0,    -11.0,     59.0),
+    ( 1,  0,  2, -2, 2,       28_593.0,        0.0,      -1.0,    -12_338.0,     10.0,     -3.0),
+    (-1,  0,  2,  0, 1,       20_441.0,       21.0,      10.0,    -10_758.0,      0.0,     -3.0),
+    ( 2,  0,  0,  0, 0,       29_243.0,        0.0,     -74.0,       -609.0,      0.0,     13.0),
+    ( 0,  0,  2,  0, 0,       25_887.0,        0.0,     -66.0,       -550.0,      0.0,     11.0),
+    ( 0,  1,  0,  0, 1,      -14_053.0,      -25.0,      79.0,      8_551.0,     -2.0,    -45.0),
+    (-1,  0,  0,  2, 1,       15_164.0,       10.0,      11.0,     -8_001.0,      0.0,     -1.0),
+    ( 0,  2,  2, -2, 2,      -15_794.0,       72.0,     -16.0,      6_850.0,    -42.0,     -5.0),
+    ( 0,  0, -2,  2, 0,       21_783.0,        0.0,      13.0,       -167.0,      0.0,     13.0),
+    ( 1,  0,  0, -2, 1,      -12_873.0,      -10.0,     -37.0,      6_953.0,      0.0,    -14.0),
+    ( 0, -1,  0,  0, 1,      -12_654.0,       11.0,      63.0,      6_415.0,      0.0,     26.0),
+    (-1,  0,  2,  2, 1,      -10_204.0,        0.0,      25.0,      5_222.0,      0.0,     15.0),
+    ( 0,  2,  0,  0, 0,       16_707.0,      -85.0,     -10.0,        168.0,     -1.0,     10.0),
+    ( 1,  0,  2,  2, 2,       -7_691.0,        0.0,      44.0,      3_268.0,      0.0,     19.0),
+    (-2,  0,  2,  0, 0,      -11_024.0,        0.0,     -14.0,        104.0,      0.0,      2.0),
+    ( 0,  1,  2,  0, 2,        7_566.0,      -21.0,     -11.0,     -3_250.0,      0.0,     -5.0),
+    ( 0,  0,  2,  2, 1,       -6_637.0,      -11.0,      25.0,      3_353.0,      0.0,     14.0),
+    ( 0, -1,  2,  0, 2,       -7_141.0,       21.0,       8.0,      3_070.0,      0.0,      4.0),
+    ( 0,  0,  0,  2, 1,       -6_302.0,      -11.0,       2.0,      3_272.0,      0.0,      4.0),
+    ( 1,  0,  2, -2, 1,        5_800.0,       10.0,       2.0,     -3_045.0,      0.0,     -1.0),
+    ( 2,  0,  2, -2, 2,        6_443.0,        0.0,      -7.0,     -2_768.0,      0.0,     -4.0),
+    (-2,  0,  0,  2, 1,       -5_774.0,      -11.0,     -15.0,      3_041.0,      0.0,     -5.0),
+    ( 2,  0,  2,  0, 1,       -5_350.0,        0.0,      21.0,      2_695.0,      0.0,     12.0),
+    ( 0, -1,  2, -2, 1,       -4_752.0,      -11.0,      -3.0,      2_719.0,      0.0,     -3.0),
+    ( 0,  0,  0, -2, 1,       -4_940.0,      -11.0,     -21.0,      2_720.0,      0.0,     -9.0),
+    (-1, -1,  0,  2, 0,        7_350.0,        0.0,      -8.0,        -51.0,      0.0,      4.0),
+    ( 2,  0,  0, -2, 1,        4_065.0,        0.0,       6.0,     -2_206.0,      0.0,      1.0),
+    ( 1,  0,  0,  2, 0,        6_579.0,        0.0,     -24.0,       -199.0,      0.0,      2.0),
+    ( 0,  1,  2, -2, 1,        3_579.0,        0.0,       5.0,     -1_900.0,      0.0,      1.0),
+    ( 1, -1,  0,  0, 0,        4_725.0,        0.0,      -6.0,        -41.0,      0.0,      3.0),
+    (-2,  0,  2,  0, 2,       -3_075.0,        0.0,      -2.0,      1_313.0,      0.0,     -1.0),
+    ( 3,  0,  2,  0, 2,       -2_904.0,        0.0,      15.0,      1_233.0,      0.0,      7.0),
+    ( 0, -1,  0,  2, 0,        4_348.0,        0.0,     -10.0,        -81.0,      0.0,      2.0),
+    ( 1, -1,  2,  0, 2,       -2_878.0,        0.0,       8.0,      1_232.0,      0.0,      4.0),
+    ( 0,  0,  0,  1, 0,       -4_230.0,        0.0,       5.0,        -20.0,      0.0,     -2.0),
+    (-1, -1,  2,  2, 2,       -2_819.0,        0.0,       7.0,      1_207.0,      0.0,      3.0),
+    (-1,  0,  2,  0, 0,       -4_056.0,        0.0,       5.0,         40.0,      0.0,     -2.0),
+    ( 0, -1,  2,  2, 2,       -2_647.0,        0.0,      11.0,      1_129.0,      0.0,      5.0),
+    (-2,  0,  0,  0, 1,       -2_294.0,        0.0,     -10.0,      1_266.0,      0.0,     -4.0),
+    ( 1,  1,  2,  0, 2,        2_481.0,        0.0,      -7.0,     -1_062.0,      0.0,     -3.0),
+    ( 2,  0,  0,  0, 1,        2_179.0,        0.0,      -2.0,     -1_129.0,      0.0,     -2.0),
+    (-1,  1,  0,  1, 0,        3_276.0,        0.0,       1.0,         -9.0,      0.0,      0.0),
+    ( 1,  1,  0,  0, 0,       -3_389.0,        0.0,       5.0,         35.0,      0.0,     -2.0),
+    ( 1,  0,  2,  0, 0,        3_339.0,        0.0,     -13.0,       -107.0,      0.0,      1.0),
+    (-1,  0,  2, -2, 1,       -1_987.0,        0.0,      -6.0,      1_073.0,      0.0,     -2.0),
+    ( 1,  0,  0,  0, 2,       -1_981.0,        0.0,       0.0,        854.0,      0.0,      0.0),
+    (-1,  0,  0,  1, 0,        4_026.0,        0.0,    -353.0,       -553.0,      0.0,   -139.0),
+    ( 0,  0,  2,  1, 2,        1_660.0,        0.0,      -5.0,       -710.0,      0.0,     -2.0),
+    (-1,  0,  2,  4, 2,       -1_521.0,        0.0,       9.0,        647.0,      0.0,      4.0),
+    (-1,  1,  0,  1, 1,        1_314.0,        0.0,       0.0,       -700.0,      0.0,      0.0),
+    ( 0, -2,  2, -2, 1,       -1_283.0,        0.0,       0.0,        672.0,      0.0,      0.0),
+    ( 1,  0,  2,  2, 1,       -1_331.0,        0.0,       8.0,        663.0,      0.0,      4.0),
+    (-2,  0,  2,  2, 2,        1_383.0,        0.0,      -2.0,       -594.0,      0.0,     -2.0),
+    (-1,  0,  0,  0, 2,        1_405.0,        0.0,       4.0,       -610.0,      0.0,      2.0),
+    ( 1,  1,  2, -2, 2,        1_290.0,        0.0,       0.0,       -556.0,      0.0,      0.0),
+];
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod nutation_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    // Example 22.a, Astronomical Algorithms 2nd ed.
+    fn example_22a() -> AstroTime {
+        Builder::from_julian_date(2_446_895.5).dynamical_time().build().unwrap()
+    }
+
+    fn arcseconds(angle: RadianAngle) -> f64 {
+        angle.radians().to_degrees() * 3600.0
+    }
+
+    #[test]
+    fn test_fundamental_arguments() {
+        // Example 47.a, Astronomical Algorithms 2nd ed.
+        let time = Builder::from_julian_date(2_448_724.5).dynamical_time().build().unwrap();
+        let args = FundamentalArguments::for_time(&time).unwrap();
+        let degrees = |angle: RadianAngle| angle.radians().to_degrees();
+
+        assert!(approx_eq(args.julian_centuries(), -0.077_221_081_451, 1.0e-12));
+        assert!(approx_eq(degrees(args.moon_mean_longitude()), 134.290_182, 1.0e-6));
+        assert!(approx_eq(degrees(args.mean_elongation()), 113.842_304, 1.0e-6));
+        assert!(approx_eq(degrees(args.sun_mean_anomaly()), 97.643_514, 1.0e-6));
+        assert!(approx_eq(degrees(args.moon_mean_anomaly()), 5.150_833, 1.0e-6));
+        assert!(approx_eq(degrees(args.moon_argument_of_latitude()), 219.889_721, 1.0e-6));
+        assert!(approx_eq(degrees(args.moon_ascending_node()), 274.400_656, 1.0e-5));
+    }
+
+    #[test]
+    fn test_nutation() {
+        let (delta_psi, delta_eps) = nutation(&example_22a()).unwrap();
+        assert!(approx_eq(arcseconds(delta_psi), -3.788, 1.0e-3));
+        assert!(approx_eq(arcseconds(delta_eps), 9.443, 1.0e-3));
+    }
+
+    #[test]
+    fn test_equation_of_equinoxes() {
+        // Example 12.a, Astronomical Algorithms 2nd ed., -0.2317 seconds of time.
+        let time = Builder::from_gregorian_utc(1987, 4, 10, 0, 0, 0).build().unwrap();
+        let eq_eq = equation_of_equinoxes(&time).unwrap();
+        assert!(approx_eq(eq_eq.radians().to_degrees() * 240.0, -0.231_7, 1.0e-3));
+    }
+
+    #[test]
+    fn test_nutation_2000b() {
+        // Test case from the IAU SOFA library, TT MJD 53736.0
+        let time = Builder::from_julian_date(2_453_736.5).dynamical_time().build().unwrap();
+        let (delta_psi, delta_eps) = nutation_with(&time, NutationModel::Iau2000B).unwrap();
+        assert!(approx_eq(delta_psi.radians(), -0.963_255_229_114_836_3e-5, 1.0e-13));
+        assert!(approx_eq(delta_eps.radians(), 0.406_319_710_662_115_9e-4, 1.0e-13));
+
+        // The models agree to a few milli-arc-seconds.
+        let (psi_1980, eps_1980) = nutation_with(&time, NutationModel::Iau1980).unwrap();
+        assert!(approx_eq(arcseconds(psi_1980), arcseconds(delta_psi), 0.05));
+        assert!(approx_eq(arcseconds(eps_1980), arcseconds(delta_eps), 0.05));
+    }
+
+    #[test]
+    fn test_obliquity_models() {
+        let laskar = mean_obliquity_with(&example_22a(), ObliquityModel::Laskar).unwrap();
+        assert!(approx_eq(arcseconds(laskar), (23.0 * 60.0 + 26.0) * 60.0 + 27.407, 1.0e-3));
+
+        // 5000 years in the future the models diverge, but both are usable.
+        let time = Builder::from_julian_date(2_451_545.0 + 5000.0 * 365.25)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let iau = mean_obliquity_with(&time, ObliquityModel::Iau1980).unwrap();
+        let laskar = mean_obliquity_with(&time, ObliquityModel::Laskar).unwrap();
+        assert!(approx_eq(arcseconds(iau), arcseconds(laskar), 100.0));
+        assert!(!approx_eq(arcseconds(iau), arcseconds(laskar), 1.0));
+
+        // Beyond 10,000 years the Laskar series is not valid.
+        let time = Builder::from_julian_date(2_451_545.0 + 12_000.0 * 365.25)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        match mean_obliquity_with(&time, ObliquityModel::Laskar) {
+            Err(AstroAlgorithmsError::RangeError(DateRangeError::DateOverflow(_, limit))) => {
+                assert!(approx_eq(limit, 2_451_545.0 + 3_652_500.0, 1.0e-6))
+            }
+            _ => panic!("Expected a date overflow"),
+        }
+        assert!(mean_obliquity_with(&time, ObliquityModel::Iau1980).is_ok());
+    }
+
+    #[test]
+    fn test_obliquity() {
+        let eps_0 = mean_obliquity(&example_22a()).unwrap();
+        assert!(approx_eq(arcseconds(eps_0), (23.0 * 60.0 + 26.0) * 60.0 + 27.407, 1.0e-3));
+
+        let eps = true_obliquity(&example_22a()).unwrap();
+        assert!(approx_eq(arcseconds(eps), (23.0 * 60.0 + 26.0) * 60.0 + 36.850, 1.0e-3));
+    }
+}
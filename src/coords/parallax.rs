@@ -0,0 +1,116 @@
+//!
+//! Module with quick parallax corrections for nearby bodies like the Moon and planets.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! These treat the Earth as a sphere and work directly with the altitude, which is good enough for
+//! quick corrections. The rigorous method of chapter 40 of Astronomical Algorithms, 2nd Edition by
+//! Jean Meeus works with right ascension and declination and accounts for the figure of the Earth.
+//!
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+
+/// Equatorial radius of the Earth in kilometers, as used by Meeus.
+pub const EARTH_EQUATORIAL_RADIUS_KM: f64 = 6378.14;
+
+/// Calculate the equatorial horizontal parallax of a body at a distance in kilometers.
+///
+/// Returns an error if the distance is not greater than the radius of the Earth.
+pub fn horizontal_parallax(distance_km: f64) -> AstroResult<RadianAngle> {
+    if distance_km.is_nan() {
+        Err(AstroAlgorithmsError::EncounteredNaN)
+    } else if distance_km <= EARTH_EQUATORIAL_RADIUS_KM {
+        Err(AstroAlgorithmsError::InvalidArgument("Distance must be greater than Earth's radius"
+            .to_owned()))
+    } else {
+        RadianAngle::new(asin(EARTH_EQUATORIAL_RADIUS_KM / distance_km))
+    }
+}
+
+/// Calculate the parallax in altitude for a body with the given horizontal parallax and geocentric
+/// altitude.
+///
+/// Subtract the result from the geocentric altitude to get the topocentric altitude.
+pub fn parallax_in_altitude(horizontal_parallax: RadianAngle,
+                            geocentric_altitude: RadianAngle)
+                            -> RadianAngle {
+    let sin_pi = sin(horizontal_parallax.radians());
+    let h = geocentric_altitude.radians();
+
+    RadianAngle::new(atan2(sin_pi * cos(h), 1.0 - sin_pi * sin(h))).unwrap()
+}
+
+/// Calculate the distance of a body from the observer, given its distance from the center of the
+/// Earth, horizontal parallax, and geocentric altitude.
+///
+/// The result is in the same units as `geocentric_distance`.
+pub fn topocentric_distance(geocentric_distance: f64,
+                            horizontal_parallax: RadianAngle,
+                            geocentric_altitude: RadianAngle)
+                            -> f64 {
+    let sin_pi = sin(horizontal_parallax.radians());
+    let sin_h = sin(geocentric_altitude.radians());
+
+    geocentric_distance * f64::sqrt(1.0 - 2.0 * sin_pi * sin_h + sin_pi * sin_pi)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod parallax_tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_horizontal_parallax() {
+        // Example 47.a, Astronomical Algorithms 2nd ed.
+        let pi = horizontal_parallax(368_409.7).unwrap();
+        assert!(approx_eq(pi.radians().to_degrees(), 0.991_990, 1.0e-6));
+
+        match horizontal_parallax(6000.0) {
+            Err(AstroAlgorithmsError::InvalidArgument(_)) => {}
+            result => panic!("{:?}", result),
+        }
+        assert_eq!(horizontal_parallax(f64::NAN), Err(AstroAlgorithmsError::EncounteredNaN));
+    }
+
+    #[test]
+    fn test_parallax_in_altitude() {
+        let pi = horizontal_parallax(384_400.0).unwrap();
+
+        // On the observer's horizon the geocentric altitude equals the horizontal parallax.
+        let at_horizon = parallax_in_altitude(pi, pi);
+        assert!(approx_eq(at_horizon.radians(), pi.radians(), 1.0e-15));
+
+        let at_zenith = parallax_in_altitude(pi, RadianAngle::new(FRAC_PI_2).unwrap());
+        assert!(approx_eq(at_zenith.radians(), 0.0, 1.0e-15));
+
+        // The topocentric altitude should satisfy sin(p) = sin(pi) cos(h').
+        let h = RadianAngle::new(0.5).unwrap();
+        let p = parallax_in_altitude(pi, h).radians();
+        assert!(approx_eq(p.sin(), pi.radians().sin() * (0.5 - p).cos(), 1.0e-15));
+    }
+
+    #[test]
+    fn test_topocentric_distance() {
+        let pi = horizontal_parallax(384_400.0).unwrap();
+
+        let at_zenith = topocentric_distance(384_400.0, pi, RadianAngle::new(FRAC_PI_2).unwrap());
+        assert!(approx_eq(at_zenith, 384_400.0 - EARTH_EQUATORIAL_RADIUS_KM, 1.0e-8));
+
+        let at_horizon = topocentric_distance(384_400.0, pi, RadianAngle::new(0.0).unwrap());
+        assert!(approx_eq(at_horizon,
+                          f64::sqrt(384_400.0f64.powi(2) +
+                                    EARTH_EQUATORIAL_RADIUS_KM * EARTH_EQUATORIAL_RADIUS_KM),
+                          1.0e-8));
+    }
+}
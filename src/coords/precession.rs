@@ -0,0 +1,253 @@
+//!
+//! Module for the precession of equatorial and ecliptic coordinates.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Uses the rigorous method of chapter 21 of Astronomical Algorithms, 2nd Edition by Jean Meeus,
+//! with the IAU 1976 precession angles.
+//!
+use super::super::astro_time::{AstroTime, Epoch};
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+use super::ecliptic::*;
+use super::equatorial::*;
+use super::rotation::precession_matrix_between;
+
+// Epochs closer than this in days are treated as the same.
+const EPOCH_TOLERANCE_DAYS: f64 = 1.0;
+
+/// Precess equatorial coordinates from their epoch to the mean equator and equinox of another
+/// epoch.
+///
+/// Proper motion is not accounted for, it must be applied by the caller.
+pub fn precess_equatorial(eq: EquatorialCoords,
+                          epoch: AstroTime)
+                          -> AstroResult<EquatorialCoords> {
+    precession_matrix_between(&eq.epoch(), &epoch)?.rotate_equatorial(eq, epoch)
+}
+
+/// Precess equatorial coordinates to a standard epoch, or an equinox such as J2024.5 given by a
+/// catalog, see `precess_equatorial`.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::{Epoch, J2000};
+/// # use astro_calc::coords::{precess_equatorial_to_epoch, EquatorialCoords, RadianAngle};
+/// let eq = EquatorialCoords::with_epoch(RadianAngle::new(1.0).unwrap(),
+///                                       RadianAngle::new(0.5).unwrap(),
+///                                       "B1950".parse::<Epoch>().unwrap())
+///     .unwrap();
+/// let eq = precess_equatorial_to_epoch(eq, Epoch::J2000).unwrap();
+/// assert!(eq.epoch() == J2000);
+/// ```
+pub fn precess_equatorial_to_epoch(eq: EquatorialCoords,
+                                   epoch: Epoch)
+                                   -> AstroResult<EquatorialCoords> {
+    precess_equatorial(eq, epoch.time()?)
+}
+
+/// Precess ecliptic coordinates from their epoch to the ecliptic and equinox of another epoch,
+/// equation 21.7.
+///
+/// This converts between the ecliptic of a date and the ecliptic of J2000.0. Coordinates referred
+/// to the true equinox have the nutation of their epoch removed before the precession and the
+/// nutation of the new epoch added after, so the frame is kept.
+pub fn precess_ecliptic(ecl: EclipticCoords, epoch: AstroTime) -> AstroResult<EclipticCoords> {
+    let frame = ecl.frame();
+    let ecl = ecl.to_frame(EclipticFrame::Mean)?;
+
+    let jd_0 = ecl.epoch().julian_ephemeris_day()?;
+    let jd = epoch.julian_ephemeris_day()?;
+    let (eta, pi, p) = ecliptic_precession_angles(jd_0, jd);
+
+    let (sin_eta, cos_eta) = sin_cos(eta);
+    let (sin_lat, cos_lat) = sin_cos(ecl.latitude().radians());
+    let (sin_d, cos_d) = sin_cos(pi - ecl.longitude().radians());
+
+    let a = cos_eta * cos_lat * sin_d - sin_eta * sin_lat;
+    let b = cos_lat * cos_d;
+    let c = cos_eta * sin_lat + sin_eta * cos_lat * sin_d;
+
+    EclipticCoords::new(RadianAngle::new(p + pi - atan2(a, b))?,
+                        RadianAngle::new(atan2(c, hypot(a, b)))?,
+                        epoch)?
+        .to_frame(frame)
+}
+
+/// Precess ecliptic coordinates to a standard epoch, or an equinox such as J2024.5 given by a
+/// catalog, see `precess_ecliptic`.
+pub fn precess_ecliptic_to_epoch(ecl: EclipticCoords,
+                                 epoch: Epoch)
+                                 -> AstroResult<EclipticCoords> {
+    precess_ecliptic(ecl, epoch.time()?)
+}
+
+/// What to do with coordinates that aren't referred to the epoch an operation needs.
+///
+/// Over a few decades the precession moves positions by more than half a degree, so mixing epochs
+/// silently gives wrong answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpochMismatch {
+    /// Use the coordinates as they are.
+    Ignore,
+    /// Return an error.
+    Error,
+    /// Precess the coordinates to the needed epoch.
+    Precess,
+}
+
+/// Refer equatorial coordinates to `epoch` according to `mismatch`.
+///
+/// Epochs less than a day apart, when the precession is about 0.14", are treated as the same and
+/// the coordinates are returned unchanged. Otherwise `EpochMismatch::Error` gives an error and
+/// `EpochMismatch::Precess` precesses them with `precess_equatorial`, which doesn't apply the
+/// nutation or aberration.
+pub fn match_epoch(eq: EquatorialCoords,
+                   epoch: AstroTime,
+                   mismatch: EpochMismatch)
+                   -> AstroResult<EquatorialCoords> {
+    let jd_0 = eq.epoch().julian_ephemeris_day()?;
+    let jd = epoch.julian_ephemeris_day()?;
+    if (jd - jd_0).abs() < EPOCH_TOLERANCE_DAYS {
+        return Ok(eq);
+    }
+
+    match mismatch {
+        EpochMismatch::Ignore => Ok(eq),
+        EpochMismatch::Error => {
+            Err(AstroAlgorithmsError::InvalidArgument(format!("Coordinates referred to JDE {:.1} \
+                                                               were needed at JDE {:.1}",
+                                                              jd_0,
+                                                              jd)))
+        }
+        EpochMismatch::Precess => precess_equatorial(eq, epoch),
+    }
+}
+
+// The precession angles (ζ, z, θ) in radians from the Julian Ephemeris Day jd_0 to jd, equation
+// 21.2.
+pub(crate) fn precession_angles(jd_0: f64, jd: f64) -> (f64, f64, f64) {
+    let big_t = (jd_0 - 2_451_545.0) / 36_525.0;
+    let t = (jd - jd_0) / 36_525.0;
+
+    let base = 2_306.218_1 + big_t * (1.396_56 - big_t * 0.000_139);
+    let zeta = t * (base + t * (0.301_88 - 0.000_344 * big_t + t * 0.017_998));
+    let z = t * (base + t * (1.094_68 + 0.000_066 * big_t + t * 0.018_203));
+    let theta = t *
+                (2_004.310_9 - big_t * (0.853_30 + big_t * 0.000_217) -
+                 t * (0.426_65 + 0.000_217 * big_t + t * 0.041_833));
+
+    ((zeta / 3600.0).to_radians(), (z / 3600.0).to_radians(), (theta / 3600.0).to_radians())
+}
+
+// The angles (η, Π, p) in radians for the precession of ecliptic coordinates from the Julian
+// Ephemeris Day jd_0 to jd, equation 21.5.
+fn ecliptic_precession_angles(jd_0: f64, jd: f64) -> (f64, f64, f64) {
+    let big_t = (jd_0 - 2_451_545.0) / 36_525.0;
+    let t = (jd - jd_0) / 36_525.0;
+
+    let eta = t *
+              (47.002_9 - big_t * (0.066_03 - big_t * 0.000_598) +
+               t * (-0.033_02 + 0.000_598 * big_t + t * 0.000_060));
+    let pi = 174.876_384 * 3600.0 + big_t * (3_289.478_9 + big_t * 0.606_22) -
+             t * (869.808_9 + 0.504_91 * big_t - t * 0.035_36);
+    let p = t *
+            (5_029.096_6 + big_t * (2.222_26 - big_t * 0.000_042) +
+             t * (1.111_13 - 0.000_042 * big_t - t * 0.000_006));
+
+    ((eta / 3600.0).to_radians(), (pi / 3600.0).to_radians(), (p / 3600.0).to_radians())
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod precession_tests {
+    use super::*;
+    use astro_time::{Builder, J2000};
+
+    #[test]
+    fn test_precess_equatorial() {
+        // Example 21.b, Astronomical Algorithms 2nd ed., θ Persei with its proper motion applied.
+        let mean = EquatorialCoords::new(RadianAngle::new(41.054_063f64.to_radians()).unwrap(),
+                                         RadianAngle::new(49.227_750f64.to_radians()).unwrap(),
+                                         J2000)
+            .unwrap();
+        let epoch = Builder::from_julian_date(2_462_088.69).dynamical_time().build().unwrap();
+
+        let precessed = precess_equatorial(mean, epoch).unwrap();
+        assert!(approx_eq(precessed.right_ascension().radians().to_degrees(), 41.547_214, 1.0e-6));
+        assert!(approx_eq(precessed.declination().radians().to_degrees(), 49.348_483, 1.0e-6));
+        assert!(precessed.epoch() == epoch);
+
+        // And back again.
+        let back = precess_equatorial(precessed, J2000).unwrap();
+        assert!(approx_eq(back.right_ascension().radians(), mean.right_ascension().radians(),
+                          1.0e-10));
+        assert!(approx_eq(back.declination().radians(), mean.declination().radians(), 1.0e-10));
+
+        let back = precess_equatorial_to_epoch(precessed, Epoch::J2000).unwrap();
+        assert!(back.epoch() == J2000);
+        assert!(precess_equatorial_to_epoch(mean, Epoch::Julian(f64::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_precess_ecliptic() {
+        // Example 21.c, Astronomical Algorithms 2nd ed., Venus back to -214 June 30.
+        let venus = EclipticCoords::new(RadianAngle::new(149.481_94f64.to_radians()).unwrap(),
+                                        RadianAngle::new(1.765_49f64.to_radians()).unwrap(),
+                                        J2000)
+            .unwrap();
+        let epoch = Builder::from_julian_date(1_643_074.5).dynamical_time().build().unwrap();
+
+        let precessed = precess_ecliptic(venus, epoch).unwrap();
+        assert!(approx_eq(precessed.longitude().radians().to_degrees(), 118.704_15, 1.0e-4));
+        assert!(approx_eq(precessed.latitude().radians().to_degrees(), 1.615_33, 1.0e-5));
+        assert!(precessed.epoch() == epoch);
+
+        // The polynomials aren't exact inverses over 22 centuries.
+        let back = precess_ecliptic(precessed, J2000).unwrap();
+        assert!(approx_eq(back.longitude().radians(), venus.longitude().radians(), 1.0e-7));
+        assert!(approx_eq(back.latitude().radians(), venus.latitude().radians(), 1.0e-7));
+        let back_b1950 = precess_ecliptic_to_epoch(precessed, Epoch::B1950).unwrap();
+        let expected = precess_ecliptic(precessed, Epoch::B1950.time().unwrap()).unwrap();
+        assert_eq!(back_b1950, expected);
+
+        // The frame is kept, with the nutation of each epoch.
+        let apparent = venus.to_frame(EclipticFrame::True).unwrap();
+        let precessed = precess_ecliptic(apparent, epoch).unwrap();
+        assert_eq!(precessed.frame(), EclipticFrame::True);
+        let mean = precessed.to_frame(EclipticFrame::Mean).unwrap();
+        assert!(approx_eq(mean.longitude().radians().to_degrees(), 118.704_15, 1.0e-4));
+    }
+
+    #[test]
+    fn test_match_epoch() {
+        let mean = EquatorialCoords::new(RadianAngle::new(41.054_063f64.to_radians()).unwrap(),
+                                         RadianAngle::new(49.227_750f64.to_radians()).unwrap(),
+                                         J2000)
+            .unwrap();
+        let epoch = Builder::from_julian_date(2_462_088.69).dynamical_time().build().unwrap();
+
+        assert_eq!(match_epoch(mean, epoch, EpochMismatch::Ignore).unwrap(), mean);
+        match match_epoch(mean, epoch, EpochMismatch::Error) {
+            Err(AstroAlgorithmsError::InvalidArgument(_)) => {}
+            result => panic!("{:?}", result),
+        }
+        assert_eq!(match_epoch(mean, epoch, EpochMismatch::Precess).unwrap(),
+                   precess_equatorial(mean, epoch).unwrap());
+
+        // Within a day is the same epoch.
+        let near = Builder::from_julian_date(2_451_545.5).dynamical_time().build().unwrap();
+        assert_eq!(match_epoch(mean, near, EpochMismatch::Error).unwrap(), mean);
+    }
+}
@@ -0,0 +1,304 @@
+//!
+//! Module for projecting the sky onto a plane, for drawing charts and finder views.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The projections are azimuthal, centered on a point of the sky, with the formulae of J. P.
+//! Snyder, "Map Projections - A Working Manual", USGS Professional Paper 1395, 1987. Chart
+//! coordinates are in radians at the center, with x to the right and y up as the sky is seen:
+//! for equatorial charts north is up and east is to the left, and for horizontal charts the
+//! zenith is up and the azimuth increases to the right.
+//!
+use std::f64::consts::PI;
+
+use super::super::astro_time::AstroTime;
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+use super::equatorial::*;
+use super::horizontal::*;
+
+/// An azimuthal projection of the sky.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Projection {
+    /// Preserves shapes and maps circles on the sky to circles. Everything but the point opposite
+    /// the center can be projected.
+    Stereographic,
+    /// Maps great circles to straight lines, as a camera lens does. Only the hemisphere around the
+    /// center can be projected.
+    Gnomonic,
+    /// The sky as seen on a globe from a long way off. Only the hemisphere around the center can
+    /// be projected.
+    Orthographic,
+}
+
+/// A projection centered on a point given in equatorial coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquatorialProjection {
+    projection: Projection,
+    center: EquatorialCoords,
+}
+
+impl EquatorialProjection {
+    /// Create a new projection centered on a point.
+    pub fn new(projection: Projection, center: EquatorialCoords) -> EquatorialProjection {
+        EquatorialProjection { projection, center }
+    }
+
+    /// The projection.
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    /// The center of the chart.
+    pub fn center(&self) -> EquatorialCoords {
+        self.center
+    }
+
+    /// Project coordinates to (x, y) on the chart, or `None` if they are outside the part of the
+    /// sky the projection can show.
+    ///
+    /// The coordinates should be referred to the same epoch as the center.
+    pub fn project(&self, eq: EquatorialCoords) -> Option<(f64, f64)> {
+        forward(self.projection,
+                (-self.center.right_ascension().radians(), self.center.declination().radians()),
+                (-eq.right_ascension().radians(), eq.declination().radians()))
+    }
+
+    /// Find the coordinates of a point (x, y) on the chart, referred to the epoch of the center.
+    ///
+    /// Returns an error for points outside the projection.
+    pub fn unproject(&self, point: (f64, f64)) -> AstroResult<EquatorialCoords> {
+        let (lon, lat) = inverse(self.projection,
+                                 (-self.center.right_ascension().radians(),
+                                  self.center.declination().radians()),
+                                 point)?;
+
+        EquatorialCoords::new(RadianAngle::new(map_to_branch(-lon, 0.0, 2.0 * PI))?,
+                              RadianAngle::new(lat)?,
+                              self.center.epoch())
+    }
+}
+
+/// A projection centered on a point given in horizontal coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HorizontalProjection {
+    projection: Projection,
+    center: HorizontalCoords,
+}
+
+impl HorizontalProjection {
+    /// Create a new projection centered on a point.
+    pub fn new(projection: Projection, center: HorizontalCoords) -> HorizontalProjection {
+        HorizontalProjection { projection, center }
+    }
+
+    /// The projection.
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    /// The center of the chart.
+    pub fn center(&self) -> HorizontalCoords {
+        self.center
+    }
+
+    /// Project coordinates to (x, y) on the chart, or `None` if they are outside the part of the
+    /// sky the projection can show.
+    pub fn project(&self, hz: HorizontalCoords) -> Option<(f64, f64)> {
+        forward(self.projection,
+                (self.center.azimuth().radians(), self.center.altitude().radians()),
+                (hz.azimuth().radians(), hz.altitude().radians()))
+    }
+
+    /// Find the coordinates of a point (x, y) on the chart.
+    ///
+    /// Returns an error for points outside the projection.
+    pub fn unproject(&self, point: (f64, f64)) -> AstroResult<HorizontalCoords> {
+        let (lon, lat) = inverse(self.projection,
+                                 (self.center.azimuth().radians(),
+                                  self.center.altitude().radians()),
+                                 point)?;
+
+        HorizontalCoords::new(RadianAngle::new(lat)?, RadianAngle::new(lon)?)
+    }
+}
+
+/// Project equatorial coordinates with the projection centered on `center`.
+///
+/// A shortcut for `EquatorialProjection::new(projection, center).project(eq)`.
+pub fn project_equatorial(projection: Projection,
+                          center: EquatorialCoords,
+                          eq: EquatorialCoords)
+                          -> Option<(f64, f64)> {
+    EquatorialProjection::new(projection, center).project(eq)
+}
+
+/// Find the equatorial coordinates of a point on a chart centered on `center`, referred to the
+/// epoch `epoch`.
+///
+/// A shortcut for `EquatorialProjection::new(projection, center).unproject(point)` that retags
+/// the result with an epoch.
+pub fn unproject_equatorial(projection: Projection,
+                            center: EquatorialCoords,
+                            point: (f64, f64),
+                            epoch: AstroTime)
+                            -> AstroResult<EquatorialCoords> {
+    let eq = EquatorialProjection::new(projection, center).unproject(point)?;
+    EquatorialCoords::new(eq.right_ascension(), eq.declination(), epoch)
+}
+
+// Equations 20-3, 22-4 and 22-5 and their inverses for a center (λ₀, φ₀), with the longitude
+// increasing to the right.
+fn forward(projection: Projection, center: (f64, f64), point: (f64, f64)) -> Option<(f64, f64)> {
+    let (sin_phi_0, cos_phi_0) = sin_cos(center.1);
+    let (sin_phi, cos_phi) = sin_cos(point.1);
+    let (sin_dl, cos_dl) = sin_cos(point.0 - center.0);
+
+    let cos_c = sin_phi_0 * sin_phi + cos_phi_0 * cos_phi * cos_dl;
+    let k = match projection {
+        Projection::Stereographic if cos_c > -1.0 + 1.0e-12 => 2.0 / (1.0 + cos_c),
+        Projection::Gnomonic if cos_c > 1.0e-12 => 1.0 / cos_c,
+        Projection::Orthographic if cos_c >= 0.0 => 1.0,
+        _ => return None,
+    };
+
+    Some((k * cos_phi * sin_dl, k * (cos_phi_0 * sin_phi - sin_phi_0 * cos_phi * cos_dl)))
+}
+
+fn inverse(projection: Projection,
+           center: (f64, f64),
+           point: (f64, f64))
+           -> AstroResult<(f64, f64)> {
+    let (x, y) = point;
+    if x.is_nan() || y.is_nan() {
+        return Err(AstroAlgorithmsError::EncounteredNaN);
+    } else if x.is_infinite() || y.is_infinite() {
+        return Err(AstroAlgorithmsError::EncounteredInf);
+    }
+
+    let rho = hypot(x, y);
+    let c = match projection {
+        Projection::Stereographic => 2.0 * atan(rho / 2.0),
+        Projection::Gnomonic => atan(rho),
+        Projection::Orthographic if rho <= 1.0 => asin(rho),
+        Projection::Orthographic => {
+            return Err(AstroAlgorithmsError::InvalidArgument("The point is outside the \
+                                                              orthographic projection"
+                .to_owned()))
+        }
+    };
+    if rho == 0.0 {
+        return Ok(center);
+    }
+
+    let (sin_phi_0, cos_phi_0) = sin_cos(center.1);
+    let (sin_c, cos_c) = sin_cos(c);
+    let lat = asin((cos_c * sin_phi_0 + y * sin_c * cos_phi_0 / rho).clamp(-1.0, 1.0));
+    let lon = center.0 + atan2(x * sin_c, rho * cos_phi_0 * cos_c - y * sin_phi_0 * sin_c);
+
+    Ok((map_to_branch(lon, 0.0, 2.0 * PI), lat))
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod projection_tests {
+    use super::*;
+    use astro_time::J2000;
+
+    fn eq(ra: f64, dec: f64) -> EquatorialCoords {
+        EquatorialCoords::new(RadianAngle::new(ra.to_radians()).unwrap(),
+                              RadianAngle::new(dec.to_radians()).unwrap(),
+                              J2000)
+            .unwrap()
+    }
+
+    const PROJECTIONS: [Projection; 3] =
+        [Projection::Stereographic, Projection::Gnomonic, Projection::Orthographic];
+
+    #[test]
+    fn test_equatorial_round_trip() {
+        // Orion, centered on its belt.
+        let center = eq(84.0, -1.0);
+        let stars = [eq(88.79, 7.41), eq(78.63, -8.20), eq(83.0, -0.30), eq(81.28, 6.35)];
+        for &projection in &PROJECTIONS {
+            let chart = EquatorialProjection::new(projection, center);
+            assert_eq!(chart.project(center), Some((0.0, 0.0)));
+
+            for &star in &stars {
+                let point = chart.project(star).unwrap();
+                let back = chart.unproject(point).unwrap();
+                assert!(angular_separation(star, back).radians() < 1.0e-12);
+            }
+
+            // North is up and east, Betelgeuse, is to the left.
+            let (x, y) = chart.project(stars[0]).unwrap();
+            assert!(x < 0.0 && y > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_horizontal_round_trip() {
+        let center = HorizontalCoords::new(RadianAngle::new(0.5).unwrap(),
+                                           RadianAngle::new(6.0).unwrap())
+            .unwrap();
+        let target = HorizontalCoords::new(RadianAngle::new(0.7).unwrap(),
+                                           RadianAngle::new(0.2).unwrap())
+            .unwrap();
+        for &projection in &PROJECTIONS {
+            let chart = HorizontalProjection::new(projection, center);
+            let (x, y) = chart.project(target).unwrap();
+
+            // Higher and further west.
+            assert!(x > 0.0 && y > 0.0);
+            let back = chart.unproject((x, y)).unwrap();
+            assert!(approx_eq(back.altitude().radians(), 0.7, 1.0e-12));
+            assert!(approx_eq(back.azimuth().radians(), 0.2, 1.0e-12));
+        }
+    }
+
+    #[test]
+    fn test_projections() {
+        // Points on a great circle are on a straight line in the gnomonic projection.
+        let center = eq(40.0, 30.0);
+        let line = great_circle_points(eq(20.0, 10.0), eq(60.0, 50.0), 5).unwrap();
+        let points: Vec<_> = line.iter()
+            .map(|&p| project_equatorial(Projection::Gnomonic, center, p).unwrap())
+            .collect();
+        for p in &points[1..4] {
+            let cross = (p.0 - points[0].0) * (points[4].1 - points[0].1) -
+                        (p.1 - points[0].1) * (points[4].0 - points[0].0);
+            assert!(cross.abs() < 1.0e-12);
+        }
+
+        // The distance from the center for a point 60 degrees away.
+        let far = eq(40.0, 90.0);
+        let distances = [2.0 * 30f64.to_radians().tan(),
+                         60f64.to_radians().tan(),
+                         60f64.to_radians().sin()];
+        for (&projection, &distance) in PROJECTIONS.iter().zip(&distances) {
+            let (x, y) = project_equatorial(projection, center, far).unwrap();
+            assert!(approx_eq(x.hypot(y), distance, 1.0e-12));
+        }
+
+        // The far side of the sky.
+        let opposite = eq(220.0, -30.0);
+        assert!(project_equatorial(Projection::Stereographic, center, opposite).is_none());
+        assert!(project_equatorial(Projection::Gnomonic, center, eq(130.0, 0.0)).is_none());
+        assert!(project_equatorial(Projection::Orthographic, center, eq(200.0, 0.0)).is_none());
+
+        assert!(unproject_equatorial(Projection::Orthographic, center, (1.0, 0.5), J2000)
+            .is_err());
+        assert!(unproject_equatorial(Projection::Gnomonic, center, (f64::NAN, 0.0), J2000)
+            .is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//!
+//! Module for atmospheric refraction corrections.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Formulas are from chapter 16 of Astronomical Algorithms, 2nd Edition by Jean Meeus. They are
+//! intended for altitudes above the horizon, below -1 degree the refraction is held constant at
+//! its value for -1 degree.
+//!
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+
+/// Pressure and temperature at the observer, used to scale the refraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphericConditions {
+    pressure: f64,
+    temperature: f64,
+}
+
+impl AtmosphericConditions {
+    /// Create a new set of conditions from the pressure in millibars and temperature in degrees
+    /// Celsius.
+    pub fn new(pressure: f64, temperature: f64) -> AstroResult<AtmosphericConditions> {
+        if pressure.is_nan() || temperature.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if pressure.is_infinite() || temperature.is_infinite() {
+            Err(AstroAlgorithmsError::EncounteredInf)
+        } else if pressure < 0.0 || temperature <= -273.15 {
+            Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue)
+        } else {
+            Ok(AtmosphericConditions {
+                pressure,
+                temperature,
+            })
+        }
+    }
+
+    /// Pressure in millibars.
+    pub fn pressure(&self) -> f64 {
+        self.pressure
+    }
+
+    /// Temperature in degrees Celsius.
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    // Factor to scale the refraction for standard conditions, page 107.
+    fn scale_factor(&self) -> f64 {
+        self.pressure / 1010.0 * 283.0 / (273.0 + self.temperature)
+    }
+}
+
+impl Default for AtmosphericConditions {
+    /// The standard conditions assumed by Meeus, 1010 millibars and 10 degrees Celsius.
+    fn default() -> AtmosphericConditions {
+        AtmosphericConditions {
+            pressure: 1010.0,
+            temperature: 10.0,
+        }
+    }
+}
+
+/// Calculate the refraction for an apparent (observed) altitude.
+///
+/// Subtract the result from the apparent altitude to get the geometric altitude. Uses the formula
+/// of G. G. Bennett, equation 16.3.
+pub fn refraction_from_apparent(apparent_altitude: RadianAngle,
+                                conditions: &AtmosphericConditions)
+                                -> RadianAngle {
+    let h0 = f64::max(apparent_altitude.radians().to_degrees(), -1.0);
+    let minutes = 1.0 / tan((h0 + 7.31 / (h0 + 4.4)).to_radians());
+
+    to_radian_angle(minutes, conditions)
+}
+
+/// Calculate the refraction for a geometric (airless) altitude.
+///
+/// Add the result to the geometric altitude to get the apparent altitude. Uses the formula of
+/// Saemundsson, equation 16.4.
+pub fn refraction_from_geometric(geometric_altitude: RadianAngle,
+                                 conditions: &AtmosphericConditions)
+                                 -> RadianAngle {
+    let h = f64::max(geometric_altitude.radians().to_degrees(), -1.0);
+    let minutes = 1.02 / tan((h + 10.3 / (h + 5.11)).to_radians());
+
+    to_radian_angle(minutes, conditions)
+}
+
+/// Calculate the airmass, the length of the path through the atmosphere relative to the path at
+/// the zenith, for an apparent (observed) altitude.
+///
+/// Uses the formula of Kasten and Young (1989), which stays finite down to the horizon. Returns
+/// `None` when the altitude is below the horizon.
+pub fn airmass(apparent_altitude: RadianAngle) -> Option<f64> {
+    let h = apparent_altitude.radians();
+    if h < 0.0 {
+        None
+    } else {
+        Some(1.0 / (sin(h) + 0.505_72 * powf(h.to_degrees() + 6.079_95, -1.636_4)))
+    }
+}
+
+/// Calculate the dip of the horizon for an observer at a height in meters above the surface that
+/// forms the visible horizon, such as the sea.
+///
+/// The sea horizon of an elevated observer is below the astronomical horizon, so objects are seen
+/// to rise earlier and set later. The dip includes the usual allowance for terrestrial refraction,
+/// 1.76' times the square root of the height in meters. Returns an error for negative heights.
+pub fn dip_of_horizon(height: f64) -> AstroResult<RadianAngle> {
+    if height.is_nan() {
+        Err(AstroAlgorithmsError::EncounteredNaN)
+    } else if height < 0.0 {
+        Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue)
+    } else {
+        RadianAngle::new((1.76 * height.sqrt() / 60.0).to_radians())
+    }
+}
+
+// Scale refraction in arc-minutes for the conditions, the formulas go slightly negative near the
+// zenith, so clamp at zero.
+fn to_radian_angle(minutes: f64, conditions: &AtmosphericConditions) -> RadianAngle {
+    let minutes = f64::max(minutes, 0.0) * conditions.scale_factor();
+    trace_event!(refraction_arcmin = minutes,
+                 pressure = conditions.pressure,
+                 temperature = conditions.temperature,
+                 "refraction");
+
+    RadianAngle::new((minutes / 60.0).to_radians()).unwrap()
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod refraction_tests {
+    use super::*;
+
+    #[test]
+    fn test_atmospheric_conditions_new() {
+        use std::f64;
+
+        let test_subject = AtmosphericConditions::new(900.0, -5.0).unwrap();
+        assert_eq!(test_subject.pressure(), 900.0);
+        assert_eq!(test_subject.temperature(), -5.0);
+
+        assert_eq!(AtmosphericConditions::new(f64::NAN, 10.0).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+        assert_eq!(AtmosphericConditions::new(1010.0, f64::INFINITY).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredInf);
+        assert_eq!(AtmosphericConditions::new(-1.0, 10.0).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredInappropriateNegativeValue);
+    }
+
+    #[test]
+    fn test_refraction_from_apparent() {
+        // Example 16.a, Astronomical Algorithms 2nd ed.
+        let h0 = RadianAngle::new(0.5f64.to_radians()).unwrap();
+        let r = refraction_from_apparent(h0, &AtmosphericConditions::default());
+        assert!(approx_eq(r.radians().to_degrees() * 60.0, 28.754, 1.0e-3));
+
+        // Vanishes at the zenith
+        let h0 = RadianAngle::new(90f64.to_radians()).unwrap();
+        let r = refraction_from_apparent(h0, &AtmosphericConditions::default());
+        assert!(approx_eq(r.radians(), 0.0, 1.0e-15));
+    }
+
+    #[test]
+    fn test_refraction_from_geometric() {
+        // Round trip should agree to about 0.1 arc-minute, see page 107.
+        let conditions = AtmosphericConditions::default();
+        for &deg in &[0.0f64, 0.5, 2.0, 10.0, 45.0, 80.0] {
+            let h = RadianAngle::new(deg.to_radians()).unwrap();
+            let r = refraction_from_geometric(h, &conditions);
+            let h0 = RadianAngle::new(h.radians() + r.radians()).unwrap();
+            let r0 = refraction_from_apparent(h0, &conditions);
+            assert!(approx_eq(r.radians().to_degrees() * 60.0,
+                              r0.radians().to_degrees() * 60.0,
+                              0.1));
+        }
+
+        // Thin, warm air refracts less.
+        let h = RadianAngle::new(1f64.to_radians()).unwrap();
+        let thin = AtmosphericConditions::new(700.0, 30.0).unwrap();
+        assert!(refraction_from_geometric(h, &thin).radians() <
+                refraction_from_geometric(h, &conditions).radians());
+    }
+
+    #[test]
+    fn test_airmass() {
+        let at = |deg: f64| airmass(RadianAngle::new(deg.to_radians()).unwrap());
+
+        assert!(approx_eq(at(90.0).unwrap(), 1.0, 1.0e-3));
+        assert!(approx_eq(at(30.0).unwrap(), 1.994, 1.0e-3));
+        assert!(approx_eq(at(0.0).unwrap(), 37.92, 0.01));
+        assert!(at(-1.0).is_none());
+    }
+
+    #[test]
+    fn test_dip_of_horizon() {
+        let minutes = |height: f64| dip_of_horizon(height).unwrap().radians().to_degrees() * 60.0;
+
+        assert_eq!(minutes(0.0), 0.0);
+        assert!(approx_eq(minutes(100.0), 17.6, 1.0e-12));
+        assert_eq!(dip_of_horizon(-1.0).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredInappropriateNegativeValue);
+    }
+}
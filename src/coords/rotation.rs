@@ -0,0 +1,261 @@
+//!
+//! Module for rotation matrices between celestial and terrestrial reference frames.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The matrices follow the classical equinox based transformation, with the IERS 2003 frame bias,
+//! the IAU 1976 precession of chapter 21 of Astronomical Algorithms, 2nd Edition by Jean Meeus,
+//! the IAU 1980 nutation, and the apparent sidereal time. Polar motion is neglected.
+//!
+use std::ops::Mul;
+
+use super::super::astro_time::{AstroTime, J2000};
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+use super::equatorial::*;
+use super::nutation::*;
+use super::precession::precession_angles;
+use super::transforms::{greenwich_sidereal_radians, SiderealTime};
+
+/// A rotation of rectangular coordinates from one frame to another.
+///
+/// Vectors are (x, y, z) tuples, and matrices are combined by multiplication so that
+/// `(a * b).rotate(v)` is `a.rotate(b.rotate(v))`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotationMatrix {
+    elements: [[f64; 3]; 3],
+}
+
+impl RotationMatrix {
+    /// The identity rotation.
+    pub fn identity() -> RotationMatrix {
+        RotationMatrix { elements: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] }
+    }
+
+    /// The elements of the matrix by row.
+    pub fn elements(&self) -> [[f64; 3]; 3] {
+        self.elements
+    }
+
+    /// Rotate a vector.
+    pub fn rotate(&self, v: (f64, f64, f64)) -> (f64, f64, f64) {
+        let m = &self.elements;
+        (m[0][0] * v.0 + m[0][1] * v.1 + m[0][2] * v.2,
+         m[1][0] * v.0 + m[1][1] * v.1 + m[1][2] * v.2,
+         m[2][0] * v.0 + m[2][1] * v.1 + m[2][2] * v.2)
+    }
+
+    /// Rotate equatorial coordinates as a unit vector, and refer the result to `epoch`.
+    pub fn rotate_equatorial(&self,
+                             eq: EquatorialCoords,
+                             epoch: AstroTime)
+                             -> AstroResult<EquatorialCoords> {
+        let v = eq.to_unit_vector();
+        let (x, y, z) = self.rotate((v[0], v[1], v[2]));
+
+        EquatorialCoords::from_unit_vector([x, y, z], epoch)
+    }
+
+    /// The inverse rotation, which is the transpose of the matrix.
+    pub fn inverse(&self) -> RotationMatrix {
+        let mut elements = [[0.0; 3]; 3];
+        for (i, row) in elements.iter_mut().enumerate() {
+            for (j, element) in row.iter_mut().enumerate() {
+                *element = self.elements[j][i];
+            }
+        }
+
+        RotationMatrix { elements }
+    }
+
+    // Rotation of the frame by an angle about the x axis.
+    fn about_x(angle: f64) -> RotationMatrix {
+        let (sin, cos) = sin_cos(angle);
+        RotationMatrix { elements: [[1.0, 0.0, 0.0], [0.0, cos, sin], [0.0, -sin, cos]] }
+    }
+
+    // Rotation of the frame by an angle about the y axis.
+    fn about_y(angle: f64) -> RotationMatrix {
+        let (sin, cos) = sin_cos(angle);
+        RotationMatrix { elements: [[cos, 0.0, -sin], [0.0, 1.0, 0.0], [sin, 0.0, cos]] }
+    }
+
+    // Rotation of the frame by an angle about the z axis.
+    fn about_z(angle: f64) -> RotationMatrix {
+        let (sin, cos) = sin_cos(angle);
+        RotationMatrix { elements: [[cos, sin, 0.0], [-sin, cos, 0.0], [0.0, 0.0, 1.0]] }
+    }
+}
+
+impl Mul for RotationMatrix {
+    type Output = RotationMatrix;
+
+    fn mul(self, other: RotationMatrix) -> RotationMatrix {
+        let mut elements = [[0.0; 3]; 3];
+        for (i, row) in elements.iter_mut().enumerate() {
+            for (j, element) in row.iter_mut().enumerate() {
+                *element = (0..3).map(|k| self.elements[i][k] * other.elements[k][j]).sum();
+            }
+        }
+
+        RotationMatrix { elements }
+    }
+}
+
+/// The frame bias from the ICRS to the mean equator and equinox of J2000.0.
+pub fn frame_bias_matrix() -> RotationMatrix {
+    let mas = (1.0 / 3_600_000.0f64).to_radians();
+    let d_alpha_0 = -14.6 * mas;
+    let xi_0 = -16.617 * mas;
+    let eta_0 = -6.819_2 * mas;
+
+    RotationMatrix::about_x(-eta_0) * RotationMatrix::about_y(xi_0) *
+    RotationMatrix::about_z(d_alpha_0)
+}
+
+/// The precession from the mean equator and equinox of J2000.0 to the mean equator and equinox of
+/// the date.
+pub fn precession_matrix(time: &AstroTime) -> AstroResult<RotationMatrix> {
+    precession_matrix_between(&J2000, time)
+}
+
+/// The precession from the mean equator and equinox of one epoch to that of another.
+pub fn precession_matrix_between(from: &AstroTime, to: &AstroTime) -> AstroResult<RotationMatrix> {
+    let (zeta, z, theta) = precession_angles(from.julian_ephemeris_day()?,
+                                             to.julian_ephemeris_day()?);
+
+    Ok(RotationMatrix::about_z(-z) * RotationMatrix::about_y(theta) *
+       RotationMatrix::about_z(-zeta))
+}
+
+/// The rotation from ecliptic to equatorial rectangular coordinates for an obliquity of the
+/// ecliptic.
+pub fn ecliptic_to_equatorial_matrix(obliquity: RadianAngle) -> RotationMatrix {
+    RotationMatrix::about_x(-obliquity.radians())
+}
+
+/// The nutation from the mean equator and equinox of the date to the true equator and equinox of
+/// the date.
+pub fn nutation_matrix(time: &AstroTime) -> AstroResult<RotationMatrix> {
+    let (delta_psi, _) = nutation(time)?;
+    let eps_0 = mean_obliquity(time)?.radians();
+    let eps = true_obliquity(time)?.radians();
+
+    Ok(RotationMatrix::about_x(-eps) * RotationMatrix::about_z(-delta_psi.radians()) *
+       RotationMatrix::about_x(eps_0))
+}
+
+/// The combined frame bias, precession, nutation and rotation of the Earth, from the ICRS to a
+/// frame fixed to the Earth.
+///
+/// In the terrestrial frame the x axis points to the Greenwich meridian on the equator, the y axis
+/// to 90 degrees east longitude, and the z axis to the north pole.
+pub fn celestial_to_terrestrial_matrix(time: &AstroTime) -> AstroResult<RotationMatrix> {
+    let theta = greenwich_sidereal_radians(time, SiderealTime::Apparent)?;
+
+    Ok(RotationMatrix::about_z(theta) * nutation_matrix(time)? * precession_matrix(time)? *
+       frame_bias_matrix())
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod rotation_tests {
+    use super::*;
+    use astro_time::Builder;
+    use coords::*;
+
+    fn vector(eq: EquatorialCoords) -> (f64, f64, f64) {
+        let (sin_a, cos_a) = eq.right_ascension().radians().sin_cos();
+        let (sin_d, cos_d) = eq.declination().radians().sin_cos();
+        (cos_d * cos_a, cos_d * sin_a, sin_d)
+    }
+
+    // Example 23.a, Astronomical Algorithms 2nd ed., θ Persei with its proper motion applied.
+    fn theta_persei() -> (EquatorialCoords, AstroTime) {
+        let eq = EquatorialCoords::new(RadianAngle::new(41.054_063f64.to_radians()).unwrap(),
+                                       RadianAngle::new(49.227_750f64.to_radians()).unwrap(),
+                                       J2000)
+            .unwrap();
+        let time = Builder::from_julian_date(2_462_088.69).dynamical_time().build().unwrap();
+
+        (eq, time)
+    }
+
+    #[test]
+    fn test_rotation_matrix() {
+        let m = precession_matrix(&theta_persei().1).unwrap() * frame_bias_matrix();
+        let product = (m * m.inverse()).elements();
+        let identity = RotationMatrix::identity().elements();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!(approx_eq(product[i][j], identity[i][j], 1.0e-15));
+            }
+        }
+
+        let v = (0.6, 0.0, 0.8);
+        let back = m.inverse().rotate(m.rotate(v));
+        assert!(approx_eq(back.0, v.0, 1.0e-15) && approx_eq(back.2, v.2, 1.0e-15));
+
+        // The ecliptic pole.
+        let eps = RadianAngle::new(23.4f64.to_radians()).unwrap();
+        let (_, y, z) = ecliptic_to_equatorial_matrix(eps).rotate((0.0, 0.0, 1.0));
+        assert!(approx_eq(y, -eps.radians().sin(), 1.0e-15));
+        assert!(approx_eq(z, eps.radians().cos(), 1.0e-15));
+    }
+
+    #[test]
+    fn test_precession_nutation_matrix() {
+        let (eq, time) = theta_persei();
+
+        // The same as the rigorous precession.
+        let precessed = vector(precess_equatorial(eq, time).unwrap());
+        let v = precession_matrix(&time).unwrap().rotate(vector(eq));
+        assert!(approx_eq(v.0, precessed.0, 1.0e-12));
+        assert!(approx_eq(v.1, precessed.1, 1.0e-12));
+        assert!(approx_eq(v.2, precessed.2, 1.0e-12));
+
+        // And back again.
+        let back = precession_matrix_between(&time, &J2000).unwrap().rotate(v);
+        assert!(approx_eq(back.0, vector(eq).0, 1.0e-10));
+        assert!(approx_eq(back.2, vector(eq).2, 1.0e-10));
+
+        // With nutation, Δα = 15.843" and Δδ = 6.218".
+        let (x, y, z) = nutation_matrix(&time).unwrap().rotate(v);
+        let ra = y.atan2(x).to_degrees();
+        let dec = z.asin().to_degrees();
+        assert!(approx_eq(ra, 41.547_214 + 15.843 / 3600.0, 0.02 / 3600.0));
+        assert!(approx_eq(dec, 49.348_483 + 6.218 / 3600.0, 0.02 / 3600.0));
+    }
+
+    #[test]
+    fn test_celestial_to_terrestrial_matrix() {
+        let (eq, time) = theta_persei();
+        let (x, y, _) = celestial_to_terrestrial_matrix(&time).unwrap().rotate(vector(eq));
+
+        // The longitude of the star in the terrestrial frame is minus its Greenwich hour angle.
+        let (nx, ny, nz) = (nutation_matrix(&time).unwrap() * precession_matrix(&time).unwrap() *
+                            frame_bias_matrix())
+            .rotate(vector(eq));
+        let true_eq = EquatorialCoords::new(RadianAngle::new(ny.atan2(nx)).unwrap(),
+                                            RadianAngle::new(nz.asin()).unwrap(),
+                                            time)
+            .unwrap();
+        let greenwich = GeoCoords::new(RadianAngle::new(0.0).unwrap(),
+                                       RadianAngle::new(0.0).unwrap(),
+                                       0.0)
+            .unwrap();
+        let h = local_hour_angle(true_eq, greenwich, &time).unwrap().radians();
+        let d = y.atan2(x) + h;
+        assert!(approx_eq(d.sin(), 0.0, 1.0e-12) && d.cos() > 0.0);
+    }
+}
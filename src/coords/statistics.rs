@@ -0,0 +1,159 @@
+//!
+//! Module for statistics of angles.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Angles wrap around, so the arithmetic mean of 359 and 1 degrees is 180 degrees rather than 0.
+//! These work with the angles as unit vectors instead, following the circular statistics of
+//! K. V. Mardia and P. E. Jupp, "Directional Statistics", 2000.
+//!
+use std::f64::consts::PI;
+
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+
+/// Calculate the mean direction of a set of angles, in the range [0, 2π).
+///
+/// Returns an error if there are no angles, or if they balance out so there is no mean direction.
+pub fn circular_mean(angles: &[RadianAngle]) -> AstroResult<RadianAngle> {
+    weighted_circular_mean(angles, &vec![1.0; angles.len()])
+}
+
+/// Calculate the mean direction of a set of angles with weights, in the range [0, 2π).
+///
+/// Returns an error if the number of weights doesn't match the number of angles, if a weight is
+/// negative, if the weights add to zero, or if there is no mean direction.
+pub fn weighted_circular_mean(angles: &[RadianAngle], weights: &[f64]) -> AstroResult<RadianAngle> {
+    let (c, s, _) = resultant(angles, weights)?;
+    if f64::sqrt(c * c + s * s) < 1.0e-12 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The angles have no mean direction"
+            .to_owned()));
+    }
+
+    let mean = map_to_branch(atan2(s, c), 0.0, 2.0 * PI);
+    RadianAngle::new(if mean >= 2.0 * PI { 0.0 } else { mean })
+}
+
+/// Calculate the mean resultant length of a set of angles, a measure of their concentration from
+/// 0 for angles spread evenly around the circle to 1 for angles that are all the same.
+pub fn mean_resultant_length(angles: &[RadianAngle]) -> AstroResult<f64> {
+    let (c, s, w) = resultant(angles, &vec![1.0; angles.len()])?;
+
+    Ok((f64::sqrt(c * c + s * s) / w).min(1.0))
+}
+
+/// Calculate the circular standard deviation of a set of angles, √(-2 ln R̄) with R̄ the mean
+/// resultant length.
+///
+/// For angles close together this is nearly the usual standard deviation. Returns an error if the
+/// angles are spread so evenly there is no mean direction.
+pub fn circular_standard_deviation(angles: &[RadianAngle]) -> AstroResult<RadianAngle> {
+    let r = mean_resultant_length(angles)?;
+    if r < 1.0e-12 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The angles have no mean direction"
+            .to_owned()));
+    }
+
+    RadianAngle::new(f64::sqrt(-2.0 * ln(r)))
+}
+
+// The weighted sums of the cosines and sines, and the sum of the weights.
+fn resultant(angles: &[RadianAngle], weights: &[f64]) -> AstroResult<(f64, f64, f64)> {
+    if angles.is_empty() {
+        return Err(AstroAlgorithmsError::InvalidArgument("No angles given".to_owned()));
+    } else if angles.len() != weights.len() {
+        return Err(AstroAlgorithmsError::InvalidArgument("The number of weights doesn't match \
+                                                          the number of angles"
+            .to_owned()));
+    }
+
+    let (mut c, mut s, mut w) = (0.0, 0.0, 0.0);
+    for (angle, &weight) in angles.iter().zip(weights) {
+        if weight.is_nan() {
+            return Err(AstroAlgorithmsError::EncounteredNaN);
+        } else if weight.is_infinite() {
+            return Err(AstroAlgorithmsError::EncounteredInf);
+        } else if weight < 0.0 {
+            return Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue);
+        }
+
+        let (sin, cos) = sin_cos(angle.radians());
+        c += weight * cos;
+        s += weight * sin;
+        w += weight;
+    }
+
+    if w == 0.0 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The weights add to zero".to_owned()));
+    }
+
+    Ok((c, s, w))
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod statistics_tests {
+    use super::*;
+
+    fn degrees(angles: &[f64]) -> Vec<RadianAngle> {
+        angles.iter().map(|a| RadianAngle::new(a.to_radians()).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_circular_mean() {
+        // Across the branch cut.
+        let mean = circular_mean(&degrees(&[359.0, 1.0])).unwrap().radians();
+        assert!(mean < 1.0e-12 || approx_eq(mean, 2.0 * PI, 1.0e-12));
+        let mean = circular_mean(&degrees(&[350.0, 20.0, 5.0])).unwrap().radians().to_degrees();
+        assert!(approx_eq(mean, 5.0, 0.1));
+        let mean = circular_mean(&degrees(&[-170.0, 170.0])).unwrap().radians().to_degrees();
+        assert!(approx_eq(mean, 180.0, 1.0e-9));
+
+        assert!(circular_mean(&[]).is_err());
+        assert!(circular_mean(&degrees(&[0.0, 90.0, 180.0, 270.0])).is_err());
+    }
+
+    #[test]
+    fn test_weighted_circular_mean() {
+        let angles = degrees(&[10.0, 40.0]);
+        let mean = weighted_circular_mean(&angles, &[1.0, 0.0]).unwrap().radians().to_degrees();
+        assert!(approx_eq(mean, 10.0, 1.0e-9));
+        let mean = weighted_circular_mean(&angles, &[1.0, 1.0]).unwrap().radians().to_degrees();
+        assert!(approx_eq(mean, 25.0, 1.0e-9));
+
+        // Twice the weight of a repeated angle.
+        let repeated = circular_mean(&degrees(&[10.0, 40.0, 40.0])).unwrap();
+        assert!(approx_eq(weighted_circular_mean(&angles, &[1.0, 2.0]).unwrap().radians(),
+                          repeated.radians(),
+                          1.0e-12));
+
+        assert!(weighted_circular_mean(&angles, &[1.0]).is_err());
+        assert!(weighted_circular_mean(&angles, &[1.0, -1.0]).is_err());
+        assert!(weighted_circular_mean(&angles, &[0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_dispersion() {
+        let same = degrees(&[30.0, 30.0, 30.0]);
+        assert!(approx_eq(mean_resultant_length(&same).unwrap(), 1.0, 1.0e-12));
+        assert!(approx_eq(circular_standard_deviation(&same).unwrap().radians(), 0.0, 1.0e-6));
+
+        // Close to the linear standard deviation for a small spread.
+        let spread = degrees(&[359.0, 1.0]);
+        let sd = circular_standard_deviation(&spread).unwrap().radians().to_degrees();
+        assert!(approx_eq(sd, 1.0, 1.0e-3));
+
+        assert!(approx_eq(mean_resultant_length(&degrees(&[0.0, 180.0])).unwrap(), 0.0, 1.0e-12));
+        assert!(circular_standard_deviation(&degrees(&[0.0, 180.0])).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+//!
+//! Module for transformations between coordinate systems.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Formulas are from chapter 13 of Astronomical Algorithms, 2nd Edition by Jean Meeus.
+//!
+use super::super::astro_time::AstroTime;
+use super::super::error::*;
+use super::super::math::*;
+use super::angles::*;
+use super::ecliptic::*;
+use super::equatorial::*;
+use super::geo::*;
+use super::horizontal::*;
+use super::precession::*;
+
+/// Choice of sidereal time for transformations involving the hour angle.
+///
+/// Apparent coordinates, referred to the true equator and equinox of the date, go with the
+/// apparent sidereal time. Mean coordinates go with the mean sidereal time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiderealTime {
+    /// Mean sidereal time, referred to the mean equinox of the date.
+    Mean,
+    /// Apparent sidereal time, corrected for nutation by the equation of the equinoxes.
+    Apparent,
+}
+
+/// Rate of rotation of the Earth relative to the stars in radians per second of Universal Time.
+pub const EARTH_ROTATION_RATE: f64 = 7.292_115_85e-5;
+
+/// Calculate the local hour angle of an object at a time, measured westward from the meridian.
+///
+/// The apparent sidereal time is used.
+pub fn local_hour_angle(eq: EquatorialCoords,
+                        geo: GeoCoords,
+                        time: &AstroTime)
+                        -> AstroResult<RadianAngle> {
+    local_hour_angle_with(eq, geo, time, SiderealTime::Apparent)
+}
+
+/// Calculate the local hour angle of an object at a time using the chosen sidereal time.
+pub fn local_hour_angle_with(eq: EquatorialCoords,
+                             geo: GeoCoords,
+                             time: &AstroTime,
+                             sidereal: SiderealTime)
+                             -> AstroResult<RadianAngle> {
+    let theta_0 = greenwich_sidereal_radians(time, sidereal)?;
+
+    RadianAngle::new(theta_0 - geo.longitude().radians() - eq.right_ascension().radians())
+}
+
+/// Transform equatorial coordinates to horizontal coordinates for an observer at a time.
+///
+/// The equatorial coordinates should be apparent coordinates, referred to the true equator and
+/// equinox of the date, since the apparent sidereal time is used.
+pub fn trans_equatorial_to_horizontal(eq: EquatorialCoords,
+                                      geo: GeoCoords,
+                                      time: &AstroTime)
+                                      -> AstroResult<HorizontalCoords> {
+    trans_equatorial_to_horizontal_with(eq, geo, time, SiderealTime::Apparent)
+}
+
+/// Transform equatorial coordinates to horizontal coordinates for an observer at a time,
+/// referring them to the equinox of the date according to `mismatch`, see `match_epoch`.
+///
+/// With `EpochMismatch::Precess` catalog positions for J2000.0 can be used directly, to within the
+/// nutation and aberration of about 30".
+pub fn trans_equatorial_to_horizontal_checked(eq: EquatorialCoords,
+                                              geo: GeoCoords,
+                                              time: &AstroTime,
+                                              mismatch: EpochMismatch)
+                                              -> AstroResult<HorizontalCoords> {
+    trans_equatorial_to_horizontal(match_epoch(eq, *time, mismatch)?, geo, time)
+}
+
+/// Transform equatorial coordinates to horizontal coordinates using the chosen sidereal time.
+pub fn trans_equatorial_to_horizontal_with(eq: EquatorialCoords,
+                                           geo: GeoCoords,
+                                           time: &AstroTime,
+                                           sidereal: SiderealTime)
+                                           -> AstroResult<HorizontalCoords> {
+    let h = local_hour_angle_with(eq, geo, time, sidereal)?.radians();
+    let (sin_phi, cos_phi) = sin_cos(geo.latitude().radians());
+    let (sin_dec, cos_dec) = sin_cos(eq.declination().radians());
+
+    // Equation 13.5 multiplied through by cos(dec), so it is well behaved at the poles.
+    let az = atan2(cos_dec * sin(h), cos_dec * cos(h) * sin_phi - sin_dec * cos_phi);
+    let alt = asin(clamp_unit(sin_phi * sin_dec + cos_phi * cos_dec * cos(h)));
+
+    HorizontalCoords::new(RadianAngle::new(alt)?, RadianAngle::new(az)?)
+}
+
+/// Transform horizontal coordinates to equatorial coordinates of the given epoch.
+///
+/// The result is apparent coordinates, referred to the true equator and equinox of the date, and
+/// tagged with `epoch`.
+pub fn trans_horizontal_to_equatorial(hz: HorizontalCoords,
+                                      geo: GeoCoords,
+                                      time: &AstroTime,
+                                      epoch: AstroTime)
+                                      -> AstroResult<EquatorialCoords> {
+    trans_horizontal_to_equatorial_with(hz, geo, time, epoch, SiderealTime::Apparent)
+}
+
+/// Transform horizontal coordinates to equatorial coordinates using the chosen sidereal time.
+pub fn trans_horizontal_to_equatorial_with(hz: HorizontalCoords,
+                                           geo: GeoCoords,
+                                           time: &AstroTime,
+                                           epoch: AstroTime,
+                                           sidereal: SiderealTime)
+                                           -> AstroResult<EquatorialCoords> {
+    let (sin_phi, cos_phi) = sin_cos(geo.latitude().radians());
+    let (sin_alt, cos_alt) = sin_cos(hz.altitude().radians());
+    let az = hz.azimuth().radians();
+
+    let h = atan2(cos_alt * sin(az), cos_alt * cos(az) * sin_phi + sin_alt * cos_phi);
+    let dec = asin(clamp_unit(sin_phi * sin_alt - cos_phi * cos_alt * cos(az)));
+    let theta_0 = greenwich_sidereal_radians(time, sidereal)?;
+
+    EquatorialCoords::new(RadianAngle::new(theta_0 - geo.longitude().radians() - h)?,
+                          RadianAngle::new(dec)?,
+                          epoch)
+}
+
+/// Transform ecliptic coordinates to equatorial coordinates using the given obliquity of the
+/// ecliptic.
+///
+/// Use the mean obliquity for mean coordinates, and the true obliquity for apparent coordinates.
+/// The result is tagged with the epoch of `ecl`.
+pub fn trans_ecliptic_to_equatorial(ecl: EclipticCoords,
+                                    obliquity: RadianAngle)
+                                    -> AstroResult<EquatorialCoords> {
+    let (sin_eps, cos_eps) = sin_cos(obliquity.radians());
+    let (sin_lon, cos_lon) = sin_cos(ecl.longitude().radians());
+    let (sin_lat, cos_lat) = sin_cos(ecl.latitude().radians());
+
+    // Equation 13.3 multiplied through by cos(lat), so it is well behaved at the poles.
+    let ra = atan2(cos_lat * sin_lon * cos_eps - sin_lat * sin_eps, cos_lat * cos_lon);
+    let dec = asin(clamp_unit(sin_lat * cos_eps + cos_lat * sin_eps * sin_lon));
+
+    EquatorialCoords::new(RadianAngle::new(ra)?, RadianAngle::new(dec)?, ecl.epoch())
+}
+
+/// Transform equatorial coordinates to ecliptic coordinates using the given obliquity of the
+/// ecliptic.
+///
+/// The result is tagged with the epoch of `eq` and the mean equinox, mark apparent coordinates
+/// with `EclipticCoords::with_frame`.
+pub fn trans_equatorial_to_ecliptic(eq: EquatorialCoords,
+                                    obliquity: RadianAngle)
+                                    -> AstroResult<EclipticCoords> {
+    let (sin_eps, cos_eps) = sin_cos(obliquity.radians());
+    let (sin_ra, cos_ra) = sin_cos(eq.right_ascension().radians());
+    let (sin_dec, cos_dec) = sin_cos(eq.declination().radians());
+
+    // Equation 13.1 multiplied through by cos(dec)
+    let lon = atan2(cos_dec * sin_ra * cos_eps + sin_dec * sin_eps, cos_dec * cos_ra);
+    let lat = asin(clamp_unit(sin_dec * cos_eps - cos_dec * sin_eps * sin_ra));
+
+    EclipticCoords::new(RadianAngle::new(lon)?, RadianAngle::new(lat)?, eq.epoch())
+}
+
+/// Calculate the rates of change of the altitude and azimuth of a fixed object, as needed by the
+/// drive of an altitude-azimuth mount.
+///
+/// Returns a tuple of (altitude rate, azimuth rate) in radians per second of time. The motion of
+/// the object itself (important for the Moon) is ignored. The azimuth rate is unbounded as the
+/// object passes through the zenith, and an error is returned for an object at the zenith. The
+/// apparent sidereal time is used.
+pub fn tracking_rates(eq: EquatorialCoords,
+                      geo: GeoCoords,
+                      time: &AstroTime)
+                      -> AstroResult<(f64, f64)> {
+    tracking_rates_with(eq, geo, time, SiderealTime::Apparent)
+}
+
+/// Calculate the altitude and azimuth rates of a fixed object using the chosen sidereal time.
+pub fn tracking_rates_with(eq: EquatorialCoords,
+                           geo: GeoCoords,
+                           time: &AstroTime,
+                           sidereal: SiderealTime)
+                           -> AstroResult<(f64, f64)> {
+    let hz = trans_equatorial_to_horizontal_with(eq, geo, time, sidereal)?;
+    let (sin_phi, cos_phi) = sin_cos(geo.latitude().radians());
+    let (sin_alt, cos_alt) = sin_cos(hz.altitude().radians());
+    let (sin_az, cos_az) = sin_cos(hz.azimuth().radians());
+    if cos_alt < 1.0e-12 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The azimuth rate is undefined at the \
+                                                          zenith"
+            .to_owned()));
+    }
+
+    let alt_rate = -EARTH_ROTATION_RATE * cos_phi * sin_az;
+    let az_rate = EARTH_ROTATION_RATE * (sin_phi + cos_phi * sin_alt / cos_alt * cos_az);
+
+    Ok((alt_rate, az_rate))
+}
+
+// Sidereal time at Greenwich in radians, sidereal time is always based on universal time.
+pub(crate) fn greenwich_sidereal_radians(time: &AstroTime,
+                                         sidereal: SiderealTime)
+                                         -> AstroResult<f64> {
+    match sidereal {
+        SiderealTime::Mean => Ok(time.as_utc()?.sidereal_greenwich().to_radians()),
+        SiderealTime::Apparent => Ok(time.apparent_sidereal_greenwich()?.to_radians()),
+    }
+}
+
+// Protect asin from round off just outside of [-1, 1].
+fn clamp_unit(val: f64) -> f64 {
+    val.clamp(-1.0, 1.0)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod transforms_tests {
+    use super::*;
+    use astro_time::{Builder, J2000};
+
+    // Example 13.b, Astronomical Algorithms 2nd ed. Venus seen from the US Naval Observatory.
+    fn example_13b() -> (EquatorialCoords, GeoCoords, AstroTime) {
+        let time = Builder::from_gregorian_utc(1987, 4, 10, 19, 21, 0).build().unwrap();
+        let geo = GeoCoords::new(RadianAngle::from(DMSAngle::new(38, 55, 17.0).unwrap()),
+                                 RadianAngle::from(DMSAngle::new(77, 3, 56.0).unwrap()),
+                                 0.0)
+            .unwrap();
+
+        let eq = EquatorialCoords::new(RadianAngle::from(HMSAngle::new(23, 9, 16.641).unwrap()),
+                                       RadianAngle::from(DMSAngle::new(-6, 43, 11.61).unwrap()),
+                                       time)
+            .unwrap();
+
+        (eq, geo, time)
+    }
+
+    #[test]
+    fn test_local_hour_angle() {
+        let (eq, geo, time) = example_13b();
+        let h = local_hour_angle(eq, geo, &time).unwrap();
+        assert!(approx_eq(map_to_branch(h.radians().to_degrees(), 0.0, 360.0),
+                          64.352_133,
+                          2.0e-4));
+
+        // The mean sidereal time, 8h 34m 57.0896s, is ahead of the apparent sidereal time,
+        // 8h 34m 56.853s, by the equation of the equinoxes.
+        let h_mean = local_hour_angle_with(eq, geo, &time, SiderealTime::Mean).unwrap();
+        assert!(approx_eq((h_mean.radians() - h.radians()).to_degrees() * 240.0, 0.2366, 1.0e-3));
+    }
+
+    #[test]
+    fn test_trans_equatorial_to_horizontal() {
+        let (eq, geo, time) = example_13b();
+        let hz = trans_equatorial_to_horizontal(eq, geo, &time).unwrap();
+        assert!(approx_eq(hz.azimuth().radians().to_degrees(), 68.0337, 2.0e-4));
+        assert!(approx_eq(hz.altitude().radians().to_degrees(), 15.1249, 2.0e-4));
+
+        // The same time as dynamical time is converted to universal time first.
+        let dt = time.as_dt().unwrap();
+        let hz2 = trans_equatorial_to_horizontal(eq, geo, &dt).unwrap();
+        assert!(approx_eq(hz.azimuth().radians(), hz2.azimuth().radians(), 1.0e-10));
+
+        // Referred to J2000.0 the position is half a degree off unless it is precessed back.
+        let mean = precess_equatorial(eq, J2000).unwrap();
+        let checked = |mismatch| trans_equatorial_to_horizontal_checked(mean, geo, &time, mismatch);
+        let hz2 = checked(EpochMismatch::Precess).unwrap();
+        assert!(approx_eq(hz.azimuth().radians(), hz2.azimuth().radians(), 1.0e-10));
+        assert!(approx_eq(hz.altitude().radians(), hz2.altitude().radians(), 1.0e-10));
+        let hz2 = checked(EpochMismatch::Ignore).unwrap();
+        assert!((hz.azimuth().radians() - hz2.azimuth().radians()).abs() > 1.0e-3);
+        assert!(checked(EpochMismatch::Error).is_err());
+    }
+
+    #[test]
+    fn test_trans_horizontal_to_equatorial() {
+        let (eq, geo, time) = example_13b();
+        let hz = trans_equatorial_to_horizontal(eq, geo, &time).unwrap();
+        let eq2 = trans_horizontal_to_equatorial(hz, geo, &time, eq.epoch()).unwrap();
+        assert!(approx_eq(eq.right_ascension().radians(),
+                          eq2.right_ascension().radians(),
+                          1.0e-10));
+        assert!(approx_eq(eq.declination().radians(), eq2.declination().radians(), 1.0e-10));
+    }
+
+    #[test]
+    fn test_ecliptic_equatorial() {
+        // Example 13.a, Astronomical Algorithms 2nd ed. Pollux.
+        let eps = RadianAngle::new(23.439_291_1f64.to_radians()).unwrap();
+        let eq = EquatorialCoords::new(RadianAngle::new(116.328_942f64.to_radians()).unwrap(),
+                                       RadianAngle::new(28.026_183f64.to_radians()).unwrap(),
+                                       J2000)
+            .unwrap();
+
+        let ecl = trans_equatorial_to_ecliptic(eq, eps).unwrap();
+        assert!(approx_eq(ecl.longitude().radians().to_degrees(), 113.215_630, 1.0e-6));
+        assert!(approx_eq(ecl.latitude().radians().to_degrees(), 6.684_170, 1.0e-6));
+
+        let eq2 = trans_ecliptic_to_equatorial(ecl, eps).unwrap();
+        assert!(approx_eq(eq.right_ascension().radians(),
+                          eq2.right_ascension().radians(),
+                          1.0e-12));
+        assert!(approx_eq(eq.declination().radians(), eq2.declination().radians(), 1.0e-12));
+        assert!(eq2.epoch() == J2000);
+    }
+
+    #[test]
+    fn test_tracking_rates() {
+        let (eq, geo, time) = example_13b();
+        let (alt_rate, az_rate) = tracking_rates(eq, geo, &time).unwrap();
+
+        // Compare with a numerical derivative over one second.
+        let later = Builder::from_julian_date(time.julian_day_number() + 1.0 / 86_400.0)
+            .build()
+            .unwrap();
+        let before = trans_equatorial_to_horizontal(eq, geo, &time).unwrap();
+        let after = trans_equatorial_to_horizontal(eq, geo, &later).unwrap();
+
+        let num_alt = after.altitude().radians() - before.altitude().radians();
+        let num_az = after.azimuth().radians() - before.azimuth().radians();
+        assert!(approx_eq(alt_rate, num_alt, 1.0e-8));
+        assert!(approx_eq(az_rate, num_az, 1.0e-8));
+
+        // Venus is in the west and setting.
+        assert!(alt_rate < 0.0);
+    }
+
+    #[test]
+    fn test_poles_and_zenith() {
+        let (_, geo, time) = example_13b();
+        let degrees = |angle: RadianAngle| angle.radians().to_degrees();
+        let latitude = degrees(geo.latitude());
+
+        // The celestial poles are at the altitude of the latitude, due north and south.
+        for &(dec, alt, az) in &[(90.0, latitude, 180.0), (-90.0, -latitude, 0.0)] {
+            let eq = EquatorialCoords::new(RadianAngle::new(1.0).unwrap(),
+                                           RadianAngle::new(f64::to_radians(dec)).unwrap(),
+                                           time)
+                .unwrap();
+            let hz = trans_equatorial_to_horizontal(eq, geo, &time).unwrap();
+            assert!(approx_eq(degrees(hz.altitude()), alt, 1.0e-10));
+            assert!(approx_eq(degrees(hz.azimuth()), az, 1.0e-10));
+
+            let back = trans_horizontal_to_equatorial(hz, geo, &time, time).unwrap();
+            assert!(approx_eq(degrees(back.declination()), dec, 1.0e-10));
+        }
+
+        // The zenith has the declination of the latitude and the right ascension of the local
+        // sidereal time.
+        let zenith = HorizontalCoords::new(RadianAngle::new(f64::to_radians(90.0)).unwrap(),
+                                           RadianAngle::new(0.3).unwrap())
+            .unwrap();
+        let eq = trans_horizontal_to_equatorial(zenith, geo, &time, time).unwrap();
+        assert!(approx_eq(degrees(eq.declination()), latitude, 1.0e-10));
+        assert!(approx_eq(local_hour_angle(eq, geo, &time).unwrap().radians().sin(), 0.0, 1.0e-10));
+        let hz = trans_equatorial_to_horizontal(eq, geo, &time).unwrap();
+        assert!(approx_eq(degrees(hz.altitude()), 90.0, 1.0e-6));
+        assert!(tracking_rates(eq, geo, &time).is_err());
+
+        // The ecliptic poles.
+        let eps = RadianAngle::new(23.44f64.to_radians()).unwrap();
+        let pole = EclipticCoords::new(RadianAngle::new(0.0).unwrap(),
+                                       RadianAngle::new(f64::to_radians(90.0)).unwrap(),
+                                       J2000)
+            .unwrap();
+        let eq = trans_ecliptic_to_equatorial(pole, eps).unwrap();
+        assert!(approx_eq(degrees(eq.right_ascension()), 270.0, 1.0e-10));
+        assert!(approx_eq(degrees(eq.declination()), 90.0 - 23.44, 1.0e-10));
+        let ecl = trans_equatorial_to_ecliptic(eq, eps).unwrap();
+        assert!(approx_eq(degrees(ecl.latitude()), 90.0, 1.0e-6));
+    }
+}
@@ -0,0 +1,148 @@
+//!
+//! Module for following an angle continuously through a series of values.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Angles such as a planet's longitude are calculated in the range [0, 2π), so a series of them
+//! jumps by 2π each time they pass through zero. Unwrapping them counts the whole turns instead,
+//! so plots and differences of successive values stay smooth.
+//!
+use std::f64::consts::PI;
+
+use super::angles::*;
+
+/// An angle that accumulates whole turns as it is updated, instead of staying in [0, 2π).
+///
+/// Each update is taken to be the branch closest to the previous value, so the values must be
+/// sampled often enough that the angle changes by less than π between them.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::coords::{RadianAngle, UnwrappedAngle};
+/// let mut angle = UnwrappedAngle::new(RadianAngle::new(6.0).unwrap());
+/// angle.update(RadianAngle::new(0.5).unwrap());
+/// assert!((angle.radians() - (2.0 * std::f64::consts::PI + 0.5)).abs() < 1.0e-12);
+/// assert_eq!(angle.turns(), 1);
+/// assert!((angle.wrapped().radians() - 0.5).abs() < 1.0e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnwrappedAngle {
+    radians: f64,
+}
+
+impl UnwrappedAngle {
+    /// Start following an angle from an initial value, which is kept as it is.
+    pub fn new(start: RadianAngle) -> UnwrappedAngle {
+        UnwrappedAngle { radians: start.radians() }
+    }
+
+    /// Move to the next value of the angle, on any branch, and return the unwrapped angle.
+    pub fn update(&mut self, angle: RadianAngle) -> RadianAngle {
+        let step = map_to_branch(angle.radians() - self.radians, -PI, PI);
+        self.radians += step;
+
+        RadianAngle::new(self.radians).unwrap()
+    }
+
+    /// The unwrapped angle in radians.
+    pub fn radians(&self) -> f64 {
+        self.radians
+    }
+
+    /// The unwrapped angle.
+    pub fn angle(&self) -> RadianAngle {
+        RadianAngle::new(self.radians).unwrap()
+    }
+
+    /// The number of whole turns, counting down for negative angles, so the angle is the wrapped
+    /// angle plus this many turns.
+    pub fn turns(&self) -> i64 {
+        (self.radians / (2.0 * PI)).floor() as i64
+    }
+
+    /// The angle back in the range [0, 2π).
+    pub fn wrapped(&self) -> RadianAngle {
+        let mut radians = map_to_branch(self.radians, 0.0, 2.0 * PI);
+        if radians >= 2.0 * PI {
+            radians -= 2.0 * PI;
+        }
+
+        RadianAngle::new(radians).unwrap()
+    }
+}
+
+impl From<UnwrappedAngle> for RadianAngle {
+    fn from(angle: UnwrappedAngle) -> RadianAngle {
+        angle.angle()
+    }
+}
+
+/// Unwrap a series of angles, keeping the first one and moving each of the others onto the branch
+/// closest to the one before it.
+pub fn unwrap_angles(angles: &[RadianAngle]) -> Vec<RadianAngle> {
+    let mut unwrapped = match angles.first() {
+        Some(&first) => UnwrappedAngle::new(first),
+        None => return Vec::new(),
+    };
+
+    angles.iter().map(|&angle| unwrapped.update(angle)).collect()
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod unwrapped_tests {
+    use super::*;
+
+    fn degrees(angles: &[f64]) -> Vec<RadianAngle> {
+        angles.iter().map(|a| RadianAngle::new(a.to_radians()).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_unwrapped_angle() {
+        let mut angle = UnwrappedAngle::new(RadianAngle::new(0.1).unwrap());
+        assert_eq!(angle.turns(), 0);
+
+        // Forwards through two turns, then back through zero.
+        for a in degrees(&[120.0, 240.0, 0.0, 120.0, 240.0, 0.0, 10.0]) {
+            angle.update(a);
+        }
+        assert!(approx_eq(angle.radians().to_degrees(), 730.0, 1.0e-9));
+        assert_eq!(angle.turns(), 2);
+        assert!(approx_eq(angle.wrapped().radians().to_degrees(), 10.0, 1.0e-9));
+
+        for a in degrees(&[-100.0, 160.0, 60.0, -30.0]) {
+            angle.update(a);
+        }
+        assert!(approx_eq(angle.radians().to_degrees(), 330.0, 1.0e-9));
+        assert_eq!(angle.turns(), 0);
+
+        for a in degrees(&[200.0, 100.0, 0.0, -100.0]) {
+            angle.update(a);
+        }
+        assert!(approx_eq(RadianAngle::from(angle).radians().to_degrees(), -100.0, 1.0e-9));
+        assert_eq!(angle.turns(), -1);
+        assert!(approx_eq(angle.wrapped().radians().to_degrees(), 260.0, 1.0e-9));
+    }
+
+    #[test]
+    fn test_unwrap_angles() {
+        let unwrapped = unwrap_angles(&degrees(&[350.0, 5.0, 20.0, 355.0, 340.0]));
+        let unwrapped: Vec<f64> = unwrapped.iter().map(|a| a.radians().to_degrees()).collect();
+        let expected = [350.0, 365.0, 380.0, 355.0, 340.0];
+        for (a, b) in unwrapped.iter().zip(&expected) {
+            assert!(approx_eq(*a, *b, 1.0e-9));
+        }
+
+        assert!(unwrap_angles(&[]).is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+//!
+//! Module for positions along the ecliptic, by 30 degree sector and by constellation.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Old texts often give positions as a number of degrees within one of the twelve 30 degree
+//! sectors of the ecliptic, counted from the equinox of their day. The constellations are those of
+//! the IAU boundaries where they cross the ecliptic, which are fixed to the stars, so the equinox
+//! of the date is precessed back to J2000.0 to find them. Only the longitude is used, a body well
+//! off the ecliptic may be in a different constellation.
+//!
+use std::f64::consts::PI;
+
+use super::super::error::*;
+use super::angles::*;
+use super::ecliptic::*;
+
+/// The IAU constellations the ecliptic passes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EclipticConstellation {
+    /// Aries, the Ram.
+    Aries,
+    /// Taurus, the Bull.
+    Taurus,
+    /// Gemini, the Twins.
+    Gemini,
+    /// Cancer, the Crab.
+    Cancer,
+    /// Leo, the Lion.
+    Leo,
+    /// Virgo, the Maiden.
+    Virgo,
+    /// Libra, the Scales.
+    Libra,
+    /// Scorpius, the Scorpion.
+    Scorpius,
+    /// Ophiuchus, the Serpent Bearer.
+    Ophiuchus,
+    /// Sagittarius, the Archer.
+    Sagittarius,
+    /// Capricornus, the Sea Goat.
+    Capricornus,
+    /// Aquarius, the Water Bearer.
+    Aquarius,
+    /// Pisces, the Fishes.
+    Pisces,
+}
+
+impl EclipticConstellation {
+    /// The three letter IAU abbreviation.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            EclipticConstellation::Aries => "Ari",
+            EclipticConstellation::Taurus => "Tau",
+            EclipticConstellation::Gemini => "Gem",
+            EclipticConstellation::Cancer => "Cnc",
+            EclipticConstellation::Leo => "Leo",
+            EclipticConstellation::Virgo => "Vir",
+            EclipticConstellation::Libra => "Lib",
+            EclipticConstellation::Scorpius => "Sco",
+            EclipticConstellation::Ophiuchus => "Oph",
+            EclipticConstellation::Sagittarius => "Sgr",
+            EclipticConstellation::Capricornus => "Cap",
+            EclipticConstellation::Aquarius => "Aqr",
+            EclipticConstellation::Pisces => "Psc",
+        }
+    }
+}
+
+// The longitudes in degrees on the ecliptic of J2000.0 where each constellation begins.
+const CONSTELLATION_BOUNDARIES: [(f64, EclipticConstellation); 13] =
+    [(29.09, EclipticConstellation::Aries),
+     (53.47, EclipticConstellation::Taurus),
+     (90.43, EclipticConstellation::Gemini),
+     (118.26, EclipticConstellation::Cancer),
+     (138.18, EclipticConstellation::Leo),
+     (174.15, EclipticConstellation::Virgo),
+     (218.03, EclipticConstellation::Libra),
+     (241.14, EclipticConstellation::Scorpius),
+     (247.70, EclipticConstellation::Ophiuchus),
+     (266.27, EclipticConstellation::Sagittarius),
+     (299.68, EclipticConstellation::Capricornus),
+     (327.87, EclipticConstellation::Aquarius),
+     (351.59, EclipticConstellation::Pisces)];
+
+/// A position along the ecliptic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZodiacalPosition {
+    sector: u32,
+    longitude_in_sector: RadianAngle,
+    constellation: EclipticConstellation,
+}
+
+impl ZodiacalPosition {
+    /// The 30 degree sector counted from the equinox of the date, from 0 for longitudes 0 to 30
+    /// degrees to 11 for 330 to 360 degrees.
+    pub fn sector(&self) -> u32 {
+        self.sector
+    }
+
+    /// The longitude measured from the start of the sector.
+    pub fn longitude_in_sector(&self) -> RadianAngle {
+        self.longitude_in_sector
+    }
+
+    /// The constellation along the ecliptic at the longitude.
+    pub fn constellation(&self) -> EclipticConstellation {
+        self.constellation
+    }
+}
+
+/// Find the sector and constellation of the ecliptic at the longitude of coordinates referred to
+/// the equinox of their epoch.
+pub fn zodiacal_position(ecl: EclipticCoords) -> AstroResult<ZodiacalPosition> {
+    let longitude = ecl.longitude().radians();
+    let sector_width = PI / 6.0;
+    let sector = usize::min((longitude / sector_width).floor() as usize, 11);
+
+    // The general precession in longitude, Lieske et al. (1977).
+    let t = (ecl.epoch().julian_ephemeris_day()? - 2_451_545.0) / 36_525.0;
+    let precession = (t * (5_029.096_6 + t * 1.111_13) / 3600.0).to_radians();
+    let j2000 = map_to_branch(longitude - precession, 0.0, 2.0 * PI).to_degrees();
+
+    let constellation = CONSTELLATION_BOUNDARIES.iter()
+        .rev()
+        .find(|boundary| j2000 >= boundary.0)
+        .map_or(EclipticConstellation::Pisces, |boundary| boundary.1);
+
+    Ok(ZodiacalPosition {
+        sector: sector as u32,
+        longitude_in_sector: RadianAngle::new(longitude - sector as f64 * sector_width)?,
+        constellation,
+    })
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod zodiac_tests {
+    use super::*;
+    use astro_time::{AstroTime, Builder, J2000};
+
+    fn ecliptic(longitude: f64, epoch: AstroTime) -> EclipticCoords {
+        EclipticCoords::new(RadianAngle::new(longitude.to_radians()).unwrap(),
+                            RadianAngle::new(0.0).unwrap(),
+                            epoch)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_zodiacal_position() {
+        // Regulus, in the sector of Leo and in the constellation.
+        let position = zodiacal_position(ecliptic(149.83, J2000)).unwrap();
+        assert_eq!(position.sector(), 4);
+        assert!(approx_eq(position.longitude_in_sector().radians().to_degrees(), 29.83, 1.0e-10));
+        assert_eq!(position.constellation(), EclipticConstellation::Leo);
+
+        // Antares is in Scorpius, 4.6 degrees south of the ecliptic, but the ecliptic at its
+        // longitude is in Ophiuchus.
+        let position = zodiacal_position(ecliptic(249.76, J2000)).unwrap();
+        assert_eq!(position.sector(), 8);
+        assert_eq!(position.constellation(), EclipticConstellation::Ophiuchus);
+        assert_eq!(position.constellation().abbreviation(), "Oph");
+
+        // The vernal equinox is in Pisces.
+        let position = zodiacal_position(ecliptic(0.0, J2000)).unwrap();
+        assert_eq!(position.sector(), 0);
+        assert_eq!(position.constellation(), EclipticConstellation::Pisces);
+        let position = zodiacal_position(ecliptic(359.99, J2000)).unwrap();
+        assert_eq!(position.sector(), 11);
+        assert_eq!(position.constellation(), EclipticConstellation::Pisces);
+
+        // In 200 BC the equinox was at about 30 degrees on the ecliptic of J2000.0, in Aries.
+        let epoch = Builder::from_julian_utc(-199, 1, 1, 0, 0, 0).build().unwrap();
+        let position = zodiacal_position(ecliptic(0.0, epoch)).unwrap();
+        assert_eq!(position.constellation(), EclipticConstellation::Aries);
+        let position = zodiacal_position(ecliptic(30.0, J2000)).unwrap();
+        assert_eq!(position.constellation(), EclipticConstellation::Aries);
+    }
+}
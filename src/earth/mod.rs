@@ -0,0 +1,477 @@
+//!
+//! Module for the position and motion of the Earth.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The position is calculated from the abridged VSOP87 series in appendix III of Astronomical
+//! Algorithms, 2nd Edition by Jean Meeus, which is good to about 1 arc-second. Positions are
+//! referred to the mean ecliptic and equinox of the date, and are not converted to the FK5 system.
+//!
+use std::f64::consts::PI;
+
+use super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+use super::moon;
+use super::orbit::OrbitalElements;
+
+mod vsop87;
+
+use self::vsop87::*;
+
+// The ratio of the mass of the Earth to the mass of the Moon.
+const EARTH_MOON_MASS_RATIO: f64 = 81.300_56;
+
+/// The position and velocity of a body in rectangular coordinates.
+///
+/// The x axis points to the vernal equinox, the y axis is 90 degrees east of it in the plane of
+/// the ecliptic, and the z axis points to the north pole of the ecliptic. Positions are in
+/// astronomical units and velocities in astronomical units per day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateVector {
+    position: (f64, f64, f64),
+    velocity: (f64, f64, f64),
+}
+
+impl StateVector {
+    /// The position (x, y, z) in astronomical units.
+    pub fn position(&self) -> (f64, f64, f64) {
+        self.position
+    }
+
+    /// The velocity (x, y, z) in astronomical units per day.
+    pub fn velocity(&self) -> (f64, f64, f64) {
+        self.velocity
+    }
+}
+
+/// Calculate the heliocentric ecliptic coordinates of the Earth and its radius vector in
+/// astronomical units.
+pub fn heliocentric_position(time: &AstroTime) -> AstroResult<(EclipticCoords, f64)> {
+    let terms = EarthTerms::new(time)?;
+    let ecl = EclipticCoords::new(RadianAngle::new(terms.longitude.0)?,
+                                  RadianAngle::new(terms.latitude.0)?,
+                                  terms.epoch)?;
+
+    Ok((ecl, terms.radius_vector.0))
+}
+
+/// Calculate the rates of change of the heliocentric ecliptic longitude and latitude of the Earth
+/// in radians per day, and of its radius vector in astronomical units per day.
+///
+/// The rates are the derivatives of the VSOP87 series, so they are consistent with
+/// `heliocentric_position`.
+pub fn heliocentric_rates(time: &AstroTime) -> AstroResult<(f64, f64, f64)> {
+    let terms = EarthTerms::new(time)?;
+    let days = 365_250.0;
+
+    Ok((terms.longitude.1 / days, terms.latitude.1 / days, terms.radius_vector.1 / days))
+}
+
+/// Calculate the heliocentric position and velocity of the Earth.
+///
+/// The velocity is the rate of change of the position in the frame of the ecliptic and equinox of
+/// the date, which differs from the velocity in a fixed frame by the slow rotation of that frame
+/// due to precession, about 1 meter per second.
+pub fn heliocentric_state(time: &AstroTime) -> AstroResult<StateVector> {
+    let terms = EarthTerms::new(time)?;
+    let (l, dl) = terms.longitude;
+    let (b, db) = terms.latitude;
+    let (r, dr) = terms.radius_vector;
+
+    let (sin_l, cos_l) = sin_cos(l);
+    let (sin_b, cos_b) = sin_cos(b);
+
+    let position = (r * cos_b * cos_l, r * cos_b * sin_l, r * sin_b);
+
+    // Rates are per Julian millennium.
+    let days = 365_250.0;
+    let vx = dr * cos_b * cos_l - r * sin_b * cos_l * db - r * cos_b * sin_l * dl;
+    let vy = dr * cos_b * sin_l - r * sin_b * sin_l * db + r * cos_b * cos_l * dl;
+    let vz = dr * sin_b + r * cos_b * db;
+
+    Ok(StateVector {
+        position,
+        velocity: (vx / days, vy / days, vz / days),
+    })
+}
+
+/// The point of the Earth-Moon system that positions are referred to.
+///
+/// The correction to the barycenter is applied only when `Barycenter` is asked for. It is never
+/// applied automatically, because no theory in this crate is referred to the barycenter: the Earth
+/// series are those of VSOP87D, which give the center of the Earth, and the planets and small
+/// bodies use heliocentric elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EarthOrigin {
+    /// The center of the Earth.
+    Geocenter,
+    /// The barycenter of the Earth-Moon system, which the main VSOP87 series and most tables of
+    /// orbital elements give the orbit of.
+    Barycenter,
+}
+
+/// Calculate the heliocentric position and velocity of the center of the Earth, or of the
+/// barycenter of the Earth-Moon system.
+///
+/// The series give the center of the Earth, so the barycenter is found by moving towards the Moon
+/// by 1/82.3 of its distance, about 4,700 kilometers. Its velocity relative to the center of the
+/// Earth is about 12 meters per second.
+///
+/// The barycenter is for comparing with ephemerides or observations referred to it, see
+/// `EarthOrigin`.
+pub fn heliocentric_state_with(time: &AstroTime, origin: EarthOrigin) -> AstroResult<StateVector> {
+    let state = heliocentric_state(time)?;
+    if origin == EarthOrigin::Geocenter {
+        return Ok(state);
+    }
+
+    // The velocity of the Moon from its positions half an hour either side.
+    let jde = time.julian_ephemeris_day()?;
+    let half_hour = 1.0 / 48.0;
+    let before = moon_offset(jde - half_hour)?;
+    let after = moon_offset(jde + half_hour)?;
+    let offset = moon_offset(jde)?;
+
+    let (x, y, z) = state.position;
+    let (vx, vy, vz) = state.velocity;
+    let rate = |a: f64, b: f64| (b - a) / (2.0 * half_hour);
+
+    Ok(StateVector {
+        position: (x + offset.0, y + offset.1, z + offset.2),
+        velocity: (vx + rate(before.0, after.0),
+                   vy + rate(before.1, after.1),
+                   vz + rate(before.2, after.2)),
+    })
+}
+
+/// Calculate the mean elements of the orbit of the Earth, table 31.A, referred to the ecliptic and
+/// equinox of the date.
+///
+/// The inclination is zero, so the ascending node is taken to be at the equinox and the argument
+/// of the perihelion is the longitude of the perihelion. The time of perihelion is the one
+/// nearest to `time`.
+pub fn orbital_elements(time: &AstroTime) -> AstroResult<OrbitalElements> {
+    let epoch = time.as_dt()?;
+    let t = (epoch.julian_day_number() - 2_451_545.0) / 36_525.0;
+
+    let l = 100.466_449 + t * (36_000.769_823_1 + t * (0.000_303_68 + t * 0.000_000_021));
+    let e = 0.016_708_62 - t * (0.000_042_037 + t * (0.000_000_123_6 - t * 0.000_000_000_04));
+    let pi = 102.937_348 + t * (1.719_526_9 + t * (0.000_459_62 + t * 0.000_000_499));
+    let a: f64 = 1.000_001_018;
+
+    // Days since perihelion from the mean anomaly and mean motion.
+    let m = map_to_branch((l - pi).to_radians(), -PI, PI);
+    let n = 0.017_202_098_95 / (a * a * a).sqrt();
+    let perihelion = epoch.add_duration(AstroDuration::from_days(-m / n)?)?;
+
+    OrbitalElements::new(perihelion,
+                         a * (1.0 - e),
+                         e,
+                         RadianAngle::new(0.0)?,
+                         RadianAngle::new(0.0)?,
+                         RadianAngle::new(map_to_branch(pi.to_radians(), 0.0, 2.0 * PI))?,
+                         epoch)
+}
+
+/// The frame a radial velocity correction refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestFrame {
+    /// Relative to the barycenter of the solar system.
+    Barycentric,
+    /// Relative to the center of the Sun.
+    Heliocentric,
+}
+
+/// Calculate the barycentric radial velocity correction for an observer in kilometers per second.
+///
+/// The target should be in equatorial coordinates of the date.
+pub fn radial_velocity_correction(target: EquatorialCoords,
+                                  time: &AstroTime,
+                                  observer: GeoCoords)
+                                  -> AstroResult<f64> {
+    radial_velocity_correction_with(target, time, observer, RestFrame::Barycentric)
+}
+
+/// Calculate the radial velocity correction for an observer in kilometers per second.
+///
+/// This is the component of the observer's velocity towards the target, from the orbital motion
+/// and the rotation of the Earth. Add it to a measured radial velocity to get the radial velocity
+/// relative to the chosen frame. The motion of the Sun about the barycenter only includes Jupiter
+/// and Saturn on circular orbits, and the correction is good to a few meters per second.
+pub fn radial_velocity_correction_with(target: EquatorialCoords,
+                                       time: &AstroTime,
+                                       observer: GeoCoords,
+                                       frame: RestFrame)
+                                       -> AstroResult<f64> {
+    let (mut vx, mut vy, vz) = heliocentric_state(time)?.velocity();
+    if frame == RestFrame::Barycentric {
+        let (sun_vx, sun_vy) = sun_barycentric_velocity(time)?;
+        vx += sun_vx;
+        vy += sun_vy;
+    }
+
+    let ecl = trans_equatorial_to_ecliptic(target, mean_obliquity(time)?)?;
+    let (sin_lon, cos_lon) = sin_cos(ecl.longitude().radians());
+    let (sin_lat, cos_lat) = sin_cos(ecl.latitude().radians());
+    let orbital = (vx * cos_lat * cos_lon + vy * cos_lat * sin_lon + vz * sin_lat) *
+                  ASTRONOMICAL_UNIT_KM / 86_400.0;
+
+    // The observer moves eastward, towards objects east of the meridian.
+    let (_, rho_cos_phi) = observer.geocentric_position();
+    let h = local_hour_angle(target, observer, time)?.radians();
+    let rotational = -EARTH_ROTATION_RATE * EARTH_EQUATORIAL_RADIUS_KM * rho_cos_phi *
+                     cos(target.declination().radians()) * sin(h);
+
+    Ok(orbital + rotational)
+}
+
+// The position of the Earth-Moon barycenter relative to the center of the Earth in astronomical
+// units, in rectangular ecliptic coordinates of the date.
+fn moon_offset(jde: f64) -> AstroResult<(f64, f64, f64)> {
+    let time = Builder::from_julian_date(jde).dynamical_time().build()?;
+    let ecl = moon::apparent_ecliptic(&time)?;
+    let r = moon::distance(&time)? / ASTRONOMICAL_UNIT_KM / (1.0 + EARTH_MOON_MASS_RATIO);
+
+    let (sin_l, cos_l) = sin_cos(ecl.longitude().radians());
+    let (sin_b, cos_b) = sin_cos(ecl.latitude().radians());
+
+    Ok((r * cos_b * cos_l, r * cos_b * sin_l, r * sin_b))
+}
+
+// The velocity of the Sun about the barycenter in the plane of the ecliptic, in astronomical
+// units per day, from the mean longitudes of Jupiter and Saturn in table 31.A.
+fn sun_barycentric_velocity(time: &AstroTime) -> AstroResult<(f64, f64)> {
+    let t = (time.julian_ephemeris_day()? - 2_451_545.0) / 36_525.0;
+
+    // The velocity of the Sun due to a planet on a circular orbit, from the ratio of the mass of
+    // the planet to the Sun, the semimajor axis, and the mean longitude and mean motion in degrees.
+    let reflex = |mass: f64, a: f64, l: f64, n: f64| {
+        let speed = mass * a * n.to_radians() / 36_525.0;
+        let (sin_l, cos_l) = sin_cos(l.to_radians());
+        (speed * sin_l, -speed * cos_l)
+    };
+
+    let jupiter = reflex(1.0 / 1_047.348_6,
+                         5.202_603_209,
+                         34.351_519 + t * (3_036.302_774_8 + t * 0.000_223_3),
+                         3_036.302_774_8);
+    let saturn = reflex(1.0 / 3_497.898,
+                        9.554_909_192,
+                        50.077_444 + t * (1_223.511_068_6 + t * 0.000_519_08),
+                        1_223.511_068_6);
+
+    Ok((jupiter.0 + saturn.0, jupiter.1 + saturn.1))
+}
+
+// The VSOP87 values with their rates of change per Julian millennium.
+struct EarthTerms {
+    epoch: AstroTime,
+    longitude: (f64, f64),
+    latitude: (f64, f64),
+    radius_vector: (f64, f64),
+}
+
+impl EarthTerms {
+    fn new(time: &AstroTime) -> AstroResult<EarthTerms> {
+        let epoch = time.as_dt()?;
+        let tau = (epoch.julian_day_number() - 2_451_545.0) / 365_250.0;
+
+        Ok(EarthTerms {
+            epoch,
+            longitude: evaluate(&[L0, L1, L2, L3, L4, L5], tau),
+            latitude: evaluate(&[B0, B1], tau),
+            radius_vector: evaluate(&[R0, R1, R2, R3, R4], tau),
+        })
+    }
+}
+
+// Sum the series in powers of tau, returning the value and its derivative.
+fn evaluate(series: &[&[(f64, f64, f64)]], tau: f64) -> (f64, f64) {
+    let mut value = 0.0;
+    let mut rate = 0.0;
+
+    for (n, terms) in series.iter().enumerate() {
+        let mut sum = 0.0;
+        let mut sum_rate = 0.0;
+        for &(a, b, c) in terms.iter() {
+            let (sin, cos) = sin_cos(b + c * tau);
+            sum += a * cos;
+            sum_rate -= a * c * sin;
+        }
+
+        let power = tau.powi(n as i32);
+        value += sum * power;
+        rate += sum_rate * power;
+        if n > 0 {
+            rate += sum * n as f64 * tau.powi(n as i32 - 1);
+        }
+    }
+
+    (value * 1.0e-8, rate * 1.0e-8)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod earth_tests {
+    use super::*;
+    use astro_time::Builder;
+    use std::f64::consts::FRAC_PI_2;
+
+    // Example 25.b, Astronomical Algorithms 2nd ed.
+    fn example_25b() -> AstroTime {
+        Builder::from_julian_date(2_448_908.5).dynamical_time().build().unwrap()
+    }
+
+    #[test]
+    fn test_heliocentric_position() {
+        let (ecl, r) = heliocentric_position(&example_25b()).unwrap();
+        assert!(approx_eq(ecl.longitude().radians().to_degrees(), 19.907_372, 1.0e-6));
+        assert!(approx_eq(ecl.latitude().radians().to_degrees(), -0.000_179, 1.0e-6));
+        assert!(approx_eq(r, 0.997_607_75, 1.0e-8));
+    }
+
+    #[test]
+    fn test_heliocentric_rates() {
+        // About 1 degree per day, with the Earth approaching the Sun in October.
+        let (dl, db, dr) = heliocentric_rates(&example_25b()).unwrap();
+        assert!(approx_eq(dl.to_degrees(), 0.985_6, 0.04));
+        assert!(db.abs() < 1.0e-6);
+        assert!(dr < -1.0e-4);
+
+        // Near the perihelion of 2017 January 4.
+        let perihelion = Builder::from_gregorian_utc(2017, 1, 4, 14, 0, 0).build().unwrap();
+        let (dl, _, dr) = heliocentric_rates(&perihelion).unwrap();
+        assert!(approx_eq(dl.to_degrees(), 1.019, 0.001));
+        assert!(dr.abs() < 2.0e-5);
+    }
+
+    #[test]
+    fn test_heliocentric_state() {
+        let time = example_25b();
+        let state = heliocentric_state(&time).unwrap();
+
+        let (ecl, r) = heliocentric_position(&time).unwrap();
+        let (x, _, _) = state.position();
+        let l = ecl.longitude().radians();
+        let b = ecl.latitude().radians();
+        assert!(approx_eq(x, r * b.cos() * l.cos(), 1.0e-12));
+
+        // Compare with the change in position over an hour either side.
+        let jde = time.julian_day_number();
+        let at = |jd: f64| {
+            heliocentric_state(&Builder::from_julian_date(jd).dynamical_time().build().unwrap())
+                .unwrap()
+                .position()
+        };
+        let (before, after) = (at(jde - 1.0 / 24.0), at(jde + 1.0 / 24.0));
+        let (vx, vy, vz) = state.velocity();
+        assert!(approx_eq(vx, (after.0 - before.0) * 12.0, 1.0e-8));
+        assert!(approx_eq(vy, (after.1 - before.1) * 12.0, 1.0e-8));
+        assert!(approx_eq(vz, (after.2 - before.2) * 12.0, 1.0e-8));
+
+        // About 29.8 kilometers per second.
+        let speed = (vx * vx + vy * vy + vz * vz).sqrt() * ASTRONOMICAL_UNIT_KM / 86_400.0;
+        assert!(approx_eq(speed, 29.8, 0.5));
+    }
+
+    #[test]
+    fn test_heliocentric_state_with() {
+        let time = example_25b();
+        let center = heliocentric_state_with(&time, EarthOrigin::Geocenter).unwrap();
+        assert_eq!(center, heliocentric_state(&time).unwrap());
+
+        // The barycenter is 1/82.3 of the distance of the Moon from the center of the Earth.
+        let barycenter = heliocentric_state_with(&time, EarthOrigin::Barycenter).unwrap();
+        let (x, y, z) = barycenter.position();
+        let (cx, cy, cz) = center.position();
+        let offset = ((x - cx).powi(2) + (y - cy).powi(2) + (z - cz).powi(2)).sqrt();
+        let moon = moon::distance(&time).unwrap() / (1.0 + EARTH_MOON_MASS_RATIO);
+        assert!(approx_eq(offset * ASTRONOMICAL_UNIT_KM, moon, 1.0e-6));
+
+        let (vx, vy, vz) = barycenter.velocity();
+        let (cvx, cvy, cvz) = center.velocity();
+        let speed = ((vx - cvx).powi(2) + (vy - cvy).powi(2) + (vz - cvz).powi(2)).sqrt() *
+                    ASTRONOMICAL_UNIT_KM / 86_400.0;
+        assert!(approx_eq(speed, 0.012_5, 0.001));
+    }
+
+    #[test]
+    fn test_radial_velocity_correction() {
+        let time = example_25b();
+        let geo = GeoCoords::new(RadianAngle::new(19.8f64.to_radians()).unwrap(),
+                                 RadianAngle::new(155.5f64.to_radians()).unwrap(),
+                                 4_200.0)
+            .unwrap();
+        let obliquity = mean_obliquity(&time).unwrap();
+        let target = |lon: f64, lat: f64| {
+            let ecl = EclipticCoords::new(RadianAngle::new(lon).unwrap(),
+                                          RadianAngle::new(lat).unwrap(),
+                                          time)
+                .unwrap();
+            trans_ecliptic_to_equatorial(ecl, obliquity).unwrap()
+        };
+
+        // Towards the apex of the Earth's motion, 90 degrees behind the Sun.
+        let (ecl, _) = heliocentric_position(&time).unwrap();
+        let apex = ecl.longitude().radians() + FRAC_PI_2;
+        let v = radial_velocity_correction(target(apex, 0.0), &time, geo).unwrap();
+        assert!(approx_eq(v, 29.8, 0.6));
+
+        // Opposite directions.
+        let anti_apex = radial_velocity_correction(target(apex + PI, 0.0), &time, geo).unwrap();
+        assert!(approx_eq(v, -anti_apex, 1.0e-9));
+
+        // Only the rotation of the Earth towards the pole of the ecliptic, and the motion of
+        // the Sun about the barycenter is about 12 meters per second.
+        let pole = target(0.0, FRAC_PI_2);
+        let v = radial_velocity_correction(pole, &time, geo).unwrap();
+        assert!(v.abs() < 0.5);
+        let helio = radial_velocity_correction_with(pole, &time, geo, RestFrame::Heliocentric)
+            .unwrap();
+        let (sun_vx, sun_vy) = sun_barycentric_velocity(&time).unwrap();
+        let sun_speed = sun_vx.hypot(sun_vy) * ASTRONOMICAL_UNIT_KM / 86_400.0;
+        assert!(approx_eq(sun_speed, 0.012, 0.004));
+        // To the rounding of the pole's latitude through the transformations.
+        assert!(approx_eq(v, helio, 1.0e-9));
+    }
+
+    #[test]
+    fn test_orbital_elements() {
+        let j2000 = Builder::from_julian_date(2_451_545.0).dynamical_time().build().unwrap();
+        let elements = orbital_elements(&j2000).unwrap();
+        assert!(approx_eq(elements.longitude_of_perihelion().radians().to_degrees(),
+                          102.937_348,
+                          1.0e-9));
+        assert_eq!(elements.eccentricity(), 0.016_708_62);
+        assert!(approx_eq(elements.semimajor_axis().unwrap(), 1.000_001_018, 1.0e-15));
+
+        // Perihelion early in January.
+        let days = elements.perihelion_time().julian_day_number() - 2_451_545.0;
+        assert!(days > 0.0 && days < 5.0);
+
+        // The Keplerian orbit stays close to the VSOP87 position over a few months.
+        let time = example_25b();
+        let elements = orbital_elements(&time).unwrap();
+        let (x, y, _) = elements.heliocentric_position(&time).unwrap();
+        let (ecl, r) = heliocentric_position(&time).unwrap();
+        assert!(approx_eq(y.atan2(x), ecl.longitude().radians(), 0.01f64.to_radians()));
+        assert!(approx_eq(x.hypot(y), r, 1.0e-4));
+
+        // The orbit is becoming more circular.
+        let later = Builder::from_julian_date(2_488_070.0).dynamical_time().build().unwrap();
+        assert!(orbital_elements(&later).unwrap().eccentricity() <
+                orbital_elements(&j2000).unwrap().eccentricity());
+    }
+
+}
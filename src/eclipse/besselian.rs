@@ -0,0 +1,480 @@
+//!
+//! Module for the Besselian elements of a solar eclipse.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use super::super::astro_time::{AstroTime, Builder};
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::least_squares::least_squares;
+use super::super::math::*;
+use super::super::moon;
+use super::super::sun;
+
+// Radius of the Sun in kilometers.
+const SUN_RADIUS_KM: f64 = 696_000.0;
+
+// Radius of the Moon in Earth radii for the penumbra, and the smaller value for the umbra that
+// accounts for the valleys on the lunar limb, as used by NASA.
+const MOON_RADIUS_PENUMBRA: f64 = 0.272_507_6;
+const MOON_RADIUS_UMBRA: f64 = 0.272_488;
+
+/// The Besselian elements of a solar eclipse.
+///
+/// Each element is a polynomial in the time t, in hours of dynamical time since the reference time
+/// t₀. The elements are:
+///
+/// - x, y: the coordinates of the shadow axis on the fundamental plane in Earth radii,
+/// - d: the declination of the shadow axis in degrees,
+/// - l₁, l₂: the radii of the penumbral and umbral cones on the fundamental plane in Earth radii,
+///   l₂ is negative for a total eclipse,
+/// - μ: the Greenwich hour angle of the shadow axis in degrees,
+///
+/// and the constant tangents of the angles of the penumbral and umbral cones, tan f₁ and tan f₂.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BesselianElements {
+    t0: AstroTime,
+    delta_t: f64,
+    x: [f64; 4],
+    y: [f64; 4],
+    d: [f64; 4],
+    l1: [f64; 4],
+    l2: [f64; 4],
+    mu: [f64; 4],
+    tan_f1: f64,
+    tan_f2: f64,
+}
+
+impl BesselianElements {
+    /// Create a set of elements from polynomial coefficients.
+    ///
+    /// The `coefficients` are laid out as published, each row is a power of t from 0 to 3 and the
+    /// columns are x, y, d, l₁, l₂, and μ. Unused higher order coefficients should be 0.
+    ///
+    /// `delta_t` is the value of ΔT = TD - UT in seconds used to convert times to universal time
+    /// and to correct μ, published elements always state the value used.
+    pub fn new(t0: AstroTime,
+               delta_t: f64,
+               coefficients: [[f64; 6]; 4],
+               tan_f1: f64,
+               tan_f2: f64)
+               -> AstroResult<BesselianElements> {
+        let constants = [delta_t, tan_f1, tan_f2];
+        for val in coefficients.iter().flat_map(|row| row.iter()).chain(constants.iter()) {
+            if val.is_nan() {
+                return Err(AstroAlgorithmsError::EncounteredNaN);
+            } else if val.is_infinite() {
+                return Err(AstroAlgorithmsError::EncounteredInf);
+            }
+        }
+
+        let column = |c: usize| {
+            [coefficients[0][c], coefficients[1][c], coefficients[2][c], coefficients[3][c]]
+        };
+
+        Ok(BesselianElements {
+            t0: t0.as_dt()?,
+            delta_t,
+            x: column(0),
+            y: column(1),
+            d: column(2),
+            l1: column(3),
+            l2: column(4),
+            mu: column(5),
+            tan_f1,
+            tan_f2,
+        })
+    }
+
+    /// Compute the elements from the Sun and Moon positions calculated by this library.
+    ///
+    /// The time `t0` should be near the maximum of the eclipse, it is rounded to the nearest hour
+    /// of dynamical time. The polynomials are fitted to positions over 3 hours on either side of
+    /// t₀, and ΔT is this library's value at t₀.
+    pub fn from_ephemeris(t0: &AstroTime) -> AstroResult<BesselianElements> {
+        let jde0 = (t0.julian_ephemeris_day()? * 24.0).round() / 24.0;
+        let t0 = Builder::from_julian_date(jde0).dynamical_time().build()?;
+        let delta_t = (jde0 - t0.as_utc()?.julian_day_number()) * 86_400.0;
+
+        let hours = [-3.0, -2.0, -1.0, 0.0, 1.0, 2.0, 3.0];
+        let mut samples = Vec::with_capacity(hours.len());
+        for t in &hours {
+            let time = Builder::from_julian_date(jde0 + t / 24.0).dynamical_time().build()?;
+            samples.push(ShadowGeometry::at(&time)?);
+        }
+
+        // Keep mu continuous across 360 degrees so it can be fitted.
+        for i in 1..samples.len() {
+            while samples[i].mu < samples[i - 1].mu - 180.0 {
+                samples[i].mu += 360.0;
+            }
+        }
+
+        let fit = |element: &dyn Fn(&ShadowGeometry) -> f64| {
+            let values: Vec<f64> = samples.iter().map(element).collect();
+            fit_cubic(&hours, &values)
+        };
+        let (x, y, d) = (fit(&|s| s.x), fit(&|s| s.y), fit(&|s| s.d));
+        let (l1, l2, mu) = (fit(&|s| s.l1), fit(&|s| s.l2), fit(&|s| s.mu));
+
+        let mut coefficients = [[0.0; 6]; 4];
+        for (n, row) in coefficients.iter_mut().enumerate() {
+            *row = [x[n], y[n], d[n], l1[n], l2[n], mu[n]];
+        }
+
+        BesselianElements::new(t0, delta_t, coefficients, samples[3].tan_f1, samples[3].tan_f2)
+    }
+
+    /// Parse a set of elements as published by NASA and in almanacs.
+    ///
+    /// Since published sets only give the hour of t₀, the date is supplied separately. The text
+    /// should contain lines of the form:
+    ///
+    /// ```text
+    /// t0 = 18.000 TDT
+    /// n      x          y          d          l1         l2          μ
+    /// 0  -0.129571   0.485416   11.86696   0.542093  -0.004025   89.24544
+    /// 1   0.5406426 -0.1416400  -0.013622   0.0001241  0.0001234  15.003930
+    /// 2  -0.0000294 -0.0000905  -0.000002  -0.0000118 -0.0000117
+    /// 3  -0.0000081  0.0000020
+    /// Tan f1 = 0.0046222     Tan f2 = 0.0045992
+    /// ΔT = 70.3 s
+    /// ```
+    ///
+    /// Coefficient rows are numbered with the power of t, columns that are left off are 0. `ƒ` and
+    /// `δT` or `delta T` are also accepted, and other lines are ignored.
+    pub fn parse(year: i32, month: i32, day: i32, text: &str) -> AstroResult<BesselianElements> {
+        let mut t0_hour = None;
+        let mut delta_t = None;
+        let mut tan_f = [None, None];
+        let mut coefficients = [[0.0; 6]; 4];
+        let mut rows_found = [false; 4];
+
+        for line in text.lines() {
+            let line = line.trim().to_lowercase().replace('ƒ', "f").replace('₀', "0");
+
+            if line.starts_with("t0") {
+                t0_hour = Some(value_after_equals(&line)?);
+            } else if line.starts_with("δt") || line.starts_with("delta t") {
+                delta_t = Some(value_after_equals(&line)?);
+            } else if line.starts_with("tan") {
+                for piece in line.split("tan").map(str::trim).filter(|p| !p.is_empty()) {
+                    if piece.starts_with("f1") {
+                        tan_f[0] = Some(value_after_equals(piece)?);
+                    } else if piece.starts_with("f2") {
+                        tan_f[1] = Some(value_after_equals(piece)?);
+                    }
+                }
+            } else {
+                let mut tokens = line.split_whitespace();
+                let n = match tokens.next().map(str::parse::<usize>) {
+                    Some(Ok(n)) if n < 4 => n,
+                    _ => continue,
+                };
+
+                for (c, token) in tokens.enumerate() {
+                    if c >= 6 {
+                        return Err(parse_error("Too many columns in a row of coefficients"));
+                    }
+                    coefficients[n][c] = token.parse()
+                        .map_err(|_| parse_error("Invalid number in a row of coefficients"))?;
+                }
+                rows_found[n] = true;
+            }
+        }
+
+        let t0_hour = t0_hour.ok_or_else(|| parse_error("Missing t0"))?;
+        let delta_t = delta_t.ok_or_else(|| parse_error("Missing delta T"))?;
+        let tan_f1 = tan_f[0].ok_or_else(|| parse_error("Missing tan f1"))?;
+        let tan_f2 = tan_f[1].ok_or_else(|| parse_error("Missing tan f2"))?;
+        if !rows_found[0] || !rows_found[1] {
+            return Err(parse_error("Missing coefficients"));
+        }
+
+        let midnight = Builder::from_gregorian_utc(year, month, day, 0, 0, 0)
+            .dynamical_time()
+            .build()?;
+        let t0 = Builder::from_julian_date(midnight.julian_day_number() + t0_hour / 24.0)
+            .dynamical_time()
+            .build()?;
+
+        BesselianElements::new(t0, delta_t, coefficients, tan_f1, tan_f2)
+    }
+
+    /// The reference time t₀ in dynamical time.
+    pub fn t0(&self) -> AstroTime {
+        self.t0
+    }
+
+    /// The value of ΔT = TD - UT in seconds assumed by the elements.
+    pub fn delta_t(&self) -> f64 {
+        self.delta_t
+    }
+
+    /// The tangent of the angle of the penumbral cone.
+    pub fn tan_f1(&self) -> f64 {
+        self.tan_f1
+    }
+
+    /// The tangent of the angle of the umbral cone.
+    pub fn tan_f2(&self) -> f64 {
+        self.tan_f2
+    }
+
+    // Evaluate the elements and their hourly rates of change at t hours from t0.
+    pub(crate) fn evaluate(&self, t: f64) -> ShadowAxis {
+        ShadowAxis {
+            x: polynomial(&self.x, t),
+            y: polynomial(&self.y, t),
+            d: polynomial(&self.d, t).to_radians(),
+            l1: polynomial(&self.l1, t),
+            l2: polynomial(&self.l2, t),
+            mu: polynomial(&self.mu, t).to_radians(),
+            dx: derivative(&self.x, t),
+            dy: derivative(&self.y, t),
+            dd: derivative(&self.d, t).to_radians(),
+            dmu: derivative(&self.mu, t).to_radians(),
+        }
+    }
+
+    // Convert t hours from t0 into universal time, using the elements' value of delta T.
+    pub(crate) fn universal_time(&self, t: f64) -> AstroResult<AstroTime> {
+        Builder::from_julian_date(self.t0.julian_day_number() + (t - self.delta_t / 3600.0) / 24.0)
+            .build()
+    }
+}
+
+// The elements evaluated at a time, angles in radians and rates per hour.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShadowAxis {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) d: f64,
+    pub(crate) l1: f64,
+    pub(crate) l2: f64,
+    pub(crate) mu: f64,
+    pub(crate) dx: f64,
+    pub(crate) dy: f64,
+    pub(crate) dd: f64,
+    pub(crate) dmu: f64,
+}
+
+// The elements at an instant from the positions of the Sun and Moon, distances in Earth radii and
+// angles in degrees.
+struct ShadowGeometry {
+    x: f64,
+    y: f64,
+    d: f64,
+    l1: f64,
+    l2: f64,
+    mu: f64,
+    tan_f1: f64,
+    tan_f2: f64,
+}
+
+impl ShadowGeometry {
+    // Adapted from section 8.3 of the Explanatory Supplement to the Astronomical Almanac.
+    fn at(time: &AstroTime) -> AstroResult<ShadowGeometry> {
+        let sun = sun::apparent_equatorial(time)?;
+        let moon = moon::apparent_equatorial(time)?;
+        let r_sun = sun::radius_vector(time)? * ASTRONOMICAL_UNIT_KM / EARTH_EQUATORIAL_RADIUS_KM;
+        let r_moon = moon::distance(time)? / EARTH_EQUATORIAL_RADIUS_KM;
+
+        let to_vector = |eq: EquatorialCoords, r: f64| {
+            let (ra, dec) = (eq.right_ascension().radians(), eq.declination().radians());
+            [r * cos(dec) * cos(ra), r * cos(dec) * sin(ra), r * sin(dec)]
+        };
+        let s = to_vector(sun, r_sun);
+        let m = to_vector(moon, r_moon);
+
+        // The shadow axis runs from the Moon to the Sun.
+        let w = [s[0] - m[0], s[1] - m[1], s[2] - m[2]];
+        let g = f64::sqrt(w[0] * w[0] + w[1] * w[1] + w[2] * w[2]);
+        let a = atan2(w[1], w[0]);
+        let d = asin(w[2] / g);
+
+        let (sin_dm, cos_dm) = sin_cos(moon.declination().radians());
+        let (sin_ra, cos_ra) = sin_cos(moon.right_ascension().radians() - a);
+        let x = r_moon * cos_dm * sin_ra;
+        let y = r_moon * (sin_dm * cos(d) - cos_dm * sin(d) * cos_ra);
+        let z = r_moon * (sin_dm * sin(d) + cos_dm * cos(d) * cos_ra);
+
+        let sun_radius = SUN_RADIUS_KM / EARTH_EQUATORIAL_RADIUS_KM;
+        let sin_f1 = (sun_radius + MOON_RADIUS_PENUMBRA) / g;
+        let sin_f2 = (sun_radius - MOON_RADIUS_UMBRA) / g;
+        let tan_f1 = sin_f1 / f64::sqrt(1.0 - sin_f1 * sin_f1);
+        let tan_f2 = sin_f2 / f64::sqrt(1.0 - sin_f2 * sin_f2);
+
+        // Apparent sidereal time on the ephemeris meridian, which is the sidereal time with the
+        // dynamical time taken as universal time.
+        let (delta_psi, _) = nutation(time)?;
+        let eps = true_obliquity(time)?;
+        let theta = time.sidereal_greenwich() +
+                    (delta_psi.radians() * cos(eps.radians())).to_degrees();
+
+        Ok(ShadowGeometry {
+            x,
+            y,
+            d: d.to_degrees(),
+            l1: (z + MOON_RADIUS_PENUMBRA / sin_f1) * tan_f1,
+            l2: (z - MOON_RADIUS_UMBRA / sin_f2) * tan_f2,
+            mu: map_to_branch(theta - a.to_degrees(), 0.0, 360.0),
+            tan_f1,
+            tan_f2,
+        })
+    }
+}
+
+// Least squares fit of a cubic polynomial, returns the coefficients in increasing powers.
+fn fit_cubic(ts: &[f64], values: &[f64]) -> [f64; 4] {
+    let rows: Vec<Vec<f64>> = ts.iter().map(|&t| vec![1.0, t, t * t, t * t * t]).collect();
+    let c = least_squares(&rows, values).expect("Seven distinct times determine a cubic");
+
+    [c[0], c[1], c[2], c[3]]
+}
+
+// The number following an '=' at the start of the text, ignoring any units after it.
+fn value_after_equals(text: &str) -> AstroResult<f64> {
+    text.split('=')
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| parse_error("Expected a number after '='"))
+}
+
+fn parse_error(msg: &str) -> AstroAlgorithmsError {
+    AstroAlgorithmsError::InvalidArgument(format!("Parsing Besselian elements: {}", msg))
+}
+
+fn polynomial(c: &[f64; 4], t: f64) -> f64 {
+    c[0] + t * (c[1] + t * (c[2] + t * c[3]))
+}
+
+fn derivative(c: &[f64; 4], t: f64) -> f64 {
+    c[1] + t * (2.0 * c[2] + t * 3.0 * c[3])
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+pub(crate) mod besselian_tests {
+    use super::*;
+
+    // Elements for the total solar eclipse of 21 August 2017, from NASA.
+    pub(crate) fn eclipse_2017_08_21() -> BesselianElements {
+        let t0 = Builder::from_gregorian_utc(2017, 8, 21, 18, 0, 0)
+            .dynamical_time()
+            .build()
+            .unwrap();
+
+        BesselianElements::new(t0,
+                               70.3,
+                               [[-0.129_571, 0.485_416, 11.866_96, 0.542_093, -0.004_025,
+                                 89.245_44],
+                                [0.540_642_6, -0.141_640_0, -0.013_622, 0.000_124_1, 0.000_123_4,
+                                 15.003_930],
+                                [-0.000_029_4, -0.000_090_5, -0.000_002, -0.000_011_8,
+                                 -0.000_011_7, 0.0],
+                                [-0.000_008_1, 0.000_002_0, 0.0, 0.0, 0.0, 0.0]],
+                               0.004_622_2,
+                               0.004_599_2)
+            .unwrap()
+    }
+
+    const NASA_2017_08_21: &str = "
+            t0 = 18.000 TDT   (=18:01:10.3 UT)
+
+       n      x          y          d          l1         l2          μ
+       0  -0.129571   0.485416   11.86696   0.542093  -0.004025   89.24544
+       1   0.5406426 -0.1416400  -0.013622   0.0001241  0.0001234  15.003930
+       2  -0.0000294 -0.0000905  -0.000002  -0.0000118 -0.0000117
+       3  -0.0000081  0.0000020
+
+         Tan ƒ1 = 0.0046222     Tan ƒ2 = 0.0045992
+
+         ΔT =   70.3 s";
+
+    #[test]
+    fn test_parse() {
+        let parsed = BesselianElements::parse(2017, 8, 21, NASA_2017_08_21).unwrap();
+        assert_eq!(parsed, eclipse_2017_08_21());
+
+        let missing = NASA_2017_08_21.replace("Tan ƒ1 = 0.0046222", "");
+        assert!(BesselianElements::parse(2017, 8, 21, &missing).is_err());
+        let garbled = NASA_2017_08_21.replace("0.0001241", "0.00O1241");
+        assert!(BesselianElements::parse(2017, 8, 21, &garbled).is_err());
+    }
+
+    #[test]
+    fn test_from_ephemeris() {
+        let nasa = eclipse_2017_08_21();
+        let computed = BesselianElements::from_ephemeris(&nasa.t0()).unwrap();
+        assert!(computed.t0() == nasa.t0());
+
+        for &t in &[-2.0, 0.0, 2.0] {
+            let (c, n) = (computed.evaluate(t), nasa.evaluate(t));
+            // The low accuracy solar theory limits the agreement to about 0.01 degrees along
+            // the shadow axis, which is a few thousandths of an Earth radius at the Moon.
+            assert!(approx_eq(c.x, n.x, 5.0e-3));
+            assert!(approx_eq(c.y, n.y, 5.0e-3));
+            assert!(approx_eq(c.d.to_degrees(), n.d.to_degrees(), 0.01));
+            assert!(approx_eq(c.mu.to_degrees(), n.mu.to_degrees(), 0.01));
+            assert!(approx_eq(c.l1, n.l1, 1.0e-4));
+            assert!(approx_eq(c.l2, n.l2, 5.0e-4));
+            assert!(approx_eq(c.dx, n.dx, 1.0e-4));
+            assert!(approx_eq(c.dy, n.dy, 1.0e-4));
+        }
+        assert!(approx_eq(computed.tan_f1(), nasa.tan_f1(), 2.0e-7));
+        assert!(approx_eq(computed.tan_f2(), nasa.tan_f2(), 2.0e-7));
+    }
+
+    #[test]
+    fn test_fit_cubic() {
+        let ts = [-2.0, -1.0, 0.0, 1.0, 2.0];
+        let values: Vec<f64> = ts.iter()
+            .map(|t| 1.0 - 2.0 * t + 0.5 * t * t + 0.25 * t * t * t)
+            .collect();
+        let c = fit_cubic(&ts, &values);
+        assert!(approx_eq(c[0], 1.0, 1.0e-12));
+        assert!(approx_eq(c[1], -2.0, 1.0e-12));
+        assert!(approx_eq(c[2], 0.5, 1.0e-12));
+        assert!(approx_eq(c[3], 0.25, 1.0e-12));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let elements = eclipse_2017_08_21();
+        let axis = elements.evaluate(1.0);
+
+        assert!(approx_eq(axis.x, -0.129_571 + 0.540_642_6 - 0.000_029_4 - 0.000_008_1, 1.0e-12));
+        assert!(approx_eq(axis.dx, 0.540_642_6 - 2.0 * 0.000_029_4 - 3.0 * 0.000_008_1, 1.0e-12));
+        assert!(approx_eq(axis.mu.to_degrees(), 89.245_44 + 15.003_930, 1.0e-10));
+        assert!(approx_eq(axis.l2, -0.004_025 + 0.000_123_4 - 0.000_011_7, 1.0e-12));
+    }
+
+    #[test]
+    fn test_times() {
+        let elements = eclipse_2017_08_21();
+
+        let ut = elements.universal_time(0.5).unwrap();
+        let expected = Builder::from_gregorian_utc(2017, 8, 21, 18, 30, 0).build().unwrap();
+        assert!(approx_eq(ut.julian_day_number(),
+                          expected.julian_day_number() - 70.3 / 86_400.0,
+                          1.0e-8));
+
+        assert!(BesselianElements::new(elements.t0(),
+                                       f64::NAN,
+                                       [[0.0; 6]; 4],
+                                       0.004_622_2,
+                                       0.004_599_2)
+            .is_err());
+    }
+}
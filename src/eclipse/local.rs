@@ -0,0 +1,382 @@
+//!
+//! Module for the local circumstances of a solar eclipse for an observer.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use std::f64::consts::PI;
+
+use super::super::astro_time::AstroTime;
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::math::*;
+use super::besselian::*;
+
+/// The type of a solar eclipse as seen from a particular place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalEclipseKind {
+    /// The Moon covers only part of the Sun.
+    Partial,
+    /// The Moon is centered on the Sun, but leaves a ring of the Sun uncovered.
+    Annular,
+    /// The Moon covers the Sun completely.
+    Total,
+}
+
+/// A contact, or the maximum, of an eclipse seen by an observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EclipseContact {
+    time: AstroTime,
+    sun_altitude: RadianAngle,
+}
+
+impl EclipseContact {
+    /// The time of the contact in universal time.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+
+    /// The geometric altitude of the Sun at the time of contact, if it is negative the contact is
+    /// not visible.
+    pub fn sun_altitude(&self) -> RadianAngle {
+        self.sun_altitude
+    }
+}
+
+/// The circumstances of a solar eclipse for an observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalCircumstances {
+    kind: LocalEclipseKind,
+    magnitude: f64,
+    obscuration: f64,
+    first_contact: EclipseContact,
+    second_contact: Option<EclipseContact>,
+    maximum: EclipseContact,
+    third_contact: Option<EclipseContact>,
+    fourth_contact: EclipseContact,
+}
+
+impl LocalCircumstances {
+    /// The type of eclipse seen by the observer.
+    pub fn kind(&self) -> LocalEclipseKind {
+        self.kind
+    }
+
+    /// The magnitude at maximum eclipse, the fraction of the Sun's diameter covered by the Moon.
+    pub fn magnitude(&self) -> f64 {
+        self.magnitude
+    }
+
+    /// The obscuration at maximum eclipse, the fraction of the Sun's area covered by the Moon.
+    pub fn obscuration(&self) -> f64 {
+        self.obscuration
+    }
+
+    /// The first contact, C1, when the eclipse begins.
+    pub fn first_contact(&self) -> EclipseContact {
+        self.first_contact
+    }
+
+    /// The second contact, C2, when the total or annular phase begins.
+    pub fn second_contact(&self) -> Option<EclipseContact> {
+        self.second_contact
+    }
+
+    /// The maximum eclipse.
+    pub fn maximum(&self) -> EclipseContact {
+        self.maximum
+    }
+
+    /// The third contact, C3, when the total or annular phase ends.
+    pub fn third_contact(&self) -> Option<EclipseContact> {
+        self.third_contact
+    }
+
+    /// The fourth contact, C4, when the eclipse ends.
+    pub fn fourth_contact(&self) -> EclipseContact {
+        self.fourth_contact
+    }
+}
+
+/// Calculate the local circumstances of a solar eclipse for an observer.
+///
+/// Returns `None` if the eclipse is not seen from the observer's location. The contacts are
+/// calculated even if the Sun is below the horizon, check the altitude of the Sun to see if they
+/// are visible.
+pub fn local_circumstances(elements: &BesselianElements,
+                           geo: GeoCoords)
+                           -> AstroResult<Option<LocalCircumstances>> {
+    let observer = Observer::new(elements, geo);
+
+    // Maximum eclipse, when the observer is closest to the shadow axis.
+    let t_max = iterate(0.0, |t| {
+        let s = observer.state(t);
+        -(s.u * s.a + s.v * s.b) / s.n2
+    });
+    let s = observer.state(t_max);
+    let m = f64::sqrt(s.u * s.u + s.v * s.v);
+    if m >= s.l1 {
+        return Ok(None);
+    }
+
+    let kind = if m >= s.l2.abs() {
+        LocalEclipseKind::Partial
+    } else if s.l2 < 0.0 {
+        LocalEclipseKind::Total
+    } else {
+        LocalEclipseKind::Annular
+    };
+
+    // Apparent radii of the Sun and Moon, in the same units as the distance between them.
+    let sun_radius = (s.l1 + s.l2) / 2.0;
+    let moon_radius = (s.l1 - s.l2) / 2.0;
+    let obscuration = overlap_area(sun_radius, moon_radius, m) / (PI * sun_radius * sun_radius);
+
+    let contact = |t: f64| -> AstroResult<EclipseContact> {
+        Ok(EclipseContact {
+            time: elements.universal_time(t)?,
+            sun_altitude: observer.sun_altitude(t)?,
+        })
+    };
+
+    let (second_contact, third_contact) = if kind == LocalEclipseKind::Partial {
+        (None, None)
+    } else {
+        (Some(contact(observer.contact(t_max, false, -1.0))?),
+         Some(contact(observer.contact(t_max, false, 1.0))?))
+    };
+
+    Ok(Some(LocalCircumstances {
+        kind,
+        magnitude: (s.l1 - m) / (s.l1 + s.l2),
+        obscuration: obscuration.min(1.0),
+        first_contact: contact(observer.contact(t_max, true, -1.0))?,
+        second_contact,
+        maximum: contact(t_max)?,
+        third_contact,
+        fourth_contact: contact(observer.contact(t_max, true, 1.0))?,
+    }))
+}
+
+// The observer's geocentric position and the shadow relative to it.
+struct Observer<'a> {
+    elements: &'a BesselianElements,
+    geo: GeoCoords,
+    rho_sin_phi: f64,
+    rho_cos_phi: f64,
+}
+
+// The shadow relative to the observer at an instant.
+struct RelativeShadow {
+    u: f64,
+    v: f64,
+    a: f64,
+    b: f64,
+    n2: f64,
+    l1: f64,
+    l2: f64,
+}
+
+impl<'a> Observer<'a> {
+    fn new(elements: &'a BesselianElements, geo: GeoCoords) -> Observer<'a> {
+        let (rho_sin_phi, rho_cos_phi) = geo.geocentric_position();
+
+        Observer {
+            elements,
+            geo,
+            rho_sin_phi,
+            rho_cos_phi,
+        }
+    }
+
+    // Local hour angle and declination of the shadow axis, and the observer's coordinates on the
+    // fundamental plane.
+    fn position(&self, t: f64) -> (ShadowAxis, f64, [f64; 3]) {
+        let axis = self.elements.evaluate(t);
+
+        // The elements are in dynamical time, correct the hour angle for the rotation of the
+        // Earth during delta T.
+        let h = axis.mu - self.geo.longitude().radians() -
+                (0.004_178_07 * self.elements.delta_t()).to_radians();
+        let (sin_d, cos_d) = sin_cos(axis.d);
+
+        let xi = self.rho_cos_phi * sin(h);
+        let eta = self.rho_sin_phi * cos_d - self.rho_cos_phi * cos(h) * sin_d;
+        let zeta = self.rho_sin_phi * sin_d + self.rho_cos_phi * cos(h) * cos_d;
+
+        (axis, h, [xi, eta, zeta])
+    }
+
+    fn state(&self, t: f64) -> RelativeShadow {
+        let (axis, h, [xi, eta, zeta]) = self.position(t);
+
+        let d_xi = axis.dmu * self.rho_cos_phi * cos(h);
+        let d_eta = axis.dmu * xi * sin(axis.d) - zeta * axis.dd;
+
+        let a = axis.dx - d_xi;
+        let b = axis.dy - d_eta;
+
+        RelativeShadow {
+            u: axis.x - xi,
+            v: axis.y - eta,
+            a,
+            b,
+            n2: a * a + b * b,
+            l1: axis.l1 - zeta * self.elements.tan_f1(),
+            l2: axis.l2 - zeta * self.elements.tan_f2(),
+        }
+    }
+
+    // Time of a contact with the penumbra or umbra, before (sign = -1) or after (sign = 1) the
+    // maximum.
+    fn contact(&self, t_max: f64, penumbra: bool, sign: f64) -> f64 {
+        iterate(t_max, |t| {
+            let s = self.state(t);
+            let l = if penumbra { s.l1 } else { s.l2.abs() };
+            let n = s.n2.sqrt();
+            let sin_psi = ((s.a * s.v - s.u * s.b) / (n * l)).clamp(-1.0, 1.0);
+
+            -(s.u * s.a + s.v * s.b) / s.n2 + sign * l / n * f64::sqrt(1.0 - sin_psi * sin_psi)
+        })
+    }
+
+    fn sun_altitude(&self, t: f64) -> AstroResult<RadianAngle> {
+        let (axis, h, _) = self.position(t);
+        let phi = self.geo.latitude().radians();
+
+        RadianAngle::new(asin((sin(axis.d) * sin(phi) + cos(axis.d) * cos(phi) * cos(h))
+            .clamp(-1.0, 1.0)))
+    }
+}
+
+// Repeatedly apply the correction to the time until it converges.
+fn iterate<F: Fn(f64) -> f64>(start: f64, correction: F) -> f64 {
+    let mut t = start;
+    for _ in 0..50 {
+        let tau = correction(t);
+        t += tau;
+        if tau.abs() < 1.0e-7 {
+            break;
+        }
+    }
+
+    t
+}
+
+// Area of overlap of two circles with radii r1 and r2 whose centers are separated by dist.
+fn overlap_area(r1: f64, r2: f64, dist: f64) -> f64 {
+    if dist >= r1 + r2 {
+        0.0
+    } else if dist <= (r1 - r2).abs() {
+        PI * r1.min(r2).powi(2)
+    } else {
+        let a1 = acos((dist * dist + r1 * r1 - r2 * r2) / (2.0 * dist * r1));
+        let a2 = acos((dist * dist + r2 * r2 - r1 * r1) / (2.0 * dist * r2));
+        let kite = f64::sqrt((-dist + r1 + r2) * (dist + r1 - r2) * (dist - r1 + r2) *
+                             (dist + r1 + r2));
+
+        r1 * r1 * a1 + r2 * r2 * a2 - 0.5 * kite
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod local_tests {
+    use super::*;
+    use astro_time::Builder;
+    use eclipse::besselian::besselian_tests::eclipse_2017_08_21;
+
+    fn geo(lat: f64, west_lon: f64) -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(lat.to_radians()).unwrap(),
+                       RadianAngle::new(west_lon.to_radians()).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    fn seconds_between(a: AstroTime, b: AstroTime) -> f64 {
+        (b.julian_day_number() - a.julian_day_number()) * 86_400.0
+    }
+
+    #[test]
+    fn test_total_eclipse() {
+        // Near the point of greatest eclipse, 36° 58.0' N, 87° 40.3' W at 18:25:32 UT.
+        let elements = eclipse_2017_08_21();
+        let lc = local_circumstances(&elements, geo(36.966_7, 87.671_7)).unwrap().unwrap();
+
+        assert_eq!(lc.kind(), LocalEclipseKind::Total);
+        assert!(lc.magnitude() > 1.0);
+        assert!(approx_eq(lc.obscuration(), 1.0, 1.0e-12));
+
+        let greatest = Builder::from_gregorian_utc(2017, 8, 21, 18, 25, 32).build().unwrap();
+        assert!(seconds_between(lc.maximum().time(), greatest).abs() < 5.0);
+        assert!(approx_eq(lc.maximum().sun_altitude().radians().to_degrees(), 64.0, 0.5));
+
+        // The total phase lasts 2m 40.2s.
+        let duration = seconds_between(lc.second_contact().unwrap().time(),
+                                       lc.third_contact().unwrap().time());
+        assert!(approx_eq(duration, 160.2, 2.0));
+
+        assert!(lc.first_contact().time() < lc.second_contact().unwrap().time());
+        assert!(lc.third_contact().unwrap().time() < lc.fourth_contact().time());
+    }
+
+    #[test]
+    fn test_partial_eclipse() {
+        // Chicago, eclipse from 11:54 AM to 2:42 PM CDT with maximum at 1:20 PM.
+        let elements = eclipse_2017_08_21();
+        let lc = local_circumstances(&elements, geo(41.878, 87.630)).unwrap().unwrap();
+
+        assert_eq!(lc.kind(), LocalEclipseKind::Partial);
+        assert!(lc.second_contact().is_none() && lc.third_contact().is_none());
+        assert!(approx_eq(lc.magnitude(), 0.89, 0.02));
+        assert!(lc.obscuration() < lc.magnitude());
+
+        let expect = |h, m| Builder::from_gregorian_utc(2017, 8, 21, h, m, 0).build().unwrap();
+        assert!(seconds_between(lc.first_contact().time(), expect(16, 54)).abs() < 90.0);
+        assert!(seconds_between(lc.maximum().time(), expect(18, 20)).abs() < 90.0);
+        assert!(seconds_between(lc.fourth_contact().time(), expect(19, 42)).abs() < 90.0);
+    }
+
+    #[test]
+    fn test_computed_elements() {
+        // Chicago again from elements computed with this library, the low accuracy solar theory
+        // limits the agreement to about a minute.
+        let nasa = eclipse_2017_08_21();
+        let computed = BesselianElements::from_ephemeris(&nasa.t0()).unwrap();
+        let chicago = geo(41.878, 87.630);
+        let expected = local_circumstances(&nasa, chicago).unwrap().unwrap();
+        let lc = local_circumstances(&computed, chicago).unwrap().unwrap();
+
+        assert_eq!(lc.kind(), expected.kind());
+        assert!(approx_eq(lc.magnitude(), expected.magnitude(), 0.01));
+        for &(a, b) in &[(lc.first_contact(), expected.first_contact()),
+                         (lc.maximum(), expected.maximum()),
+                         (lc.fourth_contact(), expected.fourth_contact())] {
+            assert!(seconds_between(a.time(), b.time()).abs() < 90.0);
+        }
+    }
+
+    #[test]
+    fn test_no_eclipse() {
+        // Sydney is on the wrong side of the Earth.
+        let elements = eclipse_2017_08_21();
+        assert!(local_circumstances(&elements, geo(-33.87, -151.21)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_overlap_area() {
+        assert_eq!(overlap_area(1.0, 1.0, 2.5), 0.0);
+        assert!(approx_eq(overlap_area(1.0, 0.5, 0.2), PI * 0.25, 1.0e-15));
+        assert!(approx_eq(overlap_area(1.0, 1.0, 1.0),
+                          2.0 * PI / 3.0 - 3.0f64.sqrt() / 2.0,
+                          1.0e-12));
+    }
+}
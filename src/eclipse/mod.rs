@@ -0,0 +1,22 @@
+//!
+//! Module for predicting and describing eclipses.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Solar eclipses are described by Besselian elements, which give the position and size of the
+//! Moon's shadow on the fundamental plane through the center of the Earth as polynomials in time.
+//! The methods follow the Explanatory Supplement to the Astronomical Almanac.
+//!
+mod besselian;
+mod local;
+mod seasons;
+mod shadow;
+
+pub use self::besselian::*;
+pub use self::local::*;
+pub use self::seasons::*;
+pub use self::shadow::*;
@@ -0,0 +1,274 @@
+//!
+//! Module for eclipse seasons and the saros and inex series of eclipses.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Eclipses can only happen while the Sun is near one of the nodes of the Moon's orbit, in an
+//! eclipse season. Eclipses one saros, 223 lunations, apart are very similar and belong to the
+//! same saros series, while the series themselves are one inex, 358 lunations, apart. Every
+//! eclipse is identified by its saros and inex numbers following G. van den Bergh, "Periodicity
+//! and Variation of Solar (and Lunar) Eclipses", 1955.
+//!
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::super::astro_time::{AstroTime, Builder};
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::math::*;
+use super::super::moon::{NEW_MOON_EPOCH, SYNODIC_MONTH};
+use super::super::sun;
+
+// The largest distance of the Sun from a node of the Moon's orbit at which a partial solar eclipse
+// is possible, in radians.
+const ECLIPSE_LIMIT: f64 = 0.322_885_911_618_951;
+
+// The lunation number k of chapter 49 plus this is 358 times the saros number plus 223 times the
+// inex number of a solar eclipse.
+const LUNATION_OFFSET: i64 = 60_389;
+
+/// The kind of eclipse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EclipseKind {
+    /// An eclipse of the Sun, at new moon.
+    Solar,
+    /// An eclipse of the Moon, at full moon.
+    Lunar,
+}
+
+/// A node of the Moon's orbit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LunarNode {
+    /// Where the Moon crosses the ecliptic going north.
+    Ascending,
+    /// Where the Moon crosses the ecliptic going south.
+    Descending,
+}
+
+/// A span of time with the Sun close enough to a node of the Moon's orbit for eclipses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EclipseSeason {
+    node: LunarNode,
+    start: AstroTime,
+    middle: AstroTime,
+    end: AstroTime,
+}
+
+impl EclipseSeason {
+    /// The node the Sun passes during the season.
+    pub fn node(&self) -> LunarNode {
+        self.node
+    }
+
+    /// The start of the season in Universal Time.
+    pub fn start(&self) -> AstroTime {
+        self.start
+    }
+
+    /// The time the Sun passes the node in Universal Time.
+    pub fn middle(&self) -> AstroTime {
+        self.middle
+    }
+
+    /// The end of the season in Universal Time.
+    pub fn end(&self) -> AstroTime {
+        self.end
+    }
+}
+
+/// Calculate the eclipse seasons with the Sun passing a node during a Gregorian calendar year.
+///
+/// A season spans the time the Sun is within 18.5 degrees of the mean node, the limit for a
+/// partial solar eclipse, so every eclipse happens during one. There are two seasons in most
+/// years and three in some, since the Sun returns to a node every 173.3 days.
+pub fn eclipse_seasons(year: i32) -> AstroResult<Vec<EclipseSeason>> {
+    let jd_start = Builder::from_gregorian_utc(year, 1, 1, 0, 0, 0).build()?.julian_day_number();
+    let jd_end = Builder::from_gregorian_utc(year + 1, 1, 1, 0, 0, 0)
+        .build()?
+        .julian_day_number();
+
+    let mut seasons = vec![];
+    let mut jd = jd_start;
+    let mut f_start = node_distance(jd)?;
+    while jd < jd_end {
+        let jd_next = f64::min(jd + 5.0, jd_end);
+        let f_end = node_distance(jd_next)?;
+
+        // The distance jumps from π/2 to -π/2 half way between the nodes.
+        if f_start < 0.0 && f_end >= 0.0 {
+            let middle = bisect(jd, jd_next, 0.0)?;
+            let start = bisect(middle - 30.0, middle, -ECLIPSE_LIMIT)?;
+            let end = bisect(middle, middle + 30.0, ECLIPSE_LIMIT)?;
+
+            let longitude = sun_longitude_from_node(middle)?;
+            seasons.push(EclipseSeason {
+                node: if cos(longitude) > 0.0 {
+                    LunarNode::Ascending
+                } else {
+                    LunarNode::Descending
+                },
+                start: ut(start)?,
+                middle: ut(middle)?,
+                end: ut(end)?,
+            });
+        }
+
+        jd = jd_next;
+        f_start = f_end;
+    }
+
+    Ok(seasons)
+}
+
+/// Calculate the saros series number of an eclipse.
+///
+/// The time should be within a few days of the eclipse. Returns an error if the Moon is too far
+/// from a node at the new or full moon for an eclipse.
+pub fn saros_series(kind: EclipseKind, time: &AstroTime) -> AstroResult<i32> {
+    Ok(saros_inex(kind, time)?.0)
+}
+
+/// Calculate the inex series number of an eclipse.
+///
+/// Lunar eclipses are given the inex number of the new moon half a lunation before them. The time
+/// should be within a few days of the eclipse. Returns an error if the Moon is too far from a
+/// node at the new or full moon for an eclipse.
+pub fn inex_series(kind: EclipseKind, time: &AstroTime) -> AstroResult<i32> {
+    Ok(saros_inex(kind, time)?.1)
+}
+
+// The saros and inex numbers of an eclipse.
+fn saros_inex(kind: EclipseKind, time: &AstroTime) -> AstroResult<(i32, i32)> {
+    let args = FundamentalArguments::for_time(time)?;
+    let phase = match kind {
+        EclipseKind::Solar => 0.0,
+        EclipseKind::Lunar => PI,
+    };
+
+    // The elongation from the new or full moon, and the argument of latitude at that phase.
+    let d = map_to_branch(args.mean_elongation().radians() - phase, -PI, PI);
+    let f = args.moon_argument_of_latitude().radians() - 1.085_1 * d;
+    if d.abs() > 0.5 {
+        return Err(AstroAlgorithmsError::InvalidArgument("An eclipse must be within a few days \
+                                                          of the new or full moon"
+            .to_owned()));
+    } else if sin(f).abs() > 0.36 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The Moon is too far from a node for an \
+                                                          eclipse"
+            .to_owned()));
+    }
+
+    // The lunation number of the new moon at or before the eclipse.
+    let jde = time.julian_ephemeris_day()?;
+    let k = ((jde - NEW_MOON_EPOCH) / SYNODIC_MONTH - (d + phase) / (2.0 * PI)).round() as i64;
+
+    // Solve k + 60389 = 358 s + 223 i, 38 is the inverse of 358 modulo 223. Of the solutions 223
+    // series apart choose the one with the inex number in the range of existing series.
+    let n = k + LUNATION_OFFSET;
+    let mut saros = (38 * n).rem_euclid(223);
+    let mut inex = (n - 358 * saros) / 223;
+    while inex >= 308 {
+        saros += 223;
+        inex -= 358;
+    }
+    while inex < -50 {
+        saros -= 223;
+        inex += 358;
+    }
+
+    // The lunar saros series are numbered separately.
+    if kind == EclipseKind::Lunar {
+        saros += 12;
+    }
+
+    Ok((saros as i32, inex as i32))
+}
+
+// The distance of the Sun from the nearest node of the Moon's orbit in longitude, in the range
+// [-π/2, π/2].
+fn node_distance(jd: f64) -> AstroResult<f64> {
+    Ok(map_to_branch(sun_longitude_from_node(jd)?, -FRAC_PI_2, FRAC_PI_2))
+}
+
+// The longitude of the Sun measured from the mean ascending node of the Moon's orbit.
+fn sun_longitude_from_node(jd: f64) -> AstroResult<f64> {
+    let time = ut(jd)?;
+    let sun = sun::apparent_ecliptic(&time)?.longitude().radians();
+    let node = FundamentalArguments::for_time(&time)?.moon_ascending_node().radians();
+
+    Ok(sun - node)
+}
+
+// Bisect for the time the Sun is at a distance from the node, which increases with time.
+fn bisect(mut lo: f64, mut hi: f64, distance: f64) -> AstroResult<f64> {
+    while hi - lo > 1.0e-4 {
+        let mid = 0.5 * (lo + hi);
+        if node_distance(mid)? < distance {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Ok(0.5 * (lo + hi))
+}
+
+fn ut(jd: f64) -> AstroResult<AstroTime> {
+    Builder::from_julian_date(jd).build()
+}
+
+#[cfg(test)]
+mod seasons_tests {
+    use super::*;
+
+    fn date(year: i32, month: i32, day: i32) -> AstroTime {
+        Builder::from_gregorian_utc(year, month, day, 12, 0, 0).build().unwrap()
+    }
+
+    #[test]
+    fn test_eclipse_seasons() {
+        // The eclipses of 2019 were on January 6 and 21, July 2 and 16, and December 26.
+        let seasons = eclipse_seasons(2019).unwrap();
+        assert_eq!(seasons.len(), 3);
+
+        let eclipses = [[(1, 6), (1, 21)], [(7, 2), (7, 16)], [(12, 26), (12, 26)]];
+        for (season, pair) in seasons.iter().zip(&eclipses) {
+            for &(month, day) in pair {
+                let jd = date(2019, month, day).julian_day_number();
+                assert!(season.start().julian_day_number() < jd);
+                assert!(season.end().julian_day_number() > jd);
+            }
+
+            let length = season.end().duration_since(&season.start()).unwrap().days();
+            assert!(length > 33.0 && length < 38.0);
+        }
+
+        assert_eq!(seasons[0].node(), LunarNode::Descending);
+        assert_eq!(seasons[1].node(), LunarNode::Ascending);
+        assert_eq!(seasons[2].node(), LunarNode::Descending);
+        assert_eq!(eclipse_seasons(2020).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_saros_inex() {
+        let solar = [(2017, 8, 21, 145, 39), (2019, 7, 2, 127, 68), (2023, 10, 14, 134, 57),
+                     (2024, 4, 8, 139, 49)];
+        for &(year, month, day, saros, inex) in &solar {
+            let time = date(year, month, day);
+            assert_eq!(saros_series(EclipseKind::Solar, &time).unwrap(), saros);
+            assert_eq!(inex_series(EclipseKind::Solar, &time).unwrap(), inex);
+        }
+
+        let lunar = [(2018, 1, 31, 124), (2019, 1, 21, 134), (2022, 11, 8, 136)];
+        for &(year, month, day, saros) in &lunar {
+            assert_eq!(saros_series(EclipseKind::Lunar, &date(year, month, day)).unwrap(), saros);
+        }
+
+        // No eclipse at the new moon of 2017 September 20, or a week after an eclipse.
+        assert!(saros_series(EclipseKind::Solar, &date(2017, 9, 20)).is_err());
+        assert!(saros_series(EclipseKind::Solar, &date(2017, 8, 28)).is_err());
+    }
+}
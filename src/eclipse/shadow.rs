@@ -0,0 +1,144 @@
+//!
+//! Module for the geometry of the shadows of the Earth and Moon.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The shadows are the cones tangent to the Sun and the body, with both taken to be spheres of
+//! their equatorial radii. The umbra is the cone converging behind the body, and the penumbra the
+//! cone diverging from a vertex between the body and the Sun. The shadows are purely geometric,
+//! lunar eclipse predictions usually enlarge the shadow of the Earth by a few percent for its
+//! atmosphere.
+//!
+use super::super::bodies::Body;
+use super::super::coords::*;
+use super::super::error::*;
+
+/// A body whose shadow is of interest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowingBody {
+    /// The Earth, which shadows the Moon and artificial satellites.
+    Earth,
+    /// The Moon, which shadows the Earth.
+    Moon,
+}
+
+impl ShadowingBody {
+    /// The equatorial radius in kilometers.
+    pub fn radius_km(self) -> f64 {
+        match self {
+            ShadowingBody::Earth => EARTH_EQUATORIAL_RADIUS_KM,
+            ShadowingBody::Moon => Body::Moon.equatorial_radius_km(),
+        }
+    }
+}
+
+/// Calculate the length of the umbra, the distance in kilometers from the center of the body to
+/// the vertex of the cone, when the body is `sun_distance_km` from the Sun.
+pub fn umbra_length(body: ShadowingBody, sun_distance_km: f64) -> AstroResult<f64> {
+    let sin_f = cone_sine(body, sun_distance_km, -1.0)?;
+    Ok(body.radius_km() / sin_f)
+}
+
+/// Calculate the radius in kilometers of the umbra `distance_km` behind the center of the body,
+/// when the body is `sun_distance_km` from the Sun.
+///
+/// The radius is measured perpendicular to the axis of the shadow. It is negative beyond the
+/// vertex of the umbra, where its magnitude is the radius of the antumbra.
+pub fn umbra_radius(body: ShadowingBody,
+                    sun_distance_km: f64,
+                    distance_km: f64)
+                    -> AstroResult<f64> {
+    let sin_f = cone_sine(body, sun_distance_km, -1.0)?;
+    check_distance(distance_km)?;
+
+    Ok((body.radius_km() / sin_f - distance_km) * sin_f / f64::sqrt(1.0 - sin_f * sin_f))
+}
+
+/// Calculate the radius in kilometers of the penumbra `distance_km` behind the center of the
+/// body, when the body is `sun_distance_km` from the Sun.
+///
+/// The radius is measured perpendicular to the axis of the shadow.
+pub fn penumbra_radius(body: ShadowingBody,
+                       sun_distance_km: f64,
+                       distance_km: f64)
+                       -> AstroResult<f64> {
+    let sin_f = cone_sine(body, sun_distance_km, 1.0)?;
+    check_distance(distance_km)?;
+
+    Ok((body.radius_km() / sin_f + distance_km) * sin_f / f64::sqrt(1.0 - sin_f * sin_f))
+}
+
+// The sine of the half angle of the umbra, sign -1, or penumbra, sign 1.
+fn cone_sine(body: ShadowingBody, sun_distance_km: f64, sign: f64) -> AstroResult<f64> {
+    let sun_radius = Body::Sun.equatorial_radius_km();
+    if sun_distance_km.is_nan() {
+        Err(AstroAlgorithmsError::EncounteredNaN)
+    } else if sun_distance_km.is_infinite() {
+        Err(AstroAlgorithmsError::EncounteredInf)
+    } else if sun_distance_km <= sun_radius + body.radius_km() {
+        Err(AstroAlgorithmsError::InvalidArgument("The distance from the Sun must be greater than \
+                                                   the sum of the radii"
+            .to_owned()))
+    } else {
+        Ok((sun_radius + sign * body.radius_km()) / sun_distance_km)
+    }
+}
+
+fn check_distance(distance_km: f64) -> AstroResult<()> {
+    if distance_km.is_nan() {
+        Err(AstroAlgorithmsError::EncounteredNaN)
+    } else if distance_km.is_infinite() {
+        Err(AstroAlgorithmsError::EncounteredInf)
+    } else {
+        Ok(())
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod shadow_tests {
+    use super::*;
+
+    #[test]
+    fn test_earth_shadow() {
+        // At the mean distance of the Moon the umbra is about 2.6 times the diameter of the Moon.
+        let (sun, moon) = (ASTRONOMICAL_UNIT_KM, 384_400.0);
+        let umbra = umbra_radius(ShadowingBody::Earth, sun, moon).unwrap();
+        let penumbra = penumbra_radius(ShadowingBody::Earth, sun, moon).unwrap();
+        assert!(approx_eq(umbra, 4_607.0, 1.0));
+        assert!(approx_eq(penumbra, 8_182.0, 1.0));
+        assert!(approx_eq(umbra_length(ShadowingBody::Earth, sun).unwrap(), 1_384_195.0, 1.0));
+
+        // The cones touch the Earth just short of its center, so are a little wider there.
+        let radius = ShadowingBody::Earth.radius_km();
+        assert!(umbra_radius(ShadowingBody::Earth, sun, 0.0).unwrap() > radius);
+        assert!(penumbra_radius(ShadowingBody::Earth, sun, 0.0).unwrap() > radius);
+    }
+
+    #[test]
+    fn test_moon_shadow() {
+        // The umbra of the Moon barely reaches the Earth at mean distances, and past the vertex
+        // the radius is negative.
+        let sun = ASTRONOMICAL_UNIT_KM;
+        let length = umbra_length(ShadowingBody::Moon, sun).unwrap();
+        assert!(approx_eq(length, 374_532.0, 1.0));
+        let surface = 384_400.0 - EARTH_EQUATORIAL_RADIUS_KM;
+        assert!(approx_eq(umbra_radius(ShadowingBody::Moon, sun, surface).unwrap(), -16.2, 0.1));
+        assert!(approx_eq(umbra_radius(ShadowingBody::Moon, sun, length).unwrap(), 0.0, 1.0e-6));
+        assert!(approx_eq(penumbra_radius(ShadowingBody::Moon, sun, surface).unwrap(),
+                          3_499.8,
+                          0.1));
+
+        assert!(umbra_radius(ShadowingBody::Moon, 1.0e5, 0.0).is_err());
+        assert!(penumbra_radius(ShadowingBody::Moon, sun, f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//!
+//! Module for the stated accuracy and range of validity of the theories of the Sun, Moon and
+//! planets.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use super::super::astro_time::AstroTime;
+use super::super::bodies::Body;
+use super::super::error::*;
+use super::super::moon::Accuracy;
+
+/// The stated accuracy of a theory of the position of a body, and the years it is valid for.
+///
+/// Applications can use this to warn users when a time is outside of the range the theory was
+/// fitted to, where its errors grow without bound.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::Builder;
+/// # use astro_calc::bodies::Body;
+/// # use astro_calc::ephemeris::model_accuracy;
+/// let model = model_accuracy(Body::Jupiter);
+/// assert_eq!(model.valid_years(), Some((1800, 2050)));
+///
+/// let time = Builder::from_gregorian_utc(2100, 1, 1, 0, 0, 0).build().unwrap();
+/// assert!(!model.is_valid_at(&time).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelAccuracy {
+    name: &'static str,
+    accuracy_arcsec: f64,
+    valid_years: Option<(i32, i32)>,
+}
+
+impl ModelAccuracy {
+    /// A short description of the theory.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The stated accuracy of the geocentric longitude in seconds of arc.
+    pub fn accuracy_arcsec(&self) -> f64 {
+        self.accuracy_arcsec
+    }
+
+    /// The first and last years the theory is valid for, or `None` if no range is stated.
+    pub fn valid_years(&self) -> Option<(i32, i32)> {
+        self.valid_years
+    }
+
+    /// Check if a time is within the years the theory is valid for, through the end of the last
+    /// year. Always true if no range is stated.
+    pub fn is_valid_at(&self, time: &AstroTime) -> AstroResult<bool> {
+        match self.valid_years {
+            Some((first, last)) => {
+                let year = time.to_julian_epoch()?;
+                Ok(year >= f64::from(first) && year < f64::from(last + 1))
+            }
+            None => Ok(true),
+        }
+    }
+}
+
+/// The accuracy of the theory `apparent_equatorial` uses for a body.
+pub fn model_accuracy(body: Body) -> ModelAccuracy {
+    match body {
+        Body::Sun => {
+            ModelAccuracy {
+                name: "Low accuracy solar coordinates, Meeus chapter 25",
+                accuracy_arcsec: 36.0,
+                valid_years: None,
+            }
+        }
+        Body::Moon => lunar_model_accuracy(Accuracy::Full),
+        Body::Mercury | Body::Venus | Body::Mars => {
+            ModelAccuracy {
+                name: "Standish approximate Keplerian elements",
+                accuracy_arcsec: 60.0,
+                valid_years: Some((1800, 2050)),
+            }
+        }
+        Body::Jupiter | Body::Saturn | Body::Uranus | Body::Neptune => {
+            ModelAccuracy {
+                name: "Standish approximate Keplerian elements",
+                accuracy_arcsec: 300.0,
+                valid_years: Some((1800, 2050)),
+            }
+        }
+    }
+}
+
+/// The accuracy of the lunar theory at each of the levels of accuracy in the `moon` module.
+pub fn lunar_model_accuracy(accuracy: Accuracy) -> ModelAccuracy {
+    match accuracy {
+        Accuracy::Full => {
+            ModelAccuracy {
+                name: "Lunar theory, Meeus chapter 47",
+                accuracy_arcsec: 10.0,
+                valid_years: None,
+            }
+        }
+        Accuracy::Low => {
+            ModelAccuracy {
+                name: "Lunar theory with the largest terms, Meeus chapter 47",
+                accuracy_arcsec: 180.0,
+                valid_years: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod accuracy_tests {
+    use super::*;
+    use super::super::super::astro_time::Builder;
+
+    #[test]
+    fn test_model_accuracy() {
+        assert_eq!(model_accuracy(Body::Moon), lunar_model_accuracy(Accuracy::Full));
+        assert!(lunar_model_accuracy(Accuracy::Low).accuracy_arcsec() >
+                model_accuracy(Body::Moon).accuracy_arcsec());
+        assert!(model_accuracy(Body::Neptune).accuracy_arcsec() >
+                model_accuracy(Body::Mars).accuracy_arcsec());
+
+        let time = |year| Builder::from_gregorian_utc(year, 6, 1, 0, 0, 0).build().unwrap();
+        let mars = model_accuracy(Body::Mars);
+        assert!(mars.is_valid_at(&time(1800)).unwrap());
+        assert!(mars.is_valid_at(&time(2050)).unwrap());
+        assert!(!mars.is_valid_at(&time(1799)).unwrap());
+        assert!(!mars.is_valid_at(&time(2051)).unwrap());
+        assert!(model_accuracy(Body::Sun).is_valid_at(&time(1000)).unwrap());
+    }
+}
@@ -0,0 +1,252 @@
+//!
+//! Module for the geometry of the Sun, a body and the Earth.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The magnitudes of the planets are from chapter 41 of Astronomical Algorithms, 2nd Edition by
+//! Jean Meeus, the formulae of the Astronomical Almanac of 1984. The magnitude of the Moon is
+//! from Allen's Astrophysical Quantities.
+//!
+use std::f64::consts::PI;
+
+use super::super::astro_time::AstroTime;
+use super::super::bodies::Body;
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::math::*;
+use super::super::moon;
+use super::super::planets;
+use super::super::sun;
+use super::apparent_equatorial;
+
+// The mean distance of the Moon in kilometers, for its magnitude.
+const MOON_MEAN_DISTANCE_KM: f64 = 384_400.0;
+
+/// The positions of the Sun, a body and the Earth relative to each other at a time.
+///
+/// Calculating these takes the positions of the body and the Sun, so calculate them once and use
+/// them for the magnitude, phase and bright limb together.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometry {
+    body: Body,
+    equatorial: EquatorialCoords,
+    sun: EquatorialCoords,
+    sun_distance: f64,
+    earth_distance: f64,
+    phase_angle: RadianAngle,
+    elongation: RadianAngle,
+    sun_position_angle: RadianAngle,
+}
+
+impl Geometry {
+    /// Calculate the geometry of the Moon or a planet at a time.
+    ///
+    /// Returns an error for the Sun.
+    pub fn new(body: Body, time: &AstroTime) -> AstroResult<Geometry> {
+        let earth_distance = match body {
+            Body::Sun => {
+                return Err(AstroAlgorithmsError::InvalidArgument("The Sun is not lit by the Sun"
+                    .to_owned()))
+            }
+            Body::Moon => moon::distance(time)? / ASTRONOMICAL_UNIT_KM,
+            _ => planets::distance(body, time)?,
+        };
+
+        let equatorial = apparent_equatorial(body, time)?;
+        let sun = sun::apparent_equatorial(time)?;
+        let big_r = sun::radius_vector(time)?;
+        let psi = angular_separation(equatorial, sun).radians();
+
+        // The distance from the Sun and the phase angle from the triangle of the Sun, body and
+        // Earth, equation 48.3.
+        let sun_distance = f64::sqrt(big_r * big_r + earth_distance * earth_distance -
+                                     2.0 * big_r * earth_distance * cos(psi));
+        let phase_angle = atan2(big_r * sin(psi), earth_distance - big_r * cos(psi));
+
+        Ok(Geometry {
+            body,
+            equatorial,
+            sun,
+            sun_distance,
+            earth_distance,
+            phase_angle: RadianAngle::new(phase_angle)?,
+            elongation: RadianAngle::new(psi)?,
+            sun_position_angle: bright_limb_position_angle(equatorial, sun)?,
+        })
+    }
+
+    /// The body.
+    pub fn body(&self) -> Body {
+        self.body
+    }
+
+    /// The apparent geocentric equatorial coordinates of the body.
+    pub fn equatorial(&self) -> EquatorialCoords {
+        self.equatorial
+    }
+
+    /// The distance of the body from the Sun, r, in astronomical units.
+    pub fn sun_distance(&self) -> f64 {
+        self.sun_distance
+    }
+
+    /// The distance of the body from the Earth, Δ, in astronomical units.
+    pub fn earth_distance(&self) -> f64 {
+        self.earth_distance
+    }
+
+    /// The phase angle, the angle between the Sun and the Earth as seen from the body.
+    pub fn phase_angle(&self) -> RadianAngle {
+        self.phase_angle
+    }
+
+    /// The elongation, the angle between the Sun and the body as seen from the Earth.
+    pub fn elongation(&self) -> RadianAngle {
+        self.elongation
+    }
+
+    /// The position angle of the direction of the Sun from the body, which is the position angle
+    /// of the midpoint of the bright limb.
+    pub fn sun_position_angle(&self) -> RadianAngle {
+        self.sun_position_angle
+    }
+
+    /// The illuminated fraction of the disk, equation 41.1.
+    pub fn illuminated_fraction(&self) -> f64 {
+        (1.0 + cos(self.phase_angle.radians())) / 2.0
+    }
+
+    /// The apparent visual magnitude.
+    ///
+    /// The magnitude of Saturn includes the rings. Uranus and Neptune are too far from the Sun to
+    /// be seen at large phase angles, so their magnitudes only depend on the distances.
+    pub fn magnitude(&self) -> AstroResult<f64> {
+        let i = self.phase_angle.radians().to_degrees();
+        let distances = 5.0 * log10(self.sun_distance * self.earth_distance);
+
+        let magnitude = match self.body {
+            Body::Moon => {
+                let distances = 5.0 * log10(self.sun_distance * self.earth_distance *
+                                            ASTRONOMICAL_UNIT_KM /
+                                            MOON_MEAN_DISTANCE_KM);
+                -12.73 + distances + 0.026 * i + 4.0e-9 * i.powi(4)
+            }
+            Body::Mercury => -0.42 + distances + i * (0.038_0 - i * (0.000_273 - i * 0.000_002)),
+            Body::Venus => -4.40 + distances + i * (0.000_9 + i * (0.000_239 - i * 0.000_000_65)),
+            Body::Mars => -1.52 + distances + 0.016 * i,
+            Body::Jupiter => -9.40 + distances + 0.005 * i,
+            Body::Saturn => {
+                let (b, delta_u) = self.saturn_rings()?;
+                let sin_b = sin(b.abs());
+                -8.88 + distances + 0.044 * delta_u.abs().to_degrees() - 2.60 * sin_b +
+                1.25 * sin_b * sin_b
+            }
+            Body::Uranus => -7.19 + distances,
+            Body::Neptune => -6.87 + distances,
+            Body::Sun => unreachable!(),
+        };
+
+        Ok(magnitude)
+    }
+
+    // The Saturnicentric latitude of the Earth on the plane of the rings, and the difference of
+    // the Saturnicentric longitudes of the Sun and Earth, chapter 45.
+    fn saturn_rings(&self) -> AstroResult<(f64, f64)> {
+        let time = self.equatorial.epoch();
+        let eps = true_obliquity(&time)?;
+        let t = (time.julian_ephemeris_day()? - 2_451_545.0) / 36_525.0;
+        let i = (28.075_216 - t * (0.012_998 - t * 0.000_004)).to_radians();
+        let node = (169.508_470 + t * (1.394_681 + t * 0.000_412)).to_radians();
+
+        let to_vector = |eq: EquatorialCoords, r: f64| -> AstroResult<(f64, f64, f64)> {
+            let ecl = trans_equatorial_to_ecliptic(eq, eps)?;
+            let (lon, lat) = (ecl.longitude().radians(), ecl.latitude().radians());
+            Ok((r * cos(lat) * cos(lon), r * cos(lat) * sin(lon), r * sin(lat)))
+        };
+
+        // The heliocentric position is the geocentric position less that of the Sun.
+        let saturn = to_vector(self.equatorial, self.earth_distance)?;
+        let sun = to_vector(self.sun, 1.0)?;
+        let big_r = sun::radius_vector(&time)?;
+        let helio = (saturn.0 - big_r * sun.0, saturn.1 - big_r * sun.1, saturn.2 - big_r * sun.2);
+
+        // The longitude on the plane of the rings, and the latitude, of a direction from Saturn.
+        let ring_coords = |v: (f64, f64, f64)| {
+            let lon = atan2(v.1, v.0);
+            let lat = atan2(v.2, hypot(v.0, v.1));
+            let u = atan2(sin(i) * sin(lat) + cos(i) * cos(lat) * sin(lon - node),
+                          cos(lat) * cos(lon - node));
+            let b = asin(sin(i) * cos(lat) * sin(lon - node) - cos(i) * sin(lat));
+            (u, b)
+        };
+
+        let (u_1, _) = ring_coords(helio);
+        let (u_2, b) = ring_coords(saturn);
+
+        Ok((b, map_to_branch(u_1 - u_2, -PI, PI)))
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod geometry_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    #[test]
+    fn test_venus() {
+        // Examples 41.a and 41.c, Astronomical Algorithms 2nd ed., Venus on 1992 December 20 at
+        // 0h TD.
+        let time = Builder::from_julian_date(2_448_976.5).dynamical_time().build().unwrap();
+        let venus = Geometry::new(Body::Venus, &time).unwrap();
+        assert!(approx_eq(venus.sun_distance(), 0.724_604, 1.0e-3));
+        assert!(approx_eq(venus.earth_distance(), 0.910_947, 1.0e-4));
+        assert!(approx_eq(venus.phase_angle().radians().to_degrees(), 72.96, 0.1));
+        assert!(approx_eq(venus.illuminated_fraction(), 0.647, 1.0e-3));
+        assert!(approx_eq(venus.magnitude().unwrap(), -4.2, 0.05));
+
+        // Venus was an evening star, with the bright limb towards the Sun in the west.
+        let pa = venus.sun_position_angle().radians().to_degrees();
+        assert!(pa > 180.0 && pa < 360.0);
+
+        assert!(Geometry::new(Body::Sun, &time).is_err());
+    }
+
+    #[test]
+    fn test_moon() {
+        // Example 48.a, Astronomical Algorithms 2nd ed.
+        let time = Builder::from_julian_date(2_448_724.5).dynamical_time().build().unwrap();
+        let geometry = Geometry::new(Body::Moon, &time).unwrap();
+        assert!(approx_eq(geometry.illuminated_fraction(),
+                          moon::illuminated_fraction(&time).unwrap(),
+                          1.0e-9));
+        assert!(approx_eq(geometry.phase_angle().radians().to_degrees(), 69.075_6, 1.0e-3));
+        assert!(approx_eq(geometry.elongation().radians().to_degrees(), 110.79, 0.01));
+
+        // Full moon is about magnitude -12.7.
+        let full = Builder::from_gregorian_utc(2019, 2, 19, 15, 54, 0).build().unwrap();
+        assert!(approx_eq(Geometry::new(Body::Moon, &full).unwrap().magnitude().unwrap(),
+                          -12.9,
+                          0.2));
+    }
+
+    #[test]
+    fn test_saturn() {
+        // At opposition on 2019 July 9 with the rings open by 24 degrees, magnitude 0.1.
+        let time = Builder::from_gregorian_utc(2019, 7, 9, 17, 0, 0).build().unwrap();
+        let saturn = Geometry::new(Body::Saturn, &time).unwrap();
+        let (b, delta_u) = saturn.saturn_rings().unwrap();
+        assert!(approx_eq(b.to_degrees(), 24.0, 0.5));
+        assert!(delta_u.abs().to_degrees() < 0.5);
+        assert!(approx_eq(saturn.magnitude().unwrap(), 0.1, 0.15));
+    }
+}
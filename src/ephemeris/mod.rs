@@ -0,0 +1,207 @@
+//!
+//! Module for the apparent positions of the Sun, Moon and planets, a cache of them for fast
+//! repeated queries, the geometry of their illumination by the Sun, and the accuracy of the
+//! theories.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The cache tabulates the positions of a body and interpolates between them with the three
+//! tabular values formula of chapter 3 of Astronomical Algorithms, 2nd Edition by Jean Meeus.
+//!
+mod accuracy;
+mod geometry;
+
+pub use self::accuracy::*;
+pub use self::geometry::*;
+
+use std::f64::consts::PI;
+
+use super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::bodies::Body;
+use super::coords::*;
+use super::error::*;
+use super::moon;
+use super::planets;
+use super::sun;
+
+/// Calculate the apparent geocentric equatorial coordinates of any body, referred to the equator
+/// and equinox of the date.
+pub fn apparent_equatorial(body: Body, time: &AstroTime) -> AstroResult<EquatorialCoords> {
+    match body {
+        Body::Sun => sun::apparent_equatorial(time),
+        Body::Moon => moon::apparent_equatorial(time),
+        _ => planets::apparent_equatorial(body, time),
+    }
+}
+
+/// Positions of a body tabulated over a span of time, for fast interpolated queries.
+///
+/// Calculating a position of the Moon or a planet takes hundreds of terms, which adds up in loops
+/// scanning for events. The cache calculates the positions once on a grid, and interpolating
+/// them is many times faster. With a step of a day the interpolated positions of the planets are
+/// within a few arcseconds of the calculated ones, and with a step of a few hours so are those of
+/// the Moon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedEphemeris {
+    body: Body,
+    jd_start: f64,
+    jd_end: f64,
+    step: f64,
+    right_ascensions: Vec<f64>,
+    declinations: Vec<f64>,
+}
+
+impl CachedEphemeris {
+    /// Tabulate the positions of a body between two times every `step`.
+    ///
+    /// The grid starts at `start` and extends at least to `end`, with at least three positions.
+    pub fn new(body: Body,
+               start: &AstroTime,
+               end: &AstroTime,
+               step: AstroDuration)
+               -> AstroResult<CachedEphemeris> {
+        let jd_start = start.as_utc()?.julian_day_number();
+        let jd_end = end.as_utc()?.julian_day_number();
+        let days = step.days();
+        if jd_end < jd_start {
+            return Err(AstroAlgorithmsError::InvalidArgument("The end of the span for the \
+                                                              ephemeris must not be before the \
+                                                              start"
+                .to_owned()));
+        } else if days <= 0.0 {
+            return Err(AstroAlgorithmsError::InvalidArgument("The step of the ephemeris must be \
+                                                              positive"
+                .to_owned()));
+        }
+
+        let count = usize::max(((jd_end - jd_start) / days).ceil() as usize, 2) + 1;
+        let mut right_ascensions = Vec::with_capacity(count);
+        let mut declinations = Vec::with_capacity(count);
+        for i in 0..count {
+            let time = ut(jd_start + i as f64 * days)?;
+            let eq = apparent_equatorial(body, &time)?;
+
+            // Keep the right ascension continuous across 0h so it can be interpolated.
+            let mut ra = eq.right_ascension().radians();
+            if let Some(&previous) = right_ascensions.last() {
+                ra = previous + map_to_branch(ra - previous, -PI, PI);
+            }
+
+            right_ascensions.push(ra);
+            declinations.push(eq.declination().radians());
+        }
+
+        Ok(CachedEphemeris {
+            body,
+            jd_start,
+            jd_end,
+            step: days,
+            right_ascensions,
+            declinations,
+        })
+    }
+
+    /// The body.
+    pub fn body(&self) -> Body {
+        self.body
+    }
+
+    /// The start of the span in Universal Time.
+    pub fn start(&self) -> AstroResult<AstroTime> {
+        ut(self.jd_start)
+    }
+
+    /// The end of the span in Universal Time.
+    pub fn end(&self) -> AstroResult<AstroTime> {
+        ut(self.jd_end)
+    }
+
+    /// Interpolate the apparent geocentric equatorial coordinates of the body, referred to the
+    /// equator and equinox of the date.
+    ///
+    /// Returns a range error for times outside the span of the cache.
+    pub fn apparent_equatorial(&self, time: &AstroTime) -> AstroResult<EquatorialCoords> {
+        let jd = time.as_utc()?.julian_day_number();
+        if jd < self.jd_start {
+            return Err(AstroAlgorithmsError::RangeError(DateRangeError::DateUnderflow(jd,
+                                                                                self.jd_start)));
+        } else if jd > self.jd_end {
+            return Err(AstroAlgorithmsError::RangeError(DateRangeError::DateOverflow(jd,
+                                                                               self.jd_end)));
+        }
+
+        // Center the three tabular values on the nearest one, so the interpolating factor is
+        // small.
+        let x = (jd - self.jd_start) / self.step;
+        let last = self.right_ascensions.len() - 2;
+        let i = usize::min(usize::max(x.round() as usize, 1), last);
+        let n = x - i as f64;
+
+        let ra = interpolate(&self.right_ascensions[i - 1..i + 2], n);
+        let dec = interpolate(&self.declinations[i - 1..i + 2], n);
+
+        EquatorialCoords::new(RadianAngle::new(map_to_branch(ra, 0.0, 2.0 * PI))?,
+                              RadianAngle::new(dec)?,
+                              *time)
+    }
+}
+
+// Interpolate three tabular values with the interpolating factor n, equation 3.3.
+fn interpolate(y: &[f64], n: f64) -> f64 {
+    let a = y[1] - y[0];
+    let b = y[2] - y[1];
+    let c = b - a;
+
+    y[1] + n / 2.0 * (a + b + n * c)
+}
+
+fn ut(jd: f64) -> AstroResult<AstroTime> {
+    Builder::from_julian_date(jd).build()
+}
+
+#[cfg(test)]
+mod ephemeris_tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        // Example 3.a, Astronomical Algorithms 2nd ed., the distance of Mars.
+        let y = [0.884_226, 0.877_366, 0.870_531];
+        assert!((interpolate(&y, 4.352_64 / 24.0) - 0.876_125).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_cached_ephemeris() {
+        let start = Builder::from_gregorian_utc(2018, 3, 1, 0, 0, 0).build().unwrap();
+        let end = Builder::from_gregorian_utc(2018, 5, 1, 0, 0, 0).build().unwrap();
+
+        let cases = [(Body::Mercury, 1.0, 5.0), (Body::Mars, 1.0, 0.1), (Body::Moon, 0.125, 0.5)];
+        for &(body, step, tolerance) in &cases {
+            let step = AstroDuration::from_days(step).unwrap();
+            let cache = CachedEphemeris::new(body, &start, &end, step).unwrap();
+            assert_eq!(cache.body(), body);
+
+            for i in 0..100 {
+                let time = ut(start.julian_day_number() + 0.61 * i as f64).unwrap();
+                let cached = cache.apparent_equatorial(&time).unwrap();
+                let direct = apparent_equatorial(body, &time).unwrap();
+                let separation = angular_separation(cached, direct).radians().to_degrees();
+                assert!(separation * 3600.0 < tolerance);
+            }
+        }
+
+        let cache = CachedEphemeris::new(Body::Sun,
+                                         &start,
+                                         &start,
+                                         AstroDuration::from_days(1.0).unwrap())
+            .unwrap();
+        assert!(cache.apparent_equatorial(&start).is_ok());
+        assert!(cache.apparent_equatorial(&end).is_err());
+        let step = AstroDuration::from_days(1.0).unwrap();
+        assert!(CachedEphemeris::new(Body::Sun, &end, &start, step).is_err());
+    }
+}
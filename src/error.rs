@@ -31,6 +31,9 @@ pub enum AstroAlgorithmsError {
     /// ranges for angles, the string should provide more context.
     InvalidAngle(String),
 
+    /// Invalid argument supplied to a function, the string should provide more context.
+    InvalidArgument(String),
+
     /// Aborted due to encountering a NaN (Not a Number) with floating point
     /// numbers.
     EncounteredNaN,
@@ -0,0 +1,192 @@
+//!
+//! Module for the timetable of phenomena of the Galilean satellites.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use super::super::astro_time::{AstroTime, Builder};
+use super::super::error::*;
+use super::satellites::*;
+
+// Step for searching for events in days, shorter than any of the phenomena.
+const SEARCH_STEP: f64 = 5.0 / 1440.0;
+
+// The start and end of each phenomenon, in the order of the flags returned by phenomena().
+const PHENOMENA: [[SatelliteEventKind; 2]; 4] =
+    [[SatelliteEventKind::EclipseDisappearance, SatelliteEventKind::EclipseReappearance],
+     [SatelliteEventKind::OccultationDisappearance, SatelliteEventKind::OccultationReappearance],
+     [SatelliteEventKind::TransitIngress, SatelliteEventKind::TransitEgress],
+     [SatelliteEventKind::ShadowIngress, SatelliteEventKind::ShadowEgress]];
+
+/// The kinds of phenomena of the Galilean satellites listed in almanacs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SatelliteEventKind {
+    /// The satellite disappears into the shadow of Jupiter.
+    EclipseDisappearance,
+    /// The satellite reappears from the shadow of Jupiter.
+    EclipseReappearance,
+    /// The satellite disappears behind the disk of Jupiter.
+    OccultationDisappearance,
+    /// The satellite reappears from behind the disk of Jupiter.
+    OccultationReappearance,
+    /// The satellite moves onto the disk of Jupiter.
+    TransitIngress,
+    /// The satellite moves off of the disk of Jupiter.
+    TransitEgress,
+    /// The shadow of the satellite moves onto the disk of Jupiter.
+    ShadowIngress,
+    /// The shadow of the satellite moves off of the disk of Jupiter.
+    ShadowEgress,
+}
+
+/// A phenomenon of one of the Galilean satellites.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SatelliteEvent {
+    satellite: GalileanMoon,
+    kind: SatelliteEventKind,
+    time: AstroTime,
+}
+
+impl SatelliteEvent {
+    /// The satellite.
+    pub fn satellite(&self) -> GalileanMoon {
+        self.satellite
+    }
+
+    /// What happened.
+    pub fn kind(&self) -> SatelliteEventKind {
+        self.kind
+    }
+
+    /// The time the event is seen from the Earth, in Universal Time.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+}
+
+/// Generate the timetable of eclipses, occultations, transits and shadow transits of the Galilean
+/// satellites between two times, sorted by time.
+///
+/// The times are when the events are seen from the center of the Earth, and the phenomena are
+/// included whether or not Jupiter is observable at the time. An event where a satellite is
+/// already hidden, for instance an eclipse disappearance while it is behind the disk, is still
+/// listed.
+pub fn satellite_events(start: &AstroTime, end: &AstroTime) -> AstroResult<Vec<SatelliteEvent>> {
+    let jde_start = start.julian_ephemeris_day()?;
+    let jde_end = end.julian_ephemeris_day()?;
+    if jde_end < jde_start {
+        return Err(AstroAlgorithmsError::InvalidArgument("The end of the range for satellite \
+                                                          events must not be before the start"
+            .to_owned()));
+    }
+
+    let mut events = vec![];
+    let mut jde = jde_start;
+    let mut states = phenomena(jde)?;
+    while jde < jde_end {
+        let jde_next = f64::min(jde + SEARCH_STEP, jde_end);
+        let next_states = phenomena(jde_next)?;
+
+        for (i, &satellite) in GalileanMoon::ALL.iter().enumerate() {
+            for p in 0..4 {
+                let (before, after) = (states[i][p], next_states[i][p]);
+                if before == after {
+                    continue;
+                }
+
+                // Bisect down to about a second.
+                let (mut lo, mut hi) = (jde, jde_next);
+                while hi - lo > 1.0e-5 {
+                    let mid = 0.5 * (lo + hi);
+                    if phenomena(mid)?[i][p] == before {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+
+                events.push(SatelliteEvent {
+                    satellite,
+                    kind: PHENOMENA[p][if after { 0 } else { 1 }],
+                    time: Builder::from_julian_date(0.5 * (lo + hi))
+                        .dynamical_time()
+                        .build()?
+                        .as_utc()?,
+                });
+            }
+        }
+
+        jde = jde_next;
+        states = next_states;
+    }
+
+    events.sort_by(|a, b| {
+        a.time.julian_day_number().partial_cmp(&b.time.julian_day_number()).unwrap()
+    });
+
+    Ok(events)
+}
+
+// Whether each satellite is eclipsed, occulted, transiting, or casting a shadow on the disk.
+fn phenomena(jde: f64) -> AstroResult<[[bool; 4]; 4]> {
+    let time = Builder::from_julian_date(jde).dynamical_time().build()?;
+    let terms = SatelliteTerms::new(&time)?;
+
+    let mut states = [[false; 4]; 4];
+    let views = terms.from_earth.iter().zip(&terms.from_sun);
+    for (state, (earth, sun)) in states.iter_mut().zip(views) {
+        *state = [sun.z() < 0.0 && sun.on_disk(),
+                  earth.z() < 0.0 && earth.on_disk(),
+                  earth.z() > 0.0 && earth.on_disk(),
+                  sun.z() > 0.0 && sun.on_disk()];
+    }
+
+    Ok(states)
+}
+
+#[cfg(test)]
+mod events_tests {
+    use super::*;
+
+    #[test]
+    fn test_satellite_events() {
+        let start = Builder::from_gregorian_utc(2017, 4, 1, 0, 0, 0).build().unwrap();
+        let end = Builder::from_gregorian_utc(2017, 4, 11, 0, 0, 0).build().unwrap();
+        let events = satellite_events(&start, &end).unwrap();
+
+        for pair in events.windows(2) {
+            assert!(pair[0].time() <= pair[1].time());
+        }
+
+        // Io goes around Jupiter every 1.77 days, so it transits 5 or 6 times in 10 days and
+        // every ingress is followed by an egress a couple hours later.
+        let io: Vec<&SatelliteEvent> = events.iter()
+            .filter(|e| e.satellite() == GalileanMoon::Io)
+            .filter(|e| e.kind() == SatelliteEventKind::TransitIngress ||
+                        e.kind() == SatelliteEventKind::TransitEgress)
+            .collect();
+        assert!((10..=12).contains(&io.len()));
+        for pair in io.windows(2) {
+            let hours = (pair[1].time().julian_day_number() -
+                         pair[0].time().julian_day_number()) * 24.0;
+            if pair[0].kind() == SatelliteEventKind::TransitIngress {
+                assert_eq!(pair[1].kind(), SatelliteEventKind::TransitEgress);
+                assert!(hours > 1.5 && hours < 2.5);
+            } else {
+                assert_eq!(pair[1].kind(), SatelliteEventKind::TransitIngress);
+            }
+        }
+
+        // Near opposition Io's shadow follows it across the disk closely.
+        let shadows = events.iter()
+            .filter(|e| e.satellite() == GalileanMoon::Io &&
+                        e.kind() == SatelliteEventKind::ShadowIngress)
+            .count();
+        assert!((5..=6).contains(&shadows));
+
+        assert!(satellite_events(&end, &start).is_err());
+    }
+}
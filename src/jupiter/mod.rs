@@ -0,0 +1,18 @@
+//!
+//! Module for Jupiter's four large Galilean satellites.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The positions of the satellites are calculated with the low accuracy method of chapter 44 of
+//! Astronomical Algorithms, 2nd Edition by Jean Meeus, which is good enough to predict the times
+//! of their eclipses, occultations, transits and shadow transits to within a few minutes.
+//!
+mod events;
+mod satellites;
+
+pub use self::events::*;
+pub use self::satellites::*;
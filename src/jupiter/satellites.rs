@@ -0,0 +1,204 @@
+//!
+//! Module for the positions of the Galilean satellites relative to Jupiter.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+use super::super::astro_time::AstroTime;
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::math::*;
+
+/// The Galilean satellites of Jupiter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GalileanMoon {
+    /// Satellite I
+    Io,
+    /// Satellite II
+    Europa,
+    /// Satellite III
+    Ganymede,
+    /// Satellite IV
+    Callisto,
+}
+
+impl GalileanMoon {
+    /// All four satellites in order of their distance from Jupiter.
+    pub const ALL: [GalileanMoon; 4] = [GalileanMoon::Io,
+                                        GalileanMoon::Europa,
+                                        GalileanMoon::Ganymede,
+                                        GalileanMoon::Callisto];
+
+    fn index(self) -> usize {
+        match self {
+            GalileanMoon::Io => 0,
+            GalileanMoon::Europa => 1,
+            GalileanMoon::Ganymede => 2,
+            GalileanMoon::Callisto => 3,
+        }
+    }
+}
+
+/// Rectangular coordinates of a satellite relative to Jupiter as seen from some direction, in
+/// units of Jupiter's equatorial radius.
+///
+/// The x axis points west along Jupiter's equator, the y axis points to Jupiter's north pole, and
+/// z is positive when the satellite is closer to the viewer than Jupiter is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SatelliteCoords {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl SatelliteCoords {
+    /// The apparent distance west of the center of Jupiter.
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    /// The apparent distance north of the center of Jupiter.
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    /// The distance towards the viewer from the plane through the center of Jupiter.
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+
+    /// Whether the satellite is within the outline of Jupiter's disk, allowing for the polar
+    /// flattening of the planet.
+    pub fn on_disk(&self) -> bool {
+        let y = self.y * 1.071_374;
+        self.x * self.x + y * y < 1.0
+    }
+}
+
+/// Calculate the apparent positions of a satellite as seen from the Earth.
+pub fn satellite_position(moon: GalileanMoon, time: &AstroTime) -> AstroResult<SatelliteCoords> {
+    Ok(SatelliteTerms::new(time)?.from_earth[moon.index()])
+}
+
+/// Calculate the position of a satellite as seen from the Sun.
+///
+/// The position is for the moment sunlight left the satellite towards the Earth, so the shadow
+/// of Jupiter falls on the satellite when it is behind Jupiter and off the disk. Likewise the
+/// satellite's shadow is on Jupiter when it is in front of Jupiter and on the disk.
+pub fn satellite_position_from_sun(moon: GalileanMoon,
+                                   time: &AstroTime)
+                                   -> AstroResult<SatelliteCoords> {
+    Ok(SatelliteTerms::new(time)?.from_sun[moon.index()])
+}
+
+// Positions of all the satellites as seen from the Earth and from the Sun.
+pub(crate) struct SatelliteTerms {
+    pub(crate) from_earth: [SatelliteCoords; 4],
+    pub(crate) from_sun: [SatelliteCoords; 4],
+}
+
+impl SatelliteTerms {
+    pub(crate) fn new(time: &AstroTime) -> AstroResult<SatelliteTerms> {
+        let d = time.julian_ephemeris_day()? - 2_451_545.0;
+
+        // Positions of the Earth and Jupiter.
+        let v = (172.74 + 0.001_115_88 * d).to_radians();
+        let m = (357.529 + 0.985_600_3 * d).to_radians();
+        let n = (20.020 + 0.083_085_3 * d + 0.329 * sin(v)).to_radians();
+        let j = 66.115 + 0.902_517_9 * d - 0.329 * sin(v);
+        let a = 1.915 * sin(m) + 0.020 * sin(2.0 * m);
+        let b = 5.555 * sin(n) + 0.168 * sin(2.0 * n);
+        let k = (j + a - b).to_radians();
+
+        let big_r = 1.000_14 - 0.016_71 * cos(m) - 0.000_14 * cos(2.0 * m);
+        let r = 5.208_72 - 0.252_08 * cos(n) - 0.006_11 * cos(2.0 * n);
+        let delta = f64::sqrt(r * r + big_r * big_r - 2.0 * r * big_r * cos(k));
+        let psi = asin(big_r / delta * sin(k)).to_degrees();
+
+        // Jupiter's heliocentric longitude and the jovicentric latitudes of the Sun and Earth.
+        let lambda = 34.35 + 0.083_091 * d + 0.329 * sin(v) + b;
+        let ds = 3.12 * sin((lambda + 42.8).to_radians());
+        let de = ds - 2.22 * sin(psi.to_radians()) * cos((lambda + 22.0).to_radians()) -
+                 1.30 * (r - delta) / delta * sin((lambda - 100.5).to_radians());
+
+        // Allow for the light time from Jupiter.
+        let d = d - light_travel_time(delta)?.days();
+        let mut u = [163.806_9 + 203.405_864_6 * d - b,
+                     358.414_0 + 101.291_633_5 * d - b,
+                     5.717_6 + 50.234_518_0 * d - b,
+                     224.809_2 + 21.487_980_0 * d - b];
+        let g = (331.18 + 50.310_482 * d).to_radians();
+        let h = (87.45 + 21.569_231 * d).to_radians();
+
+        // Perturbations.
+        let (u_1, u_2, u_3) = (u[0].to_radians(), u[1].to_radians(), u[2].to_radians());
+        u[0] += 0.473 * sin(2.0 * (u_1 - u_2));
+        u[1] += 1.065 * sin(2.0 * (u_2 - u_3));
+        u[2] += 0.165 * sin(g);
+        u[3] += 0.843 * sin(h);
+
+        let radii = [5.905_7 - 0.024_4 * cos(2.0 * (u_1 - u_2)),
+                     9.396_6 - 0.088_2 * cos(2.0 * (u_2 - u_3)),
+                     14.988_3 - 0.021_6 * cos(g),
+                     26.362_7 - 0.193_9 * cos(h)];
+
+        // The angle u is measured from the inferior conjunction as seen from the Sun, the phase
+        // angle rotates it to the view from the Earth.
+        let coords = |u: f64, r: f64, lat: f64| {
+            let (sin_u, cos_u) = sin_cos(u.to_radians());
+            SatelliteCoords {
+                x: r * sin_u,
+                y: -r * cos_u * sin(lat.to_radians()),
+                z: r * cos_u,
+            }
+        };
+
+        let mut from_earth = [SatelliteCoords { x: 0.0, y: 0.0, z: 0.0 }; 4];
+        let mut from_sun = from_earth;
+        for i in 0..4 {
+            from_earth[i] = coords(u[i] + psi, radii[i], de);
+            from_sun[i] = coords(u[i], radii[i], ds);
+        }
+
+        Ok(SatelliteTerms {
+            from_earth,
+            from_sun,
+        })
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod satellites_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    #[test]
+    fn test_satellite_position() {
+        // Example 44.a, Astronomical Algorithms 2nd ed., 1992 December 16 at 0h UT.
+        let time = Builder::from_gregorian_utc(1992, 12, 16, 0, 0, 0).build().unwrap();
+
+        let expected = [(-3.44, 0.21), (7.44, 0.25), (1.24, 0.65), (7.08, 1.10)];
+        for (&moon, &(x, y)) in GalileanMoon::ALL.iter().zip(&expected) {
+            let pos = satellite_position(moon, &time).unwrap();
+            assert!(approx_eq(pos.x(), x, 0.005));
+            assert!(approx_eq(pos.y(), y, 0.005));
+        }
+    }
+
+    #[test]
+    fn test_on_disk() {
+        let on = SatelliteCoords { x: 0.5, y: 0.5, z: 1.0 };
+        let off = SatelliteCoords { x: 0.7, y: 0.7, z: 1.0 };
+        assert!(on.on_disk());
+        assert!(!off.on_disk());
+    }
+}
@@ -0,0 +1,93 @@
+//!
+//! Module for fitting linear models by least squares.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+
+/// Fit the coefficients of a linear model to observations by least squares.
+///
+/// Each row holds the values of the model's terms for one observation, and `values` the observed
+/// values. The normal equations are solved by Gaussian elimination with partial pivoting, which is
+/// plenty for the small, well conditioned models used in this crate. Returns `None` if there are
+/// no rows or the terms can't be separated by the observations.
+pub(crate) fn least_squares(rows: &[Vec<f64>], values: &[f64]) -> Option<Vec<f64>> {
+    let n = rows.first()?.len();
+
+    // Normal equations, augmented with the right hand side.
+    let mut m = vec![vec![0.0; n + 1]; n];
+    for (row, &val) in rows.iter().zip(values) {
+        for i in 0..n {
+            for j in 0..n {
+                m[i][j] += row[i] * row[j];
+            }
+            m[i][n] += row[i] * val;
+        }
+    }
+
+    let scale = (0..n).map(|i| m[i][i]).fold(0.0, f64::max);
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&a, &b| m[a][col].abs().partial_cmp(&m[b][col].abs()).unwrap())
+            .unwrap();
+        if m[pivot][col].abs() <= 1.0e-12 * scale {
+            return None;
+        }
+
+        m.swap(col, pivot);
+        for row in (col + 1)..n {
+            let factor = m[row][col] / m[col][col];
+            let pivot_row = m[col].clone();
+            for (k, val) in m[row].iter_mut().enumerate().skip(col) {
+                *val -= factor * pivot_row[k];
+            }
+        }
+    }
+
+    let mut c = vec![0.0; n];
+    for row in (0..n).rev() {
+        let known: f64 = ((row + 1)..n).map(|k| m[row][k] * c[k]).sum();
+        c[row] = (m[row][n] - known) / m[row][row];
+    }
+
+    Some(c)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod least_squares_tests {
+    use super::*;
+
+    #[test]
+    fn test_least_squares() {
+        // A plane through more points than needed.
+        let mut rows = vec![];
+        let mut values = vec![];
+        for &(x, y) in &[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0), (2.0, 1.0), (2.0, 0.0)] {
+            rows.push(vec![1.0, x, y]);
+            values.push(3.0 + 2.0 * x - y);
+        }
+        let c = least_squares(&rows, &values).unwrap();
+        assert!(approx_eq(c[0], 3.0, 1.0e-12));
+        assert!(approx_eq(c[1], 2.0, 1.0e-12));
+        assert!(approx_eq(c[2], -1.0, 1.0e-12));
+
+        // The mean of noisy values.
+        let rows = vec![vec![1.0]; 4];
+        let c = least_squares(&rows, &[1.0, 2.0, 3.0, 6.0]).unwrap();
+        assert!(approx_eq(c[0], 3.0, 1.0e-12));
+
+        // The terms can't be separated.
+        let rows = vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]];
+        assert!(least_squares(&rows, &[1.0, 2.0, 3.0]).is_none());
+        assert!(least_squares(&[], &[]).is_none());
+    }
+}
@@ -16,8 +16,46 @@
 
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "trace")]
+#[macro_use]
+extern crate tracing;
+#[cfg(feature = "deterministic")]
+extern crate libm;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+
+// Emit a debug level tracing event with the `trace` feature, for following the corrections
+// applied in a calculation. Without the feature it expands to nothing.
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "trace")]
+        debug!(target: "astro_calc", $($arg)*);
+    };
+}
 
 // Public export modules
 pub mod error;
 pub mod astro_time;
+pub mod bodies;
+pub mod conjunctions;
 pub mod coords;
+pub mod earth;
+pub mod eclipse;
+pub mod ephemeris;
+pub mod jupiter;
+pub mod moon;
+pub mod mount;
+pub mod occultation;
+pub mod orbit;
+pub mod planets;
+pub mod planner;
+pub mod rise_set;
+pub mod stars;
+pub mod sun;
+pub mod track;
+
+// Internal modules
+mod least_squares;
+mod math;
@@ -0,0 +1,108 @@
+//!
+//! Module for the transcendental functions used in the calculations.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The methods of `f64` call the platform's math library, which may round the last bit
+//! differently from one platform to another. With the `deterministic` feature these are computed
+//! in software by the `libm` crate instead, so the results are the same bit for bit everywhere.
+//! The square root, rounding, and arithmetic are exactly rounded by IEEE 754, so they are left to
+//! `f64`.
+//!
+
+// Define functions of one or two arguments that forward to `f64` or to `libm`.
+macro_rules! forward {
+    ($($name:ident => $libm:ident;)*) => {
+        $(
+            #[inline]
+            pub(crate) fn $name(x: f64) -> f64 {
+                #[cfg(feature = "deterministic")]
+                {
+                    ::libm::$libm(x)
+                }
+                #[cfg(not(feature = "deterministic"))]
+                {
+                    x.$name()
+                }
+            }
+        )*
+    };
+    ($($name:ident => $libm:ident(2);)*) => {
+        $(
+            #[inline]
+            pub(crate) fn $name(x: f64, y: f64) -> f64 {
+                #[cfg(feature = "deterministic")]
+                {
+                    ::libm::$libm(x, y)
+                }
+                #[cfg(not(feature = "deterministic"))]
+                {
+                    x.$name(y)
+                }
+            }
+        )*
+    };
+}
+
+forward! {
+    sin => sin;
+    cos => cos;
+    tan => tan;
+    asin => asin;
+    acos => acos;
+    atan => atan;
+    sinh => sinh;
+    cosh => cosh;
+    tanh => tanh;
+    exp => exp;
+    ln => log;
+    log10 => log10;
+    cbrt => cbrt;
+}
+
+forward! {
+    atan2 => atan2(2);
+    powf => pow(2);
+    hypot => hypot(2);
+}
+
+/// The sine and cosine of an angle in radians.
+#[inline]
+pub(crate) fn sin_cos(x: f64) -> (f64, f64) {
+    #[cfg(feature = "deterministic")]
+    {
+        ::libm::sincos(x)
+    }
+    #[cfg(not(feature = "deterministic"))]
+    {
+        x.sin_cos()
+    }
+}
+
+#[cfg(test)]
+mod math_tests {
+    use super::*;
+
+    #[test]
+    fn test_forward() {
+        // Exact values in any implementation.
+        assert_eq!(sin(0.0), 0.0);
+        assert_eq!(cos(0.0), 1.0);
+        assert_eq!(atan2(0.0, 1.0), 0.0);
+        assert_eq!(exp(0.0), 1.0);
+        assert_eq!(ln(1.0), 0.0);
+        assert_eq!(log10(1_000.0), 3.0);
+        assert_eq!(powf(2.0, 10.0), 1_024.0);
+        assert_eq!(hypot(3.0, 4.0), 5.0);
+        assert_eq!(cbrt(27.0), 3.0);
+        assert_eq!(sin_cos(0.0), (0.0, 1.0));
+
+        let (s, c) = sin_cos(1.0);
+        assert!((s - sin(1.0)).abs() < 1.0e-15 && (c - cos(1.0)).abs() < 1.0e-15);
+        assert!((atan2(1.0, 1.0) - atan(1.0)).abs() < 1.0e-15);
+    }
+}
@@ -0,0 +1,184 @@
+//!
+//! Module for predicting the visibility of the young crescent Moon.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Follows the q-test of B. D. Yallop, "A Method for Predicting the First Sighting of the New
+//! Crescent Moon", NAO Technical Note No. 69, 1997. The test compares the difference in altitude
+//! of the Moon and Sun with the width of the crescent at the best time for seeing it.
+//!
+use super::super::astro_time::{AstroDuration, AstroTime};
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::math::*;
+use super::super::rise_set::{moon_rise_transit_set, sun_rise_transit_set};
+use super::super::sun;
+use super::{apparent_equatorial, distance};
+
+/// The visibility classes of the q-test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrescentVisibility {
+    /// Class A, the crescent is easily visible to the naked eye.
+    EasilyVisible,
+    /// Class B, visible to the naked eye under perfect conditions.
+    PerfectConditions,
+    /// Class C, optical aid may be needed to find the crescent before seeing it with the naked
+    /// eye.
+    OpticalAidHelpful,
+    /// Class D, only visible with binoculars or a telescope.
+    OpticalAidNeeded,
+    /// Class E, not visible with a telescope.
+    NotVisibleWithTelescope,
+    /// Class F, not visible, the Moon is below the Danjon limit.
+    NotVisible,
+}
+
+/// The circumstances of the first sighting of the crescent on an evening.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrescentSighting {
+    sunset: AstroTime,
+    moonset: AstroTime,
+    best_time: AstroTime,
+    q: f64,
+    visibility: CrescentVisibility,
+}
+
+impl CrescentSighting {
+    /// The time of sunset in Universal Time.
+    pub fn sunset(&self) -> AstroTime {
+        self.sunset
+    }
+
+    /// The time of moonset in Universal Time.
+    pub fn moonset(&self) -> AstroTime {
+        self.moonset
+    }
+
+    /// The best time for seeing the crescent in Universal Time, four ninths of the lag after
+    /// sunset.
+    pub fn best_time(&self) -> AstroTime {
+        self.best_time
+    }
+
+    /// The value of the test parameter q.
+    pub fn q(&self) -> f64 {
+        self.q
+    }
+
+    /// The visibility class.
+    pub fn visibility(&self) -> CrescentVisibility {
+        self.visibility
+    }
+}
+
+/// Predict the visibility of the crescent Moon after sunset for an observer.
+///
+/// The sunset is the one on the Universal Time day containing `date`. Returns `None` if the Sun
+/// doesn't set, or if the Moon sets before the Sun so there is no crescent to see.
+pub fn crescent_visibility(date: &AstroTime,
+                           geo: GeoCoords)
+                           -> AstroResult<Option<CrescentSighting>> {
+    let sunset = match sun_rise_transit_set(date, geo, None)?.set() {
+        Some(sunset) => sunset,
+        None => return Ok(None),
+    };
+
+    // The next moonset may be on the following day.
+    let moonset = match moon_rise_transit_set(&sunset, geo, None)?.set() {
+        Some(moonset) if moonset > sunset => Some(moonset),
+        _ => {
+            let next_day = sunset.add_duration(AstroDuration::from_days(1.0)?)?;
+            moon_rise_transit_set(&next_day, geo, None)?.set()
+        }
+    };
+    let moonset = match moonset {
+        Some(moonset) if moonset > sunset => moonset,
+        _ => return Ok(None),
+    };
+
+    // The best time is 4/9 of the lag after sunset.
+    let lag = moonset.duration_since(&sunset)?;
+    let best_time = sunset.add_duration(AstroDuration::from_days(lag.days() * 4.0 / 9.0)?)?;
+
+    // The arc of light and arc of vision are geocentric, without refraction.
+    let sun_eq = sun::apparent_equatorial(&best_time)?;
+    let moon_eq = apparent_equatorial(&best_time)?;
+    let sun_hz = trans_equatorial_to_horizontal(sun_eq, geo, &best_time)?;
+    let moon_hz = trans_equatorial_to_horizontal(moon_eq, geo, &best_time)?;
+    let arcl = angular_separation(sun_eq, moon_eq).radians();
+    let arcv = (moon_hz.altitude().radians() - sun_hz.altitude().radians()).to_degrees();
+
+    // The topocentric width of the crescent in minutes of arc.
+    let parallax = horizontal_parallax(distance(&best_time)?)?.radians();
+    let semi_diameter = 0.272_45 * parallax.to_degrees() * 60.0;
+    let topocentric = semi_diameter * (1.0 + sin(moon_hz.altitude().radians()) * sin(parallax));
+    let w = topocentric * (1.0 - cos(arcl));
+
+    let q = (arcv - (11.837_1 - w * (6.322_6 - w * (0.731_9 - w * 0.101_8)))) / 10.0;
+    let visibility = if q > 0.216 {
+        CrescentVisibility::EasilyVisible
+    } else if q > -0.014 {
+        CrescentVisibility::PerfectConditions
+    } else if q > -0.160 {
+        CrescentVisibility::OpticalAidHelpful
+    } else if q > -0.232 {
+        CrescentVisibility::OpticalAidNeeded
+    } else if q > -0.293 {
+        CrescentVisibility::NotVisibleWithTelescope
+    } else {
+        CrescentVisibility::NotVisible
+    };
+
+    Ok(Some(CrescentSighting {
+        sunset,
+        moonset,
+        best_time,
+        q,
+        visibility,
+    }))
+}
+
+#[cfg(test)]
+mod crescent_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    fn mecca() -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(21.42f64.to_radians()).unwrap(),
+                       RadianAngle::new(-39.83f64.to_radians()).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_crescent_visibility() {
+        // The new moon was at 17:23 UT on 2023 March 21, after sunset at Mecca. The next evening
+        // the crescent was a day old and easily seen.
+        let date = Builder::from_gregorian_utc(2023, 3, 22, 0, 0, 0).build().unwrap();
+        let sighting = crescent_visibility(&date, mecca()).unwrap().unwrap();
+        assert_eq!(sighting.visibility(), CrescentVisibility::EasilyVisible);
+        assert!(sighting.q() > 0.216);
+        assert!(sighting.sunset() < sighting.best_time());
+        assert!(sighting.best_time() < sighting.moonset());
+
+        let lag = sighting.moonset().duration_since(&sighting.sunset()).unwrap();
+        assert!(lag.minutes() > 40.0 && lag.minutes() < 80.0);
+
+        let date = Builder::from_gregorian_utc(2023, 3, 21, 0, 0, 0).build().unwrap();
+        if let Some(sighting) = crescent_visibility(&date, mecca()).unwrap() {
+            assert_eq!(sighting.visibility(), CrescentVisibility::NotVisible);
+        }
+
+        // The Sun doesn't set at the north pole in June.
+        let pole = GeoCoords::new(RadianAngle::new(1.57).unwrap(),
+                                  RadianAngle::new(0.0).unwrap(),
+                                  0.0)
+            .unwrap();
+        let date = Builder::from_gregorian_utc(2023, 6, 19, 0, 0, 0).build().unwrap();
+        assert!(crescent_visibility(&date, pole).unwrap().is_none());
+    }
+}
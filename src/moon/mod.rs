@@ -0,0 +1,482 @@
+//!
+//! Module for the position and orientation of the Moon.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The position of the Moon is calculated with the method of chapter 47 of Astronomical
+//! Algorithms, 2nd Edition by Jean Meeus, which is good to about 10" in longitude and 4" in
+//! latitude. A shorter series is available for fast approximate positions.
+//!
+mod crescent;
+mod phases;
+mod tide;
+
+pub use self::crescent::*;
+pub use self::phases::*;
+pub use self::tide::*;
+
+use super::astro_time::AstroTime;
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+use super::rise_set::moon_rise_transit_set;
+use super::sun;
+
+/// The accuracy of the lunar theory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accuracy {
+    /// The full series of chapter 47, good to about 10" in longitude and 4" in latitude.
+    Full,
+    /// The largest 20 terms in longitude and distance and 10 terms in latitude, with only the
+    /// principal terms of the nutation. Good to about 0.05 degrees and 100 km, which is enough for
+    /// the phase or the times of moonrise and moonset, and several times faster.
+    Low,
+}
+
+/// Calculate the distance between the centers of the Earth and Moon in kilometers.
+pub fn distance(time: &AstroTime) -> AstroResult<f64> {
+    distance_with(time, Accuracy::Full)
+}
+
+/// Calculate the distance between the centers of the Earth and Moon in kilometers with the chosen
+/// accuracy.
+pub fn distance_with(time: &AstroTime, accuracy: Accuracy) -> AstroResult<f64> {
+    Ok(LunarTerms::new(time, accuracy)?.distance)
+}
+
+/// Calculate the apparent geocentric ecliptic coordinates of the Moon, referred to the ecliptic
+/// and equinox of the date.
+pub fn apparent_ecliptic(time: &AstroTime) -> AstroResult<EclipticCoords> {
+    apparent_ecliptic_with(time, Accuracy::Full)
+}
+
+/// Calculate the apparent geocentric ecliptic coordinates of the Moon with the chosen accuracy.
+pub fn apparent_ecliptic_with(time: &AstroTime,
+                              accuracy: Accuracy)
+                              -> AstroResult<EclipticCoords> {
+    let terms = LunarTerms::new(time, accuracy)?;
+    let delta_psi = match accuracy {
+        Accuracy::Full => nutation(time)?.0.radians(),
+        Accuracy::Low => {
+            (-0.004_78 * sin(terms.args.moon_ascending_node().radians())).to_radians()
+        }
+    };
+
+    Ok(EclipticCoords::new(RadianAngle::new(terms.longitude + delta_psi)?,
+                           RadianAngle::new(terms.latitude)?,
+                           terms.epoch)?
+        .with_frame(EclipticFrame::True))
+}
+
+/// Calculate the apparent geocentric equatorial coordinates of the Moon, referred to the equator
+/// and equinox of the date.
+pub fn apparent_equatorial(time: &AstroTime) -> AstroResult<EquatorialCoords> {
+    apparent_equatorial_with(time, Accuracy::Full)
+}
+
+/// Calculate the apparent geocentric equatorial coordinates of the Moon with the chosen accuracy.
+pub fn apparent_equatorial_with(time: &AstroTime,
+                                accuracy: Accuracy)
+                                -> AstroResult<EquatorialCoords> {
+    let ecl = apparent_ecliptic_with(time, accuracy)?;
+    let eps = match accuracy {
+        Accuracy::Full => true_obliquity(time)?,
+        Accuracy::Low => {
+            let omega = FundamentalArguments::for_time(time)?.moon_ascending_node().radians();
+            RadianAngle::new(mean_obliquity(time)?.radians() +
+                             (0.002_56 * cos(omega)).to_radians())?
+        }
+    };
+
+    trans_ecliptic_to_equatorial(ecl, eps)
+}
+
+/// Calculate the orientation of the disk of the Moon.
+///
+/// The axis position angle is from chapter 53, and the center latitude is the total (optical and
+/// physical) libration in latitude. The bright limb position angle is from chapter 48.
+pub fn disk_orientation(time: &AstroTime) -> AstroResult<DiskOrientation> {
+    let terms = LunarTerms::new(time, Accuracy::Full)?;
+    let (delta_psi, _) = nutation(time)?;
+    let delta_psi = delta_psi.radians();
+    let eps = true_obliquity(time)?.radians();
+    let eq = apparent_equatorial(time)?;
+
+    let i = 1.542_42f64.to_radians();
+    let m_prime = terms.args.moon_mean_anomaly().radians();
+    let f = terms.args.moon_argument_of_latitude().radians();
+    let d = terms.args.mean_elongation().radians();
+    let node = terms.args.moon_ascending_node().radians();
+    let (sin_beta, cos_beta) = sin_cos(terms.latitude);
+
+    // Optical librations
+    let w = terms.longitude - node;
+    let a = atan2(sin(w) * cos_beta * cos(i) - sin_beta * sin(i), cos(w) * cos_beta);
+    let b_prime = asin(-sin(w) * cos_beta * sin(i) - sin_beta * cos(i));
+
+    // Physical librations, the terms are in degrees.
+    let rho = -0.027_52 * cos(m_prime) - 0.022_45 * sin(f) +
+              0.006_84 * cos(m_prime - 2.0 * f) - 0.002_93 * cos(2.0 * f) -
+              0.000_85 * cos(2.0 * f - 2.0 * d) -
+              0.000_54 * cos(m_prime - 2.0 * d) - 0.000_20 * sin(m_prime + f) -
+              0.000_20 * cos(m_prime + 2.0 * f) - 0.000_20 * cos(m_prime - f) +
+              0.000_14 * cos(m_prime + 2.0 * f - 2.0 * d);
+    let sigma = -0.028_16 * sin(m_prime) + 0.022_44 * cos(f) -
+                0.006_82 * sin(m_prime - 2.0 * f) - 0.002_79 * sin(2.0 * f) -
+                0.000_83 * sin(2.0 * f - 2.0 * d) +
+                0.000_69 * sin(m_prime - 2.0 * d) + 0.000_40 * cos(m_prime + f) -
+                0.000_25 * sin(2.0 * m_prime) - 0.000_23 * sin(m_prime + 2.0 * f) +
+                0.000_20 * cos(m_prime - f) + 0.000_19 * sin(m_prime - f) +
+                0.000_13 * sin(m_prime + 2.0 * f - 2.0 * d) -
+                0.000_10 * cos(m_prime - 3.0 * f);
+    let (rho, sigma) = (rho.to_radians(), sigma.to_radians());
+
+    let b = b_prime + sigma * cos(a) - rho * sin(a);
+
+    // Position angle of the axis
+    let v = node + delta_psi + sigma / sin(i);
+    let x = sin(i + rho) * sin(v);
+    let y = sin(i + rho) * cos(v) * cos(eps) - cos(i + rho) * sin(eps);
+    let omega = atan2(x, y);
+    let sin_p = f64::sqrt(x * x + y * y) * cos(eq.right_ascension().radians() - omega) /
+                cos(b);
+
+    let chi = bright_limb_position_angle(eq, sun::apparent_equatorial(time)?)?;
+
+    DiskOrientation::new(RadianAngle::new(asin(sin_p.clamp(-1.0, 1.0)))?,
+                         RadianAngle::new(b)?,
+                         Some(chi))
+}
+
+/// Calculate the time the Moon crosses the meridian on the Universal Time day containing `date`,
+/// and the altitude of the Moon then.
+///
+/// The altitude is the topocentric altitude of the center of the Moon, corrected for the
+/// parallax, which lowers the Moon by up to a degree. Refraction isn't included.
+pub fn transit_altitude(date: &AstroTime,
+                        geo: GeoCoords)
+                        -> AstroResult<(AstroTime, RadianAngle)> {
+    let transit = moon_rise_transit_set(date, geo, None)?.transit();
+    let hz = trans_equatorial_to_horizontal(apparent_equatorial(&transit)?, geo, &transit)?;
+    let parallax = parallax_in_altitude(horizontal_parallax(distance(&transit)?)?, hz.altitude());
+
+    Ok((transit, RadianAngle::new(hz.altitude().radians() - parallax.radians())?))
+}
+
+/// Calculate the illuminated fraction of the disk of the Moon, chapter 48.
+pub fn illuminated_fraction(time: &AstroTime) -> AstroResult<f64> {
+    illuminated_fraction_with(time, Accuracy::Full)
+}
+
+/// Calculate the illuminated fraction of the disk of the Moon with the chosen accuracy for the
+/// position of the Moon.
+pub fn illuminated_fraction_with(time: &AstroTime, accuracy: Accuracy) -> AstroResult<f64> {
+    let moon = apparent_equatorial_with(time, accuracy)?;
+    let sun = sun::apparent_equatorial(time)?;
+    let psi = angular_separation(moon, sun).radians();
+
+    // Phase angle from the distances of the Sun and Moon, equation 48.3.
+    let big_r = sun::radius_vector(time)? * ASTRONOMICAL_UNIT_KM;
+    let delta = distance_with(time, accuracy)?;
+    let i = atan2(big_r * sin(psi), delta - big_r * cos(psi));
+
+    Ok((1.0 + cos(i)) / 2.0)
+}
+
+// Intermediate values of the lunar theory, angles in radians.
+struct LunarTerms {
+    epoch: AstroTime,
+    args: FundamentalArguments,
+    longitude: f64,
+    latitude: f64,
+    distance: f64,
+}
+
+impl LunarTerms {
+    fn new(time: &AstroTime, accuracy: Accuracy) -> AstroResult<LunarTerms> {
+        let epoch = time.as_dt()?;
+        let args = FundamentalArguments::for_time(&epoch)?;
+        let t = args.julian_centuries();
+
+        let l_prime = args.moon_mean_longitude().radians();
+        let d = args.mean_elongation().radians();
+        let m = args.sun_mean_anomaly().radians();
+        let m_prime = args.moon_mean_anomaly().radians();
+        let f = args.moon_argument_of_latitude().radians();
+
+        let a_1 = (119.75 + 131.849 * t).to_radians();
+        let a_2 = (53.09 + 479_264.290 * t).to_radians();
+        let a_3 = (313.45 + 481_266.484 * t).to_radians();
+
+        // Correction for the decreasing eccentricity of the Earth's orbit.
+        let e = 1.0 - t * (0.002_516 + t * 0.000_007_4);
+
+        let (longitude_terms, latitude_terms) = match accuracy {
+            Accuracy::Full => (LONGITUDE_DISTANCE_TERMS.len(), LATITUDE_TERMS.len()),
+            Accuracy::Low => (20, 10),
+        };
+
+        let mut sum_l = 0.0;
+        let mut sum_r = 0.0;
+        for &(cd, cm, cm_prime, cf, cl, cr) in
+            LONGITUDE_DISTANCE_TERMS.iter().take(longitude_terms) {
+            let arg = cd * d + cm * m + cm_prime * m_prime + cf * f;
+            let e_factor = e.powi(cm.abs() as i32);
+            sum_l += cl * e_factor * sin(arg);
+            sum_r += cr * e_factor * cos(arg);
+        }
+
+        let mut sum_b = 0.0;
+        for &(cd, cm, cm_prime, cf, cb) in LATITUDE_TERMS.iter().take(latitude_terms) {
+            let arg = cd * d + cm * m + cm_prime * m_prime + cf * f;
+            sum_b += cb * e.powi(cm.abs() as i32) * sin(arg);
+        }
+
+        // Additive terms due to Venus, Jupiter, and the flattening of the Earth.
+        if accuracy == Accuracy::Full {
+            sum_l += 3958.0 * sin(a_1) + 1962.0 * sin(l_prime - f) + 318.0 * sin(a_2);
+            sum_b += -2235.0 * sin(l_prime) + 382.0 * sin(a_3) + 175.0 * sin(a_1 - f) +
+                     175.0 * sin(a_1 + f) + 127.0 * sin(l_prime - m_prime) -
+                     115.0 * sin(l_prime + m_prime);
+        }
+
+        Ok(LunarTerms {
+            epoch,
+            args,
+            longitude: l_prime + (sum_l / 1_000_000.0).to_radians(),
+            latitude: (sum_b / 1_000_000.0).to_radians(),
+            distance: 385_000.56 + sum_r / 1000.0,
+        })
+    }
+}
+
+// Table 47.A, multiples of D, M, M', and F, then the coefficients of the sine for the longitude in
+// 0.000001 degrees and of the cosine for the distance in 0.001 km.
+#[rustfmt::skip]
+const LONGITUDE_DISTANCE_TERMS: [(f64, f64, f64, f64, f64, f64); 60] = [
+    (0.0,  0.0,  1.0,  0.0, 6_288_774.0, -20_905_355.0),
+    (2.0,  0.0, -1.0,  0.0, 1_274_027.0,  -3_699_111.0),
+    (2.0,  0.0,  0.0,  0.0,   658_314.0,  -2_955_968.0),
+    (0.0,  0.0,  2.0,  0.0,   213_618.0,    -569_925.0),
+    (0.0,  1.0,  0.0,  0.0,  -185_116.0,      48_888.0),
+    (0.0,  0.0,  0.0,  2.0,  -114_332.0,      -3_149.0),
+    (2.0,  0.0, -2.0,  0.0,    58_793.0,     246_158.0),
+    (2.0, -1.0, -1.0,  0.0,    57_066.0,    -152_138.0),
+    (2.0,  0.0,  1.0,  0.0,    53_322.0,    -170_733.0),
+    (2.0, -1.0,  0.0,  0.0,    45_758.0,    -204_586.0),
+    (0.0,  1.0, -1.0,  0.0,   -40_923.0,    -129_620.0),
+    (1.0,  0.0,  0.0,  0.0,   -34_720.0,     108_743.0),
+    (0.0,  1.0,  1.0,  0.0,   -30_383.0,     104_755.0),
+    (2.0,  0.0,  0.0, -2.0,    15_327.0,      10_321.0),
+    (0.0,  0.0,  1.0,  2.0,   -12_528.0,           0.0),
+    (0.0,  0.0,  1.0, -2.0,    10_980.0,      79_661.0),
+    (4.0,  0.0, -1.0,  0.0,    10_675.0,     -34_782.0),
+    (0.0,  0.0,  3.0,  0.0,    10_034.0,     -23_210.0),
+    (4.0,  0.0, -2.0,  0.0,     8_548.0,     -21_636.0),
+    (2.0,  1.0, -1.0,  0.0,    -7_888.0,      24_208.0),
+    (2.0,  1.0,  0.0,  0.0,    -6_766.0,      30_824.0),
+    (1.0,  0.0, -1.0,  0.0,    -5_163.0,      -8_379.0),
+    (1.0,  1.0,  0.0,  0.0,     4_987.0,     -16_675.0),
+    (2.0, -1.0,  1.0,  0.0,     4_036.0,     -12_831.0),
+    (2.0,  0.0,  2.0,  0.0,     3_994.0,     -10_445.0),
+    (4.0,  0.0,  0.0,  0.0,     3_861.0,     -11_650.0),
+    (2.0,  0.0, -3.0,  0.0,     3_665.0,      14_403.0),
+    (0.0,  1.0, -2.0,  0.0,    -2_689.0,      -7_003.0),
+    (2.0,  0.0, -1.0,  2.0,    -2_602.0,           0.0),
+    (2.0, -1.0, -2.0,  0.0,     2_390.0,      10_056.0),
+    (1.0,  0.0,  1.0,  0.0,    -2_348.0,       6_322.0),
+    (2.0, -2.0,  0.0,  0.0,     2_236.0,      -9_884.0),
+    (0.0,  1.0,  2.0,  0.0,    -2_120.0,       5_751.0),
+    (0.0,  2.0,  0.0,  0.0,    -2_069.0,           0.0),
+    (2.0, -2.0, -1.0,  0.0,     2_048.0,      -4_950.0),
+    (2.0,  0.0,  1.0, -2.0,    -1_773.0,       4_130.0),
+    (2.0,  0.0,  0.0,  2.0,    -1_595.0,           0.0),
+    (4.0, -1.0, -1.0,  0.0,     1_215.0,      -3_958.0),
+    (0.0,  0.0,  2.0,  2.0,    -1_110.0,           0.0),
+    (3.0,  0.0, -1.0,  0.0,      -892.0,       3_258.0),
+    (2.0,  1.0,  1.0,  0.0,      -810.0,       2_616.0),
+    (4.0, -1.0, -2.0,  0.0,       759.0,      -1_897.0),
+    (0.0,  2.0, -1.0,  0.0,      -713.0,      -2_117.0),
+    (2.0,  2.0, -1.0,  0.0,      -700.0,       2_354.0),
+    (2.0,  1.0, -2.0,  0.0,       691.0,           0.0),
+    (2.0, -1.0,  0.0, -2.0,       596.0,           0.0),
+    (4.0,  0.0,  1.0,  0.0,       549.0,      -1_423.0),
+    (0.0,  0.0,  4.0,  0.0,       537.0,      -1_117.0),
+    (4.0, -1.0,  0.0,  0.0,       520.0,      -1_571.0),
+    (1.0,  0.0, -2.0,  0.0,      -487.0,      -1_739.0),
+    (2.0,  1.0,  0.0, -2.0,      -399.0,           0.0),
+    (0.0,  0.0,  2.0, -2.0,      -381.0,      -4_421.0),
+    (1.0,  1.0,  1.0,  0.0,       351.0,           0.0),
+    (3.0,  0.0, -2.0,  0.0,      -340.0,           0.0),
+    (4.0,  0.0, -3.0,  0.0,       330.0,           0.0),
+    (2.0, -1.0,  2.0,  0.0,       327.0,           0.0),
+    (0.0,  2.0,  1.0,  0.0,      -323.0,       1_165.0),
+    (1.0,  1.0, -1.0,  0.0,       299.0,           0.0),
+    (2.0,  0.0,  3.0,  0.0,       294.0,           0.0),
+    (2.0,  0.0, -1.0, -2.0,         0.0,       8_752.0),
+];
+
+// Table 47.B, multiples of D, M, M', and F, then the coefficient of the sine for the latitude in
+// 0.000001 degrees.
+#[rustfmt::skip]
+const LATITUDE_TERMS: [(f64, f64, f64, f64, f64); 60] = [
+    (0.0,  0.0,  0.0,  1.0, 5_128_122.0),
+    (0.0,  0.0,  1.0,  1.0,   280_602.0),
+    (0.0,  0.0,  1.0, -1.0,   277_693.0),
+    (2.0,  0.0,  0.0, -1.0,   173_237.0),
+    (2.0,  0.0, -1.0,  1.0,    55_413.0),
+    (2.0,  0.0, -1.0, -1.0,    46_271.0),
+    (2.0,  0.0,  0.0,  1.0,    32_573.0),
+    (0.0,  0.0,  2.0,  1.0,    17_198.0),
+    (2.0,  0.0,  1.0, -1.0,     9_266.0),
+    (0.0,  0.0,  2.0, -1.0,     8_822.0),
+    (2.0, -1.0,  0.0, -1.0,     8_216.0),
+    (2.0,  0.0, -2.0, -1.0,     4_324.0),
+    (2.0,  0.0,  1.0,  1.0,     4_200.0),
+    (2.0,  1.0,  0.0, -1.0,    -3_359.0),
+    (2.0, -1.0, -1.0,  1.0,     2_463.0),
+    (2.0, -1.0,  0.0,  1.0,     2_211.0),
+    (2.0, -1.0, -1.0, -1.0,     2_065.0),
+    (0.0,  1.0, -1.0, -1.0,    -1_870.0),
+    (4.0,  0.0, -1.0, -1.0,     1_828.0),
+    (0.0,  1.0,  0.0,  1.0,    -1_794.0),
+    (0.0,  0.0,  0.0,  3.0,    -1_749.0),
+    (0.0,  1.0, -1.0,  1.0,    -1_565.0),
+    (1.0,  0.0,  0.0,  1.0,    -1_491.0),
+    (0.0,  1.0,  1.0,  1.0,    -1_475.0),
+    (0.0,  1.0,  1.0, -1.0,    -1_410.0),
+    (0.0,  1.0,  0.0, -1.0,    -1_344.0),
+    (1.0,  0.0,  0.0, -1.0,    -1_335.0),
+    (0.0,  0.0,  3.0,  1.0,     1_107.0),
+    (4.0,  0.0,  0.0, -1.0,     1_021.0),
+    (4.0,  0.0, -1.0,  1.0,       833.0),
+    (0.0,  0.0,  1.0, -3.0,       777.0),
+    (4.0,  0.0, -2.0,  1.0,       671.0),
+    (2.0,  0.0,  0.0, -3.0,       607.0),
+    (2.0,  0.0,  2.0, -1.0,       596.0),
+    (2.0, -1.0,  1.0, -1.0,       491.0),
+    (2.0,  0.0, -2.0,  1.0,      -451.0),
+    (0.0,  0.0,  3.0, -1.0,       439.0),
+    (2.0,  0.0,  2.0,  1.0,       422.0),
+    (2.0,  0.0, -3.0, -1.0,       421.0),
+    (2.0,  1.0, -1.0,  1.0,      -366.0),
+    (2.0,  1.0,  0.0,  1.0,      -351.0),
+    (4.0,  0.0,  0.0,  1.0,       331.0),
+    (2.0, -1.0,  1.0,  1.0,       315.0),
+    (2.0, -2.0,  0.0, -1.0,       302.0),
+    (0.0,  0.0,  1.0,  3.0,      -283.0),
+    (2.0,  1.0,  1.0, -1.0,      -229.0),
+    (1.0,  1.0,  0.0, -1.0,       223.0),
+    (1.0,  1.0,  0.0,  1.0,       223.0),
+    (0.0,  1.0, -2.0, -1.0,      -220.0),
+    (2.0,  1.0, -1.0, -1.0,      -220.0),
+    (1.0,  0.0,  1.0,  1.0,      -185.0),
+    (2.0, -1.0, -2.0, -1.0,       181.0),
+    (0.0,  1.0,  2.0,  1.0,      -177.0),
+    (4.0,  0.0, -2.0, -1.0,       176.0),
+    (4.0, -1.0, -1.0, -1.0,       166.0),
+    (1.0,  0.0,  1.0, -1.0,      -164.0),
+    (4.0,  0.0,  1.0, -1.0,       132.0),
+    (1.0,  0.0, -1.0, -1.0,      -119.0),
+    (4.0, -1.0,  0.0, -1.0,       115.0),
+    (2.0, -2.0,  0.0,  1.0,       107.0),
+];
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod moon_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    // Examples 47.a, 48.a, and 53.a, Astronomical Algorithms 2nd ed.
+    fn example_47a() -> AstroTime {
+        Builder::from_julian_date(2_448_724.5).dynamical_time().build().unwrap()
+    }
+
+    #[test]
+    fn test_position() {
+        let time = example_47a();
+
+        assert!(approx_eq(distance(&time).unwrap(), 368_409.7, 0.1));
+
+        let ecl = apparent_ecliptic(&time).unwrap();
+        assert!(approx_eq(ecl.longitude().radians().to_degrees(), 133.167_265, 1.0e-6));
+        assert!(approx_eq(ecl.latitude().radians().to_degrees(), -3.229_126, 1.0e-6));
+
+        let eq = apparent_equatorial(&time).unwrap();
+        assert!(approx_eq(eq.right_ascension().radians().to_degrees(), 134.688_470, 1.0e-5));
+        assert!(approx_eq(eq.declination().radians().to_degrees(), 13.768_368, 1.0e-5));
+    }
+
+    #[test]
+    fn test_transit_altitude() {
+        let geo = GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                                 RadianAngle::new(105f64.to_radians()).unwrap(),
+                                 0.0)
+            .unwrap();
+        let date = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let (transit, altitude) = transit_altitude(&date, geo).unwrap();
+
+        // On the meridian, a little below the geocentric culmination altitude.
+        let eq = apparent_equatorial(&transit).unwrap();
+        let h = local_hour_angle(eq, geo, &transit).unwrap().radians();
+        assert!(h.sin().abs() < 1.0e-4);
+        let geocentric = 90.0 - 40.0 + eq.declination().radians().to_degrees();
+        let parallax = geocentric - altitude.radians().to_degrees();
+        assert!(parallax > 0.3 && parallax < 1.0);
+    }
+
+    #[test]
+    fn test_illuminated_fraction() {
+        assert!(approx_eq(illuminated_fraction(&example_47a()).unwrap(), 0.678_6, 1.0e-4));
+    }
+
+    #[test]
+    fn test_low_accuracy() {
+        // Compare with the full theory over a month.
+        let (mut d_pos, mut d_lat, mut d_dist, mut d_fraction) = (0.0f64, 0.0f64, 0.0f64, 0.0f64);
+        for i in 0..60 {
+            let jde = 2_448_724.5 + i as f64 * 0.5;
+            let time = Builder::from_julian_date(jde).dynamical_time().build().unwrap();
+            let full = apparent_equatorial(&time).unwrap();
+            let low = apparent_equatorial_with(&time, Accuracy::Low).unwrap();
+            let full_ecl = apparent_ecliptic(&time).unwrap();
+            let low_ecl = apparent_ecliptic_with(&time, Accuracy::Low).unwrap();
+
+            d_pos = d_pos.max(angular_separation(full, low).radians().to_degrees());
+            d_lat = d_lat.max((full_ecl.latitude().radians() - low_ecl.latitude().radians())
+                .abs()
+                .to_degrees());
+            d_dist = d_dist.max((distance(&time).unwrap() -
+                                 distance_with(&time, Accuracy::Low).unwrap())
+                .abs());
+            d_fraction = d_fraction.max((illuminated_fraction(&time).unwrap() -
+                                         illuminated_fraction_with(&time, Accuracy::Low)
+                                             .unwrap())
+                .abs());
+        }
+        assert!(d_pos < 0.05);
+        assert!(d_lat < 0.05);
+        assert!(d_dist < 100.0);
+        assert!(d_fraction < 0.001);
+    }
+
+    #[test]
+    fn test_disk_orientation() {
+        let disk = disk_orientation(&example_47a()).unwrap();
+        assert!(approx_eq(disk.axis_position_angle().radians().to_degrees(), 15.08, 0.01));
+        assert!(approx_eq(disk.center_latitude().radians().to_degrees(), 4.194, 0.01));
+
+        let chi = disk.bright_limb_position_angle().unwrap();
+        assert!(approx_eq(chi.radians().to_degrees(), 285.0, 0.1));
+    }
+}
@@ -0,0 +1,354 @@
+//!
+//! Module for the times of the phases of the Moon.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Follows chapter 49 of Astronomical Algorithms, 2nd Edition by Jean Meeus. The time of each
+//! phase is the mean phase with the periodic terms added, which is good to about 17 seconds
+//! compared to the full theory of the Moon over the years 1980 to 2020.
+//!
+use super::super::astro_time::{AstroTime, Builder};
+use super::super::error::*;
+use super::super::math::*;
+
+// The mean synodic month in days, and the time of the mean new moon of 2000 January 6, equation
+// 49.1.
+pub(crate) const SYNODIC_MONTH: f64 = 29.530_588_861;
+pub(crate) const NEW_MOON_EPOCH: f64 = 2_451_550.097_66;
+
+// The periodic terms of the new and full moons, and of the quarters. Each is the coefficient in
+// days, the power of the eccentricity factor E, and the multiples of M, M', F, and Ω in the
+// argument.
+const NEW_MOON_TERMS: [(f64, i32, f64, f64, f64, f64); 25] =
+    [(-0.407_20, 0, 0.0, 1.0, 0.0, 0.0),
+     (0.172_41, 1, 1.0, 0.0, 0.0, 0.0),
+     (0.016_08, 0, 0.0, 2.0, 0.0, 0.0),
+     (0.010_39, 0, 0.0, 0.0, 2.0, 0.0),
+     (0.007_39, 1, -1.0, 1.0, 0.0, 0.0),
+     (-0.005_14, 1, 1.0, 1.0, 0.0, 0.0),
+     (0.002_08, 2, 2.0, 0.0, 0.0, 0.0),
+     (-0.001_11, 0, 0.0, 1.0, -2.0, 0.0),
+     (-0.000_57, 0, 0.0, 1.0, 2.0, 0.0),
+     (0.000_56, 1, 1.0, 2.0, 0.0, 0.0),
+     (-0.000_42, 0, 0.0, 3.0, 0.0, 0.0),
+     (0.000_42, 1, 1.0, 0.0, 2.0, 0.0),
+     (0.000_38, 1, 1.0, 0.0, -2.0, 0.0),
+     (-0.000_24, 1, -1.0, 2.0, 0.0, 0.0),
+     (-0.000_17, 0, 0.0, 0.0, 0.0, 1.0),
+     (-0.000_07, 0, 2.0, 1.0, 0.0, 0.0),
+     (0.000_04, 0, 0.0, 2.0, -2.0, 0.0),
+     (0.000_04, 0, 3.0, 0.0, 0.0, 0.0),
+     (0.000_03, 0, 1.0, 1.0, -2.0, 0.0),
+     (0.000_03, 0, 0.0, 2.0, 2.0, 0.0),
+     (-0.000_03, 0, 1.0, 1.0, 2.0, 0.0),
+     (0.000_03, 0, -1.0, 1.0, 2.0, 0.0),
+     (-0.000_02, 0, -1.0, 1.0, -2.0, 0.0),
+     (-0.000_02, 0, 1.0, 3.0, 0.0, 0.0),
+     (0.000_02, 0, 0.0, 4.0, 0.0, 0.0)];
+
+// Only the first seven coefficients differ from those of the new moon.
+const FULL_MOON_TERMS: [(f64, i32, f64, f64, f64, f64); 7] =
+    [(-0.406_14, 0, 0.0, 1.0, 0.0, 0.0),
+     (0.173_02, 1, 1.0, 0.0, 0.0, 0.0),
+     (0.016_14, 0, 0.0, 2.0, 0.0, 0.0),
+     (0.010_43, 0, 0.0, 0.0, 2.0, 0.0),
+     (0.007_34, 1, -1.0, 1.0, 0.0, 0.0),
+     (-0.005_15, 1, 1.0, 1.0, 0.0, 0.0),
+     (0.002_09, 2, 2.0, 0.0, 0.0, 0.0)];
+
+const QUARTER_TERMS: [(f64, i32, f64, f64, f64, f64); 25] =
+    [(-0.628_01, 0, 0.0, 1.0, 0.0, 0.0),
+     (0.171_72, 1, 1.0, 0.0, 0.0, 0.0),
+     (-0.011_83, 1, 1.0, 1.0, 0.0, 0.0),
+     (0.008_62, 0, 0.0, 2.0, 0.0, 0.0),
+     (0.008_04, 0, 0.0, 0.0, 2.0, 0.0),
+     (0.004_54, 1, -1.0, 1.0, 0.0, 0.0),
+     (0.002_04, 2, 2.0, 0.0, 0.0, 0.0),
+     (-0.001_80, 0, 0.0, 1.0, -2.0, 0.0),
+     (-0.000_70, 0, 0.0, 1.0, 2.0, 0.0),
+     (-0.000_40, 0, 0.0, 3.0, 0.0, 0.0),
+     (-0.000_34, 1, -1.0, 2.0, 0.0, 0.0),
+     (0.000_32, 1, 1.0, 0.0, 2.0, 0.0),
+     (0.000_32, 1, 1.0, 0.0, -2.0, 0.0),
+     (-0.000_28, 2, 2.0, 1.0, 0.0, 0.0),
+     (0.000_27, 1, 1.0, 2.0, 0.0, 0.0),
+     (-0.000_17, 0, 0.0, 0.0, 0.0, 1.0),
+     (-0.000_05, 0, -1.0, 1.0, -2.0, 0.0),
+     (0.000_04, 0, 0.0, 2.0, 2.0, 0.0),
+     (-0.000_04, 0, 1.0, 1.0, 2.0, 0.0),
+     (0.000_04, 0, -2.0, 1.0, 0.0, 0.0),
+     (0.000_03, 0, 1.0, 1.0, -2.0, 0.0),
+     (0.000_03, 0, 3.0, 0.0, 0.0, 0.0),
+     (0.000_02, 0, 0.0, 2.0, -2.0, 0.0),
+     (0.000_02, 0, -1.0, 1.0, 2.0, 0.0),
+     (-0.000_02, 0, 1.0, 3.0, 0.0, 0.0)];
+
+// The additional corrections for all the phases, the coefficient in days and the constant and
+// rate per lunation of the argument in degrees. The first argument also has a term in T².
+const PLANETARY_TERMS: [(f64, f64, f64); 14] = [(0.000_325, 299.77, 0.107_408),
+                                                (0.000_165, 251.88, 0.016_321),
+                                                (0.000_164, 251.83, 26.651_886),
+                                                (0.000_126, 349.42, 36.412_478),
+                                                (0.000_110, 84.66, 18.206_239),
+                                                (0.000_062, 141.74, 53.303_771),
+                                                (0.000_060, 207.14, 2.453_732),
+                                                (0.000_056, 154.84, 7.306_860),
+                                                (0.000_047, 34.52, 27.261_239),
+                                                (0.000_042, 207.19, 0.121_824),
+                                                (0.000_040, 291.34, 1.844_379),
+                                                (0.000_037, 161.72, 24.198_154),
+                                                (0.000_035, 239.56, 25.513_099),
+                                                (0.000_023, 331.55, 3.592_518)];
+
+/// The principal phases of the Moon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseKind {
+    /// New moon, with the Moon in conjunction with the Sun.
+    New,
+    /// First quarter, with the Moon 90 degrees east of the Sun.
+    FirstQuarter,
+    /// Full moon, with the Moon opposite the Sun.
+    Full,
+    /// Last quarter, with the Moon 90 degrees west of the Sun.
+    LastQuarter,
+}
+
+impl PhaseKind {
+    // The quarter of the lunation, counting from the new moon.
+    fn quarter(self) -> i64 {
+        match self {
+            PhaseKind::New => 0,
+            PhaseKind::FirstQuarter => 1,
+            PhaseKind::Full => 2,
+            PhaseKind::LastQuarter => 3,
+        }
+    }
+}
+
+/// An iterator over the phases of the Moon in order, see `phases_between`.
+#[derive(Debug, Clone)]
+pub struct Phases {
+    // The number of quarter lunations since the new moon of 2000 January 6.
+    quarters: i64,
+    start_jde: f64,
+    end_jde: f64,
+}
+
+impl Iterator for Phases {
+    type Item = (PhaseKind, AstroTime);
+
+    fn next(&mut self) -> Option<(PhaseKind, AstroTime)> {
+        loop {
+            let quarters = self.quarters;
+            let jde = phase_jde(quarters);
+            if jde >= self.end_jde {
+                return None;
+            }
+
+            self.quarters += 1;
+            if jde >= self.start_jde {
+                let kind = match quarters.rem_euclid(4) {
+                    0 => PhaseKind::New,
+                    1 => PhaseKind::FirstQuarter,
+                    2 => PhaseKind::Full,
+                    _ => PhaseKind::LastQuarter,
+                };
+
+                return Some((kind, ut(jde).expect("Phases after a valid time are valid")));
+            }
+        }
+    }
+}
+
+/// Find the new moons, first quarters, full moons, and last quarters from `start` up to but not
+/// including `end`, in order.
+///
+/// The times are in Universal Time.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::Builder;
+/// # use astro_calc::moon::{phases_between, PhaseKind};
+/// let start = Builder::from_gregorian_utc(2017, 8, 1, 0, 0, 0).build().unwrap();
+/// let end = Builder::from_gregorian_utc(2017, 9, 1, 0, 0, 0).build().unwrap();
+/// let phases: Vec<_> = phases_between(&start, &end).unwrap().collect();
+/// assert_eq!(phases.len(), 4);
+///
+/// // The new moon of the total solar eclipse.
+/// let (kind, time) = phases[2];
+/// assert_eq!(kind, PhaseKind::New);
+/// assert_eq!(time.to_gregorian_utc().2, 21);
+/// ```
+pub fn phases_between(start: &AstroTime, end: &AstroTime) -> AstroResult<Phases> {
+    let start_jde = start.julian_ephemeris_day()?;
+
+    // The phases are within a day of the mean phases, so start a quarter early.
+    let quarters = (4.0 * (start_jde - NEW_MOON_EPOCH) / SYNODIC_MONTH).floor() as i64 - 1;
+
+    Ok(Phases {
+        quarters,
+        start_jde,
+        end_jde: end.julian_ephemeris_day()?,
+    })
+}
+
+/// Find the next time of a phase of the Moon after `time`, in Universal Time.
+pub fn next_phase(kind: PhaseKind, time: &AstroTime) -> AstroResult<AstroTime> {
+    let jde = time.julian_ephemeris_day()?;
+
+    let mut lunations = ((jde - NEW_MOON_EPOCH) / SYNODIC_MONTH).floor() as i64 - 1;
+    while phase_jde(4 * lunations + kind.quarter()) <= jde {
+        lunations += 1;
+    }
+
+    ut(phase_jde(4 * lunations + kind.quarter()))
+}
+
+/// Find the next new moon after `time`, in Universal Time.
+pub fn next_new_moon(time: &AstroTime) -> AstroResult<AstroTime> {
+    next_phase(PhaseKind::New, time)
+}
+
+/// Find the next full moon after `time`, in Universal Time.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::Builder;
+/// # use astro_calc::moon::next_full_moon;
+/// let time = Builder::from_gregorian_utc(2017, 8, 21, 0, 0, 0).build().unwrap();
+/// let full = next_full_moon(&time).unwrap();
+///
+/// // 2017 September 6 at 7:03 UT.
+/// let (year, month, day, hour, ..) = full.to_gregorian_utc();
+/// assert_eq!((year, month, day, hour), (2017, 9, 6, 7));
+/// ```
+pub fn next_full_moon(time: &AstroTime) -> AstroResult<AstroTime> {
+    next_phase(PhaseKind::Full, time)
+}
+
+// The Julian Ephemeris Day of a phase a number of quarter lunations after the new moon of 2000
+// January 6, equations 49.1 to 49.7.
+fn phase_jde(quarters: i64) -> f64 {
+    let k = quarters as f64 / 4.0;
+    let t = k / 1_236.85;
+    let t2 = t * t;
+
+    let mean = NEW_MOON_EPOCH + SYNODIC_MONTH * k +
+               t2 * (0.000_154_37 + t * (-0.000_000_150 + t * 0.000_000_000_73));
+
+    let e = 1.0 - t * (0.002_516 + t * 0.000_007_4);
+    let m = (2.553_4 + 29.105_356_70 * k - t2 * (0.000_001_4 + t * 0.000_000_11)).to_radians();
+    let mp = (201.564_3 + 385.816_935_28 * k +
+              t2 * (0.010_758_2 + t * (0.000_012_38 - t * 0.000_000_058)))
+        .to_radians();
+    let f = (160.710_8 + 390.670_502_84 * k -
+             t2 * (0.001_611_8 + t * (0.000_002_27 - t * 0.000_000_011)))
+        .to_radians();
+    let omega = (124.774_6 - 1.563_755_88 * k + t2 * (0.002_067_2 + t * 0.000_002_15))
+        .to_radians();
+
+    let term = |&(coefficient, e_power, a, b, c, d): &(f64, i32, f64, f64, f64, f64)| {
+        coefficient * e.powi(e_power) * sin(a * m + b * mp + c * f + d * omega)
+    };
+    let periodic: f64 = match quarters.rem_euclid(4) {
+        0 => NEW_MOON_TERMS.iter().map(term).sum(),
+        2 => {
+            FULL_MOON_TERMS.iter().map(term).sum::<f64>() +
+            NEW_MOON_TERMS[FULL_MOON_TERMS.len()..].iter().map(term).sum::<f64>()
+        }
+        q => {
+            let w = 0.003_06 - 0.000_38 * e * cos(m) + 0.000_26 * cos(mp) -
+                    0.000_02 * cos(mp - m) + 0.000_02 * cos(mp + m) + 0.000_02 * cos(2.0 * f);
+            let w = if q == 1 { w } else { -w };
+
+            QUARTER_TERMS.iter().map(term).sum::<f64>() + w
+        }
+    };
+
+    let planetary: f64 = PLANETARY_TERMS.iter()
+        .enumerate()
+        .map(|(i, &(coefficient, a_0, rate))| {
+            let a = a_0 + rate * k - if i == 0 { 0.009_173 * t2 } else { 0.0 };
+            coefficient * sin(a.to_radians())
+        })
+        .sum();
+
+    mean + periodic + planetary
+}
+
+fn ut(jde: f64) -> AstroResult<AstroTime> {
+    Builder::from_julian_date(jde).dynamical_time().build()?.as_utc()
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod phases_tests {
+    use super::*;
+
+    fn date(year: i32, month: i32, day: i32) -> AstroTime {
+        Builder::from_gregorian_utc(year, month, day, 0, 0, 0).build().unwrap()
+    }
+
+    #[test]
+    fn test_phase_jde() {
+        // Example 49.a, Astronomical Algorithms 2nd ed., the new moon of 1977 February.
+        assert!(approx_eq(phase_jde(-283 * 4), 2_443_192.651_18, 1.0e-5));
+
+        // Example 49.b, the last quarter of 2044 January.
+        assert!(approx_eq(phase_jde(544 * 4 + 3), 2_467_636.491_86, 1.0e-5));
+    }
+
+    #[test]
+    fn test_phases_between() {
+        // A year has 12 or 13 of each phase, in order.
+        let phases: Vec<_> = phases_between(&date(2017, 1, 1), &date(2018, 1, 1))
+            .unwrap()
+            .collect();
+        assert_eq!(phases.iter().filter(|p| p.0 == PhaseKind::Full).count(), 12);
+        assert_eq!(phases.iter().filter(|p| p.0 == PhaseKind::New).count(), 12);
+        assert_eq!(phases.len(), 49);
+        assert_eq!(phases[0].0, PhaseKind::FirstQuarter);
+        for pair in phases.windows(2) {
+            assert_eq!((pair[0].0.quarter() + 1) % 4, pair[1].0.quarter());
+            let days = pair[1].1.duration_since(&pair[0].1).unwrap().days();
+            assert!(days > 6.0 && days < 8.5);
+        }
+
+        // The phases at the ends.
+        let (_, first) = phases[0];
+        assert!(phases_between(&first, &date(2017, 1, 6)).unwrap().next().unwrap().1 == first);
+        assert_eq!(phases_between(&date(2017, 1, 1), &first).unwrap().count(), 0);
+        assert_eq!(phases_between(&date(2018, 1, 1), &date(2017, 1, 1)).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_next_phase() {
+        // The full moon of 2017 August 7 at 18:11 UT, with a partial lunar eclipse.
+        let full = next_full_moon(&date(2017, 7, 24)).unwrap();
+        let (year, month, day, hour, minute, _) = full.to_gregorian_utc();
+        assert_eq!((year, month, day, hour), (2017, 8, 7, 18));
+        assert!((minute - 11).abs() <= 1);
+
+        // The new moon of 2017 August 21 at 18:30 UT, with the total solar eclipse.
+        let new = next_new_moon(&full).unwrap();
+        let (year, month, day, hour, minute, _) = new.to_gregorian_utc();
+        assert_eq!((year, month, day, hour), (2017, 8, 21, 18));
+        assert!((minute - 30).abs() <= 1);
+
+        // Strictly after the time.
+        assert!(next_full_moon(&full).unwrap() > full);
+        let quarter = next_phase(PhaseKind::FirstQuarter, &new).unwrap();
+        assert_eq!(quarter.to_gregorian_utc().2, 29);
+    }
+}
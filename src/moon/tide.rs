@@ -0,0 +1,166 @@
+//!
+//! Module for estimating the times of the tides from the transits of the Moon.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! This is only a rough estimate for a place with semi-diurnal tides. High water follows each
+//! transit of the Moon across the upper or lower meridian by the lunitidal interval of the place,
+//! found from the tide tables or by observation, and low water is midway between the high waters.
+//! The diurnal inequality, the shape of the coast, and the weather are all ignored, so the times
+//! may be off by an hour or more. Don't use them for navigation, use the official tide tables.
+//!
+use super::super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::rise_set::moon_rise_transit_set;
+
+/// Whether a tide is high or low water.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TideKind {
+    /// High water.
+    High,
+    /// Low water.
+    Low,
+}
+
+/// An estimated time of high or low water.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tide {
+    time: AstroTime,
+    kind: TideKind,
+}
+
+impl Tide {
+    /// The estimated time in Universal Time.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+
+    /// Whether it is high or low water.
+    pub fn kind(&self) -> TideKind {
+        self.kind
+    }
+}
+
+/// Estimate the times of high and low water on the Universal Time day containing `date`.
+///
+/// The `lunitidal_interval` is the time from the Moon's transit to the following high water at
+/// the place, between zero and a day. The tides are in order, usually two high and two low
+/// waters. See the module documentation for how rough this estimate is.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::{AstroDuration, Builder};
+/// # use astro_calc::coords::{GeoCoords, RadianAngle};
+/// # use astro_calc::moon::tide_times;
+/// let date = Builder::from_gregorian_utc(2017, 8, 10, 0, 0, 0).build().unwrap();
+/// let place = GeoCoords::new(RadianAngle::new(50.8f64.to_radians()).unwrap(),
+///                            RadianAngle::new(1.1f64.to_radians()).unwrap(),
+///                            0.0)
+///     .unwrap();
+/// let interval = AstroDuration::from_hours(11.0).unwrap();
+/// let tides = tide_times(&date, place, interval).unwrap();
+/// assert!(tides.len() >= 3 && tides.len() <= 4);
+/// ```
+pub fn tide_times(date: &AstroTime,
+                  geo: GeoCoords,
+                  lunitidal_interval: AstroDuration)
+                  -> AstroResult<Vec<Tide>> {
+    let interval = lunitidal_interval.days();
+    if !(0.0..1.0).contains(&interval) {
+        return Err(AstroAlgorithmsError::InvalidArgument("The lunitidal interval must be \
+                                                          between zero and a day"
+            .to_owned()));
+    }
+
+    let jd_0 = (date.as_utc()?.julian_day_number() - 0.5).floor() + 0.5;
+
+    // The upper transits around the day. A day without a transit gives the transit of the next
+    // day instead, so drop the repeats.
+    let mut upper: Vec<f64> = Vec::with_capacity(5);
+    for day in -2..3 {
+        let day = Builder::from_julian_date(jd_0 + f64::from(day)).build()?;
+        let transit = moon_rise_transit_set(&day, geo, None)?.transit().julian_day_number();
+        if !upper.iter().any(|&other| (transit - other).abs() < 0.5) {
+            upper.push(transit);
+        }
+    }
+
+    // The lower transits are taken midway between the upper transits.
+    let mut transits = Vec::with_capacity(2 * upper.len());
+    for pair in upper.windows(2) {
+        transits.push(pair[0]);
+        transits.push((pair[0] + pair[1]) / 2.0);
+    }
+
+    let mut tides = Vec::with_capacity(4);
+    for pair in transits.windows(2) {
+        let high = pair[0] + interval;
+        let low = (pair[0] + pair[1]) / 2.0 + interval;
+        for &(jd, kind) in &[(high, TideKind::High), (low, TideKind::Low)] {
+            if (jd_0..jd_0 + 1.0).contains(&jd) {
+                tides.push(Tide {
+                    time: Builder::from_julian_date(jd).build()?,
+                    kind,
+                });
+            }
+        }
+    }
+
+    Ok(tides)
+}
+
+#[cfg(test)]
+mod tide_tests {
+    use super::*;
+
+    fn geo(lat: f64, west_lon: f64) -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(lat.to_radians()).unwrap(),
+                       RadianAngle::new(west_lon.to_radians()).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tide_times() {
+        let date = Builder::from_gregorian_utc(2017, 8, 10, 0, 0, 0).build().unwrap();
+        let place = geo(50.8, 1.1);
+        let transit = moon_rise_transit_set(&date, place, None).unwrap().transit();
+
+        // With no interval, high water is at the transit.
+        let tides = tide_times(&date, place, AstroDuration::from_hours(0.0).unwrap()).unwrap();
+        assert!(tides.iter().any(|tide| tide.kind() == TideKind::High && tide.time() == transit));
+
+        // The tides alternate about 6 hours 12 minutes apart within the day.
+        let interval = AstroDuration::from_hours(11.0).unwrap();
+        let tides = tide_times(&date, place, interval).unwrap();
+        assert!(tides.len() >= 3 && tides.len() <= 4);
+        for pair in tides.windows(2) {
+            assert!(pair[0].kind() != pair[1].kind());
+            let hours = pair[1].time().duration_since(&pair[0].time()).unwrap().hours();
+            assert!((5.5..7.0).contains(&hours), "{}", hours);
+        }
+        let jd_0 = date.julian_day_number();
+        for tide in &tides {
+            assert!((jd_0..jd_0 + 1.0).contains(&tide.time().julian_day_number()));
+        }
+
+        // Shifting the interval by half a lunar day gives nearly the same tides.
+        let later = AstroDuration::from_hours(11.0 + 12.42).unwrap();
+        let shifted = tide_times(&date, place, later).unwrap();
+        let (first, other) = (tides[0], shifted[0]);
+        assert_eq!(first.kind(), other.kind());
+        let minutes = other.time().duration_since(&first.time()).unwrap().minutes().abs();
+        assert!(minutes < 15.0, "{}", minutes);
+
+        for hours in &[-1.0, 24.0] {
+            let interval = AstroDuration::from_hours(*hours).unwrap();
+            assert!(tide_times(&date, place, interval).is_err());
+        }
+    }
+}
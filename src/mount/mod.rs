@@ -0,0 +1,266 @@
+//!
+//! Module for telescope mounts.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The errors of an equatorial mount are small rotations of its axes, and to first order they add
+//! terms to the hour angle and declination read from the mount in the form used by P. T. Wallace's
+//! TPOINT. The misalignment of the polar axis adds
+//!
+//! Δh = ME tan δ sin h - MA tan δ cos h, and Δδ = ME cos h + MA sin h,
+//!
+//! where ME is the displacement of the axis from the north celestial pole towards the meridian,
+//! and MA towards the west. An index error, a constant offset in each axis, is added to both.
+//!
+mod pointing;
+
+pub use self::pointing::*;
+
+use std::f64::consts::PI;
+
+use super::astro_time::AstroTime;
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+
+/// The position of a star read from a mount, with the apparent position of the star.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MountReading {
+    target: EquatorialCoords,
+    hour_angle: RadianAngle,
+    declination: RadianAngle,
+    time: AstroTime,
+}
+
+impl MountReading {
+    /// Create a new reading of the hour angle and declination from the mount's axes while it was
+    /// centered on a star at `target` at a time.
+    ///
+    /// The target should be in apparent coordinates of the date.
+    pub fn new(target: EquatorialCoords,
+               hour_angle: RadianAngle,
+               declination: RadianAngle,
+               time: AstroTime)
+               -> MountReading {
+        MountReading {
+            target,
+            hour_angle,
+            declination,
+            time,
+        }
+    }
+
+    /// The apparent position of the star.
+    pub fn target(&self) -> EquatorialCoords {
+        self.target
+    }
+
+    /// The hour angle read from the mount.
+    pub fn hour_angle(&self) -> RadianAngle {
+        self.hour_angle
+    }
+
+    /// The declination read from the mount.
+    pub fn declination(&self) -> RadianAngle {
+        self.declination
+    }
+
+    /// The time of the reading.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+
+    // The true hour angle and declination, and the differences of the readings from them.
+    fn residuals(&self, geo: GeoCoords) -> AstroResult<(f64, f64, f64, f64)> {
+        let h = local_hour_angle(self.target, geo, &self.time)?.radians();
+        let dec = self.target.declination().radians();
+        let delta_h = map_to_branch(self.hour_angle.radians() - h, -PI, PI);
+        let delta_dec = self.declination.radians() - dec;
+
+        Ok((h, dec, delta_h, delta_dec))
+    }
+}
+
+/// The misalignment of the polar axis of a mount.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolarAlignmentError {
+    altitude: RadianAngle,
+    azimuth: RadianAngle,
+}
+
+impl PolarAlignmentError {
+    /// The altitude of the polar axis less that of the pole, positive if the axis is too high.
+    pub fn altitude(&self) -> RadianAngle {
+        self.altitude
+    }
+
+    /// The azimuth of the polar axis less that of the pole, positive if the axis points east of
+    /// the pole.
+    ///
+    /// This is the angle to turn the mount about the vertical, the offset on the sky is smaller
+    /// by a factor of the cosine of the latitude.
+    pub fn azimuth(&self) -> RadianAngle {
+        self.azimuth
+    }
+
+    /// The total angle between the polar axis and the pole.
+    pub fn total(&self, geo: GeoCoords) -> RadianAngle {
+        let azimuth = self.azimuth.radians() * cos(geo.latitude().radians());
+        RadianAngle::new(hypot(self.altitude.radians(), azimuth)).unwrap()
+    }
+}
+
+/// Calculate the misalignment of the polar axis of a mount from readings on two stars.
+///
+/// The index errors of both axes are solved for, so the mount need not be synchronized on either
+/// star. The solution is to first order in the errors, which is good for errors up to a degree or
+/// so. In the southern hemisphere the axis is aligned on the south celestial pole.
+///
+/// The stars should be well apart in hour angle and away from the celestial equator, where the
+/// hour angle is insensitive to the misalignment. An error is returned if the readings can't
+/// separate the errors.
+pub fn polar_alignment_error(first: &MountReading,
+                             second: &MountReading,
+                             geo: GeoCoords)
+                             -> AstroResult<PolarAlignmentError> {
+    let (h_1, dec_1, delta_h_1, delta_dec_1) = first.residuals(geo)?;
+    let (h_2, dec_2, delta_h_2, delta_dec_2) = second.residuals(geo)?;
+
+    // The index errors cancel in the differences of the two stars, leaving two equations in ME
+    // and MA.
+    let (tan_1, tan_2) = (tan(dec_1), tan(dec_2));
+    let a_11 = tan_1 * sin(h_1) - tan_2 * sin(h_2);
+    let a_12 = tan_2 * cos(h_2) - tan_1 * cos(h_1);
+    let a_21 = cos(h_1) - cos(h_2);
+    let a_22 = sin(h_1) - sin(h_2);
+    let b_1 = map_to_branch(delta_h_1 - delta_h_2, -PI, PI);
+    let b_2 = delta_dec_1 - delta_dec_2;
+
+    let det = a_11 * a_22 - a_12 * a_21;
+    if det.abs() < 1.0e-6 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The stars are too close together or \
+                                                          too near the celestial equator"
+            .to_owned()));
+    }
+
+    let me = (b_1 * a_22 - b_2 * a_12) / det;
+    let ma = (a_11 * b_2 - a_21 * b_1) / det;
+
+    // The southern end of the axis is displaced the opposite way on the sky.
+    let latitude = geo.latitude().radians();
+    let sign = if latitude < 0.0 { -1.0 } else { 1.0 };
+
+    Ok(PolarAlignmentError {
+        altitude: RadianAngle::new(sign * me)?,
+        azimuth: RadianAngle::new(-sign * ma / cos(latitude))?,
+    })
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod mount_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    fn arcminutes(angle: RadianAngle) -> f64 {
+        angle.radians().to_degrees() * 60.0
+    }
+
+    fn coords(ra_deg: f64, dec_deg: f64, time: AstroTime) -> EquatorialCoords {
+        EquatorialCoords::new(RadianAngle::new(ra_deg.to_radians()).unwrap(),
+                              RadianAngle::new(dec_deg.to_radians()).unwrap(),
+                              time)
+            .unwrap()
+    }
+
+    // Read a star with a mount whose axis points at `pole`, given in hour angle coordinates as
+    // (x towards the meridian, y towards the east, z), with index errors in each axis.
+    fn reading(target: EquatorialCoords,
+               geo: GeoCoords,
+               time: AstroTime,
+               pole: [f64; 3],
+               index: (f64, f64))
+               -> MountReading {
+        let norm = (pole[0] * pole[0] + pole[1] * pole[1] + pole[2] * pole[2]).sqrt();
+        let z = [pole[0] / norm, pole[1] / norm, pole[2] / norm];
+        let x = [1.0 - z[0] * z[0], -z[0] * z[1], -z[0] * z[2]];
+        let norm = (x[0] * x[0] + x[1] * x[1] + x[2] * x[2]).sqrt();
+        let x = [x[0] / norm, x[1] / norm, x[2] / norm];
+        let y = [z[1] * x[2] - z[2] * x[1], z[2] * x[0] - z[0] * x[2], z[0] * x[1] - z[1] * x[0]];
+
+        let h = local_hour_angle(target, geo, &time).unwrap().radians();
+        let dec = target.declination().radians();
+        let s = [dec.cos() * h.cos(), -dec.cos() * h.sin(), dec.sin()];
+        let dot = |a: [f64; 3]| a[0] * s[0] + a[1] * s[1] + a[2] * s[2];
+
+        MountReading::new(target,
+                          RadianAngle::new(f64::atan2(-dot(y), dot(x)) + index.0).unwrap(),
+                          RadianAngle::new(dot(z).asin() + index.1).unwrap(),
+                          time)
+    }
+
+    #[test]
+    fn test_polar_alignment_error() {
+        // The axis 10' too high and 5' east of the pole, with index errors of a degree.
+        let geo = GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                                 RadianAngle::new(105f64.to_radians()).unwrap(),
+                                 0.0)
+            .unwrap();
+        let (alt, az) = ((10.0f64 / 60.0).to_radians(), (5.0f64 / 60.0).to_radians());
+        let pole = [alt, az * geo.latitude().radians().cos(), 1.0];
+        let index = (1f64.to_radians(), -1f64.to_radians());
+
+        let time = Builder::from_gregorian_utc(2017, 3, 1, 5, 0, 0).build().unwrap();
+        let theta = time.apparent_sidereal_greenwich().unwrap() - 105.0;
+        let east = coords(theta + 50.0, 50.0, time);
+        let west = coords(theta - 40.0, 20.0, time);
+
+        let first = reading(east, geo, time, pole, index);
+        let second = reading(west, geo, time, pole, index);
+        let error = polar_alignment_error(&first, &second, geo).unwrap();
+        assert!(approx_eq(arcminutes(error.altitude()), 10.0, 0.1));
+        assert!(approx_eq(arcminutes(error.azimuth()), 5.0, 0.1));
+        assert!(approx_eq(arcminutes(error.total(geo)), 10.0f64.hypot(5.0 * 0.766), 0.1));
+
+        // A well aligned mount.
+        let pole = [0.0, 0.0, 1.0];
+        let first = reading(east, geo, time, pole, index);
+        let second = reading(west, geo, time, pole, index);
+        let error = polar_alignment_error(&first, &second, geo).unwrap();
+        assert!(arcminutes(error.total(geo)) < 1.0e-6);
+
+        // Stars on the equator can't separate the errors.
+        let first = reading(coords(theta + 50.0, 0.0, time), geo, time, pole, index);
+        let second = reading(coords(theta - 40.0, 0.0, time), geo, time, pole, index);
+        assert!(polar_alignment_error(&first, &second, geo).is_err());
+    }
+
+    #[test]
+    fn test_southern_hemisphere() {
+        // The axis points 10' above and 5' east of the south celestial pole, so the northern end
+        // of the axis is displaced away from the meridian and to the west.
+        let geo = GeoCoords::new(RadianAngle::new(-30f64.to_radians()).unwrap(),
+                                 RadianAngle::new(-150f64.to_radians()).unwrap(),
+                                 0.0)
+            .unwrap();
+        let (alt, az) = ((10.0f64 / 60.0).to_radians(), (5.0f64 / 60.0).to_radians());
+        let pole = [-alt, -az * geo.latitude().radians().cos(), 1.0];
+
+        let time = Builder::from_gregorian_utc(2017, 3, 1, 12, 0, 0).build().unwrap();
+        let theta = time.apparent_sidereal_greenwich().unwrap() + 150.0;
+        let first = reading(coords(theta + 60.0, -50.0, time), geo, time, pole, (0.0, 0.0));
+        let second = reading(coords(theta - 30.0, -20.0, time), geo, time, pole, (0.0, 0.0));
+        let error = polar_alignment_error(&first, &second, geo).unwrap();
+        assert!(approx_eq(arcminutes(error.altitude()), 10.0, 0.1));
+        assert!(approx_eq(arcminutes(error.azimuth()), 5.0, 0.1));
+    }
+}
@@ -0,0 +1,265 @@
+//!
+//! Module for the pointing models of altitude-azimuth telescopes.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The model gives the offsets of the readings of the mount's axes, when it is centered on a
+//! target, from the predicted position of the target. With E the altitude and A the azimuth,
+//! measured westward from the south, the terms add
+//!
+//! ΔA = IA + CA sec E + NPAE tan E + AS tan E sin A - AW tan E cos A
+//!
+//! ΔE = IE + AS cos A + AW sin A + TF cos E
+//!
+//! where IA and IE are the index errors, CA the collimation error, NPAE the non-perpendicularity
+//! of the axes, AS and AW the tilt of the azimuth axis towards the south and west, and TF the
+//! flexure of the tube.
+//!
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::least_squares::least_squares;
+use super::super::math::*;
+
+/// A term of the pointing model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointingTerm {
+    /// The index error of the azimuth axis, a constant offset.
+    AzimuthIndex,
+    /// The index error of the altitude axis, a constant offset.
+    AltitudeIndex,
+    /// The collimation error, the optical axis not being perpendicular to the altitude axis.
+    Collimation,
+    /// The altitude axis not being perpendicular to the azimuth axis.
+    NonPerpendicularity,
+    /// The tilt of the azimuth axis from the vertical towards the south.
+    TiltSouth,
+    /// The tilt of the azimuth axis from the vertical towards the west.
+    TiltWest,
+    /// The flexure of the tube, which sags most when horizontal.
+    TubeFlexure,
+}
+
+// The terms in the order of the coefficients.
+const TERMS: [PointingTerm; 7] = [PointingTerm::AzimuthIndex,
+                                  PointingTerm::AltitudeIndex,
+                                  PointingTerm::Collimation,
+                                  PointingTerm::NonPerpendicularity,
+                                  PointingTerm::TiltSouth,
+                                  PointingTerm::TiltWest,
+                                  PointingTerm::TubeFlexure];
+
+/// A pointing model of an altitude-azimuth mount.
+///
+/// The default model is a perfect mount with all terms zero.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PointingModel {
+    coefficients: [f64; 7],
+}
+
+impl PointingModel {
+    /// Fit a model to pairs of predicted and measured positions, where the measured position is
+    /// read from the mount while it is centered on the target.
+    ///
+    /// The azimuth offsets are weighted by the cosine of the altitude, so all offsets are weighted
+    /// by their size on the sky. The positions should be spread over the sky, and an error is
+    /// returned if the terms can't be separated.
+    pub fn fit(pairs: &[(HorizontalCoords, HorizontalCoords)]) -> AstroResult<PointingModel> {
+        let mut rows = Vec::with_capacity(2 * pairs.len());
+        let mut values = Vec::with_capacity(2 * pairs.len());
+
+        for &(predicted, measured) in pairs {
+            let (delta_a, delta_e) = differences(predicted, measured);
+            let cos_e = cos(predicted.altitude().radians());
+
+            let (a_row, e_row) = partials(predicted);
+            rows.push(a_row.iter().map(|c| c * cos_e).collect());
+            values.push(delta_a * cos_e);
+            rows.push(e_row.to_vec());
+            values.push(delta_e);
+        }
+
+        match least_squares(&rows, &values) {
+            Some(c) => {
+                let mut coefficients = [0.0; 7];
+                coefficients.copy_from_slice(&c);
+                Ok(PointingModel { coefficients })
+            }
+            None => {
+                Err(AstroAlgorithmsError::InvalidArgument("Not enough positions to fit the \
+                                                           pointing model"
+                    .to_owned()))
+            }
+        }
+    }
+
+    /// Create a copy of the model with a term set.
+    pub fn with_term(mut self, term: PointingTerm, value: RadianAngle) -> PointingModel {
+        self.coefficients[index(term)] = value.radians();
+        self
+    }
+
+    /// The value of a term.
+    pub fn term(&self, term: PointingTerm) -> RadianAngle {
+        RadianAngle::new(self.coefficients[index(term)]).unwrap()
+    }
+
+    /// Calculate the offsets in azimuth and altitude of the mount's readings from the predicted
+    /// position of a target.
+    pub fn offsets(&self, predicted: HorizontalCoords) -> (RadianAngle, RadianAngle) {
+        let (a_row, e_row) = partials(predicted);
+        let dot = |row: [f64; 7]| -> f64 {
+            row.iter().zip(&self.coefficients).map(|(r, c)| r * c).sum()
+        };
+
+        (RadianAngle::new(dot(a_row)).unwrap(), RadianAngle::new(dot(e_row)).unwrap())
+    }
+
+    /// Correct the predicted position of a target to where the mount should point to center it.
+    ///
+    /// The altitude is limited to the zenith.
+    pub fn correct(&self, predicted: HorizontalCoords) -> AstroResult<HorizontalCoords> {
+        let (delta_a, delta_e) = self.offsets(predicted);
+        let altitude = (predicted.altitude().radians() + delta_e.radians())
+            .clamp(-FRAC_PI_2, FRAC_PI_2);
+
+        HorizontalCoords::new(RadianAngle::new(altitude)?,
+                              RadianAngle::new(predicted.azimuth().radians() + delta_a.radians())?)
+    }
+
+    /// The root mean square of the distances on the sky between the measured positions and the
+    /// corrected predicted positions.
+    pub fn rms_residual(&self, pairs: &[(HorizontalCoords, HorizontalCoords)]) -> RadianAngle {
+        if pairs.is_empty() {
+            return RadianAngle::new(0.0).unwrap();
+        }
+
+        let sum: f64 = pairs.iter()
+            .map(|&(predicted, measured)| {
+                let (delta_a, delta_e) = differences(predicted, measured);
+                let (model_a, model_e) = self.offsets(predicted);
+                let cos_e = cos(predicted.altitude().radians());
+                ((delta_a - model_a.radians()) * cos_e).powi(2) +
+                (delta_e - model_e.radians()).powi(2)
+            })
+            .sum();
+
+        RadianAngle::new(f64::sqrt(sum / pairs.len() as f64)).unwrap()
+    }
+}
+
+fn index(term: PointingTerm) -> usize {
+    TERMS.iter().position(|&t| t == term).unwrap()
+}
+
+// The differences of the measured and predicted azimuth and altitude.
+fn differences(predicted: HorizontalCoords, measured: HorizontalCoords) -> (f64, f64) {
+    let delta_a = map_to_branch(measured.azimuth().radians() - predicted.azimuth().radians(),
+                                -PI,
+                                PI);
+    (delta_a, measured.altitude().radians() - predicted.altitude().radians())
+}
+
+// The partial derivatives of the azimuth and altitude offsets with respect to the terms.
+fn partials(hz: HorizontalCoords) -> ([f64; 7], [f64; 7]) {
+    let (sin_a, cos_a) = sin_cos(hz.azimuth().radians());
+    let e = hz.altitude().radians();
+    let (tan_e, cos_e) = (tan(e), cos(e));
+
+    ([1.0, 0.0, 1.0 / cos_e, tan_e, tan_e * sin_a, -tan_e * cos_a, 0.0],
+     [0.0, 1.0, 0.0, 0.0, cos_a, sin_a, cos_e])
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod pointing_tests {
+    use super::*;
+
+    fn arcseconds(angle: RadianAngle) -> f64 {
+        angle.radians().to_degrees() * 3600.0
+    }
+
+    fn from_arcseconds(seconds: f64) -> RadianAngle {
+        RadianAngle::new((seconds / 3600.0).to_radians()).unwrap()
+    }
+
+    fn hz(alt_deg: f64, az_deg: f64) -> HorizontalCoords {
+        HorizontalCoords::new(RadianAngle::new(alt_deg.to_radians()).unwrap(),
+                              RadianAngle::new(az_deg.to_radians()).unwrap())
+            .unwrap()
+    }
+
+    fn mount() -> PointingModel {
+        PointingModel::default()
+            .with_term(PointingTerm::AzimuthIndex, from_arcseconds(120.0))
+            .with_term(PointingTerm::AltitudeIndex, from_arcseconds(-60.0))
+            .with_term(PointingTerm::Collimation, from_arcseconds(45.0))
+            .with_term(PointingTerm::NonPerpendicularity, from_arcseconds(-20.0))
+            .with_term(PointingTerm::TiltSouth, from_arcseconds(30.0))
+            .with_term(PointingTerm::TiltWest, from_arcseconds(-15.0))
+            .with_term(PointingTerm::TubeFlexure, from_arcseconds(25.0))
+    }
+
+    #[test]
+    fn test_fit() {
+        // Stars spread over the sky, read with the mount's errors and a few arc-seconds of noise.
+        let mut pairs = vec![];
+        let noise = [2.0, -1.0, -2.0, 1.0];
+        for (i, &alt) in [15.0, 35.0, 55.0, 75.0].iter().enumerate() {
+            for az in 0..8 {
+                let predicted = hz(alt, 45.0 * az as f64 + 10.0 * i as f64);
+                let (delta_a, delta_e) = mount().offsets(predicted);
+                let n = from_arcseconds(noise[(az + i) % 4]).radians();
+                let alt = predicted.altitude().radians() + delta_e.radians() + n;
+                let az = predicted.azimuth().radians() + delta_a.radians() - n;
+                let measured = HorizontalCoords::new(RadianAngle::new(alt).unwrap(),
+                                                     RadianAngle::new(az).unwrap())
+                    .unwrap();
+                pairs.push((predicted, measured));
+            }
+        }
+
+        let model = PointingModel::fit(&pairs).unwrap();
+        for &term in &TERMS {
+            assert!(approx_eq(arcseconds(model.term(term)), arcseconds(mount().term(term)), 2.0));
+        }
+        assert!(arcseconds(model.rms_residual(&pairs)) < 2.5);
+        assert!(arcseconds(PointingModel::default().rms_residual(&pairs)) > 60.0);
+
+        // Too few positions.
+        assert!(PointingModel::fit(&pairs[..3]).is_err());
+    }
+
+    #[test]
+    fn test_correct() {
+        let predicted = hz(45.0, 200.0);
+        let corrected = mount().correct(predicted).unwrap();
+        let (delta_a, delta_e) = mount().offsets(predicted);
+        assert!(approx_eq(corrected.altitude().radians() - predicted.altitude().radians(),
+                          delta_e.radians(),
+                          1.0e-12));
+        assert!(approx_eq(corrected.azimuth().radians() - predicted.azimuth().radians(),
+                          delta_a.radians(),
+                          1.0e-12));
+
+        // A perfect mount needs no correction.
+        assert_eq!(PointingModel::default().correct(predicted).unwrap(), predicted);
+
+        // At 45 degrees the collimation is larger in azimuth by the secant of the altitude.
+        let collimation = PointingModel::default()
+            .with_term(PointingTerm::Collimation, from_arcseconds(60.0));
+        let (delta_a, delta_e) = collimation.offsets(predicted);
+        assert!(approx_eq(arcseconds(delta_a), 60.0 * 2f64.sqrt(), 1.0e-9));
+        assert_eq!(delta_e.radians(), 0.0);
+    }
+}
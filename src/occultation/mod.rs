@@ -0,0 +1,303 @@
+//!
+//! Module for predicting occultations of stars by the Moon.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! An occultation is treated like a solar eclipse with the Sun replaced by a star. Since the star
+//! is infinitely far away the Moon's shadow is a cylinder, and the observer sees the star behind
+//! the Moon while inside it. The geometry is worked out on the fundamental plane through the
+//! center of the Earth perpendicular to the direction of the star.
+//!
+use std::f64::consts::PI;
+
+use super::astro_time::{AstroTime, Builder};
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+use super::moon;
+use super::stars::Star;
+
+// Radius of the Moon in Earth radii.
+const MOON_RADIUS: f64 = 0.272_507_6;
+
+// Steps for the coarse and fine searches, in days.
+const COARSE_STEP: f64 = 1.0 / 24.0;
+const FINE_STEP: f64 = 2.0 / 1440.0;
+
+// Largest geocentric separation of the Moon and a star at the ends of a coarse step for which an
+// occultation is possible somewhere on the Earth during the step, in radians.
+const CANDIDATE_SEPARATION: f64 = 0.028;
+
+/// Whether a star is disappearing behind or reappearing from behind the Moon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccultationKind {
+    /// The star disappears behind the Moon's limb.
+    Disappearance,
+    /// The star reappears from behind the Moon's limb.
+    Reappearance,
+}
+
+/// A disappearance or reappearance of a star at the limb of the Moon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occultation {
+    star: String,
+    kind: OccultationKind,
+    time: AstroTime,
+    position_angle: RadianAngle,
+    altitude: RadianAngle,
+}
+
+impl Occultation {
+    /// The name of the star.
+    pub fn star(&self) -> &str {
+        &self.star
+    }
+
+    /// Whether this is a disappearance or a reappearance.
+    pub fn kind(&self) -> OccultationKind {
+        self.kind
+    }
+
+    /// The time of the event in Universal Time.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+
+    /// The position angle on the Moon's limb where the event happens, measured from the north
+    /// point of the limb towards the east.
+    pub fn position_angle(&self) -> RadianAngle {
+        self.position_angle
+    }
+
+    /// The geometric altitude of the star at the time of the event.
+    pub fn altitude(&self) -> RadianAngle {
+        self.altitude
+    }
+}
+
+/// Predict the occultations of stars by the Moon for an observer between two times.
+///
+/// Only events with the star above the observer's horizon are returned, sorted by time. Events
+/// near the ends of the range belonging to an occultation that began before `start` or ends
+/// after `end` are included on their own, and very short grazing occultations lasting less than a
+/// couple minutes may be missed.
+pub fn lunar_occultations(stars: &[Star],
+                          geo: GeoCoords,
+                          start: &AstroTime,
+                          end: &AstroTime)
+                          -> AstroResult<Vec<Occultation>> {
+    let jd_start = start.as_utc()?.julian_day_number();
+    let jd_end = end.as_utc()?.julian_day_number();
+    if jd_end < jd_start {
+        return Err(AstroAlgorithmsError::InvalidArgument("The end of the range for occultations \
+                                                          must not be before the start"
+            .to_owned()));
+    }
+
+    // The mean places are good enough to find candidates.
+    let mut positions = Vec::with_capacity(stars.len());
+    for star in stars {
+        positions.push(star.mean_position(start)?);
+    }
+
+    let mut events = vec![];
+    let mut jd = jd_start;
+    let mut moon_start = moon::apparent_equatorial(&ut(jd)?)?;
+    while jd < jd_end {
+        let jd_next = f64::min(jd + COARSE_STEP, jd_end);
+        let moon_end = moon::apparent_equatorial(&ut(jd_next)?)?;
+
+        for (star, position) in stars.iter().zip(&positions) {
+            if angular_separation(moon_start, *position).radians() < CANDIDATE_SEPARATION ||
+               angular_separation(moon_end, *position).radians() < CANDIDATE_SEPARATION {
+                let apparent = star.apparent_position(&ut(jd)?)?;
+                find_events(star, apparent, geo, jd, jd_next, &mut events)?;
+            }
+        }
+
+        jd = jd_next;
+        moon_start = moon_end;
+    }
+
+    events.sort_by(|a, b| {
+        a.time.julian_day_number().partial_cmp(&b.time.julian_day_number()).unwrap()
+    });
+
+    Ok(events)
+}
+
+// Search between two Julian days for crossings of the Moon's limb.
+fn find_events(star: &Star,
+               apparent: EquatorialCoords,
+               geo: GeoCoords,
+               jd_start: f64,
+               jd_end: f64,
+               events: &mut Vec<Occultation>)
+               -> AstroResult<()> {
+    let mut jd = jd_start;
+    let mut f_start = shadow_function(apparent, geo, jd)?.0;
+
+    while jd < jd_end {
+        let jd_next = f64::min(jd + FINE_STEP, jd_end);
+        let f_end = shadow_function(apparent, geo, jd_next)?.0;
+
+        if f_start.signum() != f_end.signum() {
+            // Bisect down to about 0.01 seconds.
+            let (mut lo, mut hi) = (jd, jd_next);
+            while hi - lo > 1.0e-7 {
+                let mid = 0.5 * (lo + hi);
+                if shadow_function(apparent, geo, mid)?.0.signum() == f_start.signum() {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+
+            let jd_event = 0.5 * (lo + hi);
+            let time = ut(jd_event)?;
+            let altitude = trans_equatorial_to_horizontal(apparent, geo, &time)?.altitude();
+
+            if altitude.radians() > 0.0 {
+                let (_, position_angle) = shadow_function(apparent, geo, jd_event)?;
+                events.push(Occultation {
+                    star: star.name().to_owned(),
+                    kind: if f_start > 0.0 {
+                        OccultationKind::Disappearance
+                    } else {
+                        OccultationKind::Reappearance
+                    },
+                    time,
+                    position_angle,
+                    altitude,
+                });
+            }
+        }
+
+        jd = jd_next;
+        f_start = f_end;
+    }
+
+    Ok(())
+}
+
+// The square of the distance of the observer from the axis of the Moon's shadow minus the square
+// of the Moon's radius, which is negative while the star is hidden, along with the position angle
+// of the star from the center of the Moon.
+fn shadow_function(star: EquatorialCoords,
+                   geo: GeoCoords,
+                   jd: f64)
+                   -> AstroResult<(f64, RadianAngle)> {
+    let time = ut(jd)?;
+    let moon = moon::apparent_equatorial(&time)?;
+    let r = moon::distance(&time)? / EARTH_EQUATORIAL_RADIUS_KM;
+
+    // The Moon on the fundamental plane.
+    let (sin_d0, cos_d0) = sin_cos(star.declination().radians());
+    let (sin_d, cos_d) = sin_cos(moon.declination().radians());
+    let (sin_da, cos_da) = sin_cos(moon.right_ascension().radians() -
+                                   star.right_ascension().radians());
+    let x = r * cos_d * sin_da;
+    let y = r * (sin_d * cos_d0 - cos_d * sin_d0 * cos_da);
+
+    // The observer on the fundamental plane.
+    let (rho_sin_phi, rho_cos_phi) = geo.geocentric_position();
+    let (sin_h, cos_h) = sin_cos(local_hour_angle(star, geo, &time)?.radians());
+    let xi = rho_cos_phi * sin_h;
+    let eta = rho_sin_phi * cos_d0 - rho_cos_phi * sin_d0 * cos_h;
+
+    // x is towards the east and y towards the north.
+    let (u, v) = (xi - x, eta - y);
+    let mut position_angle = atan2(u, v);
+    if position_angle < 0.0 {
+        position_angle += 2.0 * PI;
+    }
+
+    Ok((u * u + v * v - MOON_RADIUS * MOON_RADIUS, RadianAngle::new(position_angle)?))
+}
+
+fn ut(jd: f64) -> AstroResult<AstroTime> {
+    Builder::from_julian_date(jd).build()
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod occultation_tests {
+    use super::*;
+
+    // An observer with the Moon overhead at the time, and a star at the Moon's position.
+    fn setup(jd: f64) -> (Star, GeoCoords) {
+        let time = ut(jd).unwrap();
+        let moon = moon::apparent_equatorial(&time).unwrap();
+        let star = Star::new("test", moon);
+
+        let h = local_hour_angle(moon, GeoCoords::new(RadianAngle::new(0.0).unwrap(),
+                                                      RadianAngle::new(0.0).unwrap(),
+                                                      0.0)
+                                     .unwrap(),
+                                 &time)
+            .unwrap();
+        let geo = GeoCoords::new(moon.declination(), h, 0.0).unwrap();
+
+        (star, geo)
+    }
+
+    #[test]
+    fn test_lunar_occultations() {
+        let jd = 2_457_986.5;
+        let (star, geo) = setup(jd);
+        let start = ut(jd - 1.0).unwrap();
+        let end = ut(jd + 1.0).unwrap();
+
+        let stars = [star];
+        let events = lunar_occultations(&stars, geo, &start, &end).unwrap();
+        assert_eq!(events.len(), 2);
+        let (d, r) = (&events[0], &events[1]);
+
+        assert_eq!(d.star(), "test");
+        assert_eq!(d.kind(), OccultationKind::Disappearance);
+        assert_eq!(r.kind(), OccultationKind::Reappearance);
+
+        // Nearly central with the Moon high in the sky, where the rotation of the Earth carries
+        // the observer along with the Moon, so it lasts well over an hour.
+        let t = |e: &Occultation| e.time().julian_day_number();
+        assert!(t(d) < jd && jd < t(r));
+        assert!(approx_eq((t(r) - t(d)) * 24.0, 1.5, 0.25));
+        assert!(d.altitude().radians().to_degrees() > 75.0);
+
+        // The Moon moves east, so the star disappears on the eastern limb and reappears on the
+        // western limb.
+        let pa = |e: &Occultation| e.position_angle().radians().to_degrees();
+        assert!(pa(d) > 0.0 && pa(d) < 180.0);
+        assert!(pa(r) > 180.0 && pa(r) < 360.0);
+
+        // At the events the star is on the limb.
+        for e in &events {
+            let apparent = stars[0].apparent_position(&e.time()).unwrap();
+            let f = shadow_function(apparent, geo, t(e)).unwrap().0;
+            assert!(f.abs() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn test_no_occultations() {
+        let jd = 2_457_986.5;
+        let (star, geo) = setup(jd);
+
+        // A week later the Moon is far away from the star.
+        let start = ut(jd + 7.0).unwrap();
+        let end = ut(jd + 8.0).unwrap();
+        let stars = [star];
+        assert!(lunar_occultations(&stars, geo, &start, &end).unwrap().is_empty());
+
+        assert!(lunar_occultations(&stars, geo, &end, &start).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//!
+//! Module for the embedded orbital elements of a few well known comets and asteroids.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The elements are osculating elements near a recent perihelion, or for Halley and Encke the
+//! apparitions worked in Astronomical Algorithms. Since the orbits are Keplerian, the positions
+//! degrade away from those perihelia, to a degree or more for the comets after a few revolutions.
+//! They are meant for examples and quick looks, use current elements from the Minor Planet Center
+//! for anything more.
+//!
+use super::super::astro_time::{Builder, Epoch};
+use super::super::coords::RadianAngle;
+use super::OrbitalElements;
+
+struct CatalogEntry {
+    name: &'static str,
+    designation: &'static str,
+    perihelion_jde: f64,
+    perihelion_distance: f64,
+    eccentricity: f64,
+    // The angles in degrees.
+    inclination: f64,
+    ascending_node: f64,
+    argument_of_perihelion: f64,
+    equinox: Epoch,
+}
+
+const CATALOG: [CatalogEntry; 4] = [CatalogEntry {
+                                        name: "Ceres",
+                                        designation: "1 Ceres",
+                                        perihelion_jde: 2_459_914.9,
+                                        perihelion_distance: 2.546_5,
+                                        eccentricity: 0.079_57,
+                                        inclination: 10.586_8,
+                                        ascending_node: 80.249_4,
+                                        argument_of_perihelion: 73.298_7,
+                                        equinox: Epoch::J2000,
+                                    },
+                                    CatalogEntry {
+                                        name: "Vesta",
+                                        designation: "4 Vesta",
+                                        perihelion_jde: 2_459_574.6,
+                                        perihelion_distance: 2.152_0,
+                                        eccentricity: 0.088_74,
+                                        inclination: 7.143_9,
+                                        ascending_node: 103.703_4,
+                                        argument_of_perihelion: 151.538_1,
+                                        equinox: Epoch::J2000,
+                                    },
+                                    CatalogEntry {
+                                        name: "Halley",
+                                        designation: "1P/Halley",
+                                        perihelion_jde: 2_446_470.938_67,
+                                        perihelion_distance: 0.587_099_2,
+                                        eccentricity: 0.967_272_5,
+                                        inclination: 162.239_32,
+                                        ascending_node: 58.143_97,
+                                        argument_of_perihelion: 111.846_58,
+                                        equinox: Epoch::B1950,
+                                    },
+                                    CatalogEntry {
+                                        name: "Encke",
+                                        designation: "2P/Encke",
+                                        perihelion_jde: 2_448_193.045_02,
+                                        perihelion_distance: 0.330_885_9,
+                                        eccentricity: 0.850_219_6,
+                                        inclination: 11.945_24,
+                                        ascending_node: 334.750_06,
+                                        argument_of_perihelion: 186.233_52,
+                                        equinox: Epoch::J2000,
+                                    }];
+
+/// Look up the embedded orbital elements of a comet or asteroid by its name, such as "Ceres" or
+/// "Halley", or its designation, such as "1 Ceres" or "1P/Halley", ignoring case.
+///
+/// Available with the `small-bodies` feature. Returns `None` if the body isn't in the catalog.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::orbit::elements_by_name;
+/// let ceres = elements_by_name("ceres").unwrap();
+/// assert!(ceres.semimajor_axis().unwrap() > 2.7);
+/// assert!(elements_by_name("Pluto").is_none());
+/// ```
+pub fn elements_by_name(name: &str) -> Option<OrbitalElements> {
+    let name = name.trim();
+    let entry = CATALOG.iter().find(|entry| {
+            entry.name.eq_ignore_ascii_case(name) || entry.designation.eq_ignore_ascii_case(name)
+        })?;
+
+    let time = |jde| Builder::from_julian_date(jde).dynamical_time().build().unwrap();
+    let degrees = |angle: f64| RadianAngle::new(angle.to_radians()).unwrap();
+    let equinox = entry.equinox.time().ok()?;
+
+    OrbitalElements::new(time(entry.perihelion_jde),
+                         entry.perihelion_distance,
+                         entry.eccentricity,
+                         degrees(entry.inclination),
+                         degrees(entry.ascending_node),
+                         degrees(entry.argument_of_perihelion),
+                         equinox)
+        .ok()
+}
+
+/// The designations of the comets and asteroids in the embedded catalog.
+///
+/// Available with the `small-bodies` feature.
+pub fn catalog_designations() -> Vec<&'static str> {
+    CATALOG.iter().map(|entry| entry.designation).collect()
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod catalog_tests {
+    use super::*;
+    use astro_time::{B1950, J2000};
+
+    #[test]
+    fn test_equinoxes() {
+        assert!(elements_by_name("Ceres").unwrap().equinox() == J2000);
+        assert!(elements_by_name("Halley").unwrap().equinox() == B1950);
+    }
+
+    #[test]
+    fn test_elements_by_name() {
+        for designation in catalog_designations() {
+            assert!(elements_by_name(designation).is_some());
+        }
+        assert_eq!(elements_by_name("HALLEY"), elements_by_name(" 1p/halley "));
+        assert!(elements_by_name("").is_none());
+        assert!(elements_by_name("3P").is_none());
+
+        // The periods, in years.
+        let period = |name| elements_by_name(name).unwrap().semimajor_axis().unwrap().powf(1.5);
+        assert!(approx_eq(period("Ceres"), 4.60, 0.01));
+        assert!(approx_eq(period("Vesta"), 3.63, 0.01));
+        assert!(approx_eq(period("Halley"), 76.0, 0.1));
+        assert!(approx_eq(period("Encke"), 3.28, 0.01));
+    }
+
+    #[test]
+    fn test_halley() {
+        // At its closest approach of 1986 April 11 Halley was 0.42 AU from the Earth, far south
+        // in Lupus.
+        let time = Builder::from_gregorian_utc(1986, 4, 11, 0, 0, 0).build().unwrap();
+        let halley = elements_by_name("Halley").unwrap();
+        let (eq, distance) = halley.geocentric_position(&time).unwrap();
+        assert!(approx_eq(distance, 0.42, 0.01));
+        assert!(approx_eq(eq.declination().radians().to_degrees(), -45.0, 3.0));
+    }
+}
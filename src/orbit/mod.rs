@@ -0,0 +1,426 @@
+//!
+//! Module for bodies moving on unperturbed orbits around the Sun.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Follows chapters 30, 33 and 34 of Astronomical Algorithms, 2nd Edition by Jean Meeus for
+//! elliptic, parabolic and hyperbolic orbits. The orbits are Keplerian, so the perturbations by
+//! the planets are neglected.
+//!
+#[cfg(feature = "small-bodies")]
+mod catalog;
+
+#[cfg(feature = "small-bodies")]
+pub use self::catalog::*;
+
+use std::f64::consts::PI;
+
+use super::astro_time::AstroTime;
+use super::coords::*;
+use super::earth::{self, EarthOrigin};
+use super::error::*;
+use super::math::*;
+
+// The Gaussian gravitational constant, radians per day.
+const GAUSSIAN_GRAVITATIONAL_CONSTANT: f64 = 0.017_202_098_95;
+
+// The greatest distance from the Sun sampled on parabolic and hyperbolic orbits, in astronomical
+// units.
+const OPEN_ORBIT_SAMPLE_LIMIT: f64 = 50.0;
+
+/// The elements of an orbit around the Sun.
+///
+/// The angles are referred to the ecliptic and equinox of an epoch, usually J2000.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    perihelion_time: AstroTime,
+    perihelion_distance: f64,
+    eccentricity: f64,
+    inclination: RadianAngle,
+    ascending_node: RadianAngle,
+    argument_of_perihelion: RadianAngle,
+    equinox: AstroTime,
+}
+
+impl OrbitalElements {
+    /// Create new elements from the time of perihelion passage, the perihelion distance in
+    /// astronomical units, the eccentricity, the inclination, the longitude of the ascending node,
+    /// the argument of the perihelion, and the epoch of the ecliptic and equinox.
+    ///
+    /// The perihelion distance must be positive, the eccentricity must not be negative, and the
+    /// inclination must be in the range [0, π].
+    pub fn new(perihelion_time: AstroTime,
+               perihelion_distance: f64,
+               eccentricity: f64,
+               inclination: RadianAngle,
+               ascending_node: RadianAngle,
+               argument_of_perihelion: RadianAngle,
+               equinox: AstroTime)
+               -> AstroResult<OrbitalElements> {
+        if perihelion_distance.is_nan() || eccentricity.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if perihelion_distance.is_infinite() || eccentricity.is_infinite() {
+            Err(AstroAlgorithmsError::EncounteredInf)
+        } else if perihelion_distance <= 0.0 || eccentricity < 0.0 {
+            Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue)
+        } else if !(0.0..=PI).contains(&inclination.radians()) {
+            Err(AstroAlgorithmsError::InvalidAngle("Inclination limited to range [0, 180] degrees"
+                .to_owned()))
+        } else {
+            Ok(OrbitalElements {
+                perihelion_time,
+                perihelion_distance,
+                eccentricity,
+                inclination,
+                ascending_node,
+                argument_of_perihelion,
+                equinox,
+            })
+        }
+    }
+
+    /// The time of the perihelion passage.
+    pub fn perihelion_time(&self) -> AstroTime {
+        self.perihelion_time
+    }
+
+    /// The perihelion distance in astronomical units.
+    pub fn perihelion_distance(&self) -> f64 {
+        self.perihelion_distance
+    }
+
+    /// The eccentricity.
+    pub fn eccentricity(&self) -> f64 {
+        self.eccentricity
+    }
+
+    /// The inclination to the ecliptic.
+    pub fn inclination(&self) -> RadianAngle {
+        self.inclination
+    }
+
+    /// The longitude of the ascending node.
+    pub fn ascending_node(&self) -> RadianAngle {
+        self.ascending_node
+    }
+
+    /// The argument of the perihelion, measured from the ascending node.
+    pub fn argument_of_perihelion(&self) -> RadianAngle {
+        self.argument_of_perihelion
+    }
+
+    /// The epoch of the ecliptic and equinox the angles are referred to.
+    pub fn equinox(&self) -> AstroTime {
+        self.equinox
+    }
+
+    /// The longitude of the perihelion, the sum of the longitude of the ascending node and the
+    /// argument of the perihelion, in the range [0, 2π).
+    pub fn longitude_of_perihelion(&self) -> RadianAngle {
+        let pi = self.ascending_node.radians() + self.argument_of_perihelion.radians();
+
+        RadianAngle::new(map_to_branch(pi, 0.0, 2.0 * PI)).unwrap()
+    }
+
+    /// The semimajor axis in astronomical units, or `None` for parabolic and hyperbolic orbits.
+    pub fn semimajor_axis(&self) -> Option<f64> {
+        if self.eccentricity < 1.0 {
+            Some(self.perihelion_distance / (1.0 - self.eccentricity))
+        } else {
+            None
+        }
+    }
+
+    /// Calculate the heliocentric rectangular ecliptic coordinates (x, y, z) in astronomical
+    /// units at a time, referred to the ecliptic and equinox of the elements.
+    pub fn heliocentric_position(&self, time: &AstroTime) -> AstroResult<(f64, f64, f64)> {
+        let days = time.julian_ephemeris_day()? - self.perihelion_time.julian_ephemeris_day()?;
+        let (q, e) = (self.perihelion_distance, self.eccentricity);
+        let k = GAUSSIAN_GRAVITATIONAL_CONSTANT;
+
+        let (v, r) = if e < 1.0 {
+            // Kepler's equation, chapter 30.
+            let a = q / (1.0 - e);
+            let m = map_to_branch(k * days / (a * a * a).sqrt(), -PI, PI);
+            let start = m + 0.85 * e * sin(m).signum();
+            let big_e = solve(start, |x| x - e * sin(x) - m, |x| 1.0 - e * cos(x));
+            let v = 2.0 * atan(((1.0 + e) / (1.0 - e)).sqrt() * tan(big_e / 2.0));
+            (v, a * (1.0 - e * cos(big_e)))
+        } else if e == 1.0 {
+            // Barker's equation, chapter 34.
+            let w = 3.0 * k / (2.0 * q * q * q).sqrt() * days;
+            let y = cbrt(w / 2.0 + (w * w / 4.0 + 1.0).sqrt());
+            let s = y - 1.0 / y;
+            (2.0 * atan(s), q * (1.0 + s * s))
+        } else {
+            let a = q / (e - 1.0);
+            let m = k * days / (a * a * a).sqrt();
+            let h = solve(m.signum() * ln(2.0 * m.abs() / e + 1.0),
+                          |x| e * sinh(x) - x - m,
+                          |x| e * cosh(x) - 1.0);
+            let v = 2.0 * atan(((e + 1.0) / (e - 1.0)).sqrt() * tanh(h / 2.0));
+            (v, a * (e * cosh(h) - 1.0))
+        };
+
+        Ok(self.position_at(v, r))
+    }
+
+    /// Calculate the geocentric equatorial coordinates and the distance from the Earth in
+    /// astronomical units at a time.
+    ///
+    /// The position is corrected for the light time, and is referred to the mean equator and
+    /// equinox of the time. Aberration and nutation are not applied.
+    pub fn geocentric_position(&self, time: &AstroTime) -> AstroResult<(EquatorialCoords, f64)> {
+        self.geocentric_position_with(time, EarthOrigin::Geocenter)
+    }
+
+    /// Calculate the equatorial coordinates and the distance in astronomical units at a time as
+    /// seen from the center of the Earth or the Earth-Moon barycenter.
+    ///
+    /// The position is corrected for the light time, and is referred to the mean equator and
+    /// equinox of the time. Aberration and nutation are not applied.
+    pub fn geocentric_position_with(&self,
+                                    time: &AstroTime,
+                                    origin: EarthOrigin)
+                                    -> AstroResult<(EquatorialCoords, f64)> {
+        let rotation = self.equatorial_rotation(time)?;
+        let earth = earth_equatorial(time, origin)?;
+
+        let (eq, tau) = light_time_corrected(time, |t| {
+            let point = rotation.rotate(self.heliocentric_position(t)?);
+            let (x, y, z) = (point.0 - earth.0, point.1 - earth.1, point.2 - earth.2);
+            Ok((geocentric(point, earth, time)?, (x * x + y * y + z * z).sqrt()))
+        })?;
+
+        Ok((eq, light_travel_distance(tau)))
+    }
+
+    /// Sample `n` heliocentric positions around the orbit, equally spaced in the true anomaly, as
+    /// rectangular ecliptic coordinates (x, y, z) in astronomical units referred to the ecliptic
+    /// and equinox of the elements.
+    ///
+    /// Elliptic orbits are sampled all the way around starting at the perihelion. Parabolic and
+    /// hyperbolic orbits are sampled out to 50 astronomical units from the Sun.
+    pub fn sample_orbit(&self, n: usize) -> Vec<(f64, f64, f64)> {
+        let (q, e) = (self.perihelion_distance, self.eccentricity);
+        let p = q * (1.0 + e);
+
+        let (start, span) = if e < 1.0 {
+            (0.0, 2.0 * PI * (1.0 - 1.0 / n.max(1) as f64))
+        } else {
+            let limit = f64::max(OPEN_ORBIT_SAMPLE_LIMIT, 2.0 * q);
+            let v_max = acos(((p / limit - 1.0) / e).clamp(-1.0, 1.0));
+            (-v_max, 2.0 * v_max)
+        };
+
+        (0..n)
+            .map(|i| {
+                let v = start + span * if n > 1 { i as f64 / (n - 1) as f64 } else { 0.5 };
+                self.position_at(v, p / (1.0 + e * cos(v)))
+            })
+            .collect()
+    }
+
+    /// Sample `n` points around the orbit as in `sample_orbit`, projected onto the sky as seen
+    /// from the center of the Earth at a time.
+    ///
+    /// The points are geometric equatorial coordinates referred to the mean equator and equinox of
+    /// the time, which outline the path of the body among the stars.
+    pub fn sample_orbit_from_earth(&self,
+                                   n: usize,
+                                   time: &AstroTime)
+                                   -> AstroResult<Vec<EquatorialCoords>> {
+        let to_date = self.equatorial_rotation(time)?;
+        let earth = earth_equatorial(time, EarthOrigin::Geocenter)?;
+
+        self.sample_orbit(n)
+            .into_iter()
+            .map(|point| geocentric(to_date.rotate(point), earth, time))
+            .collect()
+    }
+
+    // The rectangular ecliptic coordinates for a true anomaly and radius vector.
+    fn position_at(&self, v: f64, r: f64) -> (f64, f64, f64) {
+        let (sin_node, cos_node) = sin_cos(self.ascending_node.radians());
+        let (sin_i, cos_i) = sin_cos(self.inclination.radians());
+        let (sin_u, cos_u) = sin_cos(self.argument_of_perihelion.radians() + v);
+
+        (r * (cos_node * cos_u - sin_node * sin_u * cos_i),
+         r * (sin_node * cos_u + cos_node * sin_u * cos_i),
+         r * sin_u * sin_i)
+    }
+
+    // The rotation from the ecliptic of the elements to the mean equator of a date.
+    fn equatorial_rotation(&self, time: &AstroTime) -> AstroResult<RotationMatrix> {
+        Ok(precession_matrix_between(&self.equinox, time)? *
+           ecliptic_to_equatorial_matrix(mean_obliquity(&self.equinox)?))
+    }
+}
+
+// Newton's method for f(x) = 0 from a starting value.
+fn solve<F, D>(mut x: f64, f: F, derivative: D) -> f64
+    where F: Fn(f64) -> f64,
+          D: Fn(f64) -> f64
+{
+    for _ in 0..50 {
+        let dx = f(x) / derivative(x);
+        x -= dx;
+        if dx.abs() < 1.0e-14 {
+            break;
+        }
+    }
+
+    x
+}
+
+// The heliocentric rectangular equatorial coordinates of the Earth, referred to the mean equator
+// and equinox of the date.
+fn earth_equatorial(time: &AstroTime, origin: EarthOrigin) -> AstroResult<(f64, f64, f64)> {
+    let position = earth::heliocentric_state_with(time, origin)?.position();
+
+    Ok(ecliptic_to_equatorial_matrix(mean_obliquity(time)?).rotate(position))
+}
+
+// The direction of a heliocentric position as seen from the Earth.
+fn geocentric(point: (f64, f64, f64),
+              earth: (f64, f64, f64),
+              time: &AstroTime)
+              -> AstroResult<EquatorialCoords> {
+    let (x, y, z) = (point.0 - earth.0, point.1 - earth.1, point.2 - earth.2);
+
+    EquatorialCoords::new(RadianAngle::new(atan2(y, x))?,
+                          RadianAngle::new(atan2(z, hypot(x, y)))?,
+                          *time)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod orbit_tests {
+    use super::*;
+    use astro_time::{Builder, J2000};
+
+    fn degrees(angle: f64) -> RadianAngle {
+        RadianAngle::new(angle.to_radians()).unwrap()
+    }
+
+    // Example 33.a, Astronomical Algorithms 2nd ed., comet Encke.
+    fn encke() -> OrbitalElements {
+        let perihelion = Builder::from_julian_date(2_448_192.5 + 0.545_02)
+            .dynamical_time()
+            .build()
+            .unwrap();
+        let a = 2.209_140_4;
+        let e = 0.850_219_6;
+
+        OrbitalElements::new(perihelion,
+                             a * (1.0 - e),
+                             e,
+                             degrees(11.945_24),
+                             degrees(334.750_06),
+                             degrees(186.233_52),
+                             J2000)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_orbital_elements_new() {
+        let elements = encke();
+        assert!(approx_eq(elements.semimajor_axis().unwrap(), 2.209_140_4, 1.0e-12));
+        assert!(approx_eq(elements.longitude_of_perihelion().radians().to_degrees(),
+                          160.983_58,
+                          1.0e-9));
+
+        let zero = degrees(0.0);
+        assert!(OrbitalElements::new(J2000, 0.0, 0.5, zero, zero, zero, J2000).is_err());
+        assert!(OrbitalElements::new(J2000, 1.0, -0.5, zero, zero, zero, J2000).is_err());
+        assert_eq!(OrbitalElements::new(J2000, f64::NAN, 0.5, zero, zero, zero, J2000)
+                       .unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+        assert_eq!(OrbitalElements::new(J2000, 1.0, f64::INFINITY, zero, zero, zero, J2000)
+                       .unwrap_err(),
+                   AstroAlgorithmsError::EncounteredInf);
+        assert!(OrbitalElements::new(J2000, 1.0, 0.5, degrees(-1.0), zero, zero, J2000).is_err());
+        assert!(OrbitalElements::new(J2000, 1.0, 0.5, degrees(181.0), zero, zero, J2000).is_err());
+        assert!(OrbitalElements::new(J2000, 1.0, 0.5, degrees(162.3), zero, zero, J2000).is_ok());
+        assert!(OrbitalElements::new(J2000, 1.0, 1.5, zero, zero, zero, J2000)
+            .unwrap()
+            .semimajor_axis()
+            .is_none());
+    }
+
+    #[test]
+    fn test_heliocentric_position() {
+        let elements = encke();
+        let time = Builder::from_julian_date(2_448_170.5).dynamical_time().build().unwrap();
+
+        // The book gives α = 158.558 965° and δ = 19.158 496° for the equinox of J2000.0,
+        // including the light time.
+        let (eq, distance) = elements.geocentric_position(&time).unwrap();
+        let eq = precess_equatorial(eq, J2000).unwrap();
+        assert!(approx_eq(eq.right_ascension().radians().to_degrees(), 158.558_965, 1.0e-4));
+        assert!(approx_eq(eq.declination().radians().to_degrees(), 19.158_496, 1.0e-4));
+        assert!(approx_eq(distance, 0.824_3, 1.0e-3));
+    }
+
+    #[test]
+    fn test_open_orbits() {
+        // A parabolic orbit at perihelion, and 90 degrees from it.
+        let zero = degrees(0.0);
+        let parabola = OrbitalElements::new(J2000, 1.0, 1.0, zero, zero, zero, J2000).unwrap();
+        let (x, y, _) = parabola.heliocentric_position(&J2000).unwrap();
+        assert!(approx_eq(x, 1.0, 1.0e-12) && approx_eq(y, 0.0, 1.0e-12));
+
+        // At v = 90°, s = 1 so the time from perihelion is 4 / 3 days times sqrt(2) / k.
+        let days = 4.0 / 3.0 * 2f64.sqrt() / GAUSSIAN_GRAVITATIONAL_CONSTANT;
+        let later = Builder::from_julian_date(2_451_545.0 + days).dynamical_time().build().unwrap();
+        let (x, y, _) = parabola.heliocentric_position(&later).unwrap();
+        assert!(approx_eq(x, 0.0, 1.0e-9) && approx_eq(y, 2.0, 1.0e-9));
+
+        // A hyperbolic orbit is continuous with an elliptic one of nearly the same eccentricity.
+        let hyperbola = OrbitalElements::new(J2000, 1.0, 1.000_01, zero, zero, zero, J2000)
+            .unwrap();
+        let ellipse = OrbitalElements::new(J2000, 1.0, 0.999_99, zero, zero, zero, J2000)
+            .unwrap();
+        let (hx, hy, _) = hyperbola.heliocentric_position(&later).unwrap();
+        let (ex, ey, _) = ellipse.heliocentric_position(&later).unwrap();
+        assert!(approx_eq(hx, ex, 1.0e-4) && approx_eq(hy, ey, 1.0e-4));
+    }
+
+    #[test]
+    fn test_sample_orbit() {
+        let elements = encke();
+        let points = elements.sample_orbit(36);
+        assert_eq!(points.len(), 36);
+
+        // Starts at the perihelion and reaches the aphelion half way around.
+        let r = |p: &(f64, f64, f64)| (p.0 * p.0 + p.1 * p.1 + p.2 * p.2).sqrt();
+        let q = elements.perihelion_distance();
+        let a = elements.semimajor_axis().unwrap();
+        assert!(approx_eq(r(&points[0]), q, 1.0e-12));
+        assert!(approx_eq(r(&points[18]), 2.0 * a - q, 1.0e-12));
+
+        // A parabola is sampled symmetrically out to 50 AU.
+        let zero = degrees(0.0);
+        let parabola = OrbitalElements::new(J2000, 1.0, 1.0, zero, zero, zero, J2000).unwrap();
+        let points = parabola.sample_orbit(11);
+        assert!(approx_eq(r(&points[0]), 50.0, 1.0e-9));
+        assert!(approx_eq(r(&points[5]), 1.0, 1.0e-12));
+        assert!(approx_eq(points[0].1, -points[10].1, 1.0e-9));
+        assert!(parabola.sample_orbit(0).is_empty());
+
+        // On the sky.
+        let time = Builder::from_julian_date(2_448_170.5).dynamical_time().build().unwrap();
+        let sky = elements.sample_orbit_from_earth(36, &time).unwrap();
+        assert_eq!(sky.len(), 36);
+        assert!(sky[0].epoch() == time);
+    }
+}
@@ -0,0 +1,226 @@
+//!
+//! Module for the positions of the major planets.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The positions are calculated from the approximate Keplerian elements of E. M. Standish, JPL,
+//! for the years 1800 to 2050, referred to the mean ecliptic and equinox of J2000.0. They are good
+//! to about 1' for the inner planets, and several arcminutes for the outer planets.
+//!
+use std::f64::consts::PI;
+
+use super::astro_time::{AstroDuration, AstroTime, J2000};
+use super::bodies::Body;
+use super::coords::*;
+use super::earth::EarthOrigin;
+use super::error::*;
+use super::math::*;
+use super::orbit::OrbitalElements;
+
+/// Calculate the mean elements of the orbit of a planet, referred to the ecliptic and equinox of
+/// J2000.0.
+///
+/// The time of perihelion is the one nearest to `time`. Returns an error for the Sun and Moon.
+pub fn orbital_elements(body: Body, time: &AstroTime) -> AstroResult<OrbitalElements> {
+    let epoch = time.as_dt()?;
+    let t = (epoch.julian_day_number() - 2_451_545.0) / 36_525.0;
+    let elements = standish_elements(body)?;
+    let element = |i: usize| elements[i].0 + elements[i].1 * t;
+
+    let a = element(0);
+    let e = element(1);
+    let pi = element(4);
+    let node = element(5);
+
+    // Days since perihelion from the mean anomaly and mean motion.
+    let m = map_to_branch((element(3) - pi).to_radians(), -PI, PI);
+    let n = 0.017_202_098_95 / (a * a * a).sqrt();
+    let perihelion = epoch.add_duration(AstroDuration::from_days(-m / n)?)?;
+
+    OrbitalElements::new(perihelion,
+                         a * (1.0 - e),
+                         e,
+                         RadianAngle::new(element(2).to_radians())?,
+                         RadianAngle::new(map_to_branch(node.to_radians(), 0.0, 2.0 * PI))?,
+                         RadianAngle::new(map_to_branch((pi - node).to_radians(), 0.0, 2.0 * PI))?,
+                         J2000)
+}
+
+/// Calculate the apparent geocentric equatorial coordinates of a planet, referred to the equator
+/// and equinox of the date.
+///
+/// The position is corrected for the light time and nutation. The aberration, at most about 20",
+/// is smaller than the errors of the elements and is neglected. Returns an error for the Sun and
+/// Moon.
+pub fn apparent_equatorial(body: Body, time: &AstroTime) -> AstroResult<EquatorialCoords> {
+    apparent_equatorial_with(body, time, EarthOrigin::Geocenter)
+}
+
+/// Calculate the apparent equatorial coordinates of a planet as seen from the center of the Earth
+/// or the Earth-Moon barycenter, referred to the equator and equinox of the date.
+///
+/// Seen from the barycenter the position differs by up to a few arcseconds for the nearest
+/// planets, and `apparent_equatorial` uses the center, see `EarthOrigin`. Returns an error for the
+/// Sun and Moon.
+pub fn apparent_equatorial_with(body: Body,
+                                time: &AstroTime,
+                                origin: EarthOrigin)
+                                -> AstroResult<EquatorialCoords> {
+    let (eq, _) = orbital_elements(body, time)?.geocentric_position_with(time, origin)?;
+
+    let (sin_a, cos_a) = sin_cos(eq.right_ascension().radians());
+    let (sin_d, cos_d) = sin_cos(eq.declination().radians());
+    let (x, y, z) = nutation_matrix(time)?.rotate((cos_d * cos_a, cos_d * sin_a, sin_d));
+
+    EquatorialCoords::new(RadianAngle::new(atan2(y, x))?,
+                          RadianAngle::new(atan2(z, hypot(x, y)))?,
+                          eq.epoch())
+}
+
+/// Calculate the distance between the Earth and a planet in astronomical units.
+///
+/// This is the distance the light travelled, so it is the distance at the time the light left the
+/// planet. Returns an error for the Sun and Moon.
+pub fn distance(body: Body, time: &AstroTime) -> AstroResult<f64> {
+    distance_with(body, time, EarthOrigin::Geocenter)
+}
+
+/// Calculate the distance of a planet from the center of the Earth or the Earth-Moon barycenter
+/// in astronomical units.
+///
+/// This is the distance the light travelled. Returns an error for the Sun and Moon.
+pub fn distance_with(body: Body, time: &AstroTime, origin: EarthOrigin) -> AstroResult<f64> {
+    Ok(orbital_elements(body, time)?.geocentric_position_with(time, origin)?.1)
+}
+
+// The elements and their rates per Julian century: the semimajor axis in AU, the eccentricity,
+// then the inclination, mean longitude, longitude of the perihelion and longitude of the
+// ascending node in degrees.
+fn standish_elements(body: Body) -> AstroResult<[(f64, f64); 6]> {
+    match body {
+        Body::Mercury => {
+            Ok([(0.387_099_27, 0.000_000_37),
+                (0.205_635_93, 0.000_019_06),
+                (7.004_979_02, -0.005_947_49),
+                (252.250_323_5, 149_472.674_111_75),
+                (77.457_796_28, 0.160_476_89),
+                (48.330_765_93, -0.125_340_81)])
+        }
+        Body::Venus => {
+            Ok([(0.723_335_66, 0.000_003_90),
+                (0.006_776_72, -0.000_041_07),
+                (3.394_676_05, -0.000_788_90),
+                (181.979_099_5, 58_517.815_387_29),
+                (131.602_467_18, 0.002_683_29),
+                (76.679_842_55, -0.277_694_18)])
+        }
+        Body::Mars => {
+            Ok([(1.523_710_34, 0.000_018_47),
+                (0.093_394_10, 0.000_078_82),
+                (1.849_691_42, -0.008_131_31),
+                (-4.553_432_05, 19_140.302_684_99),
+                (-23.943_629_59, 0.444_410_88),
+                (49.559_538_91, -0.292_573_43)])
+        }
+        Body::Jupiter => {
+            Ok([(5.202_887_00, -0.000_116_07),
+                (0.048_386_24, -0.000_132_53),
+                (1.304_396_95, -0.001_837_14),
+                (34.396_440_51, 3_034.746_127_75),
+                (14.728_479_83, 0.212_526_68),
+                (100.473_909_09, 0.204_691_06)])
+        }
+        Body::Saturn => {
+            Ok([(9.536_675_94, -0.001_250_60),
+                (0.053_861_79, -0.000_509_91),
+                (2.485_991_87, 0.001_936_09),
+                (49.954_244_23, 1_222.493_622_01),
+                (92.598_878_31, -0.418_972_16),
+                (113.662_424_48, -0.288_677_94)])
+        }
+        Body::Uranus => {
+            Ok([(19.189_164_64, -0.001_961_76),
+                (0.047_257_44, -0.000_043_97),
+                (0.772_637_83, -0.002_429_39),
+                (313.238_104_51, 428.482_027_85),
+                (170.954_276_3, 0.408_052_81),
+                (74.016_925_03, 0.042_405_89)])
+        }
+        Body::Neptune => {
+            Ok([(30.069_922_76, 0.000_262_91),
+                (0.008_590_48, 0.000_051_05),
+                (1.770_043_47, 0.000_353_72),
+                (-55.120_029_69, 218.459_453_25),
+                (44.964_762_27, -0.322_414_64),
+                (131.784_225_74, -0.005_086_64)])
+        }
+        Body::Sun | Body::Moon => {
+            Err(AstroAlgorithmsError::InvalidArgument("The Sun and Moon are not planets"
+                .to_owned()))
+        }
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod planets_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    #[test]
+    fn test_orbital_elements() {
+        let time = Builder::from_julian_date(2_451_545.0).dynamical_time().build().unwrap();
+        let mars = orbital_elements(Body::Mars, &time).unwrap();
+        assert!(approx_eq(mars.semimajor_axis().unwrap(), 1.523_710_34, 1.0e-8));
+        assert!(approx_eq(mars.eccentricity(), 0.093_394_10, 1.0e-8));
+        assert!(approx_eq(mars.longitude_of_perihelion().radians().to_degrees(),
+                          336.056_370_41,
+                          1.0e-8));
+
+        // The mean anomaly at the epoch, 19.39 degrees, gives the time of perihelion.
+        let days = time.duration_since(&mars.perihelion_time()).unwrap().days();
+        assert!(approx_eq(days, 19.390_197_46f64.to_radians() / 0.524_033_3f64.to_radians(), 0.1));
+
+        assert!(orbital_elements(Body::Moon, &time).is_err());
+        assert!(apparent_equatorial(Body::Sun, &time).is_err());
+    }
+
+    #[test]
+    fn test_apparent_equatorial() {
+        // Example 33.a, Astronomical Algorithms 2nd ed., Venus on 1992 December 20 at 0h TD.
+        let time = Builder::from_julian_date(2_448_976.5).dynamical_time().build().unwrap();
+        let eq = apparent_equatorial(Body::Venus, &time).unwrap();
+
+        let ra = (21.0 + 4.0 / 60.0 + 41.454 / 3600.0) * 15.0;
+        let dec = -(18.0 + 53.0 / 60.0 + 16.84 / 3600.0);
+        assert!(approx_eq(eq.right_ascension().radians().to_degrees(), ra, 0.02));
+        assert!(approx_eq(eq.declination().radians().to_degrees(), dec, 0.02));
+        assert!(approx_eq(distance(Body::Venus, &time).unwrap(), 0.910_947, 1.0e-4));
+    }
+
+    #[test]
+    fn test_barycenter() {
+        // Venus near inferior conjunction, 0.3 AU away, seen from about 4,700 km apart.
+        let time = Builder::from_gregorian_utc(2020, 6, 3, 0, 0, 0).build().unwrap();
+        let center = apparent_equatorial_with(Body::Venus, &time, EarthOrigin::Geocenter).unwrap();
+        let barycenter = apparent_equatorial_with(Body::Venus, &time, EarthOrigin::Barycenter)
+            .unwrap();
+        assert_eq!(center, apparent_equatorial(Body::Venus, &time).unwrap());
+
+        let shift = angular_separation(center, barycenter).radians().to_degrees() * 3600.0;
+        assert!(shift > 1.0 && shift < 25.0);
+
+        let r = distance_with(Body::Venus, &time, EarthOrigin::Barycenter).unwrap();
+        let change = (r - distance(Body::Venus, &time).unwrap()).abs() * ASTRONOMICAL_UNIT_KM;
+        assert!(change < 5_000.0);
+    }
+}
@@ -0,0 +1,211 @@
+//!
+//! Module for forecasting the visibility of the planets.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The forecast follows a planet through its apparition a week at a time, like the planet
+//! summaries of an almanac. Planets are looked for in twilight, so the altitude is given at the end
+//! of evening civil twilight for an evening object, and at the start of morning civil twilight for
+//! a morning object, when the Sun is 6 degrees below the horizon.
+//!
+use super::super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::super::bodies::Body;
+use super::super::coords::*;
+use super::super::ephemeris::Geometry;
+use super::super::error::*;
+use super::super::math::*;
+use super::super::planets;
+use super::super::rise_set::rise_transit_set;
+use super::super::sun;
+
+// The altitude of the Sun at the start and end of civil twilight, radians.
+const CIVIL_TWILIGHT_ALTITUDE: f64 = -0.104_719_755_119_659_77;
+
+/// Whether a planet is seen in the evening or the morning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Apparition {
+    /// East of the Sun, so it is seen in the evening after sunset.
+    Evening,
+    /// West of the Sun, so it is seen in the morning before sunrise.
+    Morning,
+}
+
+/// The visibility of a planet on a day of a forecast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Visibility {
+    date: AstroTime,
+    magnitude: f64,
+    elongation: RadianAngle,
+    apparition: Apparition,
+    twilight: Option<AstroTime>,
+    altitude: Option<RadianAngle>,
+}
+
+impl Visibility {
+    /// The start of the Universal Time day.
+    pub fn date(&self) -> AstroTime {
+        self.date
+    }
+
+    /// The apparent visual magnitude.
+    pub fn magnitude(&self) -> f64 {
+        self.magnitude
+    }
+
+    /// The angle between the planet and the Sun.
+    pub fn elongation(&self) -> RadianAngle {
+        self.elongation
+    }
+
+    /// Whether the planet is an evening or a morning object.
+    pub fn apparition(&self) -> Apparition {
+        self.apparition
+    }
+
+    /// The end of evening, or start of morning, civil twilight on the day, or `None` if the Sun
+    /// doesn't reach 6 degrees below the horizon.
+    pub fn twilight(&self) -> Option<AstroTime> {
+        self.twilight
+    }
+
+    /// The geometric altitude of the planet at the twilight time.
+    pub fn altitude(&self) -> Option<RadianAngle> {
+        self.altitude
+    }
+}
+
+/// Forecast the visibility of a planet every week from `start` until `end`.
+///
+/// Each entry is for the Universal Time day a whole number of weeks after `start`. Returns an error
+/// if the body isn't a planet or `end` is before `start`.
+pub fn visibility_forecast(planet: Body,
+                           geo: GeoCoords,
+                           start: &AstroTime,
+                           end: &AstroTime)
+                           -> AstroResult<Vec<Visibility>> {
+    match planet {
+        Body::Sun | Body::Moon => {
+            return Err(AstroAlgorithmsError::InvalidArgument("Only the planets have a visibility \
+                                                              forecast"
+                .to_owned()))
+        }
+        _ => {}
+    }
+
+    let weeks = end.duration_since(start)?.days() / 7.0;
+    if weeks < 0.0 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The end of the forecast is before the \
+                                                          start"
+            .to_owned()));
+    }
+
+    let week = AstroDuration::from_days(7.0)?;
+    let jd_0 = (start.as_utc()?.julian_day_number() - 0.5).floor() + 0.5;
+    let mut date = Builder::from_julian_date(jd_0).build()?;
+    let mut forecast = Vec::with_capacity(weeks as usize + 1);
+    for _ in 0..(weeks.floor() as usize + 1) {
+        forecast.push(visibility(planet, geo, &date)?);
+        date = (date + week)?;
+    }
+
+    Ok(forecast)
+}
+
+fn visibility(planet: Body, geo: GeoCoords, date: &AstroTime) -> AstroResult<Visibility> {
+    let geometry = Geometry::new(planet, date)?;
+    let sun = sun::apparent_equatorial(date)?;
+
+    let d_ra = geometry.equatorial().right_ascension().radians() - sun.right_ascension().radians();
+    let apparition = if sin(d_ra) > 0.0 {
+        Apparition::Evening
+    } else {
+        Apparition::Morning
+    };
+
+    let civil = rise_transit_set(date,
+                                 geo,
+                                 RadianAngle::new(CIVIL_TWILIGHT_ALTITUDE)?,
+                                 sun::apparent_equatorial)?;
+    let twilight = match apparition {
+        Apparition::Evening => civil.set(),
+        Apparition::Morning => civil.rise(),
+    };
+    let altitude = match twilight {
+        Some(time) => {
+            let eq = planets::apparent_equatorial(planet, &time)?;
+            Some(trans_equatorial_to_horizontal(eq, geo, &time)?.altitude())
+        }
+        None => None,
+    };
+
+    Ok(Visibility {
+        date: *date,
+        magnitude: geometry.magnitude()?,
+        elongation: geometry.elongation(),
+        apparition,
+        twilight,
+        altitude,
+    })
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod forecast_tests {
+    use super::*;
+
+    fn geo(latitude: f64) -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(latitude.to_radians()).unwrap(),
+                       RadianAngle::new(0.0).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_constants() {
+        assert!(approx_eq(CIVIL_TWILIGHT_ALTITUDE, -6f64.to_radians(), 1.0e-15));
+    }
+
+    #[test]
+    fn test_venus() {
+        // Venus was at its greatest eastern elongation of 47.1 degrees on 2017 January 12, and
+        // at inferior conjunction on March 25, then a morning star.
+        let start = Builder::from_gregorian_utc(2016, 12, 29, 0, 0, 0).build().unwrap();
+        let end = Builder::from_gregorian_utc(2017, 4, 20, 12, 0, 0).build().unwrap();
+        let forecast = visibility_forecast(Body::Venus, geo(51.5), &start, &end).unwrap();
+        assert_eq!(forecast.len(), 17);
+
+        let elongation = forecast[2].elongation().radians().to_degrees();
+        assert!(approx_eq(elongation, 47.1, 0.2));
+        assert_eq!(forecast[2].apparition(), Apparition::Evening);
+        assert!(forecast[2].magnitude() < -4.0);
+        assert!(forecast[2].altitude().unwrap().radians().to_degrees() > 25.0);
+
+        let last = forecast.last().unwrap();
+        assert_eq!(last.apparition(), Apparition::Morning);
+        assert!(last.altitude().unwrap().radians() > 0.0);
+        let (_, month, day, ..) = last.date().to_gregorian_utc();
+        assert_eq!((month, day), (4, 20));
+
+        assert!(visibility_forecast(Body::Moon, geo(51.5), &start, &end).is_err());
+        assert!(visibility_forecast(Body::Venus, geo(51.5), &end, &start).is_err());
+    }
+
+    #[test]
+    fn test_white_nights() {
+        // The Sun doesn't reach -6 degrees at midsummer in the far north.
+        let start = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let forecast = visibility_forecast(Body::Jupiter, geo(65.0), &start, &start).unwrap();
+        assert_eq!(forecast.len(), 1);
+        assert!(forecast[0].twilight().is_none());
+        assert!(forecast[0].altitude().is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//!
+//! Module for framing targets with a camera.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! A camera behind a lens or telescope sees a gnomonic projection of the sky. Positions on the
+//! sensor are in pixels from its center, with x to the right and y up. With a rotation of zero the
+//! top of the sensor points north and east is to the left; the rotation is the position angle of
+//! the top of the sensor, measured from the north towards the east.
+//!
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::math::*;
+
+/// A camera, the focal length of the optics and the size of the sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    focal_length_mm: f64,
+    pixel_size_um: f64,
+    width_px: u32,
+    height_px: u32,
+}
+
+impl Camera {
+    /// Create a new camera from the focal length in millimeters, the size of the (square) pixels
+    /// in micrometers, and the dimensions of the sensor in pixels.
+    pub fn new(focal_length_mm: f64,
+               pixel_size_um: f64,
+               width_px: u32,
+               height_px: u32)
+               -> AstroResult<Camera> {
+        if focal_length_mm.is_nan() || pixel_size_um.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if focal_length_mm.is_infinite() || pixel_size_um.is_infinite() {
+            Err(AstroAlgorithmsError::EncounteredInf)
+        } else if focal_length_mm <= 0.0 || pixel_size_um <= 0.0 || width_px == 0 ||
+                  height_px == 0 {
+            Err(AstroAlgorithmsError::InvalidArgument("The focal length, pixel size and sensor \
+                                                       dimensions must be positive"
+                .to_owned()))
+        } else {
+            Ok(Camera {
+                focal_length_mm,
+                pixel_size_um,
+                width_px,
+                height_px,
+            })
+        }
+    }
+
+    /// The focal length in millimeters.
+    pub fn focal_length_mm(&self) -> f64 {
+        self.focal_length_mm
+    }
+
+    /// The size of the pixels in micrometers.
+    pub fn pixel_size_um(&self) -> f64 {
+        self.pixel_size_um
+    }
+
+    /// The width of the sensor in pixels.
+    pub fn width_px(&self) -> u32 {
+        self.width_px
+    }
+
+    /// The height of the sensor in pixels.
+    pub fn height_px(&self) -> u32 {
+        self.height_px
+    }
+
+    /// The angle covered by one pixel at the center of the sensor.
+    pub fn pixel_scale(&self) -> RadianAngle {
+        RadianAngle::new(atan(self.pixel_mm() / self.focal_length_mm)).unwrap()
+    }
+
+    /// The field of view, the angles covered by the width and the height of the sensor.
+    pub fn field_of_view(&self) -> (RadianAngle, RadianAngle) {
+        (self.field_across(self.width_px as f64), self.field_across(self.height_px as f64))
+    }
+
+    /// The angle covered by the diagonal of the sensor.
+    pub fn diagonal_field_of_view(&self) -> RadianAngle {
+        self.field_across(hypot(self.width_px as f64, self.height_px as f64))
+    }
+
+    // The size of a pixel in millimeters.
+    fn pixel_mm(&self) -> f64 {
+        self.pixel_size_um / 1000.0
+    }
+
+    // The angle covered by a line of pixels through the center of the sensor.
+    fn field_across(&self, pixels: f64) -> RadianAngle {
+        let half = pixels * self.pixel_mm() / 2.0;
+        RadianAngle::new(2.0 * atan(half / self.focal_length_mm)).unwrap()
+    }
+}
+
+/// A camera pointed at the sky, with a center and a rotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    camera: Camera,
+    center: EquatorialCoords,
+    rotation: RadianAngle,
+}
+
+impl Frame {
+    /// Create a new frame centered on `center`, with the top of the sensor at the position angle
+    /// `rotation`.
+    pub fn new(camera: Camera, center: EquatorialCoords, rotation: RadianAngle) -> Frame {
+        Frame {
+            camera,
+            center,
+            rotation,
+        }
+    }
+
+    /// The camera.
+    pub fn camera(&self) -> Camera {
+        self.camera
+    }
+
+    /// The center of the frame.
+    pub fn center(&self) -> EquatorialCoords {
+        self.center
+    }
+
+    /// The position angle of the top of the sensor.
+    pub fn rotation(&self) -> RadianAngle {
+        self.rotation
+    }
+
+    /// Find the position of a target on the plane of the sensor in pixels from the center, or
+    /// `None` if it is 90 degrees or more from the center.
+    ///
+    /// The position may be off the edges of the sensor. The target should be referred to the same
+    /// epoch as the center.
+    pub fn sensor_position(&self, target: EquatorialCoords) -> Option<(f64, f64)> {
+        let rho = angular_separation(self.center, target).radians();
+        if cos(rho) < 1.0e-12 {
+            return None;
+        }
+
+        // The distance from the center on the sensor, and the angle from the top of the sensor
+        // towards the left, the east with no rotation.
+        let r = self.camera.focal_length_mm * tan(rho) / self.camera.pixel_mm();
+        let theta = position_angle(self.center, target).radians() - self.rotation.radians();
+
+        Some((-r * sin(theta), r * cos(theta)))
+    }
+
+    /// Whether a target is on the sensor.
+    pub fn contains(&self, target: EquatorialCoords) -> bool {
+        match self.sensor_position(target) {
+            Some((x, y)) => {
+                x.abs() <= self.camera.width_px as f64 / 2.0 &&
+                y.abs() <= self.camera.height_px as f64 / 2.0
+            }
+            None => false,
+        }
+    }
+
+    /// Whether all of the targets fit on the sensor together.
+    pub fn contains_all(&self, targets: &[EquatorialCoords]) -> bool {
+        targets.iter().all(|&target| self.contains(target))
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod framing_tests {
+    use super::*;
+    use astro_time::J2000;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn coords(ra_deg: f64, dec_deg: f64) -> EquatorialCoords {
+        EquatorialCoords::new(RadianAngle::new(ra_deg.to_radians()).unwrap(),
+                              RadianAngle::new(dec_deg.to_radians()).unwrap(),
+                              J2000)
+            .unwrap()
+    }
+
+    // A full frame sensor with 3.76 micrometer pixels on a 1000 mm telescope.
+    fn camera() -> Camera {
+        Camera::new(1000.0, 3.76, 6248, 4176).unwrap()
+    }
+
+    #[test]
+    fn test_camera() {
+        let camera = camera();
+        assert!(approx_eq(camera.pixel_scale().radians().to_degrees() * 3600.0, 0.7755, 1.0e-4));
+
+        let (width, height) = camera.field_of_view();
+        assert!(approx_eq(width.radians().to_degrees(), 1.345_9, 1.0e-4));
+        assert!(approx_eq(height.radians().to_degrees(), 0.899_6, 1.0e-4));
+        assert!(camera.diagonal_field_of_view().radians() > width.radians());
+
+        assert!(Camera::new(0.0, 3.76, 100, 100).is_err());
+        assert!(Camera::new(1000.0, f64::NAN, 100, 100).is_err());
+        assert!(Camera::new(1000.0, 3.76, 0, 100).is_err());
+    }
+
+    #[test]
+    fn test_frame() {
+        // The Orion Nebula, with a target 0.6 degrees north and one a little to the east.
+        let center = coords(83.82, -5.39);
+        let north = coords(83.82, -4.79);
+        let east = coords(84.0, -5.39);
+
+        let frame = Frame::new(camera(), center, RadianAngle::new(0.0).unwrap());
+        assert_eq!(frame.sensor_position(center), Some((0.0, 0.0)));
+        let (x, y) = frame.sensor_position(north).unwrap();
+        assert!(approx_eq(x, 0.0, 1.0e-6));
+        assert!(approx_eq(y * frame.camera().pixel_scale().radians(),
+                          0.6f64.to_radians(),
+                          1.0e-6));
+        let (x, _) = frame.sensor_position(east).unwrap();
+        assert!(x < 0.0);
+
+        // The target to the north only fits with the long side of the sensor north-south.
+        assert!(frame.contains(east));
+        assert!(!frame.contains_all(&[center, north, east]));
+        let rotated = Frame::new(camera(), center, RadianAngle::new(FRAC_PI_2).unwrap());
+        let (x, y) = rotated.sensor_position(east).unwrap();
+        assert!(approx_eq(x, 0.0, 1.0) && y > 0.0);
+        assert!(rotated.contains_all(&[center, north, east]));
+
+        // The far side of the sky.
+        assert!(frame.sensor_position(coords(263.82, 5.39)).is_none());
+        assert!(!frame.contains(coords(263.82, 5.39)));
+    }
+}
@@ -0,0 +1,296 @@
+//!
+//! Module for planning observing sessions.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Times are scored by how good they are for observing a target, combining the airmass of the
+//! target, the darkness of the sky during twilight, and the light of the Moon. A score of 1 is a
+//! target at the zenith of a dark, moonless sky, and 0 is a target that can't be observed.
+//!
+mod forecast;
+mod framing;
+mod sky;
+
+pub use self::forecast::*;
+pub use self::framing::*;
+pub use self::sky::*;
+
+use super::astro_time::{AstroTime, Builder};
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+use super::moon;
+use super::sun;
+
+// Step between the times scored when looking for the best window, in days.
+const SCORING_STEP: f64 = 5.0 / 1440.0;
+
+/// Limits used when scoring observing times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObservingConstraints {
+    minimum_altitude: RadianAngle,
+    window_fraction: f64,
+}
+
+impl ObservingConstraints {
+    /// Create new constraints.
+    ///
+    /// Times with the target below the minimum (geometric) altitude score 0. The best observing
+    /// window extends either side of the best time while the score is at least `window_fraction`
+    /// of the best score, which must be in the range (0, 1].
+    pub fn new(minimum_altitude: RadianAngle,
+               window_fraction: f64)
+               -> AstroResult<ObservingConstraints> {
+        if window_fraction.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if window_fraction <= 0.0 || window_fraction > 1.0 {
+            Err(AstroAlgorithmsError::InvalidArgument("Window fraction must be in the range \
+                                                       (0, 1]"
+                .to_owned()))
+        } else {
+            Ok(ObservingConstraints {
+                minimum_altitude,
+                window_fraction,
+            })
+        }
+    }
+
+    /// The minimum altitude of the target.
+    pub fn minimum_altitude(&self) -> RadianAngle {
+        self.minimum_altitude
+    }
+
+    /// The fraction of the best score that defines the best observing window.
+    pub fn window_fraction(&self) -> f64 {
+        self.window_fraction
+    }
+}
+
+impl Default for ObservingConstraints {
+    /// A minimum altitude of 20 degrees, and a window within 90% of the best score.
+    fn default() -> ObservingConstraints {
+        ObservingConstraints {
+            minimum_altitude: RadianAngle::new(20f64.to_radians()).unwrap(),
+            window_fraction: 0.9,
+        }
+    }
+}
+
+/// The best span of time to observe a target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObservingWindow {
+    start: AstroTime,
+    best: AstroTime,
+    end: AstroTime,
+    score: f64,
+}
+
+impl ObservingWindow {
+    /// The start of the window in Universal Time.
+    pub fn start(&self) -> AstroTime {
+        self.start
+    }
+
+    /// The time with the best score in Universal Time.
+    pub fn best(&self) -> AstroTime {
+        self.best
+    }
+
+    /// The end of the window in Universal Time.
+    pub fn end(&self) -> AstroTime {
+        self.end
+    }
+
+    /// The score at the best time.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+}
+
+/// Score a time for observing a target using the default constraints.
+///
+/// The target should be in apparent coordinates of the date.
+pub fn observing_score(target: EquatorialCoords,
+                       geo: GeoCoords,
+                       time: &AstroTime)
+                       -> AstroResult<f64> {
+    observing_score_with(target, geo, time, &ObservingConstraints::default())
+}
+
+/// Score a time for observing a target in the range [0, 1].
+///
+/// The score is the product of three factors:
+///
+/// - The inverse of the airmass of the target, or 0 below the minimum altitude.
+/// - The darkness of the sky, 0 when the Sun is above -6 degrees rising linearly to 1 at the end
+///   of astronomical twilight when the Sun is at -18 degrees.
+/// - The moonlight, when the Moon is up this is reduced from 1 by its illuminated fraction
+///   scaled by (1 + cos d) / 2 where d is its separation from the target.
+pub fn observing_score_with(target: EquatorialCoords,
+                            geo: GeoCoords,
+                            time: &AstroTime,
+                            constraints: &ObservingConstraints)
+                            -> AstroResult<f64> {
+    let hz = trans_equatorial_to_horizontal(target, geo, time)?;
+    if hz.altitude().radians() < constraints.minimum_altitude.radians() {
+        return Ok(0.0);
+    }
+    let airmass = match airmass(hz.apparent_altitude(&AtmosphericConditions::default())) {
+        Some(airmass) => airmass,
+        None => return Ok(0.0),
+    };
+
+    let sun = trans_equatorial_to_horizontal(sun::apparent_equatorial(time)?, geo, time)?;
+    let darkness = ((-6.0 - sun.altitude().radians().to_degrees()) / 12.0).clamp(0.0, 1.0);
+
+    let moon_eq = moon::apparent_equatorial(time)?;
+    let moon_hz = trans_equatorial_to_horizontal(moon_eq, geo, time)?;
+    let moonlight = if moon_hz.altitude().radians() > 0.0 {
+        let d = angular_separation(moon_eq, target).radians();
+        1.0 - moon::illuminated_fraction(time)? * (1.0 + cos(d)) / 2.0
+    } else {
+        1.0
+    };
+
+    Ok(darkness * moonlight / airmass)
+}
+
+/// Find the best window for observing a target between two times using the default constraints.
+pub fn best_observing_window(target: EquatorialCoords,
+                             geo: GeoCoords,
+                             start: &AstroTime,
+                             end: &AstroTime)
+                             -> AstroResult<Option<ObservingWindow>> {
+    best_observing_window_with(target, geo, start, end, &ObservingConstraints::default())
+}
+
+/// Find the best window for observing a target between two times.
+///
+/// Times are scored every 5 minutes, and `None` is returned if the target can't be observed at
+/// all between the times.
+pub fn best_observing_window_with(target: EquatorialCoords,
+                                  geo: GeoCoords,
+                                  start: &AstroTime,
+                                  end: &AstroTime,
+                                  constraints: &ObservingConstraints)
+                                  -> AstroResult<Option<ObservingWindow>> {
+    let jd_start = start.as_utc()?.julian_day_number();
+    let jd_end = end.as_utc()?.julian_day_number();
+    if jd_end < jd_start {
+        return Err(AstroAlgorithmsError::InvalidArgument("The end of the range for observing \
+                                                          must not be before the start"
+            .to_owned()));
+    }
+
+    let steps = ((jd_end - jd_start) / SCORING_STEP).ceil() as usize;
+    let mut samples = Vec::with_capacity(steps + 1);
+    for i in 0..(steps + 1) {
+        let time = ut(f64::min(jd_start + i as f64 * SCORING_STEP, jd_end))?;
+        samples.push((time, observing_score_with(target, geo, &time, constraints)?));
+    }
+
+    let (best, &(best_time, score)) = samples.iter()
+        .enumerate()
+        .max_by(|a, b| (a.1).1.partial_cmp(&(b.1).1).unwrap())
+        .unwrap();
+    if score <= 0.0 {
+        return Ok(None);
+    }
+
+    let threshold = constraints.window_fraction * score;
+    let mut first = best;
+    while first > 0 && samples[first - 1].1 >= threshold {
+        first -= 1;
+    }
+    let mut last = best;
+    while last + 1 < samples.len() && samples[last + 1].1 >= threshold {
+        last += 1;
+    }
+
+    Ok(Some(ObservingWindow {
+        start: samples[first].0,
+        best: best_time,
+        end: samples[last].0,
+        score,
+    }))
+}
+
+fn ut(jd: f64) -> AstroResult<AstroTime> {
+    Builder::from_julian_date(jd).build()
+}
+
+#[cfg(test)]
+mod planner_tests {
+    use super::*;
+
+    // Universal Time for hours of local mean time from the start of 2017 August 5 at 105° W.
+    fn local(hours: f64) -> AstroTime {
+        ut(2_457_970.5 + (hours + 7.0) / 24.0).unwrap()
+    }
+
+    fn setup() -> (EquatorialCoords, GeoCoords, AstroTime) {
+        // Vega from 40° N, 105° W on the night of 2017 August 5 into 6, with a nearly full Moon.
+        let time = local(24.0);
+        let vega = EquatorialCoords::new(RadianAngle::new(279.234_7f64.to_radians()).unwrap(),
+                                         RadianAngle::new(38.783_7f64.to_radians()).unwrap(),
+                                         time)
+            .unwrap();
+        let geo = GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                                 RadianAngle::new(105f64.to_radians()).unwrap(),
+                                 0.0)
+            .unwrap();
+
+        (vega, geo, time)
+    }
+
+    #[test]
+    fn test_observing_constraints_new() {
+        let min_alt = RadianAngle::new(0.5).unwrap();
+        assert!(ObservingConstraints::new(min_alt, 0.8).is_ok());
+        assert!(ObservingConstraints::new(min_alt, 0.0).is_err());
+        assert!(ObservingConstraints::new(min_alt, 1.1).is_err());
+        assert_eq!(ObservingConstraints::new(min_alt, f64::NAN).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+    }
+
+    #[test]
+    fn test_observing_score() {
+        let (vega, geo, time) = setup();
+
+        assert_eq!(observing_score(vega, geo, &local(12.0)).unwrap(), 0.0);
+
+        let score = observing_score(vega, geo, &time).unwrap();
+        assert!(score > 0.0 && score < 1.0);
+    }
+
+    #[test]
+    fn test_best_observing_window() {
+        let (vega, geo, _) = setup();
+        let evening = local(19.0);
+        let morning = local(31.0);
+
+        let window = best_observing_window(vega, geo, &evening, &morning).unwrap().unwrap();
+        assert!(window.start() <= window.best() && window.best() <= window.end());
+        assert!(window.score() > 0.0);
+
+        // Dark sky at the best time, and the score falls off at the ends of the window.
+        let sun = trans_equatorial_to_horizontal(sun::apparent_equatorial(&window.best())
+                                                     .unwrap(),
+                                                 geo,
+                                                 &window.best())
+            .unwrap();
+        assert!(sun.altitude().radians().to_degrees() < -18.0);
+        let at_start = observing_score(vega, geo, &window.start()).unwrap();
+        assert!(at_start >= 0.9 * window.score() && at_start <= window.score());
+
+        // Only daytime in the range.
+        let window = best_observing_window(vega, geo, &local(12.0), &local(14.0)).unwrap();
+        assert!(window.is_none());
+
+        assert!(best_observing_window(vega, geo, &morning, &evening).is_err());
+    }
+}
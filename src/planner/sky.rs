@@ -0,0 +1,241 @@
+//!
+//! Module for estimating the brightness of the night sky and the faintest stars visible.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The moonlight follows K. Krisciunas and B. E. Schaefer, "A Model of the Brightness of
+//! Moonlight", PASP 103, 1991, in the V band. Twilight is a linear fit to the brightness of the
+//! sky at the zenith while the Sun is between 0 and -18 degrees. The naked eye limiting magnitude
+//! is found from the sky brightness with the relation of Schaefer's visibility model as given by
+//! the Clear Sky Chart.
+//!
+use super::super::astro_time::AstroTime;
+use super::super::bodies::Body;
+use super::super::coords::*;
+use super::super::ephemeris::Geometry;
+use super::super::error::*;
+use super::super::math::*;
+use super::super::sun;
+
+/// The atmosphere and site used to estimate the brightness of the sky.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyConditions {
+    extinction: f64,
+    dark_sky_brightness: f64,
+}
+
+impl SkyConditions {
+    /// Create new conditions from the extinction coefficient in magnitudes per airmass, and the
+    /// brightness of the moonless sky at the zenith after twilight in magnitudes per square
+    /// arcsecond.
+    pub fn new(extinction: f64, dark_sky_brightness: f64) -> AstroResult<SkyConditions> {
+        if extinction.is_nan() || dark_sky_brightness.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if extinction.is_infinite() || dark_sky_brightness.is_infinite() {
+            Err(AstroAlgorithmsError::EncounteredInf)
+        } else if extinction < 0.0 {
+            Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue)
+        } else {
+            Ok(SkyConditions {
+                extinction,
+                dark_sky_brightness,
+            })
+        }
+    }
+
+    /// The extinction coefficient in magnitudes per airmass.
+    pub fn extinction(&self) -> f64 {
+        self.extinction
+    }
+
+    /// The brightness of the dark sky at the zenith in magnitudes per square arcsecond.
+    pub fn dark_sky_brightness(&self) -> f64 {
+        self.dark_sky_brightness
+    }
+}
+
+impl Default for SkyConditions {
+    /// The V band extinction of 0.172 at Mauna Kea used by Krisciunas and Schaefer, and a dark
+    /// sky of 21.7 magnitudes per square arcsecond.
+    fn default() -> SkyConditions {
+        SkyConditions {
+            extinction: 0.172,
+            dark_sky_brightness: 21.7,
+        }
+    }
+}
+
+/// Estimate the brightness of the sky in the direction of a target in magnitudes per square
+/// arcsecond using the default conditions.
+///
+/// The target should be in apparent coordinates of the date.
+pub fn sky_brightness(target: EquatorialCoords,
+                      geo: GeoCoords,
+                      time: &AstroTime)
+                      -> AstroResult<f64> {
+    sky_brightness_with(target, geo, time, &SkyConditions::default())
+}
+
+/// Estimate the brightness of the sky in the direction of a target in magnitudes per square
+/// arcsecond, smaller numbers being brighter.
+///
+/// The sky is the sum of the dark sky, brightened towards the horizon, the twilight, and the
+/// moonlight scattered by the atmosphere. With the Sun above the horizon the twilight is held at
+/// its value at sunset, so the daytime sky is too dark by several magnitudes.
+pub fn sky_brightness_with(target: EquatorialCoords,
+                           geo: GeoCoords,
+                           time: &AstroTime,
+                           conditions: &SkyConditions)
+                           -> AstroResult<f64> {
+    let k = conditions.extinction;
+    let target_hz = trans_equatorial_to_horizontal(target, geo, time)?;
+    let x = optical_pathlength(target_hz.altitude());
+
+    // The dark sky, brighter towards the horizon with the longer path through the air glow.
+    let dark = nanolamberts(conditions.dark_sky_brightness) * powf(10f64, -0.4 * k * (x - 1.0)) * x;
+
+    // Twilight, from about 11 magnitudes per square arcsecond at sunset.
+    let sun_hz = trans_equatorial_to_horizontal(sun::apparent_equatorial(time)?, geo, time)?;
+    let sun_altitude = sun_hz.altitude().radians().to_degrees().min(0.0);
+    let twilight = if sun_altitude > -18.0 {
+        nanolamberts(11.0 - 0.57 * sun_altitude) * x
+    } else {
+        0.0
+    };
+
+    // The moonlight scattered towards the target, equation 15 of Krisciunas and Schaefer.
+    let moon = Geometry::new(Body::Moon, time)?;
+    let moon_hz = trans_equatorial_to_horizontal(moon.equatorial(), geo, time)?;
+    let moonlight = if moon_hz.altitude().radians() > 0.0 {
+        let alpha = moon.phase_angle().radians().to_degrees();
+        let illuminance = powf(10f64, -0.4 * (3.84 + 0.026 * alpha + 4.0e-9 * alpha.powi(4)));
+
+        let rho = angular_separation(moon.equatorial(), target).radians();
+        let scattering = powf(10f64, 5.36) * (1.06 + cos(rho) * cos(rho)) +
+                         powf(10f64, 6.15 - rho.to_degrees() / 40.0);
+
+        let x_moon = optical_pathlength(moon_hz.altitude());
+        scattering * illuminance * powf(10f64, -0.4 * k * x_moon) *
+        (1.0 - powf(10f64, -0.4 * k * x))
+    } else {
+        0.0
+    };
+
+    Ok(magnitudes(dark + twilight + moonlight))
+}
+
+/// Estimate the faintest star visible to the naked eye near a target using the default
+/// conditions.
+///
+/// The target should be in apparent coordinates of the date.
+pub fn limiting_magnitude(target: EquatorialCoords,
+                          geo: GeoCoords,
+                          time: &AstroTime)
+                          -> AstroResult<f64> {
+    limiting_magnitude_with(target, geo, time, &SkyConditions::default())
+}
+
+/// Estimate the faintest star visible to the naked eye near a target.
+///
+/// This is the limit for the brightness of the sky, less the extinction of the star's light
+/// beyond that at the zenith. It is about 6.5 for a dark sky, and is a guide for ranking times
+/// and nights rather than a prediction for any particular observer.
+pub fn limiting_magnitude_with(target: EquatorialCoords,
+                               geo: GeoCoords,
+                               time: &AstroTime,
+                               conditions: &SkyConditions)
+                               -> AstroResult<f64> {
+    let sky = sky_brightness_with(target, geo, time, conditions)?;
+    let hz = trans_equatorial_to_horizontal(target, geo, time)?;
+    let x = optical_pathlength(hz.altitude());
+
+    Ok(7.93 - 5.0 * log10(powf(10f64, 4.316 - sky / 5.0) + 1.0) -
+       conditions.extinction * (x - 1.0))
+}
+
+// The optical pathlength in airmasses for the altitude, equation 3 of Krisciunas and Schaefer.
+// Unlike the airmass this stays finite below the horizon.
+fn optical_pathlength(altitude: RadianAngle) -> f64 {
+    let cos_alt = cos(altitude.radians());
+    1.0 / f64::sqrt(1.0 - 0.96 * cos_alt * cos_alt)
+}
+
+// Convert a surface brightness in V magnitudes per square arcsecond to nanolamberts, and back.
+fn nanolamberts(magnitudes: f64) -> f64 {
+    34.08 * exp(20.723_3 - 0.921_04 * magnitudes)
+}
+
+fn magnitudes(nanolamberts: f64) -> f64 {
+    (20.723_3 - ln(nanolamberts / 34.08)) / 0.921_04
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod sky_tests {
+    use super::*;
+    use astro_time::Builder;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    fn zenith(geo: GeoCoords, time: &AstroTime) -> EquatorialCoords {
+        let ra = time.sidereal_greenwich() - geo.longitude().radians().to_degrees();
+        EquatorialCoords::new(RadianAngle::new(map_to_branch(ra.to_radians(), 0.0, 2.0 * PI))
+                                  .unwrap(),
+                              geo.latitude(),
+                              *time)
+            .unwrap()
+    }
+
+    fn geo() -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                       RadianAngle::new(105f64.to_radians()).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert!(approx_eq(magnitudes(nanolamberts(21.7)), 21.7, 1.0e-12));
+        assert!(approx_eq(optical_pathlength(RadianAngle::new(FRAC_PI_2).unwrap()), 1.0, 1.0e-9));
+    }
+
+    #[test]
+    fn test_dark_sky() {
+        // Local midnight at 105 W on 2017 August 21, the new moon.
+        let time = Builder::from_gregorian_utc(2017, 8, 22, 7, 0, 0).build().unwrap();
+        let target = zenith(geo(), &time);
+        assert!(approx_eq(sky_brightness(target, geo(), &time).unwrap(), 21.7, 0.01));
+        let limit = limiting_magnitude(target, geo(), &time).unwrap();
+        assert!(approx_eq(limit, 6.5, 0.2));
+
+        // Worse in a city.
+        let city = SkyConditions::new(0.3, 18.0).unwrap();
+        assert!(limiting_magnitude_with(target, geo(), &time, &city).unwrap() < limit - 1.0);
+        assert!(SkyConditions::new(-0.1, 21.0).is_err());
+    }
+
+    #[test]
+    fn test_moonlight_and_twilight() {
+        // Local midnight two days before the full moon of 2017 August 7, with the Moon up.
+        let time = Builder::from_gregorian_utc(2017, 8, 6, 7, 0, 0).build().unwrap();
+        let target = zenith(geo(), &time);
+        let moonlit = sky_brightness(target, geo(), &time).unwrap();
+        assert!(moonlit < 20.0 && moonlit > 17.0);
+        assert!(limiting_magnitude(target, geo(), &time).unwrap() < 6.0);
+
+        // During civil twilight the sky is far brighter, and darkens as the Sun sets deeper.
+        let dusk = Builder::from_gregorian_utc(2017, 8, 22, 2, 0, 0).build().unwrap();
+        let later = Builder::from_gregorian_utc(2017, 8, 22, 2, 30, 0).build().unwrap();
+        let bright = sky_brightness(zenith(geo(), &dusk), geo(), &dusk).unwrap();
+        assert!(bright < 15.0);
+        assert!(sky_brightness(zenith(geo(), &later), geo(), &later).unwrap() > bright);
+    }
+}
@@ -0,0 +1,310 @@
+//!
+//! Module for tables of the rising and setting of the Sun and Moon through a year.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The days of the table are local calendar days, which needn't line up with the Universal Time
+//! days `rise_transit_set` works with. That finds one of each event in a day, so when the time of
+//! an event drifts across the start of the day, one is missed or found twice. Here the events are
+//! found for days starting at both 0h and 12h Universal Time, so every event is well inside one of
+//! the days, and then sorted into the local days they fall on. A local day can have no moonrise,
+//! as happens about once a month, and a day lengthened or shortened by a change to or from
+//! daylight saving time gets the events of all of its hours.
+//!
+use super::super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::super::coords::*;
+use super::super::error::*;
+use super::super::moon;
+use super::super::sun;
+use super::{rise_transit_set_from, standard_altitude_moon, STANDARD_ALTITUDE_SUN};
+
+// Events closer than this in days are the same event found from neighboring days.
+const SAME_EVENT_DAYS: f64 = 1.0 / 1440.0;
+
+/// The rising and setting of the Sun and Moon on a local calendar day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlmanacDay {
+    date: (i32, i32, i32),
+    start: AstroTime,
+    end: AstroTime,
+    sunrise: Option<AstroTime>,
+    sunset: Option<AstroTime>,
+    moonrise: Option<AstroTime>,
+    moonset: Option<AstroTime>,
+    moon_illuminated_fraction: f64,
+    moon_waxing: bool,
+}
+
+impl AlmanacDay {
+    /// The local calendar date as (year, month, day).
+    pub fn date(&self) -> (i32, i32, i32) {
+        self.date
+    }
+
+    /// The local midnight starting the day, in Universal Time.
+    pub fn start(&self) -> AstroTime {
+        self.start
+    }
+
+    /// The local midnight ending the day, in Universal Time.
+    pub fn end(&self) -> AstroTime {
+        self.end
+    }
+
+    /// The time of sunrise in Universal Time, or `None` if the Sun doesn't rise on the day.
+    pub fn sunrise(&self) -> Option<AstroTime> {
+        self.sunrise
+    }
+
+    /// The time of sunset in Universal Time, or `None` if the Sun doesn't set on the day.
+    pub fn sunset(&self) -> Option<AstroTime> {
+        self.sunset
+    }
+
+    /// The time of moonrise in Universal Time, or `None` if the Moon doesn't rise on the day.
+    pub fn moonrise(&self) -> Option<AstroTime> {
+        self.moonrise
+    }
+
+    /// The time of moonset in Universal Time, or `None` if the Moon doesn't set on the day.
+    pub fn moonset(&self) -> Option<AstroTime> {
+        self.moonset
+    }
+
+    /// The illuminated fraction of the Moon's disk in the middle of the day.
+    pub fn moon_illuminated_fraction(&self) -> f64 {
+        self.moon_illuminated_fraction
+    }
+
+    /// Whether the illuminated fraction of the Moon grows through the day.
+    pub fn moon_waxing(&self) -> bool {
+        self.moon_waxing
+    }
+}
+
+/// Tabulate the rising and setting of the Sun and Moon for every day of a Gregorian calendar year,
+/// with the days in Universal Time.
+pub fn almanac(year: i32, geo: GeoCoords) -> AstroResult<Vec<AlmanacDay>> {
+    let zero = AstroDuration::from_days(0.0)?;
+
+    almanac_with(year, geo, |_| zero)
+}
+
+/// Tabulate the rising and setting of the Sun and Moon for every day of a Gregorian calendar year
+/// in a time zone, `zone_offset` ahead of Universal Time.
+///
+/// The times are still in Universal Time, convert them with `AstroTime::to_zone_time`.
+pub fn almanac_zone(year: i32,
+                    geo: GeoCoords,
+                    zone_offset: AstroDuration)
+                    -> AstroResult<Vec<AlmanacDay>> {
+    almanac_with(year, geo, |_| zone_offset)
+}
+
+/// Tabulate the rising and setting of the Sun and Moon for every day of a Gregorian calendar year
+/// with a time zone offset that can change through the year, as with daylight saving time.
+///
+/// The function `zone_offset` gives the offset ahead of Universal Time in effect at the start of a
+/// local day, and is called with the local date at 0h as if it were Universal Time. Offsets must
+/// be within a day.
+///
+/// # Examples
+///
+/// ```
+/// # use astro_calc::astro_time::AstroDuration;
+/// # use astro_calc::coords::{GeoCoords, RadianAngle};
+/// # use astro_calc::rise_set::almanac_with;
+/// // Central European Time, with summer time from April through October.
+/// let berlin = GeoCoords::new(RadianAngle::new(52.52f64.to_radians()).unwrap(),
+///                             RadianAngle::new(-13.40f64.to_radians()).unwrap(),
+///                             0.0)
+///     .unwrap();
+/// let table = almanac_with(2017, berlin, |date| {
+///         let (_, month, _, _, _, _) = date.to_gregorian_utc();
+///         let hours = if (4..=10).contains(&month) { 2.0 } else { 1.0 };
+///         AstroDuration::from_hours(hours).unwrap()
+///     })
+///     .unwrap();
+/// assert_eq!(table.len(), 365);
+/// ```
+pub fn almanac_with<F>(year: i32, geo: GeoCoords, zone_offset: F) -> AstroResult<Vec<AlmanacDay>>
+    where F: Fn(&AstroTime) -> AstroDuration
+{
+    let jd_start = Builder::from_gregorian_utc(year, 1, 1, 0, 0, 0).build()?.julian_day_number();
+    let jd_end = Builder::from_gregorian_utc(year + 1, 1, 1, 0, 0, 0)
+        .build()?
+        .julian_day_number();
+    let days = (jd_end - jd_start).round() as usize;
+
+    // The local midnights bounding the days, in Universal Time.
+    let mut bounds = Vec::with_capacity(days + 1);
+    for i in 0..(days + 1) {
+        let date = Builder::from_julian_date(jd_start + i as f64).build()?;
+        let offset = zone_offset(&date).days();
+        if offset.abs() >= 1.0 {
+            return Err(AstroAlgorithmsError::InvalidArgument("Time zone offsets must be within a \
+                                                              day"
+                .to_owned()));
+        }
+        bounds.push(Builder::from_julian_date(jd_start + i as f64 - offset).build()?);
+    }
+
+    // The events of every Universal Time day the local days overlap.
+    let (mut sunrises, mut sunsets, mut moonrises, mut moonsets) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for i in -2..(2 * days as i32 + 2) {
+        let jd_0 = jd_start + 0.5 * f64::from(i);
+        let sun = rise_transit_set_from(jd_0,
+                                        geo,
+                                        STANDARD_ALTITUDE_SUN,
+                                        sun::apparent_equatorial)?;
+        sunrises.extend(sun.rise());
+        sunsets.extend(sun.set());
+
+        let distance = moon::distance(&Builder::from_julian_date(jd_0).build()?)?;
+        let moon = rise_transit_set_from(jd_0,
+                                         geo,
+                                         standard_altitude_moon(distance)?.radians(),
+                                         moon::apparent_equatorial)?;
+        moonrises.extend(moon.rise());
+        moonsets.extend(moon.set());
+    }
+    for events in &mut [&mut sunrises, &mut sunsets, &mut moonrises, &mut moonsets] {
+        sort_events(events);
+    }
+
+    let mut fractions = Vec::with_capacity(days + 1);
+    for bound in &bounds {
+        fractions.push(moon::illuminated_fraction(bound)?);
+    }
+
+    let mut table = Vec::with_capacity(days);
+    for i in 0..days {
+        let (start, end) = (bounds[i], bounds[i + 1]);
+        let middle = Builder::from_julian_date((start.julian_day_number() +
+                                                end.julian_day_number()) /
+                                               2.0)
+            .build()?;
+        let (year, month, day, _, _, _) = Builder::from_julian_date(jd_start + i as f64)
+            .build()?
+            .to_gregorian_utc();
+
+        table.push(AlmanacDay {
+            date: (year, month, day),
+            start,
+            end,
+            sunrise: event_between(&sunrises, start, end),
+            sunset: event_between(&sunsets, start, end),
+            moonrise: event_between(&moonrises, start, end),
+            moonset: event_between(&moonsets, start, end),
+            moon_illuminated_fraction: moon::illuminated_fraction(&middle)?,
+            moon_waxing: fractions[i + 1] > fractions[i],
+        });
+    }
+
+    Ok(table)
+}
+
+// Sort events and drop the ones found again from a neighboring day.
+fn sort_events(events: &mut Vec<AstroTime>) {
+    events.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    events.dedup_by(|a, b| (a.julian_day_number() - b.julian_day_number()).abs() < SAME_EVENT_DAYS);
+}
+
+// The first of the sorted events in [start, end).
+fn event_between(events: &[AstroTime], start: AstroTime, end: AstroTime) -> Option<AstroTime> {
+    let i = events.partition_point(|&event| event < start);
+
+    events.get(i).cloned().filter(|&event| event < end)
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod almanac_tests {
+    use super::*;
+
+    fn geo(lat: f64, lon: f64) -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(lat.to_radians()).unwrap(),
+                       RadianAngle::new(lon.to_radians()).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_almanac() {
+        // Boulder, Colorado, on Mountain Standard Time.
+        let zone = AstroDuration::from_hours(-7.0).unwrap();
+        let table = almanac_zone(2016, geo(40.0, 105.3), zone).unwrap();
+        assert_eq!(table.len(), 366);
+        assert_eq!(table[0].date(), (2016, 1, 1));
+        assert_eq!(table[365].date(), (2016, 12, 31));
+
+        for day in &table {
+            assert!(day.end().julian_day_number() - day.start().julian_day_number() == 1.0);
+            let sunrise = day.sunrise().unwrap();
+            let sunset = day.sunset().unwrap();
+            assert!(sunrise >= day.start() && sunset < day.end() && sunrise < sunset);
+
+            // Local times in the morning and the evening.
+            let (_, _, _, hour, _, _) = sunrise.to_zone_time(zone).unwrap();
+            assert!((4..9).contains(&hour));
+            let (_, _, _, hour, _, _) = sunset.to_zone_time(zone).unwrap();
+            assert!((16..21).contains(&hour));
+
+            for event in day.moonrise().iter().chain(day.moonset().iter()) {
+                assert!(*event >= day.start() && *event < day.end());
+            }
+            assert!((0.0..=1.0).contains(&day.moon_illuminated_fraction()));
+        }
+
+        // About one day a month without a moonrise, and the same without a moonset.
+        let no_moonrise = table.iter().filter(|day| day.moonrise().is_none()).count();
+        let no_moonset = table.iter().filter(|day| day.moonset().is_none()).count();
+        assert!((11..=14).contains(&no_moonrise));
+        assert!((11..=14).contains(&no_moonset));
+
+        // The Moon was full on 2016 January 23 at 18:46 local time and new on February 8.
+        assert!(table[22].moon_illuminated_fraction() > 0.99 && table[22].moon_waxing());
+        assert!(!table[23].moon_waxing());
+        assert!(table[38].moon_illuminated_fraction() < 0.02);
+    }
+
+    #[test]
+    fn test_almanac_polar_and_daylight_saving() {
+        // Tromsø has midnight sun and polar night, on Central European Time with summer time from
+        // the last Sunday of March to the last Sunday of October.
+        let table = almanac_with(2017, geo(69.65, -18.96), |date| {
+                let (_, month, day, _, _, _) = date.to_gregorian_utc();
+                let summer = (month == 3 && day > 26) || (4..=9).contains(&month) ||
+                             (month == 10 && day <= 29);
+                AstroDuration::from_hours(if summer { 2.0 } else { 1.0 }).unwrap()
+            })
+            .unwrap();
+        assert_eq!(table.len(), 365);
+
+        let length = |day: &AlmanacDay| {
+            (day.end().julian_day_number() - day.start().julian_day_number()) * 24.0
+        };
+        assert!(approx_eq(length(&table[84]), 23.0, 1.0e-6));
+        assert!(approx_eq(length(&table[301]), 25.0, 1.0e-6));
+        assert!(approx_eq(length(&table[100]), 24.0, 1.0e-6));
+
+        // No sunrise or sunset at midsummer or midwinter.
+        let june_21 = &table[171];
+        assert!(june_21.sunrise().is_none() && june_21.sunset().is_none());
+        let december_21 = &table[354];
+        assert!(december_21.sunrise().is_none() && december_21.sunset().is_none());
+
+        // Both at the equinoxes.
+        assert!(table[79].sunrise().is_some() && table[79].sunset().is_some());
+    }
+}
@@ -0,0 +1,656 @@
+//!
+//! Module for the times of rising, transit and setting.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Follows chapter 15 of Astronomical Algorithms, 2nd Edition by Jean Meeus. Rather than
+//! interpolating positions tabulated at 0h, the corrections to the times are iterated with the
+//! position of the body calculated at each trial time, which handles the fast moving Moon well.
+//!
+mod almanac;
+
+pub use self::almanac::*;
+
+use std::f64::consts::PI;
+
+use super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+use super::moon;
+use super::sun;
+
+/// The standard altitude in radians of the center of a star or planet at rising or setting,
+/// -0°34' for the refraction at the horizon.
+pub const STANDARD_ALTITUDE_STAR: f64 = -0.009_890_199_094_634_533;
+
+/// The standard altitude in radians of the center of the Sun at rising or setting, -0°50' for the
+/// refraction at the horizon and the semi-diameter of the Sun.
+pub const STANDARD_ALTITUDE_SUN: f64 = -0.014_544_410_433_286_08;
+
+// The altitudes of the Sun in radians bounding the golden hour, -4 and 6 degrees, and the bottom
+// of the blue hour, -6 degrees.
+const GOLDEN_HOUR_BOTTOM: f64 = -0.069_813_170_079_773_18;
+const GOLDEN_HOUR_TOP: f64 = 0.104_719_755_119_659_77;
+const BLUE_HOUR_BOTTOM: f64 = -0.104_719_755_119_659_77;
+
+/// The standard altitude of the center of the Moon at rising or setting, which depends on its
+/// distance in kilometers through the parallax and semi-diameter.
+pub fn standard_altitude_moon(distance_km: f64) -> AstroResult<RadianAngle> {
+    let parallax = horizontal_parallax(distance_km)?;
+
+    RadianAngle::new(0.727_5 * parallax.radians() + STANDARD_ALTITUDE_STAR)
+}
+
+/// The times a body rises, crosses the meridian, and sets on a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiseTransitSet {
+    rise: Option<AstroTime>,
+    transit: AstroTime,
+    set: Option<AstroTime>,
+}
+
+impl RiseTransitSet {
+    /// The time of rising in Universal Time, or `None` if the body doesn't cross the horizon.
+    pub fn rise(&self) -> Option<AstroTime> {
+        self.rise
+    }
+
+    /// The time of the transit across the meridian in Universal Time.
+    pub fn transit(&self) -> AstroTime {
+        self.transit
+    }
+
+    /// The time of setting in Universal Time, or `None` if the body doesn't cross the horizon.
+    pub fn set(&self) -> Option<AstroTime> {
+        self.set
+    }
+}
+
+/// Calculate the times of rising, transit, and setting of a body on the Universal Time day
+/// containing `date`.
+///
+/// The function `position` gives the apparent equatorial coordinates of the body at a time, and
+/// `standard_altitude` is the geometric altitude of the center of the body at rising and setting.
+/// The rising and setting are `None` when the body is circumpolar or never rises.
+pub fn rise_transit_set<F>(date: &AstroTime,
+                           geo: GeoCoords,
+                           standard_altitude: RadianAngle,
+                           position: F)
+                           -> AstroResult<RiseTransitSet>
+    where F: Fn(&AstroTime) -> AstroResult<EquatorialCoords>
+{
+    rise_transit_set_with(date, geo, standard_altitude, None, position)
+}
+
+/// Calculate the times of rising, transit, and setting of a body with the horizon lowered by the
+/// dip, see `dip_of_horizon`.
+///
+/// This is for an observer on a hill or in an aircraft watching the body cross a sea horizon.
+pub fn rise_transit_set_with<F>(date: &AstroTime,
+                                geo: GeoCoords,
+                                standard_altitude: RadianAngle,
+                                dip: Option<RadianAngle>,
+                                position: F)
+                                -> AstroResult<RiseTransitSet>
+    where F: Fn(&AstroTime) -> AstroResult<EquatorialCoords>
+{
+    let h_0 = standard_altitude.radians() - dip.map_or(0.0, |dip| dip.radians());
+    let jd_0 = (date.as_utc()?.julian_day_number() - 0.5).floor() + 0.5;
+
+    rise_transit_set_from(jd_0, geo, h_0, position)
+}
+
+// The times of rising, transit, and setting in the day starting at the Julian Day jd_0, which
+// needn't be at 0h, with the geometric altitude h_0 in radians at rising and setting.
+fn rise_transit_set_from<F>(jd_0: f64,
+                            geo: GeoCoords,
+                            h_0: f64,
+                            position: F)
+                            -> AstroResult<RiseTransitSet>
+    where F: Fn(&AstroTime) -> AstroResult<EquatorialCoords>
+{
+    // Approximate times as fractions of a day, equations 15.1 and 15.2.
+    let start = Builder::from_julian_date(jd_0).build()?;
+    let eq = position(&start)?;
+    let h = local_hour_angle(eq, geo, &start)?.radians();
+    let m_0 = fraction_of_day(-h / (2.0 * PI));
+
+    let (sin_phi, cos_phi) = sin_cos(geo.latitude().radians());
+    let (sin_d, cos_d) = sin_cos(eq.declination().radians());
+    let cos_h_0 = (sin(h_0) - sin_phi * sin_d) / (cos_phi * cos_d);
+
+    let transit = iterate(jd_0, m_0, |t| {
+        let h = local_hour_angle(position(t)?, geo, t)?.radians();
+        Ok(-hour_angle_branch(h) / (2.0 * PI))
+    })?;
+
+    if cos_h_0.abs() > 1.0 {
+        return Ok(RiseTransitSet {
+            rise: None,
+            transit,
+            set: None,
+        });
+    }
+
+    let correction = |t: &AstroTime| {
+        let eq = position(t)?;
+        let h = hour_angle_branch(local_hour_angle(eq, geo, t)?.radians());
+        let alt = trans_equatorial_to_horizontal(eq, geo, t)?.altitude().radians();
+
+        // Rates in radians per day, the sidereal day is a bit shorter than a solar day.
+        let rate = 2.0 * PI * 1.002_737_909_35 * cos(eq.declination().radians()) * cos_phi *
+                   sin(h);
+        Ok((alt - h_0) / rate)
+    };
+
+    let half_arc = acos(cos_h_0) / (2.0 * PI);
+
+    Ok(RiseTransitSet {
+        rise: Some(iterate(jd_0, fraction_of_day(m_0 - half_arc), correction)?),
+        transit,
+        set: Some(iterate(jd_0, fraction_of_day(m_0 + half_arc), correction)?),
+    })
+}
+
+/// Calculate the times of sunrise, the Sun's transit, and sunset on the Universal Time day
+/// containing `date`.
+pub fn sun_rise_transit_set(date: &AstroTime,
+                            geo: GeoCoords,
+                            dip: Option<RadianAngle>)
+                            -> AstroResult<RiseTransitSet> {
+    rise_transit_set_with(date,
+                          geo,
+                          RadianAngle::new(STANDARD_ALTITUDE_SUN)?,
+                          dip,
+                          sun::apparent_equatorial)
+}
+
+/// Calculate the times of moonrise, the Moon's transit, and moonset on the Universal Time day
+/// containing `date`.
+///
+/// The Moon's position is geocentric, the parallax is accounted for in the standard altitude
+/// which is calculated for the Moon's distance at the start of the day.
+pub fn moon_rise_transit_set(date: &AstroTime,
+                             geo: GeoCoords,
+                             dip: Option<RadianAngle>)
+                             -> AstroResult<RiseTransitSet> {
+    let h_0 = standard_altitude_moon(moon::distance(date)?)?;
+
+    rise_transit_set_with(date, geo, h_0, dip, moon::apparent_equatorial)
+}
+
+/// The directions of rising and setting on a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiseSetAzimuths {
+    rise: Option<RadianAngle>,
+    set: Option<RadianAngle>,
+}
+
+impl RiseSetAzimuths {
+    /// The azimuth of rising, measured westward from the South, or `None` if the body doesn't
+    /// cross the horizon.
+    pub fn rise(&self) -> Option<RadianAngle> {
+        self.rise
+    }
+
+    /// The azimuth of setting, measured westward from the South, or `None` if the body doesn't
+    /// cross the horizon.
+    pub fn set(&self) -> Option<RadianAngle> {
+        self.set
+    }
+
+    /// The amplitude of rising, the angle along the horizon from due east, positive to the north.
+    pub fn rise_amplitude(&self) -> Option<RadianAngle> {
+        self.rise
+            .map(|az| RadianAngle::new(map_to_branch(1.5 * PI - az.radians(), -PI, PI)).unwrap())
+    }
+
+    /// The amplitude of setting, the angle along the horizon from due west, positive to the north.
+    pub fn set_amplitude(&self) -> Option<RadianAngle> {
+        self.set
+            .map(|az| RadianAngle::new(map_to_branch(az.radians() - 0.5 * PI, -PI, PI)).unwrap())
+    }
+}
+
+/// Calculate the azimuths of rising and setting from the times found by `rise_transit_set`, with
+/// the same function `position` for the apparent equatorial coordinates of the body.
+///
+/// Archaeoastronomers use these to check the alignment of structures with the rising and setting
+/// of the Sun and Moon at the solstices and lunar standstills.
+pub fn rise_set_azimuths<F>(rts: &RiseTransitSet,
+                            geo: GeoCoords,
+                            position: F)
+                            -> AstroResult<RiseSetAzimuths>
+    where F: Fn(&AstroTime) -> AstroResult<EquatorialCoords>
+{
+    let azimuth = |time: Option<AstroTime>| -> AstroResult<Option<RadianAngle>> {
+        match time {
+            Some(time) => {
+                let hz = trans_equatorial_to_horizontal(position(&time)?, geo, &time)?;
+                Ok(Some(RadianAngle::new(map_to_branch(hz.azimuth().radians(), 0.0, 2.0 * PI))?))
+            }
+            None => Ok(None),
+        }
+    };
+
+    Ok(RiseSetAzimuths {
+        rise: azimuth(rts.rise())?,
+        set: azimuth(rts.set())?,
+    })
+}
+
+/// Calculate the azimuths of sunrise and sunset on the Universal Time day containing `date`.
+pub fn sun_rise_set_azimuths(date: &AstroTime, geo: GeoCoords) -> AstroResult<RiseSetAzimuths> {
+    rise_set_azimuths(&sun_rise_transit_set(date, geo, None)?, geo, sun::apparent_equatorial)
+}
+
+/// Calculate the azimuths of moonrise and moonset on the Universal Time day containing `date`.
+pub fn moon_rise_set_azimuths(date: &AstroTime, geo: GeoCoords) -> AstroResult<RiseSetAzimuths> {
+    rise_set_azimuths(&moon_rise_transit_set(date, geo, None)?, geo, moon::apparent_equatorial)
+}
+
+/// The daylight on a day, for tabulating through the seasons.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Daylight {
+    date: AstroTime,
+    length: AstroDuration,
+    solar_declination: RadianAngle,
+}
+
+impl Daylight {
+    /// The start of the Universal Time day.
+    pub fn date(&self) -> AstroTime {
+        self.date
+    }
+
+    /// The time from sunrise to sunset.
+    pub fn length(&self) -> AstroDuration {
+        self.length
+    }
+
+    /// The declination of the Sun at its transit.
+    pub fn solar_declination(&self) -> RadianAngle {
+        self.solar_declination
+    }
+}
+
+/// Calculate the time from sunrise to sunset for the sunrise on the Universal Time day containing
+/// `date`.
+///
+/// If the sunset is before the sunrise on that day, the sunset of the next day is used. The length
+/// is a full day when the Sun doesn't set, and zero when it doesn't rise.
+pub fn day_length(date: &AstroTime, geo: GeoCoords) -> AstroResult<AstroDuration> {
+    Ok(daylight(date, geo)?.length)
+}
+
+/// Calculate the daylight for every day of a Gregorian calendar year.
+pub fn daylight_series(year: i32, geo: GeoCoords) -> AstroResult<Vec<Daylight>> {
+    let jd_start = Builder::from_gregorian_utc(year, 1, 1, 0, 0, 0).build()?.julian_day_number();
+    let jd_end = Builder::from_gregorian_utc(year + 1, 1, 1, 0, 0, 0)
+        .build()?
+        .julian_day_number();
+
+    let days = (jd_end - jd_start).round() as usize;
+    let mut series = Vec::with_capacity(days);
+    for i in 0..days {
+        series.push(daylight(&Builder::from_julian_date(jd_start + i as f64).build()?, geo)?);
+    }
+
+    Ok(series)
+}
+
+fn daylight(date: &AstroTime, geo: GeoCoords) -> AstroResult<Daylight> {
+    let rts = sun_rise_transit_set(date, geo, None)?;
+    let transit = rts.transit();
+    let sun = sun::apparent_equatorial(&transit)?;
+
+    let length = match (rts.rise(), rts.set()) {
+        (Some(rise), Some(set)) if set > rise => set.duration_since(&rise)?,
+        (Some(rise), Some(_)) => {
+            let next_day = date.add_duration(AstroDuration::from_days(1.0)?)?;
+            match sun_rise_transit_set(&next_day, geo, None)?.set() {
+                Some(set) => set.duration_since(&rise)?,
+                None => AstroDuration::from_days(1.0)?,
+            }
+        }
+        _ => {
+            // Up all day if the Sun is above the horizon at its transit.
+            let alt = trans_equatorial_to_horizontal(sun, geo, &transit)?.altitude().radians();
+            AstroDuration::from_days(if alt > STANDARD_ALTITUDE_SUN { 1.0 } else { 0.0 })?
+        }
+    };
+
+    Ok(Daylight {
+        date: Builder::from_julian_date((date.as_utc()?.julian_day_number() - 0.5).floor() + 0.5)
+            .build()?,
+        length,
+        solar_declination: sun.declination(),
+    })
+}
+
+/// Calculate the intervals when the geometric altitude of the center of the Sun is between `low`
+/// and `high`, around its transit on the Universal Time day containing `date`.
+///
+/// Usually there are two intervals, one in the morning and one in the evening. There is one when
+/// the Sun doesn't climb above `high`, and none when it doesn't reach the band. When the Sun
+/// stays above `low` through the night, the evening interval ends at the next morning, and when
+/// it stays in the band all day the interval is the whole day. The intervals are in order, each
+/// as the start and end times in Universal Time.
+///
+/// Returns an error if `low` isn't below `high`.
+pub fn sun_altitude_intervals(date: &AstroTime,
+                              geo: GeoCoords,
+                              low: RadianAngle,
+                              high: RadianAngle)
+                              -> AstroResult<Vec<(AstroTime, AstroTime)>> {
+    if low.radians() >= high.radians() {
+        return Err(AstroAlgorithmsError::InvalidArgument("The bottom of the altitude band must \
+                                                          be below the top"
+            .to_owned()));
+    }
+
+    let lower = rise_transit_set(date, geo, low, sun::apparent_equatorial)?;
+    let upper = rise_transit_set(date, geo, high, sun::apparent_equatorial)?;
+
+    let mut intervals = match (lower.rise(), lower.set(), upper.rise(), upper.set()) {
+        (Some(low_rise), Some(low_set), Some(high_rise), Some(high_set)) => {
+            vec![interval(low_rise, high_rise)?, interval(high_set, low_set)?]
+        }
+        // Below the top of the band all day.
+        (Some(low_rise), Some(low_set), None, None) => vec![interval(low_rise, low_set)?],
+        // Above the bottom of the band all night.
+        (None, None, Some(_), Some(high_set)) => {
+            let next_day = date.add_duration(AstroDuration::from_days(1.0)?)?;
+            match rise_transit_set(&next_day, geo, high, sun::apparent_equatorial)?.rise() {
+                Some(next_rise) => vec![interval(high_set, next_rise)?],
+                None => vec![],
+            }
+        }
+        (None, None, None, None) => {
+            let transit = lower.transit();
+            let sun = sun::apparent_equatorial(&transit)?;
+            let alt = trans_equatorial_to_horizontal(sun, geo, &transit)?.altitude().radians();
+            if alt > low.radians() && alt < high.radians() {
+                let jd_0 = (date.as_utc()?.julian_day_number() - 0.5).floor() + 0.5;
+                vec![(Builder::from_julian_date(jd_0).build()?,
+                      Builder::from_julian_date(jd_0 + 1.0).build()?)]
+            } else {
+                vec![]
+            }
+        }
+        _ => vec![],
+    };
+    intervals.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    Ok(intervals)
+}
+
+/// Calculate the golden hours on the Universal Time day containing `date`, when the Sun is between
+/// 4 degrees below and 6 degrees above the horizon and the light is warm and soft.
+///
+/// See `sun_altitude_intervals`.
+pub fn golden_hours(date: &AstroTime, geo: GeoCoords) -> AstroResult<Vec<(AstroTime, AstroTime)>> {
+    sun_altitude_intervals(date,
+                           geo,
+                           RadianAngle::new(GOLDEN_HOUR_BOTTOM)?,
+                           RadianAngle::new(GOLDEN_HOUR_TOP)?)
+}
+
+/// Calculate the blue hours on the Universal Time day containing `date`, when the Sun is between
+/// 6 and 4 degrees below the horizon and the sky is a deep blue.
+///
+/// See `sun_altitude_intervals`.
+pub fn blue_hours(date: &AstroTime, geo: GeoCoords) -> AstroResult<Vec<(AstroTime, AstroTime)>> {
+    sun_altitude_intervals(date,
+                           geo,
+                           RadianAngle::new(BLUE_HOUR_BOTTOM)?,
+                           RadianAngle::new(GOLDEN_HOUR_BOTTOM)?)
+}
+
+// The times found for a day are fractions of the same Universal Time day, so an interval that
+// crosses midnight ends on the next day.
+fn interval(start: AstroTime, end: AstroTime) -> AstroResult<(AstroTime, AstroTime)> {
+    if end < start {
+        Ok((start, end.add_duration(AstroDuration::from_days(1.0)?)?))
+    } else {
+        Ok((start, end))
+    }
+}
+
+// Iterate the correction to a time given as a fraction of the day after jd_0.
+fn iterate<F>(jd_0: f64, mut m: f64, correction: F) -> AstroResult<AstroTime>
+    where F: Fn(&AstroTime) -> AstroResult<f64>
+{
+    for _ in 0..10 {
+        let dm = correction(&Builder::from_julian_date(jd_0 + m).build()?)?;
+        m += dm;
+        if dm.abs() < 1.0e-6 {
+            break;
+        }
+    }
+
+    Builder::from_julian_date(jd_0 + m).build()
+}
+
+fn fraction_of_day(m: f64) -> f64 {
+    m - m.floor()
+}
+
+// Put an hour angle in the range [-π, π).
+fn hour_angle_branch(h: f64) -> f64 {
+    let h = map_to_branch(h, -PI, PI);
+    if h >= PI { h - 2.0 * PI } else { h }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod rise_set_tests {
+    use super::*;
+
+    fn geo(lat: f64, west_lon: f64) -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(lat.to_radians()).unwrap(),
+                       RadianAngle::new(west_lon.to_radians()).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    fn hours(time: AstroTime, jd_0: f64) -> f64 {
+        (time.julian_day_number() - jd_0) * 24.0
+    }
+
+    // Example 15.a, Astronomical Algorithms 2nd ed., Venus at 0h TD on 1988 March 19, 20 and 21
+    // interpolated as in chapter 3.
+    fn venus(time: &AstroTime) -> AstroResult<EquatorialCoords> {
+        let n = time.julian_ephemeris_day()? - 2_447_240.5;
+        let interpolate = |y: [f64; 3]| {
+            let (a, b) = (y[1] - y[0], y[2] - y[1]);
+            y[1] + n / 2.0 * (a + b + n * (b - a))
+        };
+
+        let ra = interpolate([40.680_21, 41.731_29, 42.782_04]).to_radians();
+        let dec = interpolate([18.047_61, 18.440_92, 18.827_42]).to_radians();
+        EquatorialCoords::new(RadianAngle::new(ra)?, RadianAngle::new(dec)?, *time)
+    }
+
+    #[test]
+    fn test_rise_transit_set() {
+        // Boston, rising at 12h 25m, transit at 19h 41m, and setting at 2h 55m UT.
+        let jd_0 = 2_447_240.5;
+        let date = Builder::from_julian_date(jd_0 + 0.3).build().unwrap();
+        let h_0 = RadianAngle::new(STANDARD_ALTITUDE_STAR).unwrap();
+        let rts = rise_transit_set(&date, geo(42.333_3, 71.083_3), h_0, venus).unwrap();
+
+        let minute = 1.0 / 60.0;
+        assert!(approx_eq(hours(rts.rise().unwrap(), jd_0), 12.0 + 25.0 * minute, minute));
+        assert!(approx_eq(hours(rts.transit(), jd_0), 19.0 + 41.0 * minute, minute));
+        assert!(approx_eq(hours(rts.set().unwrap(), jd_0), 2.0 + 55.0 * minute, minute));
+
+        // Circumpolar from the Arctic.
+        let rts = rise_transit_set(&date, geo(80.0, 0.0), h_0, venus).unwrap();
+        assert!(rts.rise().is_none() && rts.set().is_none());
+    }
+
+    #[test]
+    fn test_sun_rise_transit_set() {
+        // Greenwich on 2017 June 21, sunrise at 3:43, noon at 12:02, sunset at 20:21 UT.
+        let date = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let jd_0 = date.julian_day_number();
+        let greenwich = geo(51.477_8, 0.0);
+        let rts = sun_rise_transit_set(&date, greenwich, None).unwrap();
+
+        let minute = 1.0 / 60.0;
+        assert!(approx_eq(hours(rts.rise().unwrap(), jd_0), 3.0 + 43.0 * minute, minute));
+        assert!(approx_eq(hours(rts.transit(), jd_0), 12.0 + 2.0 * minute, minute));
+        assert!(approx_eq(hours(rts.set().unwrap(), jd_0), 20.0 + 21.0 * minute, minute));
+
+        // From 100 meters above the sea the Sun rises earlier and sets later.
+        let dip = Some(dip_of_horizon(100.0).unwrap());
+        let from_hill = sun_rise_transit_set(&date, greenwich, dip).unwrap();
+        assert!(from_hill.rise().unwrap() < rts.rise().unwrap());
+        assert!(from_hill.set().unwrap() > rts.set().unwrap());
+        assert!(from_hill.transit() == rts.transit());
+
+        // Midnight Sun
+        let rts = sun_rise_transit_set(&date, geo(75.0, 0.0), None).unwrap();
+        assert!(rts.rise().is_none());
+    }
+
+    #[test]
+    fn test_day_length() {
+        // Greenwich on the summer solstice of 2017, 16h 38m.
+        let date = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let length = day_length(&date, geo(51.477_8, 0.0)).unwrap();
+        assert!(approx_eq(length.minutes(), 16.0 * 60.0 + 38.0, 2.0));
+
+        // In Seattle the Sun sets after midnight UT.
+        let length = day_length(&date, geo(47.6, 122.3)).unwrap();
+        assert!(approx_eq(length.hours(), 16.0, 0.05));
+
+        // Midnight Sun and polar night.
+        assert_eq!(day_length(&date, geo(75.0, 0.0)).unwrap().days(), 1.0);
+        assert_eq!(day_length(&date, geo(-75.0, 0.0)).unwrap().days(), 0.0);
+    }
+
+    #[test]
+    fn test_daylight_series() {
+        let series = daylight_series(2016, geo(51.477_8, 0.0)).unwrap();
+        assert_eq!(series.len(), 366);
+
+        // The longest day is near the June solstice, when the Sun is furthest north.
+        let longest = series.iter()
+            .max_by(|a, b| a.length().partial_cmp(&b.length()).unwrap())
+            .unwrap();
+        let (_, month, day, _, _, _) = longest.date().to_gregorian_utc();
+        assert_eq!(month, 6);
+        assert!((19..=22).contains(&day));
+        assert!(approx_eq(longest.solar_declination().radians().to_degrees(), 23.44, 0.01));
+
+        let (year, month, day, hour, _, _) = series[59].date().to_gregorian_utc();
+        assert_eq!((year, month, day, hour), (2016, 2, 29, 0));
+    }
+
+    #[test]
+    fn test_constants() {
+        assert!(approx_eq(GOLDEN_HOUR_BOTTOM, -4f64.to_radians(), 1.0e-15));
+        assert!(approx_eq(GOLDEN_HOUR_TOP, 6f64.to_radians(), 1.0e-15));
+        assert!(approx_eq(BLUE_HOUR_BOTTOM, -6f64.to_radians(), 1.0e-15));
+    }
+
+    #[test]
+    fn test_sun_altitude_intervals() {
+        let date = Builder::from_gregorian_utc(2017, 3, 20, 0, 0, 0).build().unwrap();
+        let place = geo(40.0, 0.0);
+
+        // Rising through the band in the morning and setting through it in the evening.
+        let altitude = |time: &AstroTime| {
+            let eq = sun::apparent_equatorial(time).unwrap();
+            let alt = trans_equatorial_to_horizontal(eq, place, time).unwrap().altitude();
+            alt.radians().to_degrees()
+        };
+        let golden = golden_hours(&date, place).unwrap();
+        assert_eq!(golden.len(), 2);
+        assert!(approx_eq(altitude(&golden[0].0), -4.0, 0.01));
+        assert!(approx_eq(altitude(&golden[0].1), 6.0, 0.01));
+        assert!(approx_eq(altitude(&golden[1].0), 6.0, 0.01));
+        assert!(approx_eq(altitude(&golden[1].1), -4.0, 0.01));
+        for &(start, end) in &golden {
+            let minutes = end.duration_since(&start).unwrap().minutes();
+            assert!(minutes > 45.0 && minutes < 60.0);
+        }
+        assert!(golden[0].1 < golden[1].0);
+
+        // The blue hour is before sunrise and after sunset.
+        let blue = blue_hours(&date, place).unwrap();
+        let rts = sun_rise_transit_set(&date, place, None).unwrap();
+        assert_eq!(blue.len(), 2);
+        assert!(blue[0].1 == golden[0].0 && blue[0].1 < rts.rise().unwrap());
+        assert!(blue[1].0 == golden[1].1 && blue[1].0 > rts.set().unwrap());
+
+        // At midsummer in the far north the Sun stays above -4 degrees, so the evening golden
+        // hour lasts through the night and there is no blue hour.
+        let date = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let golden = golden_hours(&date, geo(70.0, 0.0)).unwrap();
+        assert_eq!(golden.len(), 1);
+        assert!(golden[0].1.duration_since(&golden[0].0).unwrap().hours() > 4.0);
+        assert!(blue_hours(&date, geo(70.0, 0.0)).unwrap().is_empty());
+
+        // Polar night, the Sun stays below the band.
+        assert!(golden_hours(&date, geo(-80.0, 0.0)).unwrap().is_empty());
+
+        let low = RadianAngle::new(0.1).unwrap();
+        assert!(sun_altitude_intervals(&date, place, low, low).is_err());
+    }
+
+    #[test]
+    fn test_rise_set_azimuths() {
+        // Due east and west at the equinox.
+        let date = Builder::from_gregorian_utc(2017, 3, 20, 0, 0, 0).build().unwrap();
+        let place = geo(40.0, 0.0);
+        let azimuths = sun_rise_set_azimuths(&date, place).unwrap();
+        let degrees = |angle: Option<RadianAngle>| angle.unwrap().radians().to_degrees();
+        assert!(approx_eq(degrees(azimuths.rise()), 270.0, 1.0));
+        assert!(approx_eq(degrees(azimuths.set()), 90.0, 1.0));
+        assert!(approx_eq(degrees(azimuths.rise_amplitude()), 0.0, 1.0));
+
+        // Rising in the northeast at the June solstice, sin A = sin δ / cos φ for a body on the
+        // horizon, less a little for the standard altitude below it.
+        let date = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let azimuths = sun_rise_set_azimuths(&date, place).unwrap();
+        let amplitude = (23.44f64.to_radians().sin() / 40f64.to_radians().cos()).asin();
+        assert!(approx_eq(degrees(azimuths.rise_amplitude()), amplitude.to_degrees(), 1.0));
+        assert!(approx_eq(degrees(azimuths.set_amplitude()), amplitude.to_degrees(), 1.0));
+        assert!(approx_eq(degrees(azimuths.rise()) + degrees(azimuths.set()), 360.0, 1.0));
+
+        // No rising or setting of the midnight Sun.
+        let azimuths = sun_rise_set_azimuths(&date, geo(75.0, 0.0)).unwrap();
+        assert!(azimuths.rise().is_none() && azimuths.set_amplitude().is_none());
+
+        let azimuths = moon_rise_set_azimuths(&date, place).unwrap();
+        assert!(azimuths.rise().is_some() || azimuths.set().is_some());
+    }
+
+    #[test]
+    fn test_moon_rise_transit_set() {
+        // The Moon's altitude at the calculated times should be the standard altitude.
+        let date = Builder::from_gregorian_utc(2017, 8, 10, 0, 0, 0).build().unwrap();
+        let place = geo(40.0, 105.0);
+        let rts = moon_rise_transit_set(&date, place, None).unwrap();
+
+        let h_0 = standard_altitude_moon(moon::distance(&date).unwrap()).unwrap().radians();
+        for time in &[rts.rise().unwrap(), rts.set().unwrap()] {
+            let eq = moon::apparent_equatorial(time).unwrap();
+            let alt = trans_equatorial_to_horizontal(eq, place, time).unwrap().altitude();
+            assert!(approx_eq(alt.radians(), h_0, 1.0e-4));
+        }
+    }
+}
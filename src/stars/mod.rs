@@ -0,0 +1,299 @@
+//!
+//! Module for the positions of stars.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! Apparent places are calculated following chapter 23 of Astronomical Algorithms, 2nd Edition by
+//! Jean Meeus, which applies proper motion, precession, nutation and annual aberration to a
+//! catalog position. Stars with a known parallax are also displaced by the annual parallax, which
+//! Meeus neglects.
+//!
+use super::astro_time::AstroTime;
+use super::coords::*;
+use super::earth;
+use super::error::*;
+use super::math::*;
+use super::sun;
+
+/// A star from a catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Star {
+    name: String,
+    position: EquatorialCoords,
+    proper_motion_ra: RadianAngle,
+    proper_motion_dec: RadianAngle,
+    parallax: RadianAngle,
+}
+
+impl Star {
+    /// Create a star with a catalog position, referred to the mean equator and equinox of the
+    /// position's epoch, and no proper motion.
+    pub fn new(name: &str, position: EquatorialCoords) -> Star {
+        Star {
+            name: name.to_owned(),
+            position,
+            proper_motion_ra: RadianAngle::new(0.0).unwrap(),
+            proper_motion_dec: RadianAngle::new(0.0).unwrap(),
+            parallax: RadianAngle::new(0.0).unwrap(),
+        }
+    }
+
+    /// Set the annual proper motion in right ascension and declination, per Julian year.
+    ///
+    /// The motion in right ascension is the change in the right ascension itself, not multiplied
+    /// by the cosine of the declination.
+    pub fn with_proper_motion(mut self, ra: RadianAngle, dec: RadianAngle) -> Star {
+        self.proper_motion_ra = ra;
+        self.proper_motion_dec = dec;
+        self
+    }
+
+    /// Set the parallax in milliarcseconds.
+    ///
+    /// Returns an error if the parallax is negative.
+    pub fn with_parallax(mut self, parallax_mas: f64) -> AstroResult<Star> {
+        if parallax_mas.is_nan() {
+            Err(AstroAlgorithmsError::EncounteredNaN)
+        } else if parallax_mas < 0.0 {
+            Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue)
+        } else {
+            self.parallax = RadianAngle::new((parallax_mas / 3_600_000.0).to_radians())?;
+            Ok(self)
+        }
+    }
+
+    /// The name of the star.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The catalog position.
+    pub fn position(&self) -> EquatorialCoords {
+        self.position
+    }
+
+    /// The parallax.
+    pub fn parallax(&self) -> RadianAngle {
+        self.parallax
+    }
+
+    /// The mean position at a time, with proper motion and precession applied.
+    pub fn mean_position(&self, time: &AstroTime) -> AstroResult<EquatorialCoords> {
+        let epoch = self.position.epoch();
+        let years = (time.julian_ephemeris_day()? - epoch.julian_ephemeris_day()?) / 365.25;
+
+        let ra = self.position.right_ascension().radians() +
+                 years * self.proper_motion_ra.radians();
+        let dec = self.position.declination().radians() +
+                  years * self.proper_motion_dec.radians();
+        let moved = EquatorialCoords::new(RadianAngle::new(ra)?, RadianAngle::new(dec)?, epoch)?;
+
+        precess_equatorial(moved, *time)
+    }
+
+    /// The displacement (Δα, Δδ) of the mean position at a time due to the annual parallax, as the
+    /// star is seen from the Earth rather than the Sun.
+    ///
+    /// Δα grows without bound towards the celestial poles, where a small displacement can change
+    /// the right ascension by a lot.
+    pub fn annual_parallax(&self, time: &AstroTime) -> AstroResult<(RadianAngle, RadianAngle)> {
+        let mean = self.mean_position(time)?;
+        let (east, north) = self.parallax_offsets(mean, time)?;
+
+        Ok((RadianAngle::new(east / cos(mean.declination().radians()))?,
+            RadianAngle::new(north)?))
+    }
+
+    /// The apparent position at a time, referred to the true equator and equinox of the date.
+    ///
+    /// The corrections of equations 23.1 and 23.3 are applied as offsets east and north on the
+    /// sky, Δα cos δ and Δδ, rather than to the right ascension, so there is no tan δ to blow up
+    /// for a star at a celestial pole.
+    pub fn apparent_position(&self, time: &AstroTime) -> AstroResult<EquatorialCoords> {
+        let mean = self.mean_position(time)?;
+        let (sin_a, cos_a) = sin_cos(mean.right_ascension().radians());
+        let (sin_d, cos_d) = sin_cos(mean.declination().radians());
+
+        let (mut east, mut north) = if self.parallax.radians() > 0.0 {
+            self.parallax_offsets(mean, time)?
+        } else {
+            (0.0, 0.0)
+        };
+
+        // Nutation, equation 23.1.
+        let (delta_psi, delta_eps) = nutation(time)?;
+        let (delta_psi, delta_eps) = (delta_psi.radians(), delta_eps.radians());
+        let eps = true_obliquity(time)?.radians();
+        let (sin_e, cos_e) = sin_cos(eps);
+
+        east += (cos_e * cos_d + sin_e * sin_a * sin_d) * delta_psi - cos_a * sin_d * delta_eps;
+        north += sin_e * cos_a * delta_psi + sin_a * delta_eps;
+
+        // Annual aberration, equation 23.3.
+        let t = (time.julian_ephemeris_day()? - 2_451_545.0) / 36_525.0;
+        let kappa = (20.495_52 / 3600.0f64).to_radians();
+        let e = 0.016_708_634 - t * (0.000_042_037 + t * 0.000_000_126_7);
+        let (sin_p, cos_p) = sin_cos((102.937_35 + t * (1.719_46 + t * 0.000_46)).to_radians());
+        let (sin_s, cos_s) = sin_cos(sun::apparent_ecliptic(time)?.longitude().radians());
+
+        east += -kappa * (cos_a * cos_s * cos_e + sin_a * sin_s) +
+                e * kappa * (cos_a * cos_p * cos_e + sin_a * sin_p);
+        let common = sin_e * cos_d - cos_e * sin_a * sin_d;
+        north += -kappa * (cos_s * common + cos_a * sin_d * sin_s) +
+                 e * kappa * (cos_p * common + cos_a * sin_d * sin_p);
+
+        // Move along the great circle through the offsets, the unit vectors towards the east and
+        // the north are defined even at the poles.
+        let v = mean.to_unit_vector();
+        let offset = hypot(east, north);
+        let (sin_o, cos_o) = sin_cos(offset);
+        let (e_x, e_y, e_z) = if offset > 0.0 {
+            ((-east * sin_a - north * sin_d * cos_a) / offset,
+             (east * cos_a - north * sin_d * sin_a) / offset,
+             north * cos_d / offset)
+        } else {
+            (0.0, 0.0, 0.0)
+        };
+
+        EquatorialCoords::from_unit_vector([v[0] * cos_o + e_x * sin_o,
+                                            v[1] * cos_o + e_y * sin_o,
+                                            v[2] * cos_o + e_z * sin_o],
+                                           *time)
+    }
+
+    // The displacement due to the annual parallax as offsets Δα cos δ and Δδ.
+    fn parallax_offsets(&self,
+                        mean: EquatorialCoords,
+                        time: &AstroTime)
+                        -> AstroResult<(f64, f64)> {
+        let (sin_a, cos_a) = sin_cos(mean.right_ascension().radians());
+        let (sin_d, cos_d) = sin_cos(mean.declination().radians());
+
+        // Geocentric equatorial coordinates of the Sun in astronomical units.
+        let (x, y, z) = earth::heliocentric_state(time)?.position();
+        let (sin_e, cos_e) = sin_cos(mean_obliquity(time)?.radians());
+        let (x, y, z) = (-x, -y * cos_e + z * sin_e, -y * sin_e - z * cos_e);
+
+        // The star is displaced towards the Sun.
+        let p = self.parallax.radians();
+
+        Ok((p * (y * cos_a - x * sin_a), p * (z * cos_d - (x * cos_a + y * sin_a) * sin_d)))
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod stars_tests {
+    use super::*;
+    use astro_time::{Builder, J2000};
+
+    // Example 23.a, Astronomical Algorithms 2nd ed., θ Persei.
+    fn theta_persei() -> Star {
+        let ra = HMSAngle::new(2, 44, 11.986).unwrap();
+        let dec = DMSAngle::new(49, 13, 42.48).unwrap();
+        let arcsec = |val: f64| RadianAngle::new((val / 3600.0).to_radians()).unwrap();
+
+        Star::new("θ Per",
+                  EquatorialCoords::new(RadianAngle::from(ra), RadianAngle::from(dec), J2000)
+                      .unwrap())
+            .with_proper_motion(arcsec(0.034_25 * 15.0), arcsec(-0.089_5))
+    }
+
+    #[test]
+    fn test_mean_position() {
+        let time = Builder::from_julian_date(2_462_088.69).dynamical_time().build().unwrap();
+        let mean = theta_persei().mean_position(&time).unwrap();
+
+        // The book rounds the position with proper motion applied to 6 decimal places.
+        assert!(approx_eq(mean.right_ascension().radians().to_degrees(), 41.547_214, 5.0e-6));
+        assert!(approx_eq(mean.declination().radians().to_degrees(), 49.348_483, 5.0e-6));
+    }
+
+    #[test]
+    fn test_apparent_position() {
+        let time = Builder::from_julian_date(2_462_088.69).dynamical_time().build().unwrap();
+        let star = theta_persei();
+        let app = star.apparent_position(&time).unwrap();
+
+        // 2h 46m 14.390s and +49° 21' 07.45"
+        let ra = RadianAngle::from(HMSAngle::new(2, 46, 14.390).unwrap()).radians();
+        let dec = RadianAngle::from(DMSAngle::new(49, 21, 7.45).unwrap()).radians();
+        let arcsec = (1.0 / 3600.0f64).to_radians();
+        assert!(approx_eq(app.right_ascension().radians(), ra, 0.1 * arcsec));
+        assert!(approx_eq(app.declination().radians(), dec, 0.1 * arcsec));
+
+        assert_eq!(star.name(), "θ Per");
+        assert!(app.epoch() == time);
+    }
+
+    #[test]
+    fn test_annual_parallax() {
+        let time = Builder::from_julian_date(2_462_088.69).dynamical_time().build().unwrap();
+        let mas = (1.0 / 3_600_000.0f64).to_radians();
+
+        // Near the pole of the ecliptic the star moves around a circle through the year.
+        let pole = EquatorialCoords::new(RadianAngle::new(270f64.to_radians()).unwrap(),
+                                         RadianAngle::new(66.56f64.to_radians()).unwrap(),
+                                         J2000)
+            .unwrap();
+        let star = Star::new("pole", pole).with_parallax(100.0).unwrap();
+        assert!(approx_eq(star.parallax().radians(), 100.0 * mas, 1.0e-15));
+
+        let (d_alpha, d_delta) = star.annual_parallax(&time).unwrap();
+        let mean = star.mean_position(&time).unwrap();
+        let shift = d_alpha.radians() * mean.declination().radians().cos();
+        assert!(approx_eq(shift.hypot(d_delta.radians()), 100.0 * mas, 2.0 * mas));
+
+        // Displaced towards the Sun.
+        let sun = sun::apparent_equatorial(&time).unwrap();
+        let apparent = star.apparent_position(&time).unwrap();
+        let without = Star::new("pole", pole).apparent_position(&time).unwrap();
+        let closer = angular_separation(without, sun).radians() -
+                     angular_separation(apparent, sun).radians();
+        assert!(approx_eq(closer, 100.0 * mas, 5.0 * mas));
+
+        assert!(Star::new("pole", pole).with_parallax(-1.0).is_err());
+        assert_eq!(Star::new("pole", pole).with_parallax(f64::NAN).unwrap_err(),
+                   AstroAlgorithmsError::EncounteredNaN);
+    }
+
+    #[test]
+    fn test_apparent_position_at_pole() {
+        let time = Builder::from_julian_date(2_462_088.69).dynamical_time().build().unwrap();
+        let arcsec = (1.0 / 3600.0f64).to_radians();
+
+        // A star at the pole of J2000.0 is displaced from its mean position by the nutation, the
+        // aberration and the parallax, tens of seconds of arc, without blowing up.
+        for &dec in &[90.0, -90.0] {
+            let pole = EquatorialCoords::new(RadianAngle::new(0.0).unwrap(),
+                                             RadianAngle::new(f64::to_radians(dec)).unwrap(),
+                                             J2000)
+                .unwrap();
+            let star = Star::new("pole", pole).with_parallax(100.0).unwrap();
+            let mean = star.mean_position(&time).unwrap();
+            let app = star.apparent_position(&time).unwrap();
+            let d = angular_separation(mean, app).radians() / arcsec;
+            assert!(d > 1.0 && d < 60.0);
+        }
+
+        // Close to the pole it agrees with just off it.
+        let near = |dec: f64| {
+            let eq = EquatorialCoords::new(RadianAngle::new(1.0).unwrap(),
+                                           RadianAngle::new(dec.to_radians()).unwrap(),
+                                           J2000)
+                .unwrap();
+            Star::new("near", eq).apparent_position(&time).unwrap()
+        };
+        let d = angular_separation(near(89.999_999), near(89.999_999_9)).radians() / arcsec;
+        assert!(d < 0.01);
+    }
+}
@@ -0,0 +1,384 @@
+//!
+//! Module for the position and orientation of the Sun.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! The position of the Sun is calculated with the low accuracy method of chapter 25 of
+//! Astronomical Algorithms, 2nd Edition by Jean Meeus, which is good to about 0.01 degrees.
+//!
+use std::f64::consts::PI;
+
+use super::astro_time::{AstroDuration, AstroTime};
+use super::coords::*;
+use super::error::*;
+use super::math::*;
+use super::rise_set::sun_rise_transit_set;
+
+/// Calculate the distance from the Earth to the Sun, the radius vector, in astronomical units.
+pub fn radius_vector(time: &AstroTime) -> AstroResult<f64> {
+    Ok(SolarTerms::new(time)?.radius_vector)
+}
+
+/// Calculate the apparent geocentric ecliptic coordinates of the Sun, referred to the ecliptic
+/// and equinox of the date.
+pub fn apparent_ecliptic(time: &AstroTime) -> AstroResult<EclipticCoords> {
+    let terms = SolarTerms::new(time)?;
+
+    Ok(EclipticCoords::new(RadianAngle::new(terms.apparent_longitude().to_radians())?,
+                           RadianAngle::new(0.0)?,
+                           terms.epoch)?
+        .with_frame(EclipticFrame::True))
+}
+
+/// Calculate the apparent geocentric equatorial coordinates of the Sun, referred to the equator
+/// and equinox of the date.
+pub fn apparent_equatorial(time: &AstroTime) -> AstroResult<EquatorialCoords> {
+    let terms = SolarTerms::new(time)?;
+    let ecl = apparent_ecliptic(time)?;
+
+    // Correct the mean obliquity for the principal term of the nutation.
+    let eps = mean_obliquity(time)?.radians() + (0.002_56 * cos(terms.omega)).to_radians();
+
+    trans_ecliptic_to_equatorial(ecl, RadianAngle::new(eps)?)
+}
+
+/// Calculate the orientation of the disk of the Sun, chapter 29.
+///
+/// The axis position angle is the position angle P of the northern extremity of the axis of
+/// rotation, and the center latitude is the heliographic latitude B₀ of the center of the disk.
+/// There is no bright limb.
+pub fn disk_orientation(time: &AstroTime) -> AstroResult<DiskOrientation> {
+    let terms = SolarTerms::new(time)?;
+    let (delta_psi, _) = nutation(time)?;
+    let eps = true_obliquity(time)?.radians();
+
+    let i = 7.25f64.to_radians();
+    let k = (73.666_7 + 1.395_833_3 * (terms.julian_ephemeris_day - 2_396_758.0) / 36_525.0)
+        .to_radians();
+
+    // Longitude including aberration, but not nutation.
+    let lambda = (terms.true_longitude - 0.005_69).to_radians();
+    let lambda_prime = lambda + delta_psi.radians();
+
+    let x = atan(-cos(lambda_prime) * tan(eps));
+    let y = atan(-cos(lambda - k) * tan(i));
+    let b_0 = asin(sin(lambda - k) * sin(i));
+
+    DiskOrientation::new(RadianAngle::new(x + y)?, RadianAngle::new(b_0)?, None)
+}
+
+/// Calculate the equation of time, apparent less mean solar time, equation 28.3.
+///
+/// A sundial is ahead of a clock keeping local mean time when the result is positive. With the
+/// low accuracy position of the Sun this is good to a few seconds.
+pub fn equation_of_time(time: &AstroTime) -> AstroResult<AstroDuration> {
+    let tau = (time.julian_ephemeris_day()? - 2_451_545.0) / 365_250.0;
+    let l_0 = 280.466_456_7 +
+              tau * (360_007.698_277_9 +
+                     tau * (0.030_320_28 +
+                            tau * (1.0 / 49_931.0 - tau * (1.0 / 15_300.0 + tau / 2_000_000.0))));
+
+    let alpha = apparent_equatorial(time)?.right_ascension().radians().to_degrees();
+    let e = l_0 - 0.005_718_3 - alpha + equation_of_equinoxes(time)?.radians().to_degrees();
+
+    AstroDuration::from_minutes(4.0 * map_to_branch(e, -180.0, 180.0))
+}
+
+/// Calculate the time of solar noon, when the Sun crosses the meridian, on the Universal Time day
+/// containing `date`, and the altitude of the Sun then.
+///
+/// The altitude is the geometric altitude of the center of the Sun, without refraction. A
+/// vertical pole casts a shadow at noon of its height divided by the tangent of the altitude.
+pub fn transit_altitude(date: &AstroTime,
+                        geo: GeoCoords)
+                        -> AstroResult<(AstroTime, RadianAngle)> {
+    let noon = sun_rise_transit_set(date, geo, None)?.transit();
+    let hz = trans_equatorial_to_horizontal(apparent_equatorial(&noon)?, geo, &noon)?;
+
+    Ok((noon, hz.altitude()))
+}
+
+/// The shadow cast by a vertical object on level ground.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    length: f64,
+    azimuth: RadianAngle,
+}
+
+impl Shadow {
+    /// The length of the shadow, in the units of the height of the object.
+    pub fn length(&self) -> f64 {
+        self.length
+    }
+
+    /// The direction the shadow points, measured westward from the South.
+    pub fn azimuth(&self) -> RadianAngle {
+        self.azimuth
+    }
+}
+
+/// Calculate the shadow of a vertical object of a height at a time and place.
+///
+/// The shadow is found from the apparent altitude of the center of the Sun, with refraction for
+/// the standard conditions, and points directly away from the Sun. Returns `None` when the Sun is
+/// not above the horizon.
+pub fn shadow(height: f64, time: &AstroTime, geo: GeoCoords) -> AstroResult<Option<Shadow>> {
+    if height.is_nan() {
+        return Err(AstroAlgorithmsError::EncounteredNaN);
+    } else if height.is_infinite() {
+        return Err(AstroAlgorithmsError::EncounteredInf);
+    } else if height < 0.0 {
+        return Err(AstroAlgorithmsError::EncounteredInappropriateNegativeValue);
+    }
+
+    let hz = trans_equatorial_to_horizontal(apparent_equatorial(time)?, geo, time)?;
+    let altitude = hz.apparent_altitude(&AtmosphericConditions::default()).radians();
+    if altitude <= 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some(Shadow {
+        length: height / tan(altitude),
+        azimuth: RadianAngle::new(map_to_branch(hz.azimuth().radians() + PI, 0.0, 2.0 * PI))?,
+    }))
+}
+
+/// The solar constant, the mean total solar irradiance at one astronomical unit, in watts per
+/// square meter.
+pub const SOLAR_CONSTANT: f64 = 1_361.0;
+
+/// The geometry of the orbit of the Earth around the Sun that controls the insolation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EarthSunGeometry {
+    true_anomaly: RadianAngle,
+    distance: f64,
+    declination: RadianAngle,
+}
+
+impl EarthSunGeometry {
+    /// Calculate the geometry at a time with the same theory as the position of the Sun.
+    pub fn new(time: &AstroTime) -> AstroResult<EarthSunGeometry> {
+        let terms = SolarTerms::new(time)?;
+
+        Ok(EarthSunGeometry {
+            true_anomaly: RadianAngle::new(map_to_branch(terms.true_anomaly, 0.0, 2.0 * PI))?,
+            distance: terms.radius_vector,
+            declination: apparent_equatorial(time)?.declination(),
+        })
+    }
+
+    /// The angle of the Earth along its orbit from the perihelion.
+    pub fn true_anomaly(&self) -> RadianAngle {
+        self.true_anomaly
+    }
+
+    /// The distance between the Earth and the Sun in astronomical units.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// The declination of the Sun.
+    pub fn declination(&self) -> RadianAngle {
+        self.declination
+    }
+}
+
+/// Calculate the daily mean insolation at the top of the atmosphere at a latitude, in watts per
+/// square meter, for the declination and distance of the Sun at `time`.
+///
+/// The irradiance on a horizontal surface is averaged over a whole day from sunrise to sunset,
+/// taken when the center of the Sun crosses the horizon without refraction. It is zero in the
+/// polar night.
+pub fn daily_insolation(time: &AstroTime, latitude: RadianAngle) -> AstroResult<f64> {
+    let geometry = EarthSunGeometry::new(time)?;
+    let (sin_phi, cos_phi) = sin_cos(latitude.radians());
+    let (sin_d, cos_d) = sin_cos(geometry.declination.radians());
+
+    // The hour angle of sunset, π in the midnight Sun and 0 in the polar night.
+    let h_0 = acos((-sin_phi * sin_d / (cos_phi * cos_d)).clamp(-1.0, 1.0));
+
+    Ok(SOLAR_CONSTANT / (PI * geometry.distance * geometry.distance) *
+       (h_0 * sin_phi * sin_d + cos_phi * cos_d * sin(h_0)))
+}
+
+// Intermediate values of the solar theory, angles in degrees except omega.
+struct SolarTerms {
+    epoch: AstroTime,
+    julian_ephemeris_day: f64,
+    true_longitude: f64,
+    // The true anomaly in radians.
+    true_anomaly: f64,
+    radius_vector: f64,
+    omega: f64,
+}
+
+impl SolarTerms {
+    fn new(time: &AstroTime) -> AstroResult<SolarTerms> {
+        let epoch = time.as_dt()?;
+        let jde = epoch.julian_day_number();
+        let t = (jde - 2_451_545.0) / 36_525.0;
+
+        let l_0 = 280.466_46 + t * (36_000.769_83 + t * 0.000_303_2);
+        let m = (357.529_11 + t * (35_999.050_29 - t * 0.000_153_7)).to_radians();
+        let e = 0.016_708_634 - t * (0.000_042_037 + t * 0.000_000_126_7);
+
+        let c = (1.914_602 - t * (0.004_817 + t * 0.000_014)) * sin(m) +
+                (0.019_993 - t * 0.000_101) * sin(2.0 * m) +
+                0.000_289 * sin(3.0 * m);
+
+        let true_longitude = l_0 + c;
+        let v = m + c.to_radians();
+        let radius_vector = 1.000_001_018 * (1.0 - e * e) / (1.0 + e * cos(v));
+
+        let omega = (125.04 - 1934.136 * t).to_radians();
+
+        Ok(SolarTerms {
+            epoch,
+            julian_ephemeris_day: jde,
+            true_longitude,
+            true_anomaly: v,
+            radius_vector,
+            omega,
+        })
+    }
+
+    // Apparent longitude in degrees, corrected for aberration and nutation.
+    fn apparent_longitude(&self) -> f64 {
+        self.true_longitude - 0.005_69 - 0.004_78 * sin(self.omega)
+    }
+}
+
+// test approximate equality, only used in unit tests.
+#[cfg(test)]
+fn approx_eq(left: f64, right: f64, tol: f64) -> bool {
+    (left - right).abs() < tol
+}
+
+#[cfg(test)]
+mod sun_tests {
+    use super::*;
+    use astro_time::Builder;
+
+    // Examples 25.a and 29.a, Astronomical Algorithms 2nd ed.
+    fn example_25a() -> AstroTime {
+        Builder::from_julian_date(2_448_908.5).dynamical_time().build().unwrap()
+    }
+
+    #[test]
+    fn test_position() {
+        let time = example_25a();
+
+        assert!(approx_eq(radius_vector(&time).unwrap(), 0.997_66, 1.0e-5));
+
+        let ecl = apparent_ecliptic(&time).unwrap();
+        assert!(approx_eq(ecl.longitude().radians().to_degrees(), 199.908_95, 1.0e-5));
+
+        let eq = apparent_equatorial(&time).unwrap();
+        assert!(approx_eq(eq.right_ascension().radians().to_degrees(), 198.380_83, 1.0e-5));
+        assert!(approx_eq(eq.declination().radians().to_degrees(), -7.785_07, 1.0e-5));
+    }
+
+    #[test]
+    fn test_transit_altitude() {
+        // At the June solstice the Sun culminates 90 - 40 + 23.44 degrees high at latitude 40
+        // degrees north, near 12h local mean time less the equation of time of -1.7 minutes.
+        let geo = GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                                 RadianAngle::new(105f64.to_radians()).unwrap(),
+                                 0.0)
+            .unwrap();
+        let date = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let (noon, altitude) = transit_altitude(&date, geo).unwrap();
+        assert!(approx_eq(altitude.radians().to_degrees(), 73.44, 0.01));
+        let (_, _, day, hour, minute, _) = noon.to_gregorian_utc();
+        assert_eq!((day, hour, minute), (21, 19, 1));
+
+        // Near the equinox at Greenwich.
+        let geo = GeoCoords::new(RadianAngle::new(51.48f64.to_radians()).unwrap(),
+                                 RadianAngle::new(0.0).unwrap(),
+                                 0.0)
+            .unwrap();
+        let date = Builder::from_gregorian_utc(2017, 3, 20, 0, 0, 0).build().unwrap();
+        let (_, altitude) = transit_altitude(&date, geo).unwrap();
+        assert!(approx_eq(altitude.radians().to_degrees(), 90.0 - 51.48, 0.1));
+    }
+
+    #[test]
+    fn test_shadow() {
+        let geo = GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                                 RadianAngle::new(105f64.to_radians()).unwrap(),
+                                 0.0)
+            .unwrap();
+
+        // At noon on the June solstice the shadow points north, 0.3 times the height.
+        let date = Builder::from_gregorian_utc(2017, 6, 21, 0, 0, 0).build().unwrap();
+        let (noon, altitude) = transit_altitude(&date, geo).unwrap();
+        let noon_shadow = shadow(2.0, &noon, geo).unwrap().unwrap();
+        assert!(approx_eq(noon_shadow.length(), 2.0 / altitude.radians().tan(), 1.0e-3));
+        assert!(approx_eq(noon_shadow.azimuth().radians().to_degrees(), 180.0, 0.1));
+
+        // In the afternoon the Sun is in the west and the shadow points east, and it grows.
+        let afternoon = noon.add_duration(AstroDuration::from_hours(4.0).unwrap()).unwrap();
+        let later = shadow(2.0, &afternoon, geo).unwrap().unwrap();
+        let azimuth = later.azimuth().radians().to_degrees();
+        assert!(azimuth > 180.0 && azimuth < 360.0);
+        assert!(later.length() > noon_shadow.length());
+
+        // No shadow at night.
+        let night = noon.add_duration(AstroDuration::from_hours(12.0).unwrap()).unwrap();
+        assert!(shadow(2.0, &night, geo).unwrap().is_none());
+        assert!(shadow(-1.0, &noon, geo).is_err());
+    }
+
+    #[test]
+    fn test_equation_of_time() {
+        // Example 28.a, Astronomical Algorithms 2nd ed., 13m 42.6s.
+        let time = Builder::from_julian_date(2_448_908.5).dynamical_time().build().unwrap();
+        let e = equation_of_time(&time).unwrap();
+        assert!(approx_eq(e.minutes(), 13.71, 0.05));
+
+        // Behind in mid February.
+        let time = Builder::from_gregorian_utc(2017, 2, 11, 12, 0, 0).build().unwrap();
+        assert!(approx_eq(equation_of_time(&time).unwrap().minutes(), -14.2, 0.1));
+    }
+
+    #[test]
+    fn test_disk_orientation() {
+        let disk = disk_orientation(&example_25a()).unwrap();
+        assert!(approx_eq(disk.axis_position_angle().radians().to_degrees(), 26.27, 0.01));
+        assert!(approx_eq(disk.center_latitude().radians().to_degrees(), 5.99, 0.01));
+        assert!(disk.bright_limb_position_angle().is_none());
+    }
+
+    #[test]
+    fn test_earth_sun_geometry() {
+        // Example 25.a, v = 277.09665 degrees, R = 0.99766 AU, and δ = -7°47'06".
+        let geometry = EarthSunGeometry::new(&example_25a()).unwrap();
+        assert!(approx_eq(geometry.true_anomaly().radians().to_degrees(), 277.096_65, 1.0e-4));
+        assert!(approx_eq(geometry.distance(), 0.997_66, 1.0e-5));
+        assert!(approx_eq(geometry.declination().radians().to_degrees(), -7.785_07, 1.0e-4));
+    }
+
+    #[test]
+    fn test_daily_insolation() {
+        let degrees = |angle: f64| RadianAngle::new(angle.to_radians()).unwrap();
+
+        // At the North Pole at the June solstice the Sun circles at the height of its declination,
+        // about 525 W/m², more than at the equator on the equinox, about 435 W/m².
+        let solstice = Builder::from_gregorian_utc(2017, 6, 21, 4, 24, 0).build().unwrap();
+        let pole = daily_insolation(&solstice, degrees(90.0)).unwrap();
+        let expected = SOLAR_CONSTANT * 23.437f64.to_radians().sin() / (1.016_3 * 1.016_3);
+        assert!(approx_eq(pole, expected, 1.0));
+
+        let equinox = Builder::from_gregorian_utc(2017, 3, 20, 10, 29, 0).build().unwrap();
+        let equator = daily_insolation(&equinox, degrees(0.0)).unwrap();
+        let r = radius_vector(&equinox).unwrap();
+        assert!(approx_eq(equator, SOLAR_CONSTANT / (PI * r * r), 0.1));
+
+        // Polar night.
+        assert_eq!(daily_insolation(&solstice, degrees(-80.0)).unwrap(), 0.0);
+    }
+}
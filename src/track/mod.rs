@@ -0,0 +1,411 @@
+//!
+//! Module for the apparent paths of bodies across the sky.
+//!
+//! Authors: Ryan Leach
+//!
+//! Copyright: Ryan Leach, 2017
+//!
+//! License: [BSD 3-clause](https://opensource.org/licenses/BSD-3-Clause)
+//!
+//! A sky track samples the position of a body over a range of dates, for plotting its path
+//! against the stars or across the sky of an observer. The stationary points, where the motion of
+//! a planet in right ascension reverses at the start and end of a retrograde loop, are found too
+//! so the loop can be annotated.
+//!
+use std::f64::consts::PI;
+
+use super::astro_time::{AstroDuration, AstroTime, Builder};
+use super::bodies::Body;
+use super::coords::*;
+use super::ephemeris::apparent_equatorial;
+use super::error::*;
+use super::moon;
+
+// Half the interval used to difference the coordinate when finding its rate of change, the
+// precision of a stationary point, and the step searching for them, in days.
+const RATE_INTERVAL: f64 = 0.01;
+const STATIONARY_PRECISION: f64 = 1.0e-3;
+const STATIONARY_SEARCH_STEP: f64 = 1.0;
+
+/// The coordinate whose motion defines the stationary points of a planet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// The apparent right ascension, as seen against the stars on an equatorial chart.
+    RightAscension,
+    /// The apparent ecliptic longitude, as usually quoted in almanacs.
+    EclipticLongitude,
+}
+
+/// Whether the motion of a planet turns westward or eastward at a stationary point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StationaryKind {
+    /// The start of retrograde, westward, motion.
+    Retrograde,
+    /// The end of retrograde motion, when the planet resumes its direct, eastward, motion.
+    Direct,
+}
+
+/// A time where the apparent motion of a planet reverses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StationaryPoint {
+    time: AstroTime,
+    kind: StationaryKind,
+}
+
+impl StationaryPoint {
+    /// The time in Universal Time.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+
+    /// Whether the retrograde motion starts or ends.
+    pub fn kind(&self) -> StationaryKind {
+        self.kind
+    }
+}
+
+/// The span of time a planet is in retrograde motion, between two stationary points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetrogradeInterval {
+    start: AstroTime,
+    end: AstroTime,
+}
+
+impl RetrogradeInterval {
+    /// The stationary point starting the retrograde motion, in Universal Time.
+    pub fn start(&self) -> AstroTime {
+        self.start
+    }
+
+    /// The stationary point ending the retrograde motion, in Universal Time.
+    pub fn end(&self) -> AstroTime {
+        self.end
+    }
+}
+
+/// The position of a body at one time on its track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    time: AstroTime,
+    equatorial: EquatorialCoords,
+    horizontal: HorizontalCoords,
+}
+
+impl TrackPoint {
+    /// The time in Universal Time.
+    pub fn time(&self) -> AstroTime {
+        self.time
+    }
+
+    /// The apparent geocentric equatorial coordinates, referred to the equator and equinox of the
+    /// date.
+    pub fn equatorial(&self) -> EquatorialCoords {
+        self.equatorial
+    }
+
+    /// The horizontal coordinates for the observer, without refraction.
+    pub fn horizontal(&self) -> HorizontalCoords {
+        self.horizontal
+    }
+}
+
+/// The sampled path of a body across the sky.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkyTrack {
+    points: Vec<TrackPoint>,
+    stationary_points: Vec<StationaryPoint>,
+}
+
+impl SkyTrack {
+    /// The positions in order of time.
+    pub fn points(&self) -> &[TrackPoint] {
+        &self.points
+    }
+
+    /// The points where the motion in right ascension reverses, in order of time.
+    pub fn stationary_points(&self) -> &[StationaryPoint] {
+        &self.stationary_points
+    }
+}
+
+/// Sample the path of a body across the sky between two times.
+///
+/// The body is sampled every `step`, starting at `start` and with a final sample at `end`. The
+/// horizontal coordinates of the Moon are corrected for parallax, for the other bodies it is
+/// negligible. Stationary points are detected from a reversal of the motion in right ascension
+/// between samples, so the step must be short compared with the retrograde loop, and refined to
+/// about a minute.
+pub fn sky_track(body: Body,
+                 observer: GeoCoords,
+                 start: &AstroTime,
+                 end: &AstroTime,
+                 step: AstroDuration)
+                 -> AstroResult<SkyTrack> {
+    let jd_start = start.as_utc()?.julian_day_number();
+    let jd_end = end.as_utc()?.julian_day_number();
+    let days = step.days();
+    if jd_end < jd_start {
+        return Err(AstroAlgorithmsError::InvalidArgument("The end of the track must not be \
+                                                          before the start"
+            .to_owned()));
+    } else if days <= 0.0 {
+        return Err(AstroAlgorithmsError::InvalidArgument("The step along the track must be \
+                                                          positive"
+            .to_owned()));
+    }
+
+    let steps = ((jd_end - jd_start) / days).ceil() as usize;
+    let mut points = Vec::with_capacity(steps + 1);
+    for i in 0..(steps + 1) {
+        let time = ut(f64::min(jd_start + i as f64 * days, jd_end))?;
+        let equatorial = apparent_equatorial(body, &time)?;
+        let mut horizontal = trans_equatorial_to_horizontal(equatorial, observer, &time)?;
+        if body == Body::Moon {
+            let parallax = horizontal_parallax(moon::distance(&time)?)?;
+            let altitude = horizontal.altitude().radians() -
+                           parallax_in_altitude(parallax, horizontal.altitude()).radians();
+            horizontal = HorizontalCoords::new(RadianAngle::new(altitude)?, horizontal.azimuth())?;
+        }
+
+        points.push(TrackPoint {
+            time,
+            equatorial,
+            horizontal,
+        });
+    }
+
+    let stationary_points = find_stationary_points(body,
+                                                   jd_start,
+                                                   jd_end,
+                                                   days,
+                                                   Motion::RightAscension)?;
+
+    Ok(SkyTrack {
+        points,
+        stationary_points,
+    })
+}
+
+/// Find the stationary points of a body between two times, in order of time.
+///
+/// The rate of the motion is checked every day, so stationary points closer together than that
+/// may be missed. The Sun and Moon are never stationary.
+pub fn stationary_points(body: Body,
+                         start: &AstroTime,
+                         end: &AstroTime,
+                         motion: Motion)
+                         -> AstroResult<Vec<StationaryPoint>> {
+    let jd_start = start.as_utc()?.julian_day_number();
+    let jd_end = end.as_utc()?.julian_day_number();
+    if jd_end < jd_start {
+        return Err(AstroAlgorithmsError::InvalidArgument("The end of the range for stationary \
+                                                          points must not be before the start"
+            .to_owned()));
+    }
+
+    find_stationary_points(body, jd_start, jd_end, STATIONARY_SEARCH_STEP, motion)
+}
+
+/// Find the retrograde motion of a planet nearest to a time.
+///
+/// This is the retrograde interval containing `time` if the planet is retrograde, otherwise the
+/// closest one before or after it. The stationary points are searched for within a synodic period
+/// of `time`. Returns `None` for the Sun and Moon, which are never retrograde.
+pub fn retrograde_interval(body: Body,
+                           time: &AstroTime,
+                           motion: Motion)
+                           -> AstroResult<Option<RetrogradeInterval>> {
+    let period = match synodic_period(body) {
+        Some(period) => period,
+        None => return Ok(None),
+    };
+
+    let jd = time.as_utc()?.julian_day_number();
+    let points = find_stationary_points(body,
+                                        jd - period,
+                                        jd + period,
+                                        STATIONARY_SEARCH_STEP,
+                                        motion)?;
+
+    // Days from the time to the interval, zero if it is inside.
+    let distance = |interval: &RetrogradeInterval| {
+        let start = interval.start.julian_day_number();
+        let end = interval.end.julian_day_number();
+        f64::max(f64::max(start - jd, jd - end), 0.0)
+    };
+
+    let intervals = points.windows(2)
+        .filter(|pair| {
+            pair[0].kind == StationaryKind::Retrograde && pair[1].kind == StationaryKind::Direct
+        })
+        .map(|pair| {
+            RetrogradeInterval {
+                start: pair[0].time,
+                end: pair[1].time,
+            }
+        });
+
+    Ok(intervals.min_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap()))
+}
+
+// Scan the rate of a coordinate between two Julian days for changes of sign, and bisect for the
+// stationary points.
+fn find_stationary_points(body: Body,
+                          jd_start: f64,
+                          jd_end: f64,
+                          step: f64,
+                          motion: Motion)
+                          -> AstroResult<Vec<StationaryPoint>> {
+    let mut points = vec![];
+    let mut jd = jd_start;
+    let mut rate_start = rate(body, jd, motion)?;
+    while jd < jd_end {
+        let jd_next = f64::min(jd + step, jd_end);
+        let rate_end = rate(body, jd_next, motion)?;
+
+        if rate_start.signum() != rate_end.signum() {
+            let (mut lo, mut hi) = (jd, jd_next);
+            while hi - lo > STATIONARY_PRECISION {
+                let mid = 0.5 * (lo + hi);
+                if rate(body, mid, motion)?.signum() == rate_start.signum() {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+
+            points.push(StationaryPoint {
+                time: ut(0.5 * (lo + hi))?,
+                kind: if rate_start > 0.0 {
+                    StationaryKind::Retrograde
+                } else {
+                    StationaryKind::Direct
+                },
+            });
+        }
+
+        jd = jd_next;
+        rate_start = rate_end;
+    }
+
+    Ok(points)
+}
+
+// The change of a coordinate over a short interval centered on a Julian day, positive eastward.
+fn rate(body: Body, jd: f64, motion: Motion) -> AstroResult<f64> {
+    let angle = |jd: f64| -> AstroResult<f64> {
+        let time = ut(jd)?;
+        let eq = apparent_equatorial(body, &time)?;
+        match motion {
+            Motion::RightAscension => Ok(eq.right_ascension().radians()),
+            Motion::EclipticLongitude => {
+                let ecl = trans_equatorial_to_ecliptic(eq, true_obliquity(&time)?)?;
+                Ok(ecl.longitude().radians())
+            }
+        }
+    };
+
+    Ok(map_to_branch(angle(jd + RATE_INTERVAL)? - angle(jd - RATE_INTERVAL)?, -PI, PI))
+}
+
+// The mean synodic period of a planet in days.
+fn synodic_period(body: Body) -> Option<f64> {
+    match body {
+        Body::Sun | Body::Moon => None,
+        Body::Mercury => Some(115.88),
+        Body::Venus => Some(583.92),
+        Body::Mars => Some(779.94),
+        Body::Jupiter => Some(398.88),
+        Body::Saturn => Some(378.09),
+        Body::Uranus => Some(369.66),
+        Body::Neptune => Some(367.49),
+    }
+}
+
+fn ut(jd: f64) -> AstroResult<AstroTime> {
+    Builder::from_julian_date(jd).build()
+}
+
+#[cfg(test)]
+mod track_tests {
+    use super::*;
+
+    fn observer() -> GeoCoords {
+        GeoCoords::new(RadianAngle::new(40f64.to_radians()).unwrap(),
+                       RadianAngle::new(105f64.to_radians()).unwrap(),
+                       0.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sky_track() {
+        let start = Builder::from_gregorian_utc(2018, 5, 1, 0, 0, 0).build().unwrap();
+        let end = Builder::from_gregorian_utc(2018, 10, 1, 0, 0, 0).build().unwrap();
+        let step = AstroDuration::from_days(2.0).unwrap();
+        let track = sky_track(Body::Mars, observer(), &start, &end, step).unwrap();
+
+        let points = track.points();
+        assert_eq!(points.len(), 78);
+        assert_eq!(points[0].time(), start);
+        assert_eq!(points[points.len() - 1].time(), end);
+
+        // Mars was stationary on 2018 June 28 and August 27, retrograde in between.
+        let stationary = track.stationary_points();
+        assert_eq!(stationary.len(), 2);
+        let first = Builder::from_gregorian_utc(2018, 6, 28, 0, 0, 0).build().unwrap();
+        let second = Builder::from_gregorian_utc(2018, 8, 27, 0, 0, 0).build().unwrap();
+        assert!(stationary[0].time().duration_since(&first).unwrap().days().abs() < 2.0);
+        assert!(stationary[1].time().duration_since(&second).unwrap().days().abs() < 2.0);
+        assert_eq!(stationary[0].kind(), StationaryKind::Retrograde);
+        assert_eq!(stationary[1].kind(), StationaryKind::Direct);
+
+        // The Moon never moves backwards.
+        let end = Builder::from_gregorian_utc(2018, 5, 3, 0, 0, 0).build().unwrap();
+        let step = AstroDuration::from_hours(1.0).unwrap();
+        let track = sky_track(Body::Moon, observer(), &start, &end, step).unwrap();
+        assert_eq!(track.points().len(), 49);
+        assert!(track.stationary_points().is_empty());
+    }
+
+    #[test]
+    fn test_sky_track_errors() {
+        let start = Builder::from_gregorian_utc(2018, 5, 1, 0, 0, 0).build().unwrap();
+        let end = Builder::from_gregorian_utc(2018, 4, 1, 0, 0, 0).build().unwrap();
+        let step = AstroDuration::from_days(1.0).unwrap();
+        assert!(sky_track(Body::Sun, observer(), &start, &end, step).is_err());
+
+        let step = AstroDuration::from_days(0.0).unwrap();
+        assert!(sky_track(Body::Sun, observer(), &end, &start, step).is_err());
+    }
+
+    #[test]
+    fn test_retrograde_interval() {
+        // Mercury was stationary in longitude on 2018 March 23 and April 15.
+        let time = Builder::from_gregorian_utc(2018, 4, 1, 0, 0, 0).build().unwrap();
+        let interval = retrograde_interval(Body::Mercury, &time, Motion::EclipticLongitude)
+            .unwrap()
+            .unwrap();
+        let start = Builder::from_gregorian_utc(2018, 3, 23, 0, 0, 0).build().unwrap();
+        let end = Builder::from_gregorian_utc(2018, 4, 15, 0, 0, 0).build().unwrap();
+        assert!(interval.start().duration_since(&start).unwrap().days().abs() < 1.0);
+        assert!(interval.end().duration_since(&end).unwrap().days().abs() < 1.0);
+
+        // At the start of 2018 the nearest retrograde motion of Mars is the one in the summer,
+        // not the one in 2016.
+        let time = Builder::from_gregorian_utc(2018, 1, 1, 0, 0, 0).build().unwrap();
+        let interval = retrograde_interval(Body::Mars, &time, Motion::EclipticLongitude)
+            .unwrap()
+            .unwrap();
+        let start = Builder::from_gregorian_utc(2018, 6, 28, 0, 0, 0).build().unwrap();
+        assert!(interval.start().duration_since(&start).unwrap().days().abs() < 2.0);
+
+        // The stationary points in right ascension are a little later.
+        let end = interval.end().add_duration(AstroDuration::from_days(10.0).unwrap()).unwrap();
+        let points = stationary_points(Body::Mars, &time, &end, Motion::RightAscension).unwrap();
+        assert_eq!(points.len(), 2);
+        assert!(points[0].time() > interval.start() && points[1].time() > interval.end());
+        assert!(retrograde_interval(Body::Sun, &time, Motion::RightAscension).unwrap().is_none());
+        assert!(stationary_points(Body::Mars, &interval.end(), &time, Motion::RightAscension)
+            .is_err());
+    }
+}